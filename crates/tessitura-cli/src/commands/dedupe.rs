@@ -0,0 +1,159 @@
+use anyhow::Result;
+use std::fmt::Display;
+use std::path::PathBuf;
+use tessitura_core::model::{Artist, Expression, Work};
+use tessitura_core::schema::Database;
+use tessitura_etl::dedupe::{
+    suggest_artist_merges, suggest_expression_merges, suggest_work_merges, MergeSuggestion,
+};
+
+use super::merge::MergeKind;
+
+/// Arguments accepted by `tessitura dedupe`.
+#[derive(Debug, clap::Args)]
+pub struct DedupeArgs {
+    /// List the suggested merges without applying them (the default)
+    #[arg(long, default_value_t = false, conflicts_with = "apply")]
+    pub suggest: bool,
+
+    /// Apply every suggested merge
+    #[arg(long, default_value_t = false)]
+    pub apply: bool,
+}
+
+/// Suggest, or apply, merges of duplicate works, artists and expressions.
+///
+/// Expressions are compared last: merging works and artists can make two
+/// expressions share a work and performers. When suggesting, expressions
+/// are compared as they are now, so running again after applying the work
+/// and artist merges may find more.
+pub fn run_dedupe(db_path: PathBuf, args: &DedupeArgs) -> Result<()> {
+    let db = Database::open(&db_path)?;
+    let works = suggest_work_merges(&db.list_works()?);
+    let artists = suggest_artist_merges(&db.list_artists()?);
+
+    if !args.apply {
+        let expressions = suggest_expression_merges(&db.list_expressions()?);
+        let groups = works.len() + artists.len() + expressions.len();
+        if groups == 0 {
+            println!("✓ No duplicate works, artists or expressions found");
+            return Ok(());
+        }
+        print_suggestions(MergeKind::Work, &works, |w| &w.id, describe_work);
+        print_suggestions(MergeKind::Artist, &artists, |a| &a.id, describe_artist);
+        print_suggestions(
+            MergeKind::Expression,
+            &expressions,
+            |e| &e.id,
+            describe_expression,
+        );
+        println!("\n{groups} group(s) to merge; run 'tessitura dedupe --apply' to merge them all");
+        return Ok(());
+    }
+
+    let tx = db.transaction()?;
+    let mut merged = 0;
+    for suggestion in &works {
+        for duplicate in &suggestion.duplicates {
+            tx.merge_works(&suggestion.survivor.id, &duplicate.id)?;
+            merged += 1;
+        }
+    }
+    for suggestion in &artists {
+        for duplicate in &suggestion.duplicates {
+            tx.merge_artists(&suggestion.survivor.id, &duplicate.id)?;
+            merged += 1;
+        }
+    }
+    for suggestion in &suggest_expression_merges(&tx.list_expressions()?) {
+        for duplicate in &suggestion.duplicates {
+            tx.merge_expressions(&suggestion.survivor.id, &duplicate.id)?;
+            merged += 1;
+        }
+    }
+    tx.commit()?;
+
+    if merged == 0 {
+        println!("✓ No duplicate works, artists or expressions found");
+    } else {
+        println!("✓ Merged {merged} duplicate(s)");
+    }
+    Ok(())
+}
+
+fn print_suggestions<T, I: Display>(
+    kind: MergeKind,
+    suggestions: &[MergeSuggestion<T>],
+    id: impl Fn(&T) -> &I,
+    describe: impl Fn(&T) -> String,
+) {
+    if suggestions.is_empty() {
+        return;
+    }
+
+    println!(
+        "\nDuplicate {}s ({} groups):",
+        kind.name(),
+        suggestions.len()
+    );
+    for suggestion in suggestions {
+        println!("\n  [{}]", suggestion.reason);
+        println!(
+            "    keep   {}  {}",
+            id(&suggestion.survivor),
+            describe(&suggestion.survivor)
+        );
+        for duplicate in &suggestion.duplicates {
+            println!("    merge  {}  {}", id(duplicate), describe(duplicate));
+        }
+        let duplicates: Vec<String> = suggestion
+            .duplicates
+            .iter()
+            .map(|d| id(d).to_string())
+            .collect();
+        println!(
+            "    → tessitura merge {} {} {}",
+            kind.name(),
+            id(&suggestion.survivor),
+            duplicates.join(" ")
+        );
+    }
+}
+
+fn describe_work(work: &Work) -> String {
+    let catalog = work
+        .catalog_number
+        .as_ref()
+        .map(|catalog| format!(", {catalog}"))
+        .unwrap_or_default();
+    let composer = work
+        .composer
+        .as_ref()
+        .map(|composer| format!(" ({composer})"))
+        .unwrap_or_default();
+    with_musicbrainz_marker(
+        format!("{}{catalog}{composer}", work.title),
+        work.musicbrainz_id.is_some(),
+    )
+}
+
+fn describe_artist(artist: &Artist) -> String {
+    with_musicbrainz_marker(artist.name.clone(), artist.musicbrainz_id.is_some())
+}
+
+fn describe_expression(expr: &Expression) -> String {
+    let title = expr.title.as_deref().unwrap_or("(untitled)");
+    let text = match expr.recorded_year {
+        Some(year) => format!("{title} ({year})"),
+        None => title.to_string(),
+    };
+    with_musicbrainz_marker(text, expr.musicbrainz_id.is_some())
+}
+
+fn with_musicbrainz_marker(text: String, has_mbid: bool) -> String {
+    if has_mbid {
+        format!("{text} [MusicBrainz]")
+    } else {
+        text
+    }
+}
@@ -0,0 +1,200 @@
+use anyhow::{bail, Result};
+use std::path::PathBuf;
+use tessitura_core::model::Item;
+use tessitura_core::schema::Database;
+use tessitura_etl::recordings::{find_same_recordings, RecordingGroup, DEFAULT_MIN_SIMILARITY};
+
+/// Arguments accepted by `tessitura dupes`.
+#[derive(Debug, clap::Args)]
+pub struct DupesArgs {
+    /// Compare fingerprints to find the same recording in different files
+    #[arg(long, default_value_t = false)]
+    pub acoustic: bool,
+
+    /// Lowest fingerprint similarity (0.5-1.0) taken as the same recording
+    #[arg(
+        long,
+        value_name = "SIMILARITY",
+        default_value_t = DEFAULT_MIN_SIMILARITY,
+        requires = "acoustic"
+    )]
+    pub threshold: f64,
+
+    /// Propose linking each group of acoustic matches to its expression,
+    /// for review
+    #[arg(long, default_value_t = false, requires = "acoustic")]
+    pub link: bool,
+}
+
+/// Report duplicate files.
+///
+/// By default files are matched by content hash: byte-identical files share
+/// a file hash, and audio-identical files share an audio hash but differ
+/// elsewhere, usually only in their tags; groups whose files are all
+/// byte-identical are already listed and are left out. Hashes are computed
+/// by `tessitura scan`. With `--acoustic`, fingerprints are compared instead
+/// (see [`run_acoustic`]).
+pub fn run_dupes(db_path: PathBuf, args: &DupesArgs) -> Result<()> {
+    let db = Database::open(&db_path)?;
+    if args.acoustic {
+        return run_acoustic(&db, args);
+    }
+
+    let identical = db.list_duplicates_by_file_hash()?;
+    let audio: Vec<Vec<Item>> = db
+        .list_duplicates_by_audio_hash()?
+        .into_iter()
+        .filter(|group| {
+            group
+                .iter()
+                .any(|item| item.file_hash.is_none() || item.file_hash != group[0].file_hash)
+        })
+        .collect();
+
+    if identical.is_empty() && audio.is_empty() {
+        let unhashed = db
+            .list_all_items()?
+            .iter()
+            .filter(|i| !i.is_missing() && i.file_hash.is_none())
+            .count();
+        if unhashed > 0 {
+            println!("No duplicates found, but {unhashed} item(s) have not been hashed yet.");
+            println!("Run 'tessitura scan <dir>' to compute their hashes.");
+        } else {
+            println!("✓ No duplicate files found");
+        }
+        return Ok(());
+    }
+
+    let mut reclaimable = 0;
+    if !identical.is_empty() {
+        println!("\nByte-identical files ({} groups):", identical.len());
+        for group in &identical {
+            reclaimable += print_group(group);
+        }
+    }
+    if !audio.is_empty() {
+        println!(
+            "\nAudio-identical files, differing in tags ({} groups):",
+            audio.len()
+        );
+        for group in &audio {
+            reclaimable += print_group(group);
+        }
+    }
+
+    println!(
+        "\n{} duplicate group(s); removing all but one file of each would free {}",
+        identical.len() + audio.len(),
+        format_size(reclaimable)
+    );
+
+    Ok(())
+}
+
+/// Report the groups of files holding the same recording, found offline by
+/// comparing their fingerprints, with the expression each group should be
+/// linked to.
+///
+/// The proposed expression is the one most of the group is already linked
+/// to; `--link` records a proposal to link each of the rest of the group to
+/// it, applied once accepted in `tessitura review`. Groups with no
+/// identified item are listed without a proposal.
+fn run_acoustic(db: &Database, args: &DupesArgs) -> Result<()> {
+    if !(0.5..=1.0).contains(&args.threshold) {
+        bail!("Similarity threshold must be between 0.5 and 1.0");
+    }
+
+    let items = db.list_all_items()?;
+    let fingerprinted = items.iter().filter(|i| i.fingerprint.is_some()).count();
+    if fingerprinted < 2 {
+        println!("Not enough fingerprinted items to compare.");
+        println!("Run 'tessitura fingerprint' to generate fingerprints.");
+        return Ok(());
+    }
+
+    println!("Comparing fingerprints of {fingerprinted} items...");
+    let groups = find_same_recordings(&items, args.threshold);
+    if groups.is_empty() {
+        println!("✓ No recording found in more than one file");
+        return Ok(());
+    }
+
+    let mut proposed = 0;
+    let mut to_link = 0;
+    for group in &groups {
+        print_recording_group(db, group)?;
+        if group.proposed_expression().is_none() {
+            continue;
+        }
+        if args.link {
+            proposed += group.propose_links(db)?;
+        } else {
+            to_link += group.unlinked().len();
+        }
+    }
+
+    println!(
+        "\n{} recording(s) found in more than one file",
+        groups.len()
+    );
+    if proposed > 0 {
+        println!("✓ Proposed {proposed} link(s) to a recording's expression");
+        println!("Review them with 'tessitura review'.");
+    } else if to_link > 0 {
+        println!("{to_link} item(s) can be linked; run again with --link to link them");
+    }
+    Ok(())
+}
+
+fn print_recording_group(db: &Database, group: &RecordingGroup) -> Result<()> {
+    let expression = group.proposed_expression();
+    let title = match expression {
+        Some(id) => db
+            .get_expression_by_id(&id)?
+            .and_then(|e| e.title)
+            .unwrap_or_else(|| id.to_string()),
+        None => "(unidentified)".to_string(),
+    };
+    println!("\n{title}  [similarity {:.0}%]", group.similarity * 100.0);
+    for item in &group.items {
+        let marker = if expression.is_some() && item.expression_id != expression {
+            "→"
+        } else {
+            " "
+        };
+        println!("  {marker} {}", item.file_path.display());
+    }
+    Ok(())
+}
+
+/// Print one group of duplicates, returning the bytes held by all but its
+/// first file.
+fn print_group(group: &[Item]) -> u64 {
+    println!();
+    for item in group {
+        println!(
+            "  {:>10}  {}",
+            format_size(item.file_size),
+            item.file_path.display()
+        );
+    }
+    group.iter().skip(1).map(|item| item.file_size).sum()
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
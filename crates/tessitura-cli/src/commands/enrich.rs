@@ -1,34 +1,64 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use tessitura_core::schema::Database;
-use tessitura_etl::Config;
+use tessitura_etl::enrich::stage::SourceOutcome;
+use tessitura_etl::{Config, EnrichStage, MusicFile};
+use treadle::{Stage, StageContext, StageState, StageStatus, StateStore, SubTask, WorkItem};
 
-pub async fn run_enrich(config: &Config, db_path: PathBuf, pending_only: bool) -> Result<()> {
+/// Subtask metadata key marking a source skipped for lack of the identifier
+/// it is queried by. The subtask is left pending, so it is tried again.
+const SKIPPED: &str = "skipped";
+
+/// Per-source counts for the enrichment summary.
+#[derive(Debug, Default)]
+struct SourceSummary {
+    enriched: usize,
+    assertions: usize,
+    not_found: usize,
+    skipped: usize,
+    failed: usize,
+    already_done: usize,
+}
+
+/// Enrich every identified item from the enabled sources.
+///
+/// Each source is a subtask of the item's `enrich` stage in the pipeline
+/// state store, so `pending_only` can skip sources that already succeeded
+/// and retry the ones that failed or were skipped. `sources` restricts the
+/// run to the named sources (all enabled sources if empty).
+pub async fn run_enrich(
+    config: &Config,
+    db_path: PathBuf,
+    pending_only: bool,
+    sources: &[String],
+) -> Result<()> {
     log::info!("Starting enrichment");
 
     let db = Database::open(&db_path)?;
 
     // Count items awaiting enrichment
     let identified_items = db.list_identified_items()?;
+    drop(db);
 
     if identified_items.is_empty() {
         println!("No identified items in database. Run 'tessitura identify' first.");
         return Ok(());
     }
 
-    let enrich_stage = tessitura_etl::EnrichStage::new(config, db_path);
-    let sources = enrich_stage.enabled_sources();
+    let enrich_stage = EnrichStage::new(config, db_path.clone());
+    let enabled = enrich_stage.enabled_sources();
 
     println!(
         "Enrichment sources enabled: {}",
-        if sources.is_empty() {
+        if enabled.is_empty() {
             "none".to_string()
         } else {
-            sources.join(", ")
+            enabled.join(", ")
         }
     );
 
-    if sources.is_empty() {
+    if enabled.is_empty() {
         println!("\nNo enrichment sources configured.");
         println!("Set API keys in config or environment variables:");
         println!("  TESS_LASTFM_API_KEY   - Last.fm folksonomy tags");
@@ -37,16 +67,180 @@ pub async fn run_enrich(config: &Config, db_path: PathBuf, pending_only: bool) -
         return Ok(());
     }
 
+    if let Some(disabled) = sources.iter().find(|s| !enabled.contains(&s.as_str())) {
+        bail!("Source '{disabled}' is not enabled (check its API key in the config)");
+    }
+    let selected: Vec<&str> = enabled
+        .iter()
+        .copied()
+        .filter(|s| sources.is_empty() || sources.iter().any(|x| x == s))
+        .collect();
+
+    let mut store = super::open_state_store(&db_path).await?;
+
     println!(
-        "{} identified items ready for enrichment{}",
+        "Enriching {} identified items from {}{}...\n",
         identified_items.len(),
+        selected.join(", "),
         if pending_only { " (pending only)" } else { "" }
     );
 
-    // TODO: Wire enrichment execution through treadle Workflow
-    // For now, show what would be enriched
-    println!("\nEnrichment pipeline ready. Full execution coming in next iteration.");
-    println!("Sources: {:?} | Items: {}", sources, identified_items.len());
+    let mut summary: BTreeMap<&str, SourceSummary> = selected
+        .iter()
+        .map(|s| (*s, SourceSummary::default()))
+        .collect();
+
+    for (idx, item) in identified_items.iter().enumerate() {
+        let progress = format!("[{}/{}]", idx + 1, identified_items.len());
+        print!("\r{} Enriching: {}", progress, item.file_path.display());
+        std::io::Write::flush(&mut std::io::stdout())?;
+
+        let item_id = item.id.to_string();
+        let work_item = MusicFile::new(item_id.clone(), item.file_path.clone());
+
+        let mut state = store
+            .get_stage_state(&item_id, "enrich")
+            .await?
+            .unwrap_or_else(StageState::new);
+        for source in &enabled {
+            if !state.subtasks.iter().any(|t| t.id == *source) {
+                state.subtasks.push(SubTask::new((*source).to_string()));
+            }
+        }
+
+        run_sources(
+            &enrich_stage,
+            &work_item,
+            &mut state,
+            &selected,
+            pending_only,
+            &mut summary,
+        )
+        .await?;
+
+        update_stage_status(&mut state);
+        store
+            .save_stage_state(&item_id, "enrich", &state)
+            .await
+            .context("Failed to save enrichment state")?;
+    }
+    println!();
+
+    print_summary(&summary);
+
+    let failed: usize = summary.values().map(|s| s.failed).sum();
+    if failed > 0 {
+        println!("\n✗ {failed} source lookup(s) failed.");
+        println!("Retry them with 'tessitura enrich --pending-only' (optionally with --source).");
+    } else {
+        println!("\n✓ Enrichment complete");
+        println!("\nNext step: Run 'tessitura harmonize' to apply mapping rules");
+    }
 
     Ok(())
 }
+
+/// Run the selected sources for one item, recording each result on its
+/// subtask and in the summary.
+async fn run_sources<'a>(
+    stage: &EnrichStage,
+    work_item: &MusicFile,
+    stage_state: &mut StageState,
+    selected: &[&'a str],
+    pending_only: bool,
+    summary: &mut BTreeMap<&'a str, SourceSummary>,
+) -> Result<()> {
+    for source in selected {
+        let Some(subtask) = stage_state.subtasks.iter_mut().find(|t| t.id == *source) else {
+            continue;
+        };
+        let counts = summary.entry(source).or_default();
+
+        if pending_only && subtask.status == StageStatus::Complete {
+            counts.already_done += 1;
+            continue;
+        }
+
+        let mut ctx = StageContext::new("enrich".to_string()).with_subtask(*source);
+        match stage.execute(work_item, &mut ctx).await {
+            Ok(_) => {
+                let outcome: Option<SourceOutcome> = ctx
+                    .metadata
+                    .get("enrich_result")
+                    .map(|v| serde_json::from_value(v.clone()))
+                    .transpose()
+                    .context("Failed to read enrichment result")?;
+                match outcome {
+                    Some(SourceOutcome::Enriched { assertions }) => {
+                        counts.enriched += 1;
+                        counts.assertions += assertions;
+                    }
+                    Some(SourceOutcome::NotFound) => counts.not_found += 1,
+                    Some(SourceOutcome::Skipped) | None => {
+                        // The item may gain the identifier later (after
+                        // identify or a tag fix), so leave it pending
+                        counts.skipped += 1;
+                        subtask.status = StageStatus::Pending;
+                        subtask.error = None;
+                        subtask
+                            .metadata
+                            .insert(SKIPPED.to_string(), true.to_string());
+                        continue;
+                    }
+                }
+                subtask.metadata.remove(SKIPPED);
+                subtask.mark_complete();
+            }
+            Err(e) => {
+                log::warn!("{source} failed for {}: {e}", work_item.id());
+                counts.failed += 1;
+                subtask.mark_failed(e.to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Derive the stage status from its subtasks: complete once every source
+/// has succeeded or been skipped, failed if any source failed, otherwise
+/// still in progress.
+fn update_stage_status(state: &mut StageState) {
+    let failed: Vec<&str> = state
+        .subtasks
+        .iter()
+        .filter(|t| t.status == StageStatus::Failed)
+        .map(|t| t.id.as_str())
+        .collect();
+
+    if !failed.is_empty() {
+        let message = format!("{} failed", failed.join(", "));
+        state.mark_failed(message);
+    } else if state
+        .subtasks
+        .iter()
+        .all(|t| t.status == StageStatus::Complete || t.metadata.contains_key(SKIPPED))
+    {
+        state.mark_complete();
+    } else {
+        state.mark_in_progress();
+    }
+}
+
+fn print_summary(summary: &BTreeMap<&str, SourceSummary>) {
+    println!(
+        "\n{:<12} {:>9} {:>11} {:>10} {:>8} {:>7} {:>13}",
+        "Source", "Enriched", "Assertions", "Not found", "Skipped", "Failed", "Already done"
+    );
+    for (source, counts) in summary {
+        println!(
+            "{:<12} {:>9} {:>11} {:>10} {:>8} {:>7} {:>13}",
+            source,
+            counts.enriched,
+            counts.assertions,
+            counts.not_found,
+            counts.skipped,
+            counts.failed,
+            counts.already_done
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use tessitura_core::schema::Database;
+use tessitura_etl::export::TagChange;
+use tessitura_etl::{Config, ExportStage, MusicFile};
+use treadle::{Stage, StageState, StateStore, WorkItem};
+
+/// Write approved metadata into the tags of every identified item.
+///
+/// With `dry_run`, prints the tag changes that would be made without
+/// touching any file. Otherwise each item's export stage is recorded in the
+/// pipeline state store.
+pub async fn run_export(config: &Config, db_path: PathBuf, dry_run: bool) -> Result<()> {
+    log::info!("Starting export");
+
+    let db = Database::open(&db_path).context("Failed to open database")?;
+    let items = db.list_identified_items()?;
+    drop(db);
+
+    if items.is_empty() {
+        println!("No identified items in database. Run 'tessitura identify' first.");
+        return Ok(());
+    }
+
+    let export_stage = ExportStage::new(db_path.clone(), config.export.mapping.clone())
+        .with_auto_accept(config.export.auto_accept.clone())
+        .with_dry_run(dry_run);
+    let mut store = if dry_run {
+        None
+    } else {
+        Some(super::open_state_store(&db_path).await?)
+    };
+
+    println!(
+        "{} tags for {} identified items...\n",
+        if dry_run { "Checking" } else { "Exporting" },
+        items.len()
+    );
+
+    let mut updated = 0;
+    let mut unchanged = 0;
+    let mut failed = 0;
+
+    for item in &items {
+        let work_item = MusicFile::new(item.id.to_string(), item.file_path.clone());
+        let mut ctx = treadle::StageContext::new("export".to_string());
+
+        let result = export_stage.execute(&work_item, &mut ctx).await;
+        if let Some(store) = store.as_mut() {
+            let item_id = work_item.id();
+            let mut state = store
+                .get_stage_state(item_id, "export")
+                .await?
+                .unwrap_or_else(StageState::new);
+            match &result {
+                Ok(_) => state.mark_complete(),
+                Err(e) => state.mark_failed(e.to_string()),
+            }
+            store
+                .save_stage_state(item_id, "export", &state)
+                .await
+                .context("Failed to save export state")?;
+        }
+        if let Err(e) = result {
+            eprintln!("✗ {}: {e}", item.file_path.display());
+            failed += 1;
+            continue;
+        }
+
+        let changes: Vec<TagChange> = ctx
+            .metadata
+            .get("export_changes")
+            .map(|v| serde_json::from_value(v.clone()))
+            .transpose()
+            .context("Failed to read export changes")?
+            .unwrap_or_default();
+
+        if changes.is_empty() {
+            unchanged += 1;
+            continue;
+        }
+
+        updated += 1;
+        println!("{}", item.file_path.display());
+        for change in &changes {
+            println!(
+                "  {:<28} {} → {}",
+                change.key,
+                format_values(&change.before),
+                format_values(&change.after)
+            );
+        }
+        println!();
+    }
+
+    if dry_run {
+        println!("Dry run: {updated} file(s) would change, {unchanged} already up to date");
+        if updated > 0 {
+            println!("Run 'tessitura export' without --dry-run to write these changes.");
+        }
+    } else {
+        println!("✓ Updated {updated} file(s), {unchanged} already up to date");
+    }
+    if failed > 0 {
+        println!("✗ {failed} file(s) could not be exported");
+    }
+
+    Ok(())
+}
+
+fn format_values(values: &[String]) -> String {
+    if values.is_empty() {
+        "(none)".to_string()
+    } else {
+        values.join("; ")
+    }
+}
@@ -1,18 +1,65 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
+use tessitura_core::model::Item;
+use tessitura_core::provenance::Assertion;
 use tessitura_core::schema::Database;
+use tessitura_core::taxonomy::rules::{source_name, ProposedTag};
+use tessitura_etl::{HarmonizeStage, MusicFile};
+use treadle::{Stage, StageContext, StageOutcome, StageState, StateStore};
 
-pub fn run_harmonize(db_path: PathBuf, rules_path: PathBuf) -> Result<()> {
+/// Number of rules and unmatched values listed in the report.
+const REPORT_LIMIT: usize = 10;
+
+/// Totals for the harmonization report.
+#[derive(Debug, Default)]
+struct HarmonizeReport {
+    harmonized: usize,
+    no_assertions: usize,
+    failed: usize,
+    needs_review: usize,
+    proposals_by_field: BTreeMap<String, usize>,
+    rule_counts: HashMap<String, usize>,
+    /// Unmatched assertions by (field, value, source).
+    unmatched: HashMap<(String, String, String), usize>,
+}
+
+/// Arguments accepted by `tessitura harmonize`.
+#[derive(Debug, clap::Args)]
+pub struct HarmonizeArgs {
+    /// Only harmonize the item with this ID
+    #[arg(long, value_name = "ID")]
+    pub item: Option<String>,
+
+    /// Only harmonize items whose album tag contains this text
+    #[arg(long, value_name = "ALBUM")]
+    pub album: Option<String>,
+}
+
+/// Apply the mapping rules to the assertions of every identified item.
+///
+/// `--item` restricts the run to one item ID and `--album` to items whose
+/// album tag contains the given text (case-insensitive), for re-harmonizing
+/// after a rules edit. Proposals still pending are replaced; decided ones are
+/// kept. The harmonize stage of each item is recorded in the pipeline state
+/// store, paused while it awaits review.
+pub async fn run_harmonize(
+    db_path: PathBuf,
+    rules_path: PathBuf,
+    args: &HarmonizeArgs,
+) -> Result<()> {
     log::info!("Starting harmonization");
 
     let db = Database::open(&db_path)?;
-
-    // Check for assertions that need harmonizing
-    // For now, count total assertions as a proxy
-    let items = db.list_identified_items()?;
+    let items = select_items(db.list_identified_items()?, args);
+    drop(db);
 
     if items.is_empty() {
-        println!("No identified items in database. Run 'tessitura identify' first.");
+        if args.item.is_some() || args.album.is_some() {
+            println!("No identified items match the filter.");
+        } else {
+            println!("No identified items in database. Run 'tessitura identify' first.");
+        }
         return Ok(());
     }
 
@@ -25,10 +72,164 @@ pub fn run_harmonize(db_path: PathBuf, rules_path: PathBuf) -> Result<()> {
     }
 
     println!("Using mapping rules: {}", rules_path.display());
-    println!("{} identified items ready for harmonization", items.len());
+    let harmonize_stage =
+        HarmonizeStage::new(&rules_path, db_path.clone()).map_err(anyhow::Error::msg)?;
+    let mut store = super::open_state_store(&db_path).await?;
 
-    // TODO: Wire harmonization execution through treadle Workflow
-    println!("\nHarmonization pipeline ready. Full execution coming in next iteration.");
+    println!("Harmonizing {} identified items...\n", items.len());
+
+    let mut report = HarmonizeReport::default();
+    for (idx, item) in items.iter().enumerate() {
+        let progress = format!("[{}/{}]", idx + 1, items.len());
+        print!("\r{} Harmonizing: {}", progress, item.file_path.display());
+        std::io::Write::flush(&mut std::io::stdout())?;
+
+        let item_id = item.id.to_string();
+        let work_item = MusicFile::new(item_id.clone(), item.file_path.clone());
+        let mut ctx = StageContext::new("harmonize".to_string());
+
+        let mut state = store
+            .get_stage_state(&item_id, "harmonize")
+            .await?
+            .unwrap_or_else(StageState::new);
+        match harmonize_stage.execute(&work_item, &mut ctx).await {
+            Ok(outcome) => {
+                let needs_review = outcome == StageOutcome::NeedsReview;
+                report.record(&ctx, needs_review)?;
+                if needs_review {
+                    state.mark_paused();
+                } else {
+                    state.mark_complete();
+                }
+            }
+            Err(e) => {
+                log::warn!("Harmonization failed for {}: {e}", item.file_path.display());
+                report.failed += 1;
+                state.mark_failed(e.to_string());
+            }
+        }
+        store
+            .save_stage_state(&item_id, "harmonize", &state)
+            .await
+            .context("Failed to save harmonization state")?;
+    }
+    println!();
+
+    report.print();
+
+    if report.failed > 0 {
+        println!("\n✗ {} item(s) could not be harmonized", report.failed);
+    }
+    if report.needs_review > 0 {
+        println!("\nNext step: Run 'tessitura review' to approve the proposed metadata");
+    } else {
+        println!("\n✓ Harmonization complete, nothing awaiting review");
+    }
 
     Ok(())
 }
+
+/// Keep the items matching the `--item` and `--album` filters.
+fn select_items(items: Vec<Item>, args: &HarmonizeArgs) -> Vec<Item> {
+    let album = args.album.as_deref().map(str::to_lowercase);
+    items
+        .into_iter()
+        .filter(|i| args.item.as_ref().is_none_or(|id| i.id.to_string() == *id))
+        .filter(|i| {
+            album.as_ref().is_none_or(|album| {
+                i.tag_album
+                    .as_ref()
+                    .is_some_and(|a| a.to_lowercase().contains(album))
+            })
+        })
+        .collect()
+}
+
+impl HarmonizeReport {
+    /// Add one item's results, read from the stage context metadata.
+    fn record(&mut self, ctx: &StageContext, needs_review: bool) -> Result<()> {
+        // The stage only reports proposals for items with assertions
+        let Some(proposals) = ctx.metadata.get("proposed_tags") else {
+            self.no_assertions += 1;
+            return Ok(());
+        };
+        let proposals: Vec<ProposedTag> = serde_json::from_value(proposals.clone())
+            .context("Failed to read harmonization proposals")?;
+        let unmatched: Vec<Assertion> = ctx
+            .metadata
+            .get("unmatched_assertions")
+            .map(|v| serde_json::from_value(v.clone()))
+            .transpose()
+            .context("Failed to read unmatched assertions")?
+            .unwrap_or_default();
+
+        self.harmonized += 1;
+        if needs_review {
+            self.needs_review += 1;
+        }
+        for proposal in proposals {
+            *self.proposals_by_field.entry(proposal.field).or_default() += 1;
+            *self.rule_counts.entry(proposal.rule_name).or_default() += 1;
+        }
+        for assertion in unmatched {
+            let value = match assertion.value {
+                serde_json::Value::String(s) => s,
+                other => other.to_string(),
+            };
+            let source = source_name(assertion.source).to_string();
+            *self
+                .unmatched
+                .entry((assertion.field, value, source))
+                .or_default() += 1;
+        }
+        Ok(())
+    }
+
+    fn print(&self) {
+        println!(
+            "\nHarmonized {} item(s); {} need review",
+            self.harmonized, self.needs_review
+        );
+        if self.no_assertions > 0 {
+            println!(
+                "{} item(s) have no assertions yet (run 'tessitura enrich')",
+                self.no_assertions
+            );
+        }
+
+        if !self.proposals_by_field.is_empty() {
+            println!("\nProposals by field:");
+            for (field, count) in &self.proposals_by_field {
+                println!("  {field:<18} {count:>6}");
+            }
+        }
+
+        if !self.rule_counts.is_empty() {
+            println!("\nMost frequent rules:");
+            for (rule, count) in top(&self.rule_counts) {
+                println!("  {rule:<32} {count:>6}");
+            }
+        }
+
+        if !self.unmatched.is_empty() {
+            println!("\nAssertions matching no rule:");
+            for ((field, value, source), count) in top(&self.unmatched) {
+                println!("  {field:<16} {value:<32} {source:<12} {count:>6}");
+            }
+            if self.unmatched.len() > REPORT_LIMIT {
+                println!(
+                    "  ... and {} more distinct value(s)",
+                    self.unmatched.len() - REPORT_LIMIT
+                );
+            }
+        }
+    }
+}
+
+/// The most frequent entries, highest count first and then by key.
+fn top<K: Ord>(counts: &HashMap<K, usize>) -> Vec<(&K, usize)> {
+    let mut entries: Vec<(&K, usize)> = counts.iter().map(|(k, c)| (k, *c)).collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    entries.truncate(REPORT_LIMIT);
+    entries
+}
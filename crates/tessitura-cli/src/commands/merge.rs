@@ -0,0 +1,81 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use std::str::FromStr;
+use tessitura_core::model::{ArtistId, ExpressionId, WorkId};
+use tessitura_core::schema::Database;
+
+/// Kinds of entity that can be merged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum MergeKind {
+    Work,
+    Expression,
+    Artist,
+}
+
+impl MergeKind {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Work => "work",
+            Self::Expression => "expression",
+            Self::Artist => "artist",
+        }
+    }
+}
+
+/// Arguments accepted by `tessitura merge`.
+#[derive(Debug, clap::Args)]
+pub struct MergeArgs {
+    /// Kind of entity to merge
+    #[arg(value_enum)]
+    pub kind: MergeKind,
+
+    /// ID of the entity to keep
+    pub survivor: String,
+
+    /// IDs of the duplicates to merge into it
+    #[arg(required = true)]
+    pub duplicates: Vec<String>,
+}
+
+/// Merge duplicate works, expressions or artists into a survivor.
+///
+/// All merges run in one transaction: if any fails, none is applied.
+pub fn run_merge(db_path: PathBuf, args: &MergeArgs) -> Result<()> {
+    let db = Database::open(&db_path)?;
+    let tx = db.transaction()?;
+    for duplicate in &args.duplicates {
+        merge(&tx, args.kind, &args.survivor, duplicate)?;
+        println!(
+            "✓ Merged {} {duplicate} into {}",
+            args.kind.name(),
+            args.survivor
+        );
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Merge one duplicate, given by ID, into the survivor.
+fn merge(db: &Database, kind: MergeKind, survivor: &str, duplicate: &str) -> Result<()> {
+    match kind {
+        MergeKind::Work => {
+            db.merge_works(&parse_id::<WorkId>(survivor)?, &parse_id(duplicate)?)?;
+        }
+        MergeKind::Expression => {
+            db.merge_expressions(&parse_id::<ExpressionId>(survivor)?, &parse_id(duplicate)?)?;
+        }
+        MergeKind::Artist => {
+            db.merge_artists(&parse_id::<ArtistId>(survivor)?, &parse_id(duplicate)?)?;
+        }
+    }
+    Ok(())
+}
+
+fn parse_id<T>(id: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    id.parse()
+        .with_context(|| format!("Invalid ID (not a UUID): {id}"))
+}
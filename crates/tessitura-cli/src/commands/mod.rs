@@ -1,12 +1,18 @@
 pub mod config;
+pub mod dedupe;
+pub mod dupes;
 pub mod enrich;
+pub mod export;
 pub mod fingerprint;
 pub mod harmonize;
 pub mod identify;
+pub mod merge;
 pub mod process;
 pub mod review;
 pub mod rules;
 pub mod scan;
+pub mod search;
+pub mod similar;
 pub mod status;
 pub mod vocab;
 
@@ -15,3 +21,33 @@ pub use identify::run_identify;
 pub use process::run_process;
 pub use scan::run_scan;
 pub use status::show_status;
+
+/// Open the treadle state store kept alongside the database.
+pub(crate) async fn open_state_store(
+    db_path: &std::path::Path,
+) -> anyhow::Result<treadle::SqliteStateStore> {
+    use anyhow::Context;
+
+    treadle::SqliteStateStore::open(state_store_path(db_path)?)
+        .await
+        .context("Failed to open pipeline state store")
+}
+
+/// Open the treadle state store only if the pipeline has created it.
+///
+/// Read-only commands use this so they don't leave an empty store behind.
+pub(crate) async fn open_existing_state_store(
+    db_path: &std::path::Path,
+) -> anyhow::Result<Option<treadle::SqliteStateStore>> {
+    if !state_store_path(db_path)?.exists() {
+        return Ok(None);
+    }
+    open_state_store(db_path).await.map(Some)
+}
+
+fn state_store_path(db_path: &std::path::Path) -> anyhow::Result<std::path::PathBuf> {
+    let parent = db_path
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("Database path has no parent directory"))?;
+    Ok(parent.join("pipeline.db"))
+}
@@ -28,7 +28,7 @@ pub async fn run_process(
     // Step 1: Scan (unless resuming and already complete)
     if !resume || should_run_scan(&db_path)? {
         println!("📁 Step 1/5: Scanning music directory...");
-        super::run_scan(music_dir.clone(), db_path.clone())
+        super::run_scan(music_dir.clone(), db_path.clone(), None)
             .await
             .context("Scan step failed")?;
         completed_steps.push("scan");
@@ -1,8 +1,55 @@
 use std::path::PathBuf;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use tessitura_core::schema::Database;
+use tessitura_etl::enrich::discogs::credit_approved_performers;
+use tessitura_etl::recordings::link_approved_recording;
 
 /// Run the review TUI for human review of proposed metadata.
-pub fn run_review(db_path: PathBuf) -> Result<()> {
-    crate::tui::run_tui(db_path)
+///
+/// Decisions are saved as they are made. On exit, approved performer
+/// credits are added to the reviewed recordings, items are linked to the
+/// recordings approved for them, and items whose proposals
+/// have all been decided are released from the harmonize review gate so
+/// the pipeline can continue to export.
+pub async fn run_review(db_path: PathBuf) -> Result<()> {
+    let reviewed = crate::tui::run_tui(db_path.clone())?;
+    if reviewed.is_empty() {
+        return Ok(());
+    }
+
+    let db = Database::open(&db_path).context("Failed to open database")?;
+    let mut linked = 0;
+    let mut credited = 0;
+    for item_id in &reviewed {
+        if link_approved_recording(&db, item_id)
+            .with_context(|| format!("Failed to link {item_id} to its recording"))?
+        {
+            linked += 1;
+        }
+        credited += credit_approved_performers(&db, item_id)
+            .with_context(|| format!("Failed to credit performers for {item_id}"))?;
+    }
+    if linked > 0 {
+        println!("✓ Linked {linked} item(s) to their approved recording");
+    }
+    if credited > 0 {
+        println!("✓ Credited {credited} approved performer(s)");
+    }
+
+    let Some(mut store) = super::open_existing_state_store(&db_path).await? else {
+        return Ok(());
+    };
+
+    let mut released = 0;
+    for item_id in &reviewed {
+        if tessitura_etl::release_review(&db, &mut store, item_id).await? {
+            released += 1;
+        }
+    }
+    if released > 0 {
+        println!("✓ Released {released} reviewed item(s) for export");
+    }
+
+    Ok(())
 }
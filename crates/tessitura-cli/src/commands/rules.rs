@@ -97,6 +97,7 @@ pub fn validate_rules() -> Result<()> {
             println!("  Genre rules:       {}", rules.genre_rules.len());
             println!("  Period rules:      {}", rules.period_rules.len());
             println!("  Instrument rules:  {}", rules.instrument_rules.len());
+            println!("  Wikidata mappings: {}", rules.wikidata_mappings.len());
             println!("  Source priorities: {}", rules.source_priority.len());
         }
         Err(e) => {
@@ -1,45 +1,81 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::path::PathBuf;
-use tessitura_etl::{build_pipeline, MusicFile};
+use std::sync::mpsc;
+use tessitura_etl::scan::{ScanEvent, ScanSummary};
+use tessitura_etl::{MusicFile, ScanStage};
+use treadle::{Stage, StageContext};
 
-pub async fn run_scan(music_dir: PathBuf, db_path: PathBuf) -> Result<()> {
+/// Scan a music directory, recording new, changed, moved and missing files.
+///
+/// The stage runs directly rather than through the workflow, so every scan
+/// re-walks the directory instead of being skipped once a previous scan
+/// completed. Files are read by `jobs` worker threads (one per CPU if
+/// unset), and an interrupted scan resumes from its checkpoint.
+pub async fn run_scan(music_dir: PathBuf, db_path: PathBuf, jobs: Option<usize>) -> Result<()> {
     log::info!("Starting scan of {}", music_dir.display());
 
-    // Build the pipeline (just scan stage for now)
-    let workflow = build_pipeline(music_dir.clone(), db_path.clone(), None)?;
+    let (progress_tx, progress_rx) = mpsc::channel();
+    let mut stage = ScanStage::new(music_dir.clone(), db_path).with_progress(progress_tx);
+    if let Some(jobs) = jobs {
+        stage = stage.with_workers(jobs);
+    }
+    let scan_job = MusicFile::new("scan-job", music_dir.clone());
+    let mut ctx = StageContext::new("scan".to_string());
 
-    // Create a state store for the pipeline
-    let parent = db_path
-        .parent()
-        .ok_or_else(|| anyhow::anyhow!("Database path has no parent directory"))?;
-    let state_path = parent.join("pipeline.db");
-    let mut store = treadle::SqliteStateStore::open(&state_path).await?;
+    println!("  ⏳ [scan] Scanning {}...", music_dir.display());
+    let renderer = std::thread::spawn(move || render_progress(&progress_rx));
+    let result = stage.execute(&scan_job, &mut ctx).await;
+    // Closes the progress channel, ending the renderer
+    drop(stage);
+    renderer
+        .join()
+        .map_err(|_| anyhow::anyhow!("Progress display panicked"))?;
+    result.context("Scan failed")?;
 
-    // Create a work item for the scan job
-    let scan_job = MusicFile::new("scan-job", music_dir.clone());
+    let summary: ScanSummary = ctx
+        .metadata
+        .get("scan_summary")
+        .map(|v| serde_json::from_value(v.clone()))
+        .transpose()
+        .context("Failed to read scan summary")?
+        .unwrap_or_default();
 
-    // Subscribe to events for progress display
-    let mut events = workflow.subscribe();
-    tokio::spawn(async move {
-        while let Ok(event) = events.recv().await {
-            match event {
-                treadle::WorkflowEvent::StageStarted { stage, .. } => {
-                    println!("  ⏳ [{stage}] Starting...");
-                }
-                treadle::WorkflowEvent::StageCompleted { stage, .. } => {
-                    println!("  ✓ [{stage}] Complete");
-                }
-                treadle::WorkflowEvent::StageFailed { stage, error, .. } => {
-                    eprintln!("  ✗ [{stage}] FAILED: {error}");
+    println!("\n✓ Scan complete: {} audio files", summary.scanned);
+    println!("  Added:    {}", summary.added);
+    println!("  Updated:  {}", summary.updated);
+    if summary.moved > 0 {
+        println!("  Moved:    {}", summary.moved);
+    }
+    if summary.restored > 0 {
+        println!("  Restored: {}", summary.restored);
+    }
+    if summary.missing > 0 {
+        println!("  Missing:  {} (no longer on disk)", summary.missing);
+    }
+    if summary.failed > 0 {
+        println!("  Failed:   {} (could not be read)", summary.failed);
+    }
+    Ok(())
+}
+
+/// Print scan progress events until the stage closes the channel.
+fn render_progress(events: &mpsc::Receiver<ScanEvent>) {
+    for event in events {
+        match event {
+            ScanEvent::Started { total, resumed } => {
+                if resumed > 0 {
+                    println!(
+                        "  Found {total} audio files; resuming after {resumed} already scanned"
+                    );
+                } else {
+                    println!("  Found {total} audio files");
                 }
-                _ => {}
             }
+            ScanEvent::Scanned { path, done, total } => {
+                print!("\r  [{done}/{total}] {}", path.display());
+                std::io::Write::flush(&mut std::io::stdout()).ok();
+            }
+            ScanEvent::Finished(_) => println!(),
         }
-    });
-
-    // Execute the workflow
-    workflow.advance(&scan_job, &mut store).await?;
-
-    println!("\n✓ Scan complete");
-    Ok(())
+    }
 }
@@ -0,0 +1,284 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tessitura_core::model::Work;
+use tessitura_core::schema::{CatalogStamp, Database};
+use tessitura_core::taxonomy::MappingRules;
+use tessitura_graph::{FacetIndex, FacetMode, MusicGraph, WorkQuery};
+
+/// Facets accepted by `tessitura search`.
+#[derive(Debug, clap::Args)]
+#[allow(clippy::struct_excessive_bools)] // Independent command-line flags
+pub struct SearchArgs {
+    /// Form (e.g., "string quartet"); repeat for alternatives
+    #[arg(long)]
+    pub form: Vec<String>,
+
+    /// Period (e.g., "20th century")
+    #[arg(long)]
+    pub period: Vec<String>,
+
+    /// Genre (e.g., "Classical")
+    #[arg(long)]
+    pub genre: Vec<String>,
+
+    /// Key (e.g., "a minor", "fis-moll", "si bémol majeur")
+    #[arg(long)]
+    pub key: Vec<String>,
+
+    /// Match keys that sound the same however spelled (e.g., "G-flat major"
+    /// for "F-sharp major")
+    #[arg(long, default_value_t = false)]
+    pub enharmonic: bool,
+
+    /// Catalog number (e.g., "Op. 131", "KV 550", "BWV 1007")
+    #[arg(long)]
+    pub catalog: Vec<String>,
+
+    /// Composer name (substring match)
+    #[arg(long)]
+    pub composer: Vec<String>,
+
+    /// Arranger or orchestrator name (substring match)
+    #[arg(long)]
+    pub arranger: Vec<String>,
+
+    /// Lyricist or librettist name (substring match)
+    #[arg(long)]
+    pub lyricist: Vec<String>,
+
+    /// Instrument (e.g., "piano")
+    #[arg(long)]
+    pub instrument: Vec<String>,
+
+    /// Performer or conductor name (substring match)
+    #[arg(long)]
+    pub performer: Vec<String>,
+
+    /// Instrument or voice a performer is credited with (e.g., "piano")
+    #[arg(long)]
+    pub role: Vec<String>,
+
+    /// Composition year or range (e.g., "1928" or "1900-1950")
+    #[arg(long)]
+    pub year: Option<String>,
+
+    /// Recording year or range (e.g., "1962" or "1960-1969")
+    #[arg(long)]
+    pub recorded: Option<String>,
+
+    /// Only live recordings
+    #[arg(long, default_value_t = false, conflicts_with = "studio")]
+    pub live: bool,
+
+    /// Only studio recordings
+    #[arg(long, default_value_t = false)]
+    pub studio: bool,
+
+    /// Match works satisfying any facet instead of all of them
+    #[arg(long, default_value_t = false)]
+    pub any: bool,
+}
+
+impl SearchArgs {
+    fn to_query(&self) -> Result<WorkQuery> {
+        let mut query = WorkQuery {
+            forms: self.form.clone(),
+            periods: self.period.clone(),
+            genres: self.genre.clone(),
+            instruments: self.instrument.clone(),
+            keys: self.key.clone(),
+            enharmonic_keys: self.enharmonic,
+            catalog_numbers: self.catalog.clone(),
+            composers: self.composer.clone(),
+            arrangers: self.arranger.clone(),
+            lyricists: self.lyricist.clone(),
+            performers: self.performer.clone(),
+            performer_roles: self.role.clone(),
+            composed_between: None,
+            recorded_between: None,
+            live: (self.live || self.studio).then_some(self.live),
+            mode: if self.any {
+                FacetMode::Any
+            } else {
+                FacetMode::All
+            },
+        };
+        if let Some(year) = &self.year {
+            let (start, end) = parse_year_range(year)?;
+            query = query.composed_between(start, end);
+        }
+        if let Some(recorded) = &self.recorded {
+            let (start, end) = parse_year_range(recorded)?;
+            query = query.recorded_between(start, end);
+        }
+        Ok(query)
+    }
+}
+
+/// Parse "1928" or "1900-1950" into an inclusive year range.
+fn parse_year_range(input: &str) -> Result<(i32, i32)> {
+    let parse = |s: &str| {
+        s.trim()
+            .parse::<i32>()
+            .with_context(|| format!("Invalid year: '{}'", s.trim()))
+    };
+    if let Some((start, end)) = input.split_once('-') {
+        Ok((parse(start)?, parse(end)?))
+    } else {
+        let year = parse(input)?;
+        Ok((year, year))
+    }
+}
+
+/// Run a faceted search over the catalog and print matching works.
+pub fn run_search(db_path: PathBuf, rules_path: PathBuf, args: &SearchArgs) -> Result<()> {
+    let query = args.to_query()?;
+
+    let db = Database::open(&db_path).context("Failed to open database")?;
+    let graph = MusicGraph::load(&db).context("Failed to build music graph")?;
+
+    let facets = load_or_build_facets(&graph, &db, &db_path, &rules_path)?;
+
+    let results = graph.search(&query, &facets);
+
+    if results.is_empty() {
+        println!("No matching works found.");
+        return Ok(());
+    }
+
+    println!(
+        "\nFound {} matching work{}\n",
+        results.len(),
+        if results.len() == 1 { "" } else { "s" }
+    );
+    println!(
+        "  {:<28} {:<40} {:<16} {:<6} Files",
+        "Composer", "Title", "Key", "Year"
+    );
+    println!("  {}", "─".repeat(96));
+
+    for result in &results {
+        println!(
+            "  {:<28} {:<40} {:<16} {:<6} {}",
+            truncate(result.composer.unwrap_or("-"), 28),
+            truncate(&result.work.title, 40),
+            truncate(&key_name(&result.work), 16),
+            result
+                .work
+                .composed_year
+                .map_or_else(|| "-".to_string(), |y| y.to_string()),
+            result.items.len()
+        );
+        for item in &result.items {
+            println!("      {}", item.file_path.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// The work's key in canonical form, or as stored if it does not parse.
+fn key_name(work: &Work) -> String {
+    work.musical_key().map_or_else(
+        || work.key.clone().unwrap_or_else(|| "-".to_string()),
+        |key| key.to_string(),
+    )
+}
+
+/// Search facets kept next to the database between runs, with what they
+/// were built from.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedFacets {
+    catalog: CatalogStamp,
+    rules_modified: Option<DateTime<Utc>>,
+    facets: FacetIndex,
+}
+
+impl CachedFacets {
+    fn path(db_path: &Path) -> PathBuf {
+        db_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join("search-facets.json")
+    }
+
+    fn load(path: &Path) -> Result<Self> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_string(self)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+fn rules_modified(rules_path: &Path) -> Option<DateTime<Utc>> {
+    std::fs::metadata(rules_path)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(DateTime::from)
+}
+
+/// Load the search facets cached by an earlier run, building them again
+/// only when the catalog or the mapping rules file changed since.
+pub(crate) fn load_or_build_facets(
+    graph: &MusicGraph,
+    db: &Database,
+    db_path: &Path,
+    rules_path: &Path,
+) -> Result<FacetIndex> {
+    let path = CachedFacets::path(db_path);
+    let catalog = db.catalog_stamp()?;
+    let rules_modified = rules_modified(rules_path);
+    match CachedFacets::load(&path) {
+        Ok(cached) if cached.catalog == catalog && cached.rules_modified == rules_modified => {
+            return Ok(cached.facets);
+        }
+        Ok(_) => log::info!("Search facets are out of date; rebuilding"),
+        Err(e) if path.exists() => log::warn!("Could not read search facets ({e}); rebuilding"),
+        Err(_) => {}
+    }
+
+    let rules = load_rules_or_default(rules_path)?;
+    let facets = FacetIndex::build(graph, db, &rules).context("Failed to build facets")?;
+    let cached = CachedFacets {
+        catalog,
+        rules_modified,
+        facets,
+    };
+    if let Err(e) = cached.save(&path) {
+        log::warn!("Could not write {} ({e})", path.display());
+    }
+    Ok(cached.facets)
+}
+
+/// Load the mapping rules, falling back to an empty rule set (and thus no
+/// periods inferred from a work's composer or year) when the rules file
+/// does not exist.
+pub(crate) fn load_rules_or_default(rules_path: &Path) -> Result<MappingRules> {
+    if rules_path.exists() {
+        Ok(MappingRules::load(rules_path)?)
+    } else {
+        log::warn!(
+            "Mapping rules not found at {}; continuing without inferred periods",
+            rules_path.display()
+        );
+        Ok(MappingRules::default())
+    }
+}
+
+/// Truncate a string to at most `max` characters, marking the cut with '…'.
+pub(crate) fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        let mut out: String = s.chars().take(max.saturating_sub(1)).collect();
+        out.push('…');
+        out
+    }
+}
@@ -0,0 +1,110 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use tessitura_core::schema::Database;
+use tessitura_graph::MusicGraph;
+use tessitura_search::{Embedder, EntityKind, VectorIndex};
+
+use super::search::{load_or_build_facets, truncate};
+
+/// Arguments accepted by `tessitura similar`.
+#[derive(Debug, clap::Args)]
+pub struct SimilarArgs {
+    /// Work to find neighbours of (e.g., "Bartók String Quartet 4")
+    pub query: String,
+
+    /// Maximum number of results
+    #[arg(short = 'n', long, default_value_t = 10)]
+    pub limit: usize,
+
+    /// Compare individual recordings instead of works
+    #[arg(long, default_value_t = false)]
+    pub recordings: bool,
+
+    /// Rebuild the similarity index even if it is up to date
+    #[arg(long, default_value_t = false)]
+    pub reindex: bool,
+}
+
+/// Location of the similarity index, next to the database.
+fn index_path(db_path: &Path) -> PathBuf {
+    db_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join("similarity-index.json")
+}
+
+/// Load the similarity index, rebuilding it when missing, stale or forced.
+fn load_or_build_index(
+    db: &Database,
+    db_path: &Path,
+    rules_path: &Path,
+    reindex: bool,
+) -> Result<VectorIndex> {
+    let path = index_path(db_path);
+
+    if !reindex && path.exists() {
+        match VectorIndex::load(&path) {
+            Ok(index) if !index.is_stale(db, rules_path)? => return Ok(index),
+            Ok(_) => log::info!("Similarity index is out of date; rebuilding"),
+            Err(e) => log::warn!("Could not read similarity index ({e}); rebuilding"),
+        }
+    }
+
+    println!("Building similarity index...");
+    let graph = MusicGraph::load(db).context("Failed to build music graph")?;
+    let facets = load_or_build_facets(&graph, db, db_path, rules_path)?;
+    let index = VectorIndex::build(&graph, &facets, db, rules_path, &Embedder::default())
+        .context("Failed to build similarity index")?;
+    index
+        .save(&path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(index)
+}
+
+/// Find the works (or recordings) most similar to the one named by the query.
+pub fn run_similar(db_path: PathBuf, rules_path: PathBuf, args: &SimilarArgs) -> Result<()> {
+    let db = Database::open(&db_path).context("Failed to open database")?;
+    let index = load_or_build_index(&db, &db_path, &rules_path, args.reindex)?;
+
+    if index.is_empty() {
+        println!("The catalog is empty. Run 'tessitura identify' first.");
+        return Ok(());
+    }
+
+    let kind = if args.recordings {
+        EntityKind::Expression
+    } else {
+        EntityKind::Work
+    };
+    let Some(source) = index.find(&args.query, Some(kind)) else {
+        bail!("No {} matches '{}'", kind_name(kind), args.query);
+    };
+
+    let neighbors = index.similar_to(source, args.limit);
+
+    println!("\nMost similar to: {}\n", source.label);
+    if neighbors.is_empty() {
+        println!("No similar {}s found.", kind_name(kind));
+        return Ok(());
+    }
+
+    println!("  {:>5}  Title", "Score");
+    println!("  {}", "─".repeat(72));
+    for neighbor in &neighbors {
+        println!(
+            "  {:>5.2}  {}",
+            neighbor.score,
+            truncate(&neighbor.label, 64)
+        );
+    }
+
+    Ok(())
+}
+
+const fn kind_name(kind: EntityKind) -> &'static str {
+    match kind {
+        EntityKind::Work => "work",
+        EntityKind::Expression => "recording",
+    }
+}
@@ -1,21 +1,74 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use tessitura_core::model::{ExpressionId, Item, WorkId};
+use tessitura_core::provenance::ProposalStatus;
 use tessitura_core::schema::Database;
+use treadle::{StageStatus, StateStore};
 
-pub fn show_status(db_path: PathBuf, _filter: Option<String>) -> Result<()> {
+/// Stages whose per-item state is recorded in the pipeline state store.
+const TRACKED_STAGES: [&str; 3] = ["enrich", "harmonize", "export"];
+
+/// Number of failing items listed.
+const FAILURE_LIMIT: usize = 20;
+
+/// Item counts by status for one stage.
+#[derive(Debug, Default)]
+struct StageCounts {
+    pending: usize,
+    in_progress: usize,
+    done: usize,
+    failed: usize,
+    needs_review: usize,
+}
+
+/// A stage that failed for an item, with its last error.
+#[derive(Debug)]
+struct Failure {
+    stage: &'static str,
+    path: PathBuf,
+    error: String,
+    at: Option<DateTime<Utc>>,
+}
+
+/// Show how far each item has progressed through the pipeline.
+///
+/// Scan, fingerprint and identify progress comes from the database, where
+/// items whose files are missing are counted separately; the
+/// enrich, harmonize and export stages come from the per-item state in the
+/// pipeline state store. `filter` restricts everything to items whose album,
+/// artist or album artist tag contains the given text (case-insensitive).
+pub async fn show_status(db_path: PathBuf, filter: Option<String>) -> Result<()> {
     let db = Database::open(&db_path)?;
 
-    // Get statistics for each pipeline step
-    let total_items = db.list_all_items()?.len();
-    let items_without_fingerprints = db.list_items_without_fingerprints()?.len();
-    let unidentified_items = db.list_unidentified_items()?.len();
-    let identified_items = db.list_identified_items()?.len();
+    let (missing, items) = load_items(&db, filter.as_deref())?;
+    let total_items = items.len();
+    let items_without_fingerprints = items.iter().filter(|i| i.fingerprint.is_none()).count();
+    let identified: Vec<&Item> = items.iter().filter(|i| i.expression_id.is_some()).collect();
+    let unidentified_items = total_items - identified.len();
+
+    let store = super::open_existing_state_store(&db_path).await?;
+    let (stages, failures) = stage_counts(store.as_ref(), &identified).await?;
+    let (pending_proposals, decided_proposals) = proposal_counts(&db, &identified)?;
+    let works = work_count(&db, &identified)?;
 
     println!("\n📊 Tessitura Status\n");
     println!("  Database: {}", db_path.display());
+    if let Some(filter) = &filter {
+        println!("  Filter:   {filter}");
+    }
     println!();
     println!("Pipeline Progress:");
-    println!("  ✓ Scan:        {} items", total_items);
+    println!(
+        "  ✓ Scan:        {} items {}",
+        total_items,
+        if missing.is_empty() {
+            String::new()
+        } else {
+            format!("({} missing from disk)", missing.len())
+        }
+    );
     println!(
         "  {} Fingerprint: {} items {}",
         if items_without_fingerprints == 0 {
@@ -31,26 +84,338 @@ pub fn show_status(db_path: PathBuf, _filter: Option<String>) -> Result<()> {
         }
     );
     println!(
-        "  {} Identify:    {} items {}",
+        "  {} Identify:    {} items {}{}",
         if unidentified_items == 0 { "✓" } else { "⏳" },
-        identified_items,
+        identified.len(),
+        if works > 0 {
+            format!("of {works} works ")
+        } else {
+            String::new()
+        },
         if unidentified_items > 0 {
             format!("({} pending)", unidentified_items)
         } else {
             String::new()
         }
     );
-    // TODO: Add enrich and harmonize status when those stages track completion
+    print_stage("Enrich:", &stages[0]);
+    print_stage("Harmonize:", &stages[1]);
+    if pending_proposals + decided_proposals > 0 {
+        println!(
+            "  {} Review:      {} proposals decided {}",
+            if pending_proposals == 0 { "✓" } else { "⏳" },
+            decided_proposals,
+            if pending_proposals > 0 {
+                format!(
+                    "({} pending on {} items)",
+                    pending_proposals, stages[1].needs_review
+                )
+            } else {
+                String::new()
+            }
+        );
+    }
+    print_stage("Export:", &stages[2]);
+
+    print_failures(&failures);
 
     if total_items == 0 {
-        println!("\nNo items found. Run 'tessitura process <dir>' to scan and process your music library.");
-    } else if items_without_fingerprints > 0 {
-        println!("\nNext step: Run 'tessitura process --resume' to continue processing");
-    } else if unidentified_items > 0 {
+        if filter.is_some() {
+            println!("\nNo items match the filter.");
+        } else {
+            println!("\nNo items found. Run 'tessitura process <dir>' to scan and process your music library.");
+        }
+    } else if items_without_fingerprints > 0 || unidentified_items > 0 {
         println!("\nNext step: Run 'tessitura process --resume' to continue processing");
-    } else if identified_items > 0 {
+    } else if stages[0].failed > 0 {
+        println!("\nNext step: Run 'tessitura enrich --pending-only' to retry failed sources");
+    } else if stages[0].done == 0 {
         println!("\nNext step: Run 'tessitura enrich' to fetch external metadata");
+    } else if stages[1].done + stages[1].needs_review == 0 {
+        println!("\nNext step: Run 'tessitura harmonize' to apply mapping rules");
+    } else if pending_proposals > 0 {
+        println!("\nNext step: Run 'tessitura review' to approve proposed tags");
+    } else if stages[2].done < identified.len() {
+        println!("\nNext step: Run 'tessitura export' to write approved tags");
     }
 
     Ok(())
 }
+
+/// Load the items matching the filter, split into missing and present.
+fn load_items(db: &Database, filter: Option<&str>) -> Result<(Vec<Item>, Vec<Item>)> {
+    let filter = filter.map(str::to_lowercase);
+    Ok(db
+        .list_all_items()?
+        .into_iter()
+        .filter(|item| filter.as_deref().is_none_or(|f| matches_filter(item, f)))
+        .partition(Item::is_missing))
+}
+
+/// Whether an item's album, artist or album artist tag contains the
+/// lowercased `filter`.
+fn matches_filter(item: &Item, filter: &str) -> bool {
+    [&item.tag_album, &item.tag_artist, &item.tag_album_artist]
+        .into_iter()
+        .flatten()
+        .any(|tag| tag.to_lowercase().contains(filter))
+}
+
+/// Count the tracked stage states of the identified items, collecting the
+/// failures.
+///
+/// Items with no recorded state are pending. If the pipeline has never run
+/// there is no state store, and every item is pending.
+async fn stage_counts(
+    store: Option<&impl StateStore>,
+    items: &[&Item],
+) -> Result<([StageCounts; 3], Vec<Failure>)> {
+    let mut counts: [StageCounts; 3] = Default::default();
+    let mut failures = Vec::new();
+
+    let Some(store) = store else {
+        for stage in &mut counts {
+            stage.pending = items.len();
+        }
+        return Ok((counts, failures));
+    };
+    for item in items {
+        let states = store
+            .get_all_stage_states(&item.id.to_string())
+            .await
+            .context("Failed to read pipeline state")?;
+        for (&stage, stage_counts) in TRACKED_STAGES.iter().zip(counts.iter_mut()) {
+            let Some(state) = states.get(stage) else {
+                stage_counts.pending += 1;
+                continue;
+            };
+            match state.status {
+                StageStatus::Pending => stage_counts.pending += 1,
+                StageStatus::InProgress => stage_counts.in_progress += 1,
+                StageStatus::Complete => stage_counts.done += 1,
+                StageStatus::Paused => stage_counts.needs_review += 1,
+                StageStatus::Failed => {
+                    stage_counts.failed += 1;
+                    failures.push(Failure {
+                        stage,
+                        path: item.file_path.clone(),
+                        error: state.error.clone().unwrap_or_default(),
+                        at: state.completed_at,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok((counts, failures))
+}
+
+/// Count the distinct works the given items are recordings of, counting a
+/// movement as the work it is part of.
+fn work_count(db: &Database, items: &[&Item]) -> Result<usize> {
+    let parents: HashMap<WorkId, Option<WorkId>> = db
+        .list_works()?
+        .into_iter()
+        .map(|w| (w.id, w.parent_work_id))
+        .collect();
+    let works: HashMap<ExpressionId, WorkId> = db
+        .list_expressions()?
+        .into_iter()
+        .map(|e| (e.id, e.work_id))
+        .collect();
+
+    let roots: HashSet<WorkId> = items
+        .iter()
+        .filter_map(|item| works.get(&item.expression_id?))
+        .map(|&work| {
+            let mut root = work;
+            let mut seen = HashSet::from([root]);
+            while let Some(&Some(parent)) = parents.get(&root) {
+                if !seen.insert(parent) {
+                    break;
+                }
+                root = parent;
+            }
+            root
+        })
+        .collect();
+    Ok(roots.len())
+}
+
+/// Count the pending and decided proposals on the given items.
+fn proposal_counts(db: &Database, items: &[&Item]) -> Result<(usize, usize)> {
+    let ids: HashSet<String> = items.iter().map(|i| i.id.to_string()).collect();
+    let mut pending = 0;
+    let mut decided = 0;
+    for (entity_id, status, count) in db.count_proposals_by_entity_and_status()? {
+        if !ids.contains(&entity_id) {
+            continue;
+        }
+        if status == ProposalStatus::Pending {
+            pending += count;
+        } else {
+            decided += count;
+        }
+    }
+    Ok((pending, decided))
+}
+
+fn print_stage(label: &str, counts: &StageCounts) {
+    println!("{}", stage_line(label, counts));
+}
+
+fn stage_line(label: &str, counts: &StageCounts) -> String {
+    let icon = if counts.failed > 0 {
+        "✗"
+    } else if counts.pending + counts.in_progress + counts.needs_review == 0 {
+        "✓"
+    } else {
+        "⏳"
+    };
+    let details: Vec<String> = [
+        (counts.failed, "failed"),
+        (counts.needs_review, "needs review"),
+        (counts.in_progress, "in progress"),
+        (counts.pending, "pending"),
+    ]
+    .into_iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, label)| format!("{count} {label}"))
+    .collect();
+
+    format!(
+        "  {icon} {label:<13}{} items {}",
+        counts.done,
+        if details.is_empty() {
+            String::new()
+        } else {
+            format!("({})", details.join(", "))
+        }
+    )
+}
+
+fn print_failures(failures: &[Failure]) {
+    if failures.is_empty() {
+        return;
+    }
+
+    println!("\nFailures:");
+    for failure in failures.iter().take(FAILURE_LIMIT) {
+        println!(
+            "  [{}] {}{}",
+            failure.stage,
+            failure.path.display(),
+            failure
+                .at
+                .map(|at| format!(" ({})", at.format("%Y-%m-%d %H:%M")))
+                .unwrap_or_default()
+        );
+        println!("      {}", failure.error);
+    }
+    if failures.len() > FAILURE_LIMIT {
+        println!("  ... and {} more", failures.len() - FAILURE_LIMIT);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tessitura_core::model::{AudioFormat, ExpressionId};
+    use treadle::{MemoryStateStore, StageState};
+
+    fn item(album: &str, artist: &str) -> Item {
+        let mut item = Item::new(
+            PathBuf::from(format!("/music/{album}.flac")),
+            AudioFormat::Flac,
+            0,
+            Utc::now(),
+        );
+        item.tag_album = Some(album.to_string());
+        item.tag_artist = Some(artist.to_string());
+        item.expression_id = Some(ExpressionId::new());
+        item
+    }
+
+    #[test]
+    fn test_matches_filter() {
+        let mut item = item("Goldberg Variations", "Glenn Gould");
+        item.tag_album_artist = Some("Johann Sebastian Bach".to_string());
+
+        assert!(matches_filter(&item, "goldberg"));
+        assert!(matches_filter(&item, "gould"));
+        assert!(matches_filter(&item, "bach"));
+        assert!(!matches_filter(&item, "mahler"));
+    }
+
+    #[tokio::test]
+    async fn test_stage_counts() {
+        let items = [item("One", "A"), item("Two", "B"), item("Three", "C")];
+        let items: Vec<&Item> = items.iter().collect();
+
+        let (counts, failures) = stage_counts(None::<&MemoryStateStore>, &items)
+            .await
+            .unwrap();
+        assert!(counts.iter().all(|c| c.pending == 3));
+        assert!(failures.is_empty());
+
+        let mut store = MemoryStateStore::new();
+        let mut complete = StageState::new();
+        complete.mark_complete();
+        let mut failed = StageState::new();
+        failed.mark_failed("MusicBrainz timed out".to_string());
+        let mut paused = StageState::new();
+        paused.mark_paused();
+        let save = [
+            (items[0], "enrich", &complete),
+            (items[1], "enrich", &failed),
+            (items[0], "harmonize", &paused),
+        ];
+        for (item, stage, state) in save {
+            store
+                .save_stage_state(&item.id.to_string(), stage, state)
+                .await
+                .unwrap();
+        }
+
+        let (counts, failures) = stage_counts(Some(&store), &items).await.unwrap();
+        assert_eq!(
+            (counts[0].done, counts[0].failed, counts[0].pending),
+            (1, 1, 1)
+        );
+        assert_eq!((counts[1].needs_review, counts[1].pending), (1, 2));
+        assert_eq!(counts[2].pending, 3);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].stage, "enrich");
+        assert_eq!(failures[0].path, items[1].file_path);
+        assert_eq!(failures[0].error, "MusicBrainz timed out");
+    }
+
+    #[test]
+    fn test_stage_line() {
+        let done = StageCounts {
+            done: 4,
+            ..StageCounts::default()
+        };
+        assert_eq!(stage_line("Enrich:", &done), "  ✓ Enrich:      4 items ");
+
+        let mixed = StageCounts {
+            done: 2,
+            failed: 1,
+            pending: 3,
+            ..StageCounts::default()
+        };
+        assert_eq!(
+            stage_line("Export:", &mixed),
+            "  ✗ Export:      2 items (1 failed, 3 pending)"
+        );
+
+        let waiting = StageCounts {
+            needs_review: 1,
+            ..StageCounts::default()
+        };
+        assert_eq!(
+            stage_line("Harmonize:", &waiting),
+            "  ⏳ Harmonize:   0 items (1 needs review)"
+        );
+    }
+}
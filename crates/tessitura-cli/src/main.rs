@@ -56,18 +56,26 @@ their metadata. For each audio file found:
 
   - Extracts embedded tags (title, artist, album, track number, year, genre)
   - Records file metadata (path, size, format, modification time)
+  - Hashes the whole file and its audio stream (excluding tags)
   - Creates Item records in the database
   - Tracks files in the pipeline for downstream identification
 
 Supported formats: FLAC, MP3, OGG, WAV, M4A/AAC
 
+Files are read in parallel (one worker per CPU, or --jobs N) and written to
+the database in batches. An interrupted scan keeps a checkpoint next to the
+database and resumes where it stopped when run again on the same directory.
+
 The scan is incremental: previously scanned files are skipped unless their
-modification time has changed. Changed files are re-scanned and updated.
-Files removed from disk are detected and marked accordingly.
+modification time has changed. Changed files are re-scanned and updated;
+a file whose audio hash is unchanged was only retagged and keeps its
+fingerprint and identification.
+Files removed from disk are detected and marked missing. Files that were
+moved or renamed are matched by file or audio hash, or by fingerprint and size,
+and keep their existing item, identification and review decisions.
 
 Output:
-  - Real-time progress indicators for each stage
-  - Summary showing files discovered, added, updated, and removed
+  - Summary showing files discovered, added, updated, moved, and missing
   - No errors for properly tagged files
 
 Database: Items are stored in the 'items' table with full tag metadata
@@ -76,6 +84,10 @@ and provenance tracking. Use 'tessitura status' to view scanned items."
     Scan {
         /// Path to the music directory
         path: PathBuf,
+
+        /// Number of files read in parallel (default: one per CPU)
+        #[arg(short = 'j', long, value_name = "N")]
+        jobs: Option<usize>,
     },
     /// Identify recordings via AcoustID/MusicBrainz
     #[command(alias = "id")]
@@ -132,17 +144,35 @@ Rate: Processes one file at a time. Large collections may take time."
   - Discogs: label, format, personnel (optional TESS_DISCOGS_TOKEN)
 
 Each source runs as an independent subtask. If one source fails, the
-others can still succeed and the failed source can be retried independently.
+others can still succeed and the failed source can be retried independently:
+per-item, per-source completion is recorded in the pipeline state store, so
+--pending-only skips sources that already succeeded for an item, and
+--source restricts a run to one or more sources.
 
 Items must be identified first via 'tessitura identify'.
 
 Rate limits are respected per source. All findings are stored as
-provenance-tracked assertions in the database."
+provenance-tracked assertions in the database. A per-source summary of
+enriched, not found, skipped and failed lookups is printed at the end.
+
+Examples:
+  tessitura enrich
+  tessitura enrich --pending-only
+  tessitura enrich --pending-only --source lastfm,discogs"
     )]
     Enrich {
-        /// Only enrich items that haven't been enriched yet
+        /// Only run sources that haven't yet succeeded for an item
         #[arg(long, default_value_t = false)]
         pending_only: bool,
+
+        /// Only query these sources (repeatable or comma-separated)
+        #[arg(
+            long = "source",
+            value_name = "SOURCE",
+            value_delimiter = ',',
+            value_parser = tessitura_etl::enrich::stage::ENRICH_SOURCES
+        )]
+        sources: Vec<String>,
     },
     /// Apply mapping rules and resolve conflicts
     #[command(alias = "harmonise")]
@@ -159,10 +189,21 @@ The harmonization process:
   6. Produces proposed tags for human review
 
 Mapping rules are loaded from the taxonomy.toml file (see config).
+Proposals still awaiting review are replaced on each run; decided ones are
+kept. The report lists proposals per field, the items needing review, the
+rules that fired most, and the assertions no rule matched.
+
+Use --item or --album to re-harmonize part of the library after editing
+the rules.
+
+Examples:
+  tessitura harmonize
+  tessitura harmonize --album \"String Quartets\"
+  tessitura harmonize --item <ITEM_ID>
 
 After harmonization, use 'tessitura review' to approve proposed tags."
     )]
-    Harmonize,
+    Harmonize(commands::harmonize::HarmonizeArgs),
     /// Review proposed metadata in a terminal UI
     #[command(
         long_about = "Opens an interactive terminal UI for reviewing proposed metadata
@@ -172,17 +213,201 @@ its proposed tags with source and confidence information.
 The review TUI supports:
   - Album list view: browse all albums awaiting review
   - Track detail view: inspect proposed tags for each track
-  - Keyboard navigation: j/k or arrow keys, Enter to select, b to go back
-
-This step is intended for human verification before tags are written
-back to audio files. Items must be harmonized first via 'tessitura harmonize'."
+  - Keyboard navigation: j/k or arrow keys to select a proposal, n/p to
+    change track, Enter to select an album, b to go back
+  - Decisions: a to accept, r to reject, 1-9 to pick an alternative,
+    e to enter a custom value, A to accept every unconflicted proposal
+    on the album, u to undo
+
+Decisions are saved immediately. When you quit, items with no pending
+proposals are released from review so 'tessitura process' can export
+them. Items must be harmonized first via 'tessitura harmonize'."
     )]
     Review,
+    /// Write approved metadata back into audio file tags
+    #[command(
+        long_about = "Writes the catalog's metadata for each identified item into its audio
+file: Vorbis comments for FLAC and Ogg, ID3v2 frames for MP3.
+
+Fields written:
+  work, composer, catalog number, key    From the identified Work
+  performers, conductor                  From the identified recording
+  MusicBrainz work/recording/release IDs
+  genre, form, period, instrumentation   From harmonization
+
+Only values approved in review are written. To write the values of a
+trusted source without review, list it in auto_accept in the [export]
+section of the config file (e.g. auto_accept = [\"MusicBrainz\"]).
+
+Fields without a value leave existing tags untouched. The tag key for each
+field can be changed (or the field disabled) in the [export.vorbis] and
+[export.id3v2] sections of the config file.
+
+Use --dry-run to see the changes for each file without writing anything.
+
+Examples:
+  tessitura export --dry-run
+  tessitura export"
+    )]
+    Export {
+        /// Show the tag changes without writing them
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+    },
+    /// Search the catalog by form, period, key, composer, instrument, etc.
+    #[command(
+        long_about = "Finds works matching a combination of facets and lists the files
+you own for each one.
+
+Facets:
+  --form, --period, --genre, --instrument
+      Canonical values proposed by harmonization and not rejected or
+      overridden in review, plus the values entered in review
+  --composer, --year
+      Fields recorded on the Work itself
+  --key, --enharmonic
+      The Work's key, in English, German, French or Italian (\"fis-moll\"
+      finds F-sharp minor); with --enharmonic, spellings of the same key
+      match too (F-sharp major finds G-flat major)
+  --catalog
+      Catalog number in any spelling (\"KV 550\" finds K. 550, and Köchel
+      sixth-edition numbers find the traditional ones), from the Work or
+      its title; a whole opus (\"Op. 18\") finds the works in it
+  --composer, --arranger, --lyricist
+      Artists credited on the work (arrangers include orchestrators,
+      lyricists include librettists)
+  --performer
+      Performers and conductors of any recording of the work
+  --role
+      Instrument or voice a performer is credited with on a recording;
+      with --performer, the same performer must match both
+  --recorded, --live, --studio
+      When a recording was made, and whether live or in a studio;
+      together with --performer and --role, one recording must match all
+
+Text facets match case-insensitively on substrings. Repeating a facet
+gives alternatives (OR). Different facets must all match (AND) unless
+--any is given, in which case matching any one facet is enough.
+
+Examples:
+  tessitura search --form \"string quartet\" --period \"20th century\" --key \"a minor\"
+  tessitura search --composer bartok --year 1900-1945
+  tessitura search --key Des-Dur --enharmonic
+  tessitura search --catalog \"Op. 131\"
+  tessitura search --performer argerich --role piano
+  tessitura search --live --recorded 1960-1969
+  tessitura search --lyricist goethe
+  tessitura search --form sonata --form partita --any"
+    )]
+    Search(Box<commands::search::SearchArgs>),
+    /// Find works or recordings similar to a given one
+    #[command(
+        long_about = "Finds the works most similar to the one named by the query, ranked
+by similarity score (1.00 = identical profile).
+
+Similarity is computed over an embedded vector index built from each
+work's harmonized form, genre, period and instrumentation, its composer
+and key, and the folksonomy tags fetched for its files. With --recordings,
+performers, conductor and recording decade are included as well.
+
+The index is stored next to the database and rebuilt automatically
+whenever the catalog has changed since it was built.
+
+The query is matched against composer and title words, ignoring case and
+accents.
+
+Examples:
+  tessitura similar \"Bartók String Quartet 4\"
+  tessitura similar \"beethoven symphony 5\" --limit 5
+  tessitura similar \"Goldberg Gould\" --recordings"
+    )]
+    Similar(commands::similar::SimilarArgs),
     /// Show pipeline status
+    #[command(
+        long_about = "Shows how many items have completed each pipeline stage: scan,
+fingerprint and identify from the database, and enrich, harmonize, review
+and export from the per-item pipeline state. Items whose enrich, harmonize
+or export stage failed are listed with their last error.
+
+An optional filter restricts the counts to items whose album, artist or
+album artist tag contains the given text (case-insensitive).
+
+Examples:
+  tessitura status
+  tessitura status \"Takács Quartet\""
+    )]
     Status {
-        /// Optional filter (album name, artist, etc.)
+        /// Only count items whose album or artist contains this text
         filter: Option<String>,
     },
+    /// Report duplicate files and recordings
+    #[command(
+        long_about = "Lists files that duplicate each other, found by the hashes recorded
+during 'tessitura scan':
+
+  - Byte-identical files share the hash of the whole file
+  - Audio-identical files share the hash of their audio stream but differ
+    in their tags (for example the same rip tagged twice)
+
+Each group shows its files with their sizes, and the total shows how much
+space removing all but one file of each group would free. Nothing is
+deleted. Files missing from disk are not reported.
+
+With --acoustic, the stored Chromaprint fingerprints are compared instead,
+entirely offline, to find the same recording in files that differ in
+encoding, mastering or track boundaries (for example the same take on a box
+set and on a compilation). Each group proposes the expression most of its
+files are already identified as; files marked → are not linked to it yet.
+--link proposes linking them, for review with 'tessitura review'.
+
+Examples:
+  tessitura dupes
+  tessitura dupes --acoustic
+  tessitura dupes --acoustic --threshold 0.9 --link"
+    )]
+    Dupes(commands::dupes::DupesArgs),
+    /// Merge duplicate works, expressions or artists
+    #[command(
+        long_about = "Merges one or more duplicate works, expressions or artists into the one
+to keep (the survivor), given by ID:
+
+  - Expressions, items, performer credits, release tracks, roles,
+    assertions and proposals of the duplicates move to the survivor
+  - The survivor takes any field it lacks (composer, key, MusicBrainz ID,
+    ...) from the duplicates
+  - The duplicates are deleted, and each merge is recorded on the survivor
+    as a 'merged_from' assertion
+
+Entities with different MusicBrainz IDs are distinct and cannot be merged.
+All merges of one command are applied together or not at all. Use
+'tessitura dedupe' to find candidates.
+
+Examples:
+  tessitura merge work <survivor-id> <duplicate-id>
+  tessitura merge artist <survivor-id> <duplicate-id> <duplicate-id>"
+    )]
+    Merge(commands::merge::MergeArgs),
+    /// Find duplicate works, expressions and artists to merge
+    #[command(
+        long_about = "Suggests works, artists and expressions that look like duplicates, for
+example a work created from a recording title when MusicBrainz had no work
+for it:
+
+  - Works match on composer and catalog number, or on title (a work
+    without a composer matches works of the same title by one composer)
+  - Artists match on name, ignoring case, punctuation and word order
+  - Expressions match on work, title, recording year and performers
+
+Each group shows the row to keep - one with a MusicBrainz ID if any, else
+the oldest - and the 'tessitura merge' command merging the others into it.
+Rows with different MusicBrainz IDs are never suggested. --apply merges
+every group.
+
+Examples:
+  tessitura dedupe --suggest
+  tessitura dedupe --apply"
+    )]
+    Dedupe(commands::dedupe::DedupeArgs),
     /// Manage controlled vocabularies (LCGFT/LCMPT)
     #[command(
         long_about = "Load and manage Library of Congress controlled vocabularies used
@@ -212,7 +437,7 @@ Examples:
   tessitura rules init                    # Create default rules file
   tessitura rules path                    # Show rules file location
   tessitura rules edit                    # Open rules in $EDITOR
-  tessitura rules validate                # Check rules syntax
+  tessitura rules validate                # Check rules syntax and patterns
 
 The rules file defines how raw metadata from enrichment sources
 (MusicBrainz, Wikidata, Last.fm, Discogs) is mapped to controlled
@@ -300,6 +525,7 @@ enum ConfigAction {
 // Removed: now using Config::load() which has default_db_path internally
 
 #[tokio::main]
+#[allow(clippy::too_many_lines)] // One arm per command
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
@@ -327,8 +553,8 @@ async fn main() -> Result<()> {
             let db_path = config.database_path.clone();
             commands::run_process(path, db_path, &config, resume).await?;
         }
-        Commands::Scan { path } => {
-            commands::run_scan(path, config.database_path).await?;
+        Commands::Scan { path, jobs } => {
+            commands::run_scan(path, config.database_path, jobs).await?;
         }
         Commands::Identify => {
             commands::run_identify(config.database_path, config.acoustid_api_key).await?;
@@ -336,18 +562,41 @@ async fn main() -> Result<()> {
         Commands::Fingerprint { force } => {
             commands::run_fingerprint(config.database_path, force).await?;
         }
-        Commands::Enrich { pending_only } => {
+        Commands::Enrich {
+            pending_only,
+            sources,
+        } => {
             let db_path = config.database_path.clone();
-            commands::enrich::run_enrich(&config, db_path, pending_only).await?;
+            commands::enrich::run_enrich(&config, db_path, pending_only, &sources).await?;
         }
-        Commands::Harmonize => {
-            commands::harmonize::run_harmonize(config.database_path, config.rules_path)?;
+        Commands::Harmonize(args) => {
+            commands::harmonize::run_harmonize(config.database_path, config.rules_path, &args)
+                .await?;
         }
         Commands::Review => {
-            commands::review::run_review(config.database_path)?;
+            commands::review::run_review(config.database_path).await?;
+        }
+        Commands::Export { dry_run } => {
+            let db_path = config.database_path.clone();
+            commands::export::run_export(&config, db_path, dry_run).await?;
+        }
+        Commands::Search(args) => {
+            commands::search::run_search(config.database_path, config.rules_path, &args)?;
+        }
+        Commands::Similar(args) => {
+            commands::similar::run_similar(config.database_path, config.rules_path, &args)?;
         }
         Commands::Status { filter } => {
-            commands::show_status(config.database_path, filter)?;
+            commands::show_status(config.database_path, filter).await?;
+        }
+        Commands::Dupes(args) => {
+            commands::dupes::run_dupes(config.database_path, &args)?;
+        }
+        Commands::Merge(args) => {
+            commands::merge::run_merge(config.database_path, &args)?;
+        }
+        Commands::Dedupe(args) => {
+            commands::dedupe::run_dedupe(config.database_path, &args)?;
         }
         Commands::Vocab { action } => match action {
             VocabAction::Load { lcgft, lcmpt } => {
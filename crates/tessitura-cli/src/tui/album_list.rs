@@ -18,13 +18,17 @@ pub fn render(frame: &mut Frame, app: &App) {
 
     render_title(frame, app, chunks[0]);
     render_table(frame, app, chunks[1]);
-    render_help(frame, chunks[2]);
+    render_help(frame, app, chunks[2]);
 }
 
 fn render_title(frame: &mut Frame, app: &App, area: Rect) {
-    let pending_count = app.albums.len();
+    let pending_count: usize = app
+        .albums
+        .iter()
+        .map(super::ReviewAlbum::pending_count)
+        .sum();
     let title = Paragraph::new(format!(
-        "Albums Awaiting Review    {} pending",
+        "Albums Awaiting Review    {} proposals pending",
         pending_count
     ))
     .style(
@@ -42,6 +46,7 @@ fn render_table(frame: &mut Frame, app: &App, area: Rect) {
         Cell::from("Album").style(Style::default().add_modifier(Modifier::BOLD)),
         Cell::from("Artist"),
         Cell::from("Tracks"),
+        Cell::from("Pending"),
         Cell::from("Conflicts"),
     ])
     .height(1);
@@ -70,6 +75,7 @@ fn render_table(frame: &mut Frame, app: &App, area: Rect) {
                 Cell::from(album.title.clone()),
                 Cell::from(album.artist.clone()),
                 Cell::from(format!("{}", album.tracks.len())),
+                Cell::from(format!("{}", album.pending_count())),
                 Cell::from(if album.conflict_count > 0 {
                     format!("{}", album.conflict_count)
                 } else {
@@ -98,6 +104,7 @@ fn render_table(frame: &mut Frame, app: &App, area: Rect) {
             Constraint::Percentage(35),
             Constraint::Percentage(25),
             Constraint::Length(8),
+            Constraint::Length(8),
             Constraint::Length(10),
         ],
     )
@@ -107,9 +114,15 @@ fn render_table(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(table, area);
 }
 
-fn render_help(frame: &mut Frame, area: Rect) {
-    let help = Paragraph::new("  \u{2191}/k Up  \u{2193}/j Down  Enter Select  q Quit")
-        .style(Style::default().fg(Color::DarkGray))
-        .block(Block::default().borders(Borders::ALL));
-    frame.render_widget(help, area);
+fn render_help(frame: &mut Frame, app: &App, area: Rect) {
+    let help = match &app.message {
+        Some(message) => {
+            Paragraph::new(format!("  {message}")).style(Style::default().fg(Color::Green))
+        }
+        None => Paragraph::new(
+            "  \u{2191}/k Up  \u{2193}/j Down  Enter Select  A Accept album  u Undo  q Quit",
+        )
+        .style(Style::default().fg(Color::DarkGray)),
+    };
+    frame.render_widget(help.block(Block::default().borders(Borders::ALL)), area);
 }
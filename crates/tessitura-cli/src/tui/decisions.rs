@@ -0,0 +1,204 @@
+//! Review decisions on proposals, written straight to the database and
+//! undoable for the rest of the session.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use tessitura_core::provenance::{Assertion, ProposalStatus, Source};
+
+use super::App;
+
+/// One proposal's change of status, with what is needed to undo it.
+#[derive(Debug, Clone)]
+pub struct Decision {
+    album: usize,
+    track: usize,
+    proposal: usize,
+    previous: ProposalStatus,
+    previous_decided_at: Option<DateTime<Utc>>,
+    /// Row ID of the user value recorded by an override.
+    added: Option<i64>,
+    /// The user value of the previous override, removed by this decision.
+    removed: Option<Assertion>,
+}
+
+impl App {
+    /// Accept, reject or override the selected proposal of the current track.
+    ///
+    /// `value` is the user's chosen value for an override; it is recorded
+    /// as a [`Source::User`] assertion on the item.
+    pub(super) fn decide_selected(
+        &mut self,
+        status: ProposalStatus,
+        value: Option<String>,
+    ) -> Result<()> {
+        let (album, track, proposal) = (
+            self.current_album(),
+            self.selected_track,
+            self.selected_proposal,
+        );
+        let Some((review_track, tag)) = self
+            .albums
+            .get(album)
+            .and_then(|a| a.tracks.get(track))
+            .and_then(|t| Some((t, t.proposed_tags.get(proposal)?)))
+        else {
+            self.message = Some("No proposal selected".to_string());
+            return Ok(());
+        };
+        let summary = match &value {
+            Some(value) => format!("{} → {value}", tag.field),
+            None => format!(
+                "{} {status}: {}",
+                tag.field,
+                review_track.display_value(tag)
+            ),
+        };
+
+        let decision = self.decide(album, track, proposal, status, value)?;
+        self.undo_stack.push(vec![decision]);
+        self.message = Some(summary);
+        Ok(())
+    }
+
+    /// Accept every pending proposal without conflicts on an album.
+    ///
+    /// Proposals with conflicting alternatives are left for individual
+    /// review. The whole batch is undone as one step.
+    pub(super) fn accept_album(&mut self, album: usize) -> Result<()> {
+        let Some(review_album) = self.albums.get(album) else {
+            return Ok(());
+        };
+        let targets: Vec<(usize, usize)> = review_album
+            .tracks
+            .iter()
+            .enumerate()
+            .flat_map(|(t, track)| {
+                track
+                    .proposed_tags
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, p)| p.status == ProposalStatus::Pending && !p.has_conflicts())
+                    .map(move |(p, _)| (t, p))
+            })
+            .collect();
+        let skipped = review_album.pending_count() - targets.len();
+
+        let mut batch = Vec::with_capacity(targets.len());
+        for (track, proposal) in targets {
+            match self.decide(album, track, proposal, ProposalStatus::Accepted, None) {
+                Ok(decision) => batch.push(decision),
+                Err(e) => {
+                    // Keep what was applied undoable as one step
+                    self.push_batch(batch);
+                    return Err(e);
+                }
+            }
+        }
+
+        self.message = Some(if skipped > 0 {
+            format!(
+                "Accepted {} proposal(s); {skipped} with conflicts left for review",
+                batch.len()
+            )
+        } else {
+            format!("Accepted {} proposal(s)", batch.len())
+        });
+        self.push_batch(batch);
+        Ok(())
+    }
+
+    /// Revert the most recent decision (or bulk accept).
+    pub(super) fn undo(&mut self) -> Result<()> {
+        let Some(batch) = self.undo_stack.pop() else {
+            self.message = Some("Nothing to undo".to_string());
+            return Ok(());
+        };
+        let count = batch.len();
+
+        for decision in batch.into_iter().rev() {
+            let item_id = self.albums[decision.album].tracks[decision.track]
+                .item_id
+                .clone();
+            let proposal = &mut self.albums[decision.album].tracks[decision.track].proposed_tags
+                [decision.proposal];
+
+            if let Some(id) = decision.added {
+                self.db.delete_assertion_by_id(id)?;
+            }
+            let restored = decision
+                .removed
+                .as_ref()
+                .map(|assertion| self.db.insert_assertion(assertion))
+                .transpose()?;
+            self.db
+                .set_proposal_decision(
+                    proposal.id,
+                    decision.previous,
+                    decision.previous_decided_at,
+                    restored,
+                )
+                .with_context(|| format!("Failed to restore proposal for {item_id}"))?;
+
+            proposal.status = decision.previous;
+            proposal.decided_at = decision.previous_decided_at;
+            proposal.override_assertion_id = restored;
+        }
+
+        self.message = Some(format!("Undid {count} decision(s)"));
+        Ok(())
+    }
+
+    /// Apply a decision to one proposal and record how to undo it.
+    fn decide(
+        &mut self,
+        album: usize,
+        track: usize,
+        proposal: usize,
+        status: ProposalStatus,
+        value: Option<String>,
+    ) -> Result<Decision> {
+        let review_track = &mut self.albums[album].tracks[track];
+        let tag = &mut review_track.proposed_tags[proposal];
+
+        // A previous override's value no longer applies; other user values
+        // for the same field are left alone
+        let removed = match tag.override_assertion_id {
+            Some(id) => self.db.delete_assertion_by_id(id)?,
+            None => None,
+        };
+
+        let added = match value {
+            Some(value) => Some(self.db.insert_assertion(&Assertion::new(
+                review_track.item_id.clone(),
+                tag.field.clone(),
+                serde_json::Value::String(value),
+                Source::User,
+            ))?),
+            None => None,
+        };
+
+        let decided_at = Utc::now();
+        self.db
+            .set_proposal_decision(tag.id, status, Some(decided_at), added)?;
+
+        let decision = Decision {
+            album,
+            track,
+            proposal,
+            previous: tag.status,
+            previous_decided_at: tag.decided_at,
+            added,
+            removed,
+        };
+        tag.status = status;
+        tag.decided_at = Some(decided_at);
+        tag.override_assertion_id = added;
+        Ok(decision)
+    }
+
+    fn push_batch(&mut self, batch: Vec<Decision>) {
+        if !batch.is_empty() {
+            self.undo_stack.push(batch);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io;
 use std::path::{Path, PathBuf};
 
@@ -9,11 +9,16 @@ use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
 };
 use ratatui::prelude::*;
+use tessitura_core::model::ExpressionId;
+use tessitura_core::provenance::{Proposal, ProposalStatus};
 use tessitura_core::schema::Database;
 
 pub mod album_list;
+pub mod decisions;
 pub mod track_detail;
 
+use decisions::Decision;
+
 /// Which view the TUI is currently displaying.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum View {
@@ -31,17 +36,51 @@ pub struct ReviewAlbum {
     pub conflict_count: usize,
 }
 
+impl ReviewAlbum {
+    /// Number of proposals on this album still awaiting a decision.
+    pub fn pending_count(&self) -> usize {
+        self.tracks.iter().map(ReviewTrack::pending_count).sum()
+    }
+}
+
 /// A single track within a review album.
 #[derive(Debug)]
 pub struct ReviewTrack {
-    /// Item ID for treadle review approval (used when accept is wired up).
-    #[allow(dead_code)]
+    /// Item ID, used to record decisions and release the review gate.
     pub item_id: String,
     pub title: String,
-    pub proposed_tags: Vec<serde_json::Value>,
-    /// Whether this track has conflicting proposals (used in future conflict display).
-    #[allow(dead_code)]
+    pub proposed_tags: Vec<Proposal>,
+    /// Whether any proposal for this track has conflicting alternatives.
     pub has_conflicts: bool,
+    /// Descriptions of the recordings proposed as links, by expression ID.
+    pub linked_recordings: HashMap<String, String>,
+}
+
+impl ReviewTrack {
+    /// Number of proposals on this track still awaiting a decision.
+    pub fn pending_count(&self) -> usize {
+        self.proposed_tags
+            .iter()
+            .filter(|p| p.status == ProposalStatus::Pending)
+            .count()
+    }
+
+    /// The value of a proposal as shown for review: the linked recording
+    /// for a link proposal, otherwise the proposed value itself.
+    pub fn display_value<'a>(&'a self, proposal: &'a Proposal) -> &'a str {
+        if is_link(proposal) {
+            if let Some(recording) = self.linked_recordings.get(&proposal.value) {
+                return recording;
+            }
+        }
+        &proposal.value
+    }
+}
+
+/// Whether a proposal links the item to another recording, rather than
+/// tagging it. Links can only be accepted or rejected.
+pub fn is_link(proposal: &Proposal) -> bool {
+    proposal.field == "expression"
 }
 
 /// Application state for the review TUI.
@@ -51,94 +90,204 @@ pub struct App {
     pub albums: Vec<ReviewAlbum>,
     pub selected_album: usize,
     pub selected_track: usize,
+    /// Selected proposal within the current track.
+    pub selected_proposal: usize,
     pub album_list_offset: usize, // First visible album in the list
+    /// Text typed for a custom value, while entering one.
+    pub input: Option<String>,
+    /// Feedback from the last action, shown in the help bar.
+    pub message: Option<String>,
     pub should_quit: bool,
+    db: Database,
+    undo_stack: Vec<Vec<Decision>>,
 }
 
 impl App {
     /// Create a new `App` by loading review data from the database.
     pub fn new(db_path: &Path) -> Result<Self> {
-        let albums = load_review_albums(db_path)?;
+        let db = Database::open(db_path)?;
+        let albums = load_review_albums(&db)?;
         Ok(Self {
             view: View::AlbumList,
             albums,
             selected_album: 0,
             selected_track: 0,
+            selected_proposal: 0,
             album_list_offset: 0,
+            input: None,
+            message: None,
             should_quit: false,
+            db,
+            undo_stack: Vec::new(),
         })
     }
 
+    /// Item IDs whose proposals have all been decided.
+    pub fn reviewed_item_ids(&self) -> Vec<String> {
+        self.albums
+            .iter()
+            .flat_map(|a| &a.tracks)
+            .filter(|t| !t.proposed_tags.is_empty() && t.pending_count() == 0)
+            .map(|t| t.item_id.clone())
+            .collect()
+    }
+
+    /// The album shown in the track detail view, or the selected album.
+    fn current_album(&self) -> usize {
+        match self.view {
+            View::TrackDetail(album_idx) => album_idx,
+            View::AlbumList => self.selected_album,
+        }
+    }
+
     fn handle_key(&mut self, key: KeyCode) {
-        match &self.view {
-            View::AlbumList => self.handle_album_list_key(key),
-            View::TrackDetail(_) => self.handle_track_detail_key(key),
+        self.message = None;
+        let result = if self.input.is_some() {
+            self.handle_input_key(key)
+        } else {
+            match &self.view {
+                View::AlbumList => self.handle_album_list_key(key),
+                View::TrackDetail(_) => self.handle_track_detail_key(key),
+            }
+        };
+        if let Err(e) = result {
+            self.message = Some(format!("Error: {e:#}"));
         }
     }
 
-    fn handle_album_list_key(&mut self, key: KeyCode) {
+    fn handle_album_list_key(&mut self, key: KeyCode) -> Result<()> {
         // Assume reasonable viewport height (will be refined in render)
         const VIEWPORT_HEIGHT: usize = 20;
 
         match key {
             KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
-            KeyCode::Char('j') | KeyCode::Down => {
-                if self.selected_album + 1 < self.albums.len() {
-                    self.selected_album += 1;
-                    // Scroll down if selection goes below visible area
-                    if self.selected_album >= self.album_list_offset + VIEWPORT_HEIGHT {
-                        self.album_list_offset = self.selected_album - VIEWPORT_HEIGHT + 1;
-                    }
+            KeyCode::Char('j') | KeyCode::Down if self.selected_album + 1 < self.albums.len() => {
+                self.selected_album += 1;
+                // Scroll down if selection goes below visible area
+                if self.selected_album >= self.album_list_offset + VIEWPORT_HEIGHT {
+                    self.album_list_offset = self.selected_album - VIEWPORT_HEIGHT + 1;
                 }
             }
-            KeyCode::Char('k') | KeyCode::Up => {
-                if self.selected_album > 0 {
-                    self.selected_album -= 1;
-                    // Scroll up if selection goes above visible area
-                    if self.selected_album < self.album_list_offset {
-                        self.album_list_offset = self.selected_album;
-                    }
+            KeyCode::Char('k') | KeyCode::Up if self.selected_album > 0 => {
+                self.selected_album -= 1;
+                // Scroll up if selection goes above visible area
+                if self.selected_album < self.album_list_offset {
+                    self.album_list_offset = self.selected_album;
                 }
             }
-            KeyCode::Enter => {
-                if !self.albums.is_empty() {
-                    self.selected_track = 0;
-                    self.view = View::TrackDetail(self.selected_album);
-                }
+            KeyCode::Enter if !self.albums.is_empty() => {
+                self.selected_track = 0;
+                self.selected_proposal = 0;
+                self.view = View::TrackDetail(self.selected_album);
             }
+            KeyCode::Char('A') => self.accept_album(self.selected_album)?,
+            KeyCode::Char('u') => self.undo()?,
             _ => {}
         }
+        Ok(())
     }
 
-    fn handle_track_detail_key(&mut self, key: KeyCode) {
+    fn handle_track_detail_key(&mut self, key: KeyCode) -> Result<()> {
+        let album_idx = self.current_album();
+        let (track_count, proposal_count) = self.albums.get(album_idx).map_or((0, 0), |album| {
+            (
+                album.tracks.len(),
+                album
+                    .tracks
+                    .get(self.selected_track)
+                    .map_or(0, |t| t.proposed_tags.len()),
+            )
+        });
+
         match key {
             KeyCode::Char('q') => self.should_quit = true,
             KeyCode::Esc | KeyCode::Char('b') => {
                 self.view = View::AlbumList;
             }
-            KeyCode::Char('n' | 'j') | KeyCode::Down => {
-                if let View::TrackDetail(album_idx) = self.view {
-                    if album_idx < self.albums.len() {
-                        let track_count = self.albums[album_idx].tracks.len();
-                        if self.selected_track + 1 < track_count {
-                            self.selected_track += 1;
-                        }
-                    }
+            KeyCode::Char('n') | KeyCode::Right if self.selected_track + 1 < track_count => {
+                self.selected_track += 1;
+                self.selected_proposal = 0;
+            }
+            KeyCode::Char('p') | KeyCode::Left if self.selected_track > 0 => {
+                self.selected_track -= 1;
+                self.selected_proposal = 0;
+            }
+            KeyCode::Char('j') | KeyCode::Down if self.selected_proposal + 1 < proposal_count => {
+                self.selected_proposal += 1;
+            }
+            KeyCode::Char('k') | KeyCode::Up if self.selected_proposal > 0 => {
+                self.selected_proposal -= 1;
+            }
+            KeyCode::Char('a') => self.decide_selected(ProposalStatus::Accepted, None)?,
+            KeyCode::Char('r') => self.decide_selected(ProposalStatus::Rejected, None)?,
+            KeyCode::Char(c @ '1'..='9') => self.pick_alternative(c)?,
+            KeyCode::Char('e') if proposal_count > 0 => {
+                if self.selected_proposal_is_link() {
+                    self.message = Some("Links can only be accepted or rejected".to_string());
+                } else {
+                    self.input = Some(String::new());
                 }
             }
-            KeyCode::Char('p' | 'k') | KeyCode::Up => {
-                if self.selected_track > 0 {
-                    self.selected_track -= 1;
+            KeyCode::Char('A') => self.accept_album(album_idx)?,
+            KeyCode::Char('u') => self.undo()?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_input_key(&mut self, key: KeyCode) -> Result<()> {
+        let Some(input) = self.input.as_mut() else {
+            return Ok(());
+        };
+        match key {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Esc => self.input = None,
+            KeyCode::Enter => {
+                let value = self.input.take().unwrap_or_default().trim().to_string();
+                if !value.is_empty() {
+                    self.decide_selected(ProposalStatus::Overridden, Some(value))?;
                 }
             }
             _ => {}
         }
+        Ok(())
+    }
+
+    fn selected_proposal_is_link(&self) -> bool {
+        self.albums
+            .get(self.current_album())
+            .and_then(|a| a.tracks.get(self.selected_track))
+            .and_then(|t| t.proposed_tags.get(self.selected_proposal))
+            .is_some_and(is_link)
+    }
+
+    /// Override the selected proposal with its numbered alternative.
+    fn pick_alternative(&mut self, digit: char) -> Result<()> {
+        let alternative = digit
+            .to_digit(10)
+            .and_then(|d| (d as usize).checked_sub(1))
+            .and_then(|index| {
+                self.albums
+                    .get(self.current_album())
+                    .and_then(|a| a.tracks.get(self.selected_track))
+                    .and_then(|t| t.proposed_tags.get(self.selected_proposal))
+                    .and_then(|p| p.alternatives.get(index))
+            })
+            .map(|alt| alt.value.clone());
+
+        let Some(value) = alternative else {
+            self.message = Some(format!("No alternative {digit}"));
+            return Ok(());
+        };
+        self.decide_selected(ProposalStatus::Overridden, Some(value))
     }
 }
 
 /// Load identified items from the database and group them into albums for review.
-fn load_review_albums(db_path: &Path) -> Result<Vec<ReviewAlbum>> {
-    let db = Database::open(db_path)?;
+fn load_review_albums(db: &Database) -> Result<Vec<ReviewAlbum>> {
     let items = db.list_identified_items()?;
 
     if items.is_empty() {
@@ -155,48 +304,95 @@ fn load_review_albums(db_path: &Path) -> Result<Vec<ReviewAlbum>> {
         albums_map.entry(album).or_default().push(item);
     }
 
-    let albums = albums_map
-        .into_iter()
-        .map(|(album_name, items)| {
-            let artist = items
-                .first()
-                .and_then(|i| i.tag_artist.clone())
-                .unwrap_or_else(|| "Unknown Artist".to_string());
-            let tracks = items
-                .iter()
-                .map(|item| ReviewTrack {
-                    item_id: item.id.to_string(),
-                    title: item
-                        .tag_title
-                        .clone()
-                        .unwrap_or_else(|| "Unknown Track".to_string()),
-                    proposed_tags: Vec::new(), // Populated from harmonization results
-                    has_conflicts: false,
-                })
-                .collect();
-            ReviewAlbum {
-                title: album_name,
-                artist,
-                conflict_count: 0,
-                tracks,
+    let mut albums = Vec::with_capacity(albums_map.len());
+    for (album_name, items) in albums_map {
+        let artist = items
+            .first()
+            .and_then(|i| i.tag_artist.clone())
+            .unwrap_or_else(|| "Unknown Artist".to_string());
+
+        let mut tracks = Vec::with_capacity(items.len());
+        for item in items {
+            let item_id = item.id.to_string();
+            // Populated from harmonization results
+            let proposed_tags = db.get_proposals_for_entity(&item_id)?;
+            let has_conflicts = proposed_tags.iter().any(Proposal::has_conflicts);
+            let mut linked_recordings = HashMap::new();
+            for proposal in proposed_tags.iter().filter(|p| is_link(p)) {
+                if let Some(recording) = describe_recording(db, &proposal.value)? {
+                    linked_recordings.insert(proposal.value.clone(), recording);
+                }
             }
-        })
-        .collect();
+            tracks.push(ReviewTrack {
+                item_id,
+                title: item
+                    .tag_title
+                    .clone()
+                    .unwrap_or_else(|| "Unknown Track".to_string()),
+                proposed_tags,
+                has_conflicts,
+                linked_recordings,
+            });
+        }
+
+        albums.push(ReviewAlbum {
+            title: album_name,
+            artist,
+            conflict_count: tracks.iter().filter(|t| t.has_conflicts).count(),
+            tracks,
+        });
+    }
 
     Ok(albums)
 }
 
+/// Describe the recording with expression ID `id` by its title, performers
+/// and recording date, or `None` if there is no such recording.
+fn describe_recording(db: &Database, id: &str) -> Result<Option<String>> {
+    let Ok(id) = id.parse::<ExpressionId>() else {
+        return Ok(None);
+    };
+    let Some(expression) = db.get_expression_by_id(&id)? else {
+        return Ok(None);
+    };
+    let title = match expression.title {
+        Some(title) => title,
+        None => db
+            .get_work_by_id(&expression.work_id)?
+            .map_or_else(|| "Untitled recording".to_string(), |w| w.title),
+    };
+    let mut performers = Vec::with_capacity(expression.performers.len());
+    for performer in &expression.performers {
+        if let Some(artist) = db.get_artist_by_id(&performer.artist_id)? {
+            performers.push(performer.credited_as.clone().unwrap_or(artist.name));
+        }
+    }
+
+    let mut description = title;
+    if !performers.is_empty() {
+        description = format!("{description} \u{2014} {}", performers.join(", "));
+    }
+    let date = expression
+        .recorded_from
+        .or_else(|| expression.recorded_year.map(|y| y.to_string()));
+    if let Some(date) = date {
+        description = format!("{description} ({date})");
+    }
+    Ok(Some(description))
+}
+
 /// Run the review TUI.
 ///
 /// Sets up the terminal, runs the main event loop, and restores the terminal
-/// on exit (including on error).
-pub fn run_tui(db_path: PathBuf) -> Result<()> {
-    let app = App::new(&db_path)?;
+/// on exit (including on error). Returns the IDs of items whose proposals
+/// have all been decided.
+pub fn run_tui(db_path: PathBuf) -> Result<Vec<String>> {
+    let mut app = App::new(&db_path)?;
 
     if app.albums.is_empty() {
         println!("No items awaiting review.");
         println!("Run 'tessitura harmonize' first to generate proposed tags.");
-        return Ok(());
+        return Ok(Vec::new());
     }
 
     // Setup terminal
@@ -207,24 +403,24 @@ pub fn run_tui(db_path: PathBuf) -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     // Run the event loop, capturing any error so we can restore the terminal
-    let result = run_event_loop(&mut terminal, app);
+    let result = run_event_loop(&mut terminal, &mut app);
 
     // Restore terminal regardless of success or failure
     disable_raw_mode()?;
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
     terminal.show_cursor()?;
 
-    result
+    result.map(|()| app.reviewed_item_ids())
 }
 
 fn run_event_loop(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
-    mut app: App,
+    app: &mut App,
 ) -> Result<()> {
     loop {
         terminal.draw(|frame| match &app.view {
-            View::AlbumList => album_list::render(frame, &app),
-            View::TrackDetail(album_idx) => track_detail::render(frame, &app, *album_idx),
+            View::AlbumList => album_list::render(frame, app),
+            View::TrackDetail(album_idx) => track_detail::render(frame, app, *album_idx),
         })?;
 
         if let Event::Key(key) = event::read()? {
@@ -1,5 +1,6 @@
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Paragraph};
+use tessitura_core::provenance::ProposalStatus;
 
 use super::App;
 
@@ -26,7 +27,7 @@ pub fn render(frame: &mut Frame, app: &App, album_idx: usize) {
     render_album_header(frame, album, chunks[0]);
     render_track_header(frame, app, album, chunks[1]);
     render_proposed_tags(frame, app, album, chunks[2]);
-    render_help(frame, chunks[3]);
+    render_help(frame, app, chunks[3]);
 }
 
 fn render_album_header(frame: &mut Frame, album: &super::ReviewAlbum, area: Rect) {
@@ -62,26 +63,40 @@ fn render_proposed_tags(frame: &mut Frame, app: &App, album: &super::ReviewAlbum
             track
                 .proposed_tags
                 .iter()
-                .map(|tag| {
-                    let field = tag
-                        .get("field")
-                        .and_then(|f| f.as_str())
-                        .unwrap_or("unknown");
-                    let value = tag.get("value").and_then(|v| v.as_str()).unwrap_or("?");
-                    let rule = tag.get("rule_name").and_then(|r| r.as_str()).unwrap_or("");
-                    let confidence = tag
-                        .get("confidence")
-                        .and_then(|c| c.as_f64())
-                        .unwrap_or(0.0);
-
-                    Line::from(vec![
-                        Span::styled(format!("  {:<20}", field), Style::default().fg(Color::Cyan)),
-                        Span::raw(format!("{:<30}", value)),
+                .enumerate()
+                .flat_map(|(i, tag)| {
+                    let line_style = if i == app.selected_proposal {
+                        Style::default().bg(Color::DarkGray)
+                    } else {
+                        Style::default()
+                    };
+                    let mut lines = vec![Line::from(vec![
+                        Span::styled(
+                            format!("  {:<20}", tag.field),
+                            Style::default().fg(Color::Cyan),
+                        ),
+                        Span::raw(format!("{:<30}", track.display_value(tag))),
                         Span::styled(
-                            format!("[{} {:.0}%]", rule, confidence * 100.0),
+                            format!("[{} {:.0}%] ", tag.rule_name, tag.confidence * 100.0),
                             Style::default().fg(Color::DarkGray),
                         ),
+                        Span::styled(tag.status.to_string(), status_style(tag.status)),
                     ])
+                    .style(line_style)];
+                    lines.extend(tag.alternatives.iter().enumerate().map(|(n, alt)| {
+                        Line::from(Span::styled(
+                            format!(
+                                "  {:<20}{}. {} [{:?} {:.0}%]",
+                                "",
+                                n + 1,
+                                alt.value,
+                                alt.source,
+                                alt.confidence * 100.0
+                            ),
+                            Style::default().fg(Color::Yellow),
+                        ))
+                    }));
+                    lines
                 })
                 .collect()
         }
@@ -97,9 +112,29 @@ fn render_proposed_tags(frame: &mut Frame, app: &App, album: &super::ReviewAlbum
     frame.render_widget(tags, area);
 }
 
-fn render_help(frame: &mut Frame, area: Rect) {
-    let help = Paragraph::new("  \u{2191}/k Prev  \u{2193}/j Next  b Back  q Quit")
+fn status_style(status: ProposalStatus) -> Style {
+    match status {
+        ProposalStatus::Pending => Style::default().fg(Color::Yellow),
+        ProposalStatus::Accepted => Style::default().fg(Color::Green),
+        ProposalStatus::Rejected => Style::default().fg(Color::Red),
+        ProposalStatus::Overridden => Style::default().fg(Color::Magenta),
+    }
+}
+
+fn render_help(frame: &mut Frame, app: &App, area: Rect) {
+    let help = if let Some(input) = &app.input {
+        Paragraph::new(format!(
+            "  Custom value: {input}\u{2588}   Enter Save  Esc Cancel"
+        ))
+        .style(Style::default().fg(Color::Yellow))
+    } else if let Some(message) = &app.message {
+        Paragraph::new(format!("  {message}")).style(Style::default().fg(Color::Green))
+    } else {
+        Paragraph::new(
+            "  \u{2191}\u{2193}/jk Select  \u{2190}\u{2192}/pn Track  a Accept  r Reject  \
+             1-9 Alternative  e Edit  A Accept album  u Undo  b Back  q Quit",
+        )
         .style(Style::default().fg(Color::DarkGray))
-        .block(Block::default().borders(Borders::ALL));
-    frame.render_widget(help, area);
+    };
+    frame.render_widget(help.block(Block::default().borders(Borders::ALL)), area);
 }
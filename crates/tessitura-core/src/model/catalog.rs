@@ -0,0 +1,557 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use regex::{Captures, Regex};
+
+/// A catalog works are numbered in: opus numbers, or a composer's thematic
+/// catalog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CatalogSystem {
+    Opus,
+    /// Werke ohne Opuszahl (Beethoven).
+    WoO,
+    /// Bach-Werke-Verzeichnis.
+    Bwv,
+    /// Buxtehude-Werke-Verzeichnis.
+    BuxWv,
+    /// Händel-Werke-Verzeichnis.
+    Hwv,
+    /// Köchel (Mozart).
+    Kochel,
+    /// Hoboken (Haydn), numbered within groups: "Hob. XVI:52".
+    Hoboken,
+    /// Deutsch (Schubert).
+    Deutsch,
+    /// Ryom (Vivaldi).
+    Ryom,
+    /// Szőllősy (Bartók).
+    Szollosy,
+    /// Somfai (Bartók).
+    Somfai,
+    /// Searle (Liszt).
+    Searle,
+    /// Zimmerman (Purcell).
+    Zimmerman,
+    /// Wagner-Werk-Verzeichnis.
+    Wwv,
+    /// Trenner (Richard Strauss).
+    Trenner,
+}
+
+impl CatalogSystem {
+    /// The prefix in the canonical form ("Op.", "BWV", "K.").
+    #[must_use]
+    pub const fn prefix(self) -> &'static str {
+        match self {
+            Self::Opus => "Op.",
+            Self::WoO => "WoO",
+            Self::Bwv => "BWV",
+            Self::BuxWv => "BuxWV",
+            Self::Hwv => "HWV",
+            Self::Kochel => "K.",
+            Self::Hoboken => "Hob.",
+            Self::Deutsch => "D.",
+            Self::Ryom => "RV",
+            Self::Szollosy => "Sz.",
+            Self::Somfai => "BB",
+            Self::Searle => "S.",
+            Self::Zimmerman => "Z.",
+            Self::Wwv => "WWV",
+            Self::Trenner => "TrV",
+        }
+    }
+
+    /// The system a prefix names, lower-cased and without dots or spaces.
+    fn from_prefix(prefix: &str) -> Option<Self> {
+        let system = match prefix {
+            "op" | "opus" => Self::Opus,
+            "woo" => Self::WoO,
+            "bwv" => Self::Bwv,
+            "buxwv" => Self::BuxWv,
+            "hwv" => Self::Hwv,
+            "k" | "kv" => Self::Kochel,
+            "hob" => Self::Hoboken,
+            "d" => Self::Deutsch,
+            "rv" => Self::Ryom,
+            "sz" => Self::Szollosy,
+            "bb" => Self::Somfai,
+            "s" => Self::Searle,
+            "z" => Self::Zimmerman,
+            "wwv" => Self::Wwv,
+            "trv" => Self::Trenner,
+            _ => return None,
+        };
+        Some(system)
+    }
+}
+
+/// Köchel numbers renumbered in the sixth edition, as (first edition,
+/// sixth edition). The first-edition number is the canonical one.
+///
+/// The table is partial: it covers the symphonies, concertos, sonatas and
+/// chamber music most often catalogued under either number. A pair missing
+/// here ("K. 292" and "K. 196c") is treated as two different works.
+const KOCHEL_REVISIONS: &[(u32, &str)] = &[
+    (183, "173db"),
+    (191, "186e"),
+    (200, "189k"),
+    (201, "186a"),
+    (250, "248b"),
+    (264, "315d"),
+    (265, "300e"),
+    (269, "261a"),
+    (279, "189d"),
+    (280, "189e"),
+    (281, "189f"),
+    (282, "189g"),
+    (283, "189h"),
+    (284, "205b"),
+    (297, "300a"),
+    (299, "297c"),
+    (301, "293a"),
+    (302, "293b"),
+    (303, "293c"),
+    (304, "300c"),
+    (305, "293d"),
+    (306, "300l"),
+    (309, "284b"),
+    (310, "300d"),
+    (311, "284c"),
+    (313, "285c"),
+    (314, "285d"),
+    (315, "285e"),
+    (330, "300h"),
+    (331, "300i"),
+    (332, "300k"),
+    (333, "315c"),
+    (341, "368a"),
+    (352, "374c"),
+    (353, "300f"),
+    (354, "299a"),
+    (355, "576b"),
+    (359, "374a"),
+    (360, "374b"),
+    (361, "370a"),
+    (364, "320d"),
+    (370, "368b"),
+    (376, "374d"),
+    (377, "374e"),
+    (378, "317d"),
+    (379, "373a"),
+    (380, "374f"),
+    (388, "384a"),
+    (394, "383a"),
+    (395, "300g"),
+    (396, "385f"),
+    (397, "385g"),
+    (398, "416e"),
+    (412, "386b"),
+];
+
+/// A catalog number in any common spelling: the system prefix, a Hoboken
+/// group, the number with a letter suffix, a second number after a slash
+/// (a Köchel revision, or the number within an opus), and "No." with the
+/// number within an opus.
+#[allow(clippy::expect_used)] // A constant pattern, compiled by the tests
+static CATALOG_NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?ix)
+        \b(?P<system>opus|op|woo|buxwv|bwv|hwv|k\.?\s?v|k|hob|d|rv|sz|bb|s|z|wwv|trv)\.?\s*
+        (?:(?P<group>[ivxlc]+[a-z]?)\s*[:/.]\s*)?
+        (?P<number>\d+)(?P<suffix>[a-z]{0,3})
+        (?:\s*/\s*(?P<alt>\d+[a-z]{0,3}))?
+        (?:\s*,?\s*(?:no|nr|n°|\#)\.?\s*(?P<sub>\d+))?
+        \b",
+    )
+    .expect("catalog number pattern is valid")
+});
+
+/// A work's number in a catalog, parsed from the ways catalogs, tags and
+/// titles spell it ("K. 550", "KV 550", "K550"; "Op. 18, No. 1",
+/// "op.18/1"; "Hob.XVI/52").
+///
+/// Numbers order naturally: by catalog, then numerically ("Op. 9" before
+/// "Op. 10"), then by suffix and number within the opus.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CatalogNumber {
+    pub system: CatalogSystem,
+
+    /// Group within the catalog, for Hoboken numbers (e.g., "XVI").
+    pub group: Option<String>,
+
+    pub number: u32,
+
+    /// Letter suffix (e.g., "a" in "BWV 1006a", "i" in "K. 300i").
+    pub suffix: Option<String>,
+
+    /// Number within an opus (e.g., 1 in "Op. 18 No. 1").
+    pub sub_number: Option<u32>,
+}
+
+impl CatalogNumber {
+    #[must_use]
+    pub const fn new(system: CatalogSystem, number: u32) -> Self {
+        Self {
+            system,
+            group: None,
+            number,
+            suffix: None,
+            sub_number: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_sub_number(mut self, sub_number: u32) -> Self {
+        self.sub_number = Some(sub_number);
+        self
+    }
+
+    /// The first catalog number in a text, such as a work title.
+    ///
+    /// Single-letter prefixes ("D", "K", "S", "Z") also start ordinary
+    /// words and key names, so in free text they need a dot ("D. 960") or
+    /// to follow a comma ("Sonata, K 331"), and "D" after "in" is a key.
+    #[must_use]
+    pub fn find(text: &str) -> Option<Self> {
+        CATALOG_NUMBER
+            .captures_iter(text)
+            .filter(|caps| Self::is_prefix_in_text(text, caps))
+            .find_map(|caps| Self::from_captures(&caps))
+    }
+
+    /// Whether a match found in free text has a prefix that is not just a
+    /// letter of the text.
+    fn is_prefix_in_text(text: &str, caps: &Captures<'_>) -> bool {
+        let Some(system) = caps.name("system") else {
+            return false;
+        };
+        if system.as_str().len() > 1 {
+            return true;
+        }
+        let before = text[..system.start()].trim_end();
+        if system.as_str().eq_ignore_ascii_case("d")
+            && before
+                .rsplit(char::is_whitespace)
+                .next()
+                .is_some_and(|word| word.eq_ignore_ascii_case("in"))
+        {
+            return false;
+        }
+        text[system.end()..].starts_with('.') || before.ends_with(',')
+    }
+
+    /// The same number in the catalog's canonical edition: Köchel
+    /// sixth-edition numbers ("K. 300i") become the first-edition ones
+    /// ("K. 331").
+    #[must_use]
+    pub fn normalized(&self) -> Self {
+        let mut normalized = self.clone();
+        if self.system == CatalogSystem::Kochel {
+            let number = format!("{}{}", self.number, self.suffix.as_deref().unwrap_or(""));
+            if let Some(&(first, _)) = KOCHEL_REVISIONS.iter().find(|(_, k6)| *k6 == number) {
+                normalized.number = first;
+                normalized.suffix = None;
+            }
+        }
+        normalized
+    }
+
+    /// Whether two numbers name the same work, in any edition of the
+    /// catalog.
+    #[must_use]
+    pub fn is_equivalent(&self, other: &Self) -> bool {
+        self.normalized() == other.normalized()
+    }
+
+    /// Whether `other` is this number, or, if this is a whole opus, one of
+    /// the works in it ("Op. 18" includes "Op. 18 No. 1").
+    #[must_use]
+    pub fn includes(&self, other: &Self) -> bool {
+        let (this, mut other) = (self.normalized(), other.normalized());
+        if this.sub_number.is_none() {
+            other.sub_number = None;
+        }
+        this == other
+    }
+
+    /// The canonical form of a catalog number, or `None` if it is not one.
+    #[must_use]
+    pub fn canonical(name: &str) -> Option<String> {
+        name.parse::<Self>()
+            .ok()
+            .map(|number| number.normalized().to_string())
+    }
+
+    fn from_captures(caps: &Captures<'_>) -> Option<Self> {
+        let prefix: String = caps["system"]
+            .chars()
+            .filter(char::is_ascii_alphabetic)
+            .collect();
+        let system = CatalogSystem::from_prefix(&prefix.to_lowercase())?;
+
+        // Only Hoboken numbers have (and need) a group
+        let group = match (system, caps.name("group")) {
+            (CatalogSystem::Hoboken, Some(group)) => {
+                let group = group.as_str();
+                let split = group.trim_end_matches(|c: char| !"ivxlcIVXLC".contains(c));
+                let (roman, letter) = group.split_at(split.len());
+                Some(format!("{}{}", roman.to_uppercase(), letter.to_lowercase()))
+            }
+            (CatalogSystem::Hoboken, None) | (_, Some(_)) => return None,
+            (_, None) => None,
+        };
+
+        // Two-letter suffixes only occur in Köchel's sixth edition ("173dB")
+        let suffix = caps["suffix"].to_lowercase();
+        if suffix.len() > 1 && system != CatalogSystem::Kochel {
+            return None;
+        }
+
+        // After a slash: the Köchel revision, or the number within an opus.
+        // Other catalogs number their works without one ("BWV 1006/1" is
+        // not a catalog number).
+        let mut sub_number = caps.name("sub").and_then(|m| m.as_str().parse().ok());
+        if let Some(alt) = caps.name("alt") {
+            match system {
+                CatalogSystem::Kochel => {}
+                CatalogSystem::Opus | CatalogSystem::WoO if sub_number.is_none() => {
+                    sub_number = Some(alt.as_str().parse().ok()?);
+                }
+                _ => return None,
+            }
+        }
+
+        Some(Self {
+            system,
+            group,
+            number: caps["number"].parse().ok()?,
+            suffix: (!suffix.is_empty()).then_some(suffix),
+            sub_number,
+        })
+    }
+
+    /// The key numbers sort by, with Hoboken groups by their numeral.
+    fn sort_key(&self) -> impl Ord + '_ {
+        (
+            self.system,
+            self.group.as_deref().map(roman_value),
+            self.group.as_deref(),
+            self.number,
+            self.suffix.as_deref(),
+            self.sub_number,
+        )
+    }
+}
+
+impl fmt::Display for CatalogNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.system.prefix())?;
+        if let Some(group) = &self.group {
+            write!(f, "{group}:")?;
+        }
+        write!(f, "{}", self.number)?;
+        if let Some(suffix) = &self.suffix {
+            f.write_str(suffix)?;
+        }
+        if let Some(sub_number) = self.sub_number {
+            write!(f, " No. {sub_number}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for CatalogNumber {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let s = s.trim();
+        CATALOG_NUMBER
+            .captures(s)
+            .filter(|caps| caps[0].len() == s.len())
+            .and_then(|caps| Self::from_captures(&caps))
+            .ok_or_else(|| crate::Error::InvalidData(format!("Unrecognized catalog number: {s}")))
+    }
+}
+
+impl PartialOrd for CatalogNumber {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CatalogNumber {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+/// The value of a Roman numeral, ignoring any trailing letters.
+fn roman_value(numeral: &str) -> i32 {
+    let digit = |c: char| match c.to_ascii_uppercase() {
+        'I' => 1,
+        'V' => 5,
+        'X' => 10,
+        'L' => 50,
+        'C' => 100,
+        _ => 0,
+    };
+    let digits: Vec<i32> = numeral.chars().map(digit).take_while(|&d| d > 0).collect();
+    let mut value = 0;
+    for (i, &d) in digits.iter().enumerate() {
+        // A smaller numeral before a larger one is subtracted ("IX")
+        if digits.get(i + 1).is_some_and(|&next| next > d) {
+            value -= d;
+        } else {
+            value += d;
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(s: &str) -> String {
+        CatalogNumber::canonical(s).unwrap()
+    }
+
+    #[test]
+    fn test_pattern_compiles() {
+        LazyLock::force(&CATALOG_NUMBER);
+    }
+
+    #[test]
+    fn test_parse_spellings() {
+        for s in ["K. 550", "KV 550", "K550", "k.v. 550", "K 550"] {
+            assert_eq!(canonical(s), "K. 550", "{s}");
+        }
+        for s in [
+            "Op. 18, No. 1",
+            "op.18 no.1",
+            "Op 18 Nr. 1",
+            "Opus 18/1",
+            "op. 18 #1",
+        ] {
+            assert_eq!(canonical(s), "Op. 18 No. 1", "{s}");
+        }
+        assert_eq!(canonical("Hob.XVI/52"), "Hob. XVI:52");
+        assert_eq!(canonical("hob. xxviib:1"), "Hob. XXVIIb:1");
+        assert_eq!(canonical("BWV 1006a"), "BWV 1006a");
+        assert_eq!(canonical("D.960"), "D. 960");
+        assert_eq!(canonical("RV 269"), "RV 269");
+        assert_eq!(canonical("Sz.91"), "Sz. 91");
+        assert_eq!(canonical("WoO 59"), "WoO 59");
+    }
+
+    #[test]
+    fn test_parse_rejects_non_catalog_numbers() {
+        for s in [
+            "",
+            "Op. posth.",
+            "Hob. 52",
+            "BWV 1006ab",
+            "Symphony No. 5",
+            "K. 550 and more",
+            "BWV 1006/1",
+            "D. 960/2",
+        ] {
+            assert!(s.parse::<CatalogNumber>().is_err(), "{s:?} parsed");
+        }
+    }
+
+    #[test]
+    fn test_find_in_title() {
+        let find = |title: &str| CatalogNumber::find(title).map(|n| n.to_string());
+        assert_eq!(
+            find("String Quartet No. 14 in C-sharp minor, Op. 131: I. Adagio").as_deref(),
+            Some("Op. 131")
+        );
+        assert_eq!(
+            find("Piano Sonata No. 11 in A major, K. 331/300i").as_deref(),
+            Some("K. 331")
+        );
+        assert_eq!(find("Symphony No. 5 in D major").as_deref(), None);
+        assert_eq!(find("Sonata in A, K 331").as_deref(), Some("K. 331"));
+        assert_eq!(
+            find("Impromptu, D. 899 No. 3").as_deref(),
+            Some("D. 899 No. 3")
+        );
+        assert_eq!(find("Partita No. 3, BWV 1006/1").as_deref(), None);
+    }
+
+    #[test]
+    fn test_find_ignores_letters_of_the_title() {
+        for title in [
+            "Mass in D 1. Kyrie",
+            "Symphony No. 5 in D 2 Andante",
+            "Vol. 2: S 4",
+            "Mass in D. 1. Kyrie",
+            "Z 3 Remix",
+        ] {
+            assert_eq!(CatalogNumber::find(title), None, "{title:?}");
+        }
+    }
+
+    #[test]
+    fn test_kochel_revisions() {
+        let k1: CatalogNumber = "K. 331".parse().unwrap();
+        let k6: CatalogNumber = "KV 300i".parse().unwrap();
+        assert_ne!(k1, k6);
+        assert!(k1.is_equivalent(&k6));
+        assert_eq!(canonical("K. 173dB"), "K. 183");
+        assert!(!k1.is_equivalent(&"K. 332".parse().unwrap()));
+
+        let equivalent = |a: &str, b: &str| {
+            a.parse::<CatalogNumber>()
+                .unwrap()
+                .is_equivalent(&b.parse().unwrap())
+        };
+        assert!(equivalent("K. 283", "K. 189h"));
+        assert!(equivalent("K. 395", "KV 300g"));
+        // Not in the (partial) revision table
+        assert!(!equivalent("K. 292", "K. 196c"));
+    }
+
+    #[test]
+    fn test_includes_works_of_an_opus() {
+        let opus = CatalogNumber::new(CatalogSystem::Opus, 18);
+        let first = opus.clone().with_sub_number(1);
+        assert!(opus.includes(&first));
+        assert!(first.includes(&first));
+        assert!(!first.includes(&opus));
+        assert!(!first.includes(&opus.clone().with_sub_number(2)));
+    }
+
+    #[test]
+    fn test_natural_order() {
+        let mut numbers: Vec<CatalogNumber> = [
+            "Op. 10",
+            "Hob. XVI:52",
+            "Op. 9 No. 2",
+            "Op. 9",
+            "Hob. IX:1",
+            "BWV 1006a",
+            "BWV 1006",
+        ]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+        numbers.sort();
+        let sorted: Vec<String> = numbers.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            sorted,
+            [
+                "Op. 9",
+                "Op. 9 No. 2",
+                "Op. 10",
+                "BWV 1006",
+                "BWV 1006a",
+                "Hob. IX:1",
+                "Hob. XVI:52"
+            ]
+        );
+    }
+}
@@ -2,6 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::model::ids::{ArtistId, ExpressionId, WorkId};
+use crate::model::links::Performer;
 
 /// A specific performance or recording of a Work.
 ///
@@ -15,8 +16,9 @@ pub struct Expression {
     /// `MusicBrainz` recording ID.
     pub musicbrainz_id: Option<String>,
 
-    /// Primary performer IDs (soloists, ensembles).
-    pub performer_ids: Vec<ArtistId>,
+    /// Credited performers (soloists, ensembles), with their instruments
+    /// or voices where known.
+    pub performers: Vec<Performer>,
 
     /// Conductor, if applicable.
     pub conductor_id: Option<ArtistId>,
@@ -24,6 +26,17 @@ pub struct Expression {
     /// Recording year.
     pub recorded_year: Option<i32>,
 
+    /// First and last day of the recording sessions, as precise as known
+    /// ("1962", "1962-05" or "1962-05-03").
+    pub recorded_from: Option<String>,
+    pub recorded_until: Option<String>,
+
+    /// Where the recording was made: a studio, hall or church, or a city.
+    pub venue: Option<String>,
+
+    /// Whether the recording was made live (`Some(false)`: in a studio).
+    pub live: Option<bool>,
+
     /// Duration in seconds.
     pub duration_secs: Option<f64>,
 
@@ -40,9 +53,13 @@ impl Expression {
             work_id,
             title: None,
             musicbrainz_id: None,
-            performer_ids: Vec::new(),
+            performers: Vec::new(),
             conductor_id: None,
             recorded_year: None,
+            recorded_from: None,
+            recorded_until: None,
+            venue: None,
+            live: None,
             duration_secs: None,
             created_at: now,
             updated_at: now,
@@ -63,10 +80,43 @@ impl Expression {
 
     #[must_use]
     pub fn with_performer(mut self, performer_id: ArtistId) -> Self {
-        self.performer_ids.push(performer_id);
+        self.performers.push(Performer::new(performer_id));
+        self
+    }
+
+    /// Add a performer credit with its instrument or voice.
+    #[must_use]
+    pub fn with_credited_performer(mut self, performer: Performer) -> Self {
+        self.performers.push(performer);
         self
     }
 
+    /// Credit a performer, merging with any existing credit for the same
+    /// artist: new instruments or voices are added to its role, and details
+    /// it lacks are taken from `performer`.
+    pub fn add_performer(&mut self, performer: Performer) {
+        let Some(existing) = self
+            .performers
+            .iter_mut()
+            .find(|p| p.artist_id == performer.artist_id)
+        else {
+            self.performers.push(performer);
+            return;
+        };
+        for role in performer.roles() {
+            existing.add_role(role);
+        }
+        existing.lcmpt_uri = existing.lcmpt_uri.take().or(performer.lcmpt_uri);
+        existing.kind = existing.kind.or(performer.kind);
+        existing.credited_as = existing.credited_as.take().or(performer.credited_as);
+    }
+
+    /// IDs of the credited performers, in credit order.
+    #[must_use]
+    pub fn performer_ids(&self) -> Vec<ArtistId> {
+        self.performers.iter().map(|p| p.artist_id).collect()
+    }
+
     #[must_use]
     pub fn with_conductor(mut self, conductor_id: ArtistId) -> Self {
         self.conductor_id = Some(conductor_id);
@@ -78,6 +128,59 @@ impl Expression {
         self.duration_secs = Some(secs);
         self
     }
+
+    #[must_use]
+    pub fn with_recording_dates(
+        mut self,
+        from: impl Into<String>,
+        until: impl Into<String>,
+    ) -> Self {
+        self.set_recording_dates(from, until);
+        self
+    }
+
+    /// Set the recording sessions' first and last days, and the recording
+    /// year from the first.
+    pub fn set_recording_dates(&mut self, from: impl Into<String>, until: impl Into<String>) {
+        let from = from.into();
+        self.recorded_year = date_year(&from).or(self.recorded_year);
+        self.recorded_from = Some(from);
+        self.recorded_until = Some(until.into());
+    }
+
+    #[must_use]
+    pub fn with_venue(mut self, venue: impl Into<String>) -> Self {
+        self.venue = Some(venue.into());
+        self
+    }
+
+    #[must_use]
+    pub const fn with_live(mut self, live: bool) -> Self {
+        self.live = Some(live);
+        self
+    }
+
+    /// The years the recording sessions span, from the recording dates or,
+    /// failing those, the recording year.
+    #[must_use]
+    pub fn recorded_years(&self) -> Option<(i32, i32)> {
+        let from = self
+            .recorded_from
+            .as_deref()
+            .and_then(date_year)
+            .or(self.recorded_year)?;
+        let until = self
+            .recorded_until
+            .as_deref()
+            .and_then(date_year)
+            .unwrap_or(from);
+        Some((from, until.max(from)))
+    }
+}
+
+/// The year of a "YYYY", "YYYY-MM" or "YYYY-MM-DD" date.
+fn date_year(date: &str) -> Option<i32> {
+    date.get(..4)?.parse().ok()
 }
 
 #[cfg(test)]
@@ -96,17 +199,57 @@ mod tests {
     fn test_expression_builder() {
         let work_id = WorkId::new();
         let performer = ArtistId::new();
+        let soloist = ArtistId::new();
         let conductor = ArtistId::new();
 
         let expr = Expression::new(work_id)
             .with_title("Karajan/BPO 1962")
             .with_performer(performer)
+            .with_credited_performer(Performer::new(soloist).with_role("violin"))
             .with_conductor(conductor)
             .with_duration(2400.5);
 
         assert_eq!(expr.title, Some("Karajan/BPO 1962".to_string()));
-        assert_eq!(expr.performer_ids.len(), 1);
+        assert_eq!(expr.performer_ids(), [performer, soloist]);
+        assert_eq!(expr.performers[1].role.as_deref(), Some("violin"));
         assert_eq!(expr.conductor_id, Some(conductor));
         assert_eq!(expr.duration_secs, Some(2400.5));
     }
+
+    #[test]
+    fn test_recorded_years() {
+        let expr = Expression::new(WorkId::new());
+        assert_eq!(expr.recorded_years(), None);
+
+        let mut expr = expr.with_recording_dates("1962-05-03", "1963-01");
+        assert_eq!(expr.recorded_year, Some(1962));
+        assert_eq!(expr.recorded_years(), Some((1962, 1963)));
+
+        expr.recorded_from = None;
+        expr.recorded_until = None;
+        assert_eq!(expr.recorded_years(), Some((1962, 1962)));
+    }
+
+    #[test]
+    fn test_add_performer_merges_credits() {
+        let pianist = ArtistId::new();
+        let mut expr = Expression::new(WorkId::new()).with_performer(pianist);
+        expr.add_performer(Performer::new(pianist).with_role("piano"));
+        expr.add_performer(
+            Performer::new(pianist)
+                .with_role("harpsichord")
+                .with_credited_as("M. Argerich"),
+        );
+        expr.add_performer(Performer::new(ArtistId::new()).with_role("violin"));
+
+        assert_eq!(expr.performers.len(), 2);
+        assert_eq!(
+            expr.performers[0].role.as_deref(),
+            Some("piano, harpsichord")
+        );
+        assert_eq!(
+            expr.performers[0].credited_as.as_deref(),
+            Some("M. Argerich")
+        );
+    }
 }
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
 use uuid::Uuid;
 
 macro_rules! define_id {
@@ -37,6 +38,14 @@ macro_rules! define_id {
             }
         }
 
+        impl FromStr for $name {
+            type Err = uuid::Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Uuid::parse_str(s).map(Self)
+            }
+        }
+
         impl AsRef<Uuid> for $name {
             fn as_ref(&self) -> &Uuid {
                 &self.0
@@ -72,6 +81,13 @@ mod tests {
         assert_eq!(*id.as_uuid(), uuid);
     }
 
+    #[test]
+    fn test_work_id_parse() {
+        let id = WorkId::new();
+        assert_eq!(id.to_string().parse::<WorkId>().unwrap(), id);
+        assert!("not-a-uuid".parse::<WorkId>().is_err());
+    }
+
     #[test]
     fn test_work_id_display() {
         let id = WorkId::new();
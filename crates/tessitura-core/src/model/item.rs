@@ -58,6 +58,10 @@ pub struct Item {
     /// SHA-256 hash of the file content (for change detection).
     pub file_hash: Option<String>,
 
+    /// SHA-256 hash of the encoded audio stream, excluding tags (so
+    /// retagging is not a content change).
+    pub audio_hash: Option<String>,
+
     /// `AcoustID` fingerprint (computed during Scan stage).
     pub fingerprint: Option<String>,
 
@@ -92,6 +96,9 @@ pub struct Item {
     /// Duration in seconds as read from file properties.
     pub duration_secs: Option<f64>,
 
+    /// When a scan first found the file gone from disk (`None` while present).
+    pub missing_since: Option<DateTime<Utc>>,
+
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -114,6 +121,7 @@ impl Item {
             file_size,
             file_mtime,
             file_hash: None,
+            audio_hash: None,
             fingerprint: None,
             fingerprint_score: None,
             tag_title: None,
@@ -125,6 +133,7 @@ impl Item {
             tag_year: None,
             tag_genre: None,
             duration_secs: None,
+            missing_since: None,
             created_at: now,
             updated_at: now,
         }
@@ -135,6 +144,12 @@ impl Item {
     pub const fn is_identified(&self) -> bool {
         self.expression_id.is_some()
     }
+
+    /// Whether the file was missing from disk at the last scan.
+    #[must_use]
+    pub const fn is_missing(&self) -> bool {
+        self.missing_since.is_some()
+    }
 }
 
 #[cfg(test)]
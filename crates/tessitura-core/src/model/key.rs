@@ -0,0 +1,464 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// A note letter, the tonic of a key before accidentals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NoteLetter {
+    C,
+    D,
+    E,
+    F,
+    G,
+    A,
+    B,
+}
+
+impl NoteLetter {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            'c' => Some(Self::C),
+            'd' => Some(Self::D),
+            'e' => Some(Self::E),
+            'f' => Some(Self::F),
+            'g' => Some(Self::G),
+            'a' => Some(Self::A),
+            'b' => Some(Self::B),
+            _ => None,
+        }
+    }
+
+    /// Semitones above C.
+    const fn semitones(self) -> i8 {
+        match self {
+            Self::C => 0,
+            Self::D => 2,
+            Self::E => 4,
+            Self::F => 5,
+            Self::G => 7,
+            Self::A => 9,
+            Self::B => 11,
+        }
+    }
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::C => "C",
+            Self::D => "D",
+            Self::E => "E",
+            Self::F => "F",
+            Self::G => "G",
+            Self::A => "A",
+            Self::B => "B",
+        }
+    }
+}
+
+/// The tonic of a key: a note letter with its accidental.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Tonic {
+    pub letter: NoteLetter,
+
+    /// Semitones the letter is raised (sharps) or lowered (flats), from -2
+    /// (double flat) to 2 (double sharp).
+    pub accidental: i8,
+}
+
+impl Tonic {
+    /// A tonic, if the accidental is at most a double sharp or flat.
+    #[must_use]
+    pub fn new(letter: NoteLetter, accidental: i8) -> Option<Self> {
+        (-2..=2)
+            .contains(&accidental)
+            .then_some(Self { letter, accidental })
+    }
+
+    /// The pitch class (0 = C, 11 = B), the same for enharmonic spellings.
+    #[must_use]
+    pub fn pitch_class(self) -> u8 {
+        (self.letter.semitones() + self.accidental)
+            .rem_euclid(12)
+            .unsigned_abs()
+    }
+}
+
+impl fmt::Display for Tonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.letter.as_str())?;
+        match self.accidental {
+            2 => f.write_str("-double-sharp"),
+            1 => f.write_str("-sharp"),
+            -1 => f.write_str("-flat"),
+            -2 => f.write_str("-double-flat"),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The mode of a key. Ionian and Aeolian are the major and minor modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    Major,
+    Minor,
+    Dorian,
+    Phrygian,
+    Lydian,
+    Mixolydian,
+    Locrian,
+}
+
+impl Mode {
+    /// The mode's name in the canonical display form.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Major => "major",
+            Self::Minor => "minor",
+            Self::Dorian => "Dorian",
+            Self::Phrygian => "Phrygian",
+            Self::Lydian => "Lydian",
+            Self::Mixolydian => "Mixolydian",
+            Self::Locrian => "Locrian",
+        }
+    }
+}
+
+/// A musical key, parsed from the ways catalogs and tags spell it.
+///
+/// Parsing accepts English ("F-sharp minor", "Bb major", "C#m"), German
+/// ("fis-moll", "Es-Dur", "B-Dur" for B-flat), French ("fa dièse mineur")
+/// and Italian ("si bemolle maggiore") spellings, church modes ("D Dorian",
+/// "e-phrygisch"), and "atonal" or "no key". Keys display in the English
+/// form `MusicBrainz` and Wikidata use: "F-sharp minor", "D Dorian".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MusicalKey {
+    Tonal {
+        tonic: Tonic,
+        mode: Mode,
+    },
+    /// Atonal, or explicitly in no key.
+    Atonal,
+}
+
+impl MusicalKey {
+    #[must_use]
+    pub const fn new(tonic: Tonic, mode: Mode) -> Self {
+        Self::Tonal { tonic, mode }
+    }
+
+    /// Whether two keys sound the same, however their tonics are spelled
+    /// (F-sharp major and G-flat major).
+    #[must_use]
+    pub fn is_enharmonic(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Tonal { tonic, mode }, Self::Tonal { tonic: t, mode: m }) => {
+                mode == m && tonic.pitch_class() == t.pitch_class()
+            }
+            (Self::Atonal, Self::Atonal) => true,
+            _ => false,
+        }
+    }
+
+    /// The canonical display form of a key name, or `None` if it is not
+    /// one.
+    #[must_use]
+    pub fn canonical(name: &str) -> Option<String> {
+        name.parse::<Self>().ok().map(|key| key.to_string())
+    }
+}
+
+impl fmt::Display for MusicalKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tonal { tonic, mode } => write!(f, "{tonic} {}", mode.as_str()),
+            Self::Atonal => f.write_str("atonal"),
+        }
+    }
+}
+
+impl FromStr for MusicalKey {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        parse(s).ok_or_else(|| crate::Error::InvalidData(format!("Unrecognized key: {s}")))
+    }
+}
+
+/// The language a mode word is in, which decides how the tonic is spelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Language {
+    English,
+    German,
+    /// French or Italian solfège syllables.
+    Romance,
+}
+
+fn parse(s: &str) -> Option<MusicalKey> {
+    let lower = s.trim().to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+        .filter(|w| !w.is_empty())
+        .collect();
+    if is_atonal(&words) {
+        return Some(MusicalKey::Atonal);
+    }
+
+    let (last, rest) = words.split_last()?;
+    let Some((mode, language)) = mode_word(last) else {
+        return shorthand(&words);
+    };
+    let tonic = match language {
+        Language::English => english_tonic(rest),
+        Language::German => german_tonic(rest).or_else(|| english_tonic(rest)),
+        Language::Romance => romance_tonic(rest),
+    }?;
+    Some(MusicalKey::new(tonic, mode))
+}
+
+fn is_atonal(words: &[&str]) -> bool {
+    matches!(
+        words,
+        [
+            "atonal"
+                | "atonality"
+                | "atonale"
+                | "atonalität"
+                | "atonalité"
+                | "atonalità"
+                | "keyless"
+        ] | ["no" | "without", "key"]
+            | ["no", "tonality"]
+    )
+}
+
+fn mode_word(word: &str) -> Option<(Mode, Language)> {
+    let found = match word {
+        "major" | "ionian" => (Mode::Major, Language::English),
+        "minor" | "aeolian" => (Mode::Minor, Language::English),
+        "dorian" => (Mode::Dorian, Language::English),
+        "phrygian" => (Mode::Phrygian, Language::English),
+        "lydian" => (Mode::Lydian, Language::English),
+        "mixolydian" => (Mode::Mixolydian, Language::English),
+        "locrian" => (Mode::Locrian, Language::English),
+        "dur" | "ionisch" => (Mode::Major, Language::German),
+        "moll" | "äolisch" | "aolisch" => (Mode::Minor, Language::German),
+        "dorisch" => (Mode::Dorian, Language::German),
+        "phrygisch" => (Mode::Phrygian, Language::German),
+        "lydisch" => (Mode::Lydian, Language::German),
+        "mixolydisch" => (Mode::Mixolydian, Language::German),
+        "lokrisch" => (Mode::Locrian, Language::German),
+        "majeur" | "maggiore" | "ionien" | "ionico" => (Mode::Major, Language::Romance),
+        "mineur" | "minore" | "éolien" | "eolien" | "eolio" => (Mode::Minor, Language::Romance),
+        "dorien" | "dorico" => (Mode::Dorian, Language::Romance),
+        "phrygien" | "frigio" => (Mode::Phrygian, Language::Romance),
+        "lydien" | "lidio" => (Mode::Lydian, Language::Romance),
+        "mixolydien" | "misolidio" => (Mode::Mixolydian, Language::Romance),
+        "locrien" | "locrio" => (Mode::Locrian, Language::Romance),
+        _ => return None,
+    };
+    Some(found)
+}
+
+/// Keys written without a mode word: "Am", "C#m", "Ebmaj", or a bare
+/// tonic for the major key.
+fn shorthand(words: &[&str]) -> Option<MusicalKey> {
+    let (last, rest) = words.split_last()?;
+    for (suffix, mode) in [
+        ("maj", Mode::Major),
+        ("min", Mode::Minor),
+        ("m", Mode::Minor),
+    ] {
+        let Some(stripped) = last.strip_suffix(suffix).filter(|s| !s.is_empty()) else {
+            continue;
+        };
+        let mut tonic_words = rest.to_vec();
+        tonic_words.push(stripped);
+        if let Some(tonic) = english_tonic(&tonic_words) {
+            return Some(MusicalKey::new(tonic, mode));
+        }
+    }
+    english_tonic(words).map(|tonic| MusicalKey::new(tonic, Mode::Major))
+}
+
+/// "c", "c#", "d♭", "bb", "b flat", "c double sharp".
+fn english_tonic(words: &[&str]) -> Option<Tonic> {
+    let (first, rest) = words.split_first()?;
+    let mut chars = first.chars();
+    let letter = NoteLetter::from_char(chars.next()?)?;
+    let accidental = symbol_accidental(chars.as_str())? + word_accidental(rest)?;
+    Tonic::new(letter, accidental)
+}
+
+/// "fis", "es", "as", "heses", "b" (B-flat) and "h" (B).
+fn german_tonic(words: &[&str]) -> Option<Tonic> {
+    let [word] = words else {
+        return None;
+    };
+    let mut chars = word.chars();
+    let (letter, mut accidental) = match chars.next()? {
+        'h' => (NoteLetter::B, 0),
+        'b' if chars.as_str().is_empty() => (NoteLetter::B, -1),
+        c => (NoteLetter::from_char(c)?, 0),
+    };
+    let mut suffix = chars.as_str();
+    // "es" and "as" drop the "e" of the flat suffix
+    if matches!(letter, NoteLetter::E | NoteLetter::A) {
+        if let Some(rest) = suffix.strip_prefix('s') {
+            accidental -= 1;
+            suffix = rest;
+        }
+    }
+    while !suffix.is_empty() {
+        if let Some(rest) = suffix.strip_prefix("is") {
+            accidental += 1;
+            suffix = rest;
+        } else if let Some(rest) = suffix.strip_prefix("es") {
+            accidental -= 1;
+            suffix = rest;
+        } else {
+            return None;
+        }
+    }
+    Tonic::new(letter, accidental)
+}
+
+/// "fa dièse", "si bémol", "sol#", "si bemolle", with "ut" for C.
+fn romance_tonic(words: &[&str]) -> Option<Tonic> {
+    let (first, rest) = words.split_first()?;
+    let (letter, symbols) = [
+        ("do", NoteLetter::C),
+        ("ut", NoteLetter::C),
+        ("ré", NoteLetter::D),
+        ("re", NoteLetter::D),
+        ("mi", NoteLetter::E),
+        ("fa", NoteLetter::F),
+        ("sol", NoteLetter::G),
+        ("la", NoteLetter::A),
+        ("si", NoteLetter::B),
+    ]
+    .into_iter()
+    .find_map(|(syllable, letter)| first.strip_prefix(syllable).map(|s| (letter, s)))?;
+    let accidental = symbol_accidental(symbols)? + word_accidental(rest)?;
+    Tonic::new(letter, accidental)
+}
+
+/// Accidental symbols written after a note letter: "#", "b", "♯", "♭", "x".
+fn symbol_accidental(symbols: &str) -> Option<i8> {
+    symbols.chars().try_fold(0, |total, c| match c {
+        '#' | '♯' => Some(total + 1),
+        'b' | '♭' => Some(total - 1),
+        'x' | '𝄪' => Some(total + 2),
+        '𝄫' => Some(total - 2),
+        '♮' => Some(total),
+        _ => None,
+    })
+}
+
+/// Accidentals written out as words, in English, French or Italian.
+fn word_accidental(words: &[&str]) -> Option<i8> {
+    let mut total = 0;
+    let mut factor = 1;
+    for word in words {
+        match *word {
+            "double" | "doppio" => {
+                factor = 2;
+                continue;
+            }
+            "sharp" | "dièse" | "diese" | "diesis" => total += factor,
+            "flat" | "bémol" | "bemol" | "bemolle" => total -= factor,
+            "natural" | "bécarre" | "becarre" | "bequadro" => {}
+            _ => return None,
+        }
+        factor = 1;
+    }
+    (factor == 1).then_some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(s: &str) -> String {
+        s.parse::<MusicalKey>().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_parse_english() {
+        assert_eq!(key("C major"), "C major");
+        assert_eq!(key("a minor"), "A minor");
+        assert_eq!(key("B-flat major"), "B-flat major");
+        assert_eq!(key("F# minor"), "F-sharp minor");
+        assert_eq!(key("E♭ major"), "E-flat major");
+        assert_eq!(key("c sharp minor"), "C-sharp minor");
+        assert_eq!(key("G double flat major"), "G-double-flat major");
+        assert_eq!(key("D Dorian"), "D Dorian");
+        assert_eq!(key("A Aeolian"), "A minor");
+    }
+
+    #[test]
+    fn test_parse_shorthand() {
+        assert_eq!(key("Am"), "A minor");
+        assert_eq!(key("C#m"), "C-sharp minor");
+        assert_eq!(key("Ebmaj"), "E-flat major");
+        assert_eq!(key("Bb"), "B-flat major");
+        assert_eq!(key("G"), "G major");
+    }
+
+    #[test]
+    fn test_parse_german() {
+        assert_eq!(key("fis-moll"), "F-sharp minor");
+        assert_eq!(key("Es-Dur"), "E-flat major");
+        assert_eq!(key("As-Dur"), "A-flat major");
+        assert_eq!(key("B-Dur"), "B-flat major");
+        assert_eq!(key("h-moll"), "B minor");
+        assert_eq!(key("Ces-Dur"), "C-flat major");
+        assert_eq!(key("e-phrygisch"), "E Phrygian");
+    }
+
+    #[test]
+    fn test_parse_french_and_italian() {
+        assert_eq!(key("fa dièse mineur"), "F-sharp minor");
+        assert_eq!(key("Si bémol majeur"), "B-flat major");
+        assert_eq!(key("ut majeur"), "C major");
+        assert_eq!(key("Si bemolle maggiore"), "B-flat major");
+        assert_eq!(key("re minore"), "D minor");
+        assert_eq!(key("sol# mineur"), "G-sharp minor");
+    }
+
+    #[test]
+    fn test_parse_atonal() {
+        assert_eq!("atonal".parse::<MusicalKey>().unwrap(), MusicalKey::Atonal);
+        assert_eq!("No key".parse::<MusicalKey>().unwrap(), MusicalKey::Atonal);
+        assert_eq!(key("atonality"), "atonal");
+    }
+
+    #[test]
+    fn test_parse_rejects_non_keys() {
+        for s in [
+            "",
+            "Op. 27",
+            "symphony",
+            "H major",
+            "fis-minor-ish",
+            "C triple sharp major",
+        ] {
+            assert!(s.parse::<MusicalKey>().is_err(), "{s:?} parsed");
+        }
+        assert_eq!(MusicalKey::canonical("BWV 1007"), None);
+        assert_eq!(
+            MusicalKey::canonical("cis-moll").as_deref(),
+            Some("C-sharp minor")
+        );
+    }
+
+    #[test]
+    fn test_enharmonic_equivalence() {
+        let parse = |s: &str| s.parse::<MusicalKey>().unwrap();
+        assert!(parse("F-sharp major").is_enharmonic(&parse("Ges-Dur")));
+        assert!(parse("B# minor").is_enharmonic(&parse("c minor")));
+        assert!(parse("Cb major").is_enharmonic(&parse("B major")));
+        assert!(!parse("F-sharp major").is_enharmonic(&parse("G-flat minor")));
+        assert!(!parse("C major").is_enharmonic(&parse("A minor")));
+        assert!(!parse("C major").is_enharmonic(&MusicalKey::Atonal));
+        assert!(MusicalKey::Atonal.is_enharmonic(&MusicalKey::Atonal));
+    }
+}
@@ -0,0 +1,305 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::ids::{ArtistId, ExpressionId, ManifestationId, WorkId};
+
+/// Whether a performer takes part alone or as a group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PerformerKind {
+    /// An individual performer, whether featured or accompanying.
+    Soloist,
+    /// A chamber group or other small ensemble.
+    Ensemble,
+    Orchestra,
+    /// A choir or vocal ensemble.
+    Chorus,
+}
+
+impl PerformerKind {
+    /// The name stored in the database.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Soloist => "soloist",
+            Self::Ensemble => "ensemble",
+            Self::Orchestra => "orchestra",
+            Self::Chorus => "chorus",
+        }
+    }
+}
+
+impl fmt::Display for PerformerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PerformerKind {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s {
+            "soloist" => Ok(Self::Soloist),
+            "ensemble" => Ok(Self::Ensemble),
+            "orchestra" => Ok(Self::Orchestra),
+            "chorus" => Ok(Self::Chorus),
+            other => Err(crate::Error::InvalidData(format!(
+                "Unknown performer kind: {other}"
+            ))),
+        }
+    }
+}
+
+/// An artist's part in a recording: the instrument or voice they are
+/// credited with, and how.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Performer {
+    pub artist_id: ArtistId,
+
+    /// Instrument or voice as credited (e.g., "piano", "soprano"), if
+    /// known. Several are separated by commas.
+    pub role: Option<String>,
+
+    /// URI of the LCMPT term for the instrument or voice, where one
+    /// matches.
+    pub lcmpt_uri: Option<String>,
+
+    pub kind: Option<PerformerKind>,
+
+    /// Name the artist is credited under on this recording, if it differs
+    /// from their own.
+    pub credited_as: Option<String>,
+}
+
+impl Performer {
+    #[must_use]
+    pub const fn new(artist_id: ArtistId) -> Self {
+        Self {
+            artist_id,
+            role: None,
+            lcmpt_uri: None,
+            kind: None,
+            credited_as: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_role(mut self, role: impl Into<String>) -> Self {
+        self.role = Some(role.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_lcmpt_uri(mut self, uri: impl Into<String>) -> Self {
+        self.lcmpt_uri = Some(uri.into());
+        self
+    }
+
+    #[must_use]
+    pub const fn with_kind(mut self, kind: PerformerKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    #[must_use]
+    pub fn with_credited_as(mut self, name: impl Into<String>) -> Self {
+        self.credited_as = Some(name.into());
+        self
+    }
+
+    /// The instruments or voices in the role, in credit order.
+    pub fn roles(&self) -> impl Iterator<Item = &str> {
+        self.role
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+    }
+
+    /// Add an instrument or voice to the role, unless already credited.
+    pub fn add_role(&mut self, role: &str) {
+        let role = role.trim();
+        if role.is_empty() || self.roles().any(|r| r.eq_ignore_ascii_case(role)) {
+            return;
+        }
+        self.role = Some(match self.role.take() {
+            Some(existing) => format!("{existing}, {role}"),
+            None => role.to_string(),
+        });
+    }
+}
+
+/// A performer credited on an Expression (a row of `expression_performers`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PerformerCredit {
+    pub expression_id: ExpressionId,
+    #[serde(flatten)]
+    pub performer: Performer,
+}
+
+/// An Expression's position on a Manifestation (a row of
+/// `manifestation_expressions`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseTrack {
+    pub manifestation_id: ManifestationId,
+    pub expression_id: ExpressionId,
+    pub track_number: Option<u32>,
+    pub disc_number: Option<u32>,
+}
+
+impl ReleaseTrack {
+    #[must_use]
+    pub const fn new(manifestation_id: ManifestationId, expression_id: ExpressionId) -> Self {
+        Self {
+            manifestation_id,
+            expression_id,
+            track_number: None,
+            disc_number: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_track_number(mut self, track: u32) -> Self {
+        self.track_number = Some(track);
+        self
+    }
+
+    #[must_use]
+    pub const fn with_disc_number(mut self, disc: u32) -> Self {
+        self.disc_number = Some(disc);
+        self
+    }
+}
+
+/// The part an artist had in creating a Work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkRole {
+    Composer,
+    Arranger,
+    Orchestrator,
+    /// Author of the words of a song or other short vocal work.
+    Lyricist,
+    /// Author of the text of an opera, oratorio or other dramatic work.
+    Librettist,
+    /// Writer of music and words, where the two are not told apart.
+    Writer,
+}
+
+impl WorkRole {
+    /// The name stored in the database.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Composer => "composer",
+            Self::Arranger => "arranger",
+            Self::Orchestrator => "orchestrator",
+            Self::Lyricist => "lyricist",
+            Self::Librettist => "librettist",
+            Self::Writer => "writer",
+        }
+    }
+}
+
+impl fmt::Display for WorkRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WorkRole {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s {
+            "composer" => Ok(Self::Composer),
+            "arranger" => Ok(Self::Arranger),
+            "orchestrator" => Ok(Self::Orchestrator),
+            "lyricist" => Ok(Self::Lyricist),
+            "librettist" => Ok(Self::Librettist),
+            "writer" => Ok(Self::Writer),
+            other => Err(crate::Error::InvalidData(format!(
+                "Unknown work role: {other}"
+            ))),
+        }
+    }
+}
+
+/// An artist credited on a Work (a row of `work_credits`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkCredit {
+    pub work_id: WorkId,
+    pub artist_id: ArtistId,
+    pub role: WorkRole,
+}
+
+impl WorkCredit {
+    #[must_use]
+    pub const fn new(work_id: WorkId, artist_id: ArtistId, role: WorkRole) -> Self {
+        Self {
+            work_id,
+            artist_id,
+            role,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_track_builder() {
+        let man_id = ManifestationId::new();
+        let expr_id = ExpressionId::new();
+        let track = ReleaseTrack::new(man_id, expr_id)
+            .with_track_number(3)
+            .with_disc_number(2);
+        assert_eq!(track.manifestation_id, man_id);
+        assert_eq!(track.expression_id, expr_id);
+        assert_eq!(track.track_number, Some(3));
+        assert_eq!(track.disc_number, Some(2));
+    }
+
+    #[test]
+    fn test_performer_roles() {
+        let mut performer = Performer::new(ArtistId::new()).with_kind(PerformerKind::Soloist);
+        assert_eq!(performer.roles().count(), 0);
+        performer.add_role("piano");
+        performer.add_role("Piano");
+        performer.add_role("harpsichord");
+        assert_eq!(performer.role.as_deref(), Some("piano, harpsichord"));
+        assert_eq!(
+            performer.roles().collect::<Vec<_>>(),
+            ["piano", "harpsichord"]
+        );
+        for kind in [
+            PerformerKind::Soloist,
+            PerformerKind::Ensemble,
+            PerformerKind::Orchestra,
+            PerformerKind::Chorus,
+        ] {
+            assert_eq!(kind.as_str().parse::<PerformerKind>().unwrap(), kind);
+        }
+        assert!("band".parse::<PerformerKind>().is_err());
+    }
+
+    #[test]
+    fn test_work_role_round_trip() {
+        for role in [
+            WorkRole::Composer,
+            WorkRole::Arranger,
+            WorkRole::Orchestrator,
+            WorkRole::Lyricist,
+            WorkRole::Librettist,
+            WorkRole::Writer,
+        ] {
+            assert_eq!(role.as_str().parse::<WorkRole>().unwrap(), role);
+        }
+        assert!("translator".parse::<WorkRole>().is_err());
+    }
+}
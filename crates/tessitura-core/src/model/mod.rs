@@ -1,13 +1,19 @@
 pub mod artist;
+pub mod catalog;
 pub mod expression;
 pub mod ids;
 pub mod item;
+pub mod key;
+pub mod links;
 pub mod manifestation;
 pub mod work;
 
 pub use artist::{Artist, ArtistRole};
+pub use catalog::{CatalogNumber, CatalogSystem};
 pub use expression::Expression;
 pub use ids::{ArtistId, ExpressionId, ItemId, ManifestationId, WorkId};
 pub use item::{AudioFormat, Item};
+pub use key::{Mode, MusicalKey, NoteLetter, Tonic};
+pub use links::{Performer, PerformerCredit, PerformerKind, ReleaseTrack, WorkCredit, WorkRole};
 pub use manifestation::Manifestation;
 pub use work::Work;
@@ -1,7 +1,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::model::catalog::CatalogNumber;
 use crate::model::ids::WorkId;
+use crate::model::key::MusicalKey;
 
 /// A distinct musical work (composition).
 ///
@@ -17,15 +19,27 @@ pub struct Work {
     /// `MusicBrainz` work ID, if identified.
     pub musicbrainz_id: Option<String>,
 
-    /// Catalog number (BWV, K., Sz., BB., Op., etc.).
+    /// Catalog number (BWV, K., Sz., BB., Op., etc.), as spelled by its
+    /// source; see [`CatalogNumber`] for the parsed form.
     pub catalog_number: Option<String>,
 
-    /// Musical key (e.g., "A minor", "D major").
+    /// Musical key (e.g., "A minor", "F-sharp major"), in the canonical
+    /// form of [`MusicalKey`] where it parses as one.
     pub key: Option<String>,
 
     /// Year or approximate date of composition.
     pub composed_year: Option<i32>,
 
+    /// The work this is a movement or other part of, if any.
+    pub parent_work_id: Option<WorkId>,
+
+    /// Position of this part within its parent work (1-based).
+    pub part_number: Option<u32>,
+
+    /// Name of the movement alone (e.g., "Allegro con brio"), without the
+    /// parent work's title.
+    pub movement_label: Option<String>,
+
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -42,6 +56,9 @@ impl Work {
             catalog_number: None,
             key: None,
             composed_year: None,
+            parent_work_id: None,
+            part_number: None,
+            movement_label: None,
             created_at: now,
             updated_at: now,
         }
@@ -76,6 +93,42 @@ impl Work {
         self.composed_year = Some(year);
         self
     }
+
+    /// Make this work a part of `parent`, at the given position if known.
+    #[must_use]
+    pub const fn with_parent(mut self, parent: WorkId, part_number: Option<u32>) -> Self {
+        self.parent_work_id = Some(parent);
+        self.part_number = part_number;
+        self
+    }
+
+    #[must_use]
+    pub fn with_movement_label(mut self, label: impl Into<String>) -> Self {
+        self.movement_label = Some(label.into());
+        self
+    }
+
+    /// The key, parsed from its stored name.
+    #[must_use]
+    pub fn musical_key(&self) -> Option<MusicalKey> {
+        self.key.as_deref()?.parse().ok()
+    }
+
+    /// The catalog number, parsed from the stored one or, failing that,
+    /// found in the title ("Piano Sonata No. 11, K. 331").
+    #[must_use]
+    pub fn catalog(&self) -> Option<CatalogNumber> {
+        self.catalog_number
+            .as_deref()
+            .and_then(|number| number.parse().ok())
+            .or_else(|| CatalogNumber::find(&self.title))
+    }
+
+    /// Whether this work is a movement or other part of another work.
+    #[must_use]
+    pub const fn is_movement(&self) -> bool {
+        self.parent_work_id.is_some()
+    }
 }
 
 #[cfg(test)]
@@ -101,5 +154,28 @@ mod tests {
         assert_eq!(work.catalog_number, Some("Sz.91".to_string()));
         assert_eq!(work.key, Some("C major".to_string()));
         assert_eq!(work.composed_year, Some(1928));
+        assert!(!work.is_movement());
+        assert_eq!(work.musical_key(), "C major".parse().ok());
+        assert_eq!(
+            work.catalog().map(|c| c.to_string()).as_deref(),
+            Some("Sz. 91")
+        );
+    }
+
+    #[test]
+    fn test_work_movement() {
+        let symphony = Work::new("Symphony No. 5 in C minor, Op. 67");
+        let movement = Work::new("Symphony No. 5 in C minor, Op. 67: I. Allegro con brio")
+            .with_parent(symphony.id, Some(1))
+            .with_movement_label("Allegro con brio");
+
+        assert!(movement.is_movement());
+        assert_eq!(
+            movement.catalog().map(|c| c.to_string()).as_deref(),
+            Some("Op. 67")
+        );
+        assert_eq!(movement.parent_work_id, Some(symphony.id));
+        assert_eq!(movement.part_number, Some(1));
+        assert_eq!(movement.movement_label.as_deref(), Some("Allegro con brio"));
     }
 }
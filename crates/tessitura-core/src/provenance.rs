@@ -1,6 +1,11 @@
+use std::fmt;
+use std::str::FromStr;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::taxonomy::rules::{Alternative, ProposedTag};
+
 /// The source of a metadata assertion.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Source {
@@ -8,6 +13,8 @@ pub enum Source {
     EmbeddedTag,
     /// `AcoustID` fingerprint matching.
     AcoustId,
+    /// Local fingerprint comparison between files in the library.
+    Fingerprint,
     /// `MusicBrainz` database.
     MusicBrainz,
     /// Wikidata.
@@ -71,6 +78,128 @@ impl Assertion {
     }
 }
 
+/// The review status of a harmonization proposal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProposalStatus {
+    /// Awaiting review.
+    Pending,
+    /// Approved as proposed.
+    Accepted,
+    /// Rejected; the value will not be exported.
+    Rejected,
+    /// Replaced by a user-chosen value (an alternative or a custom value).
+    Overridden,
+}
+
+impl ProposalStatus {
+    /// The name stored in the database.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Accepted => "accepted",
+            Self::Rejected => "rejected",
+            Self::Overridden => "overridden",
+        }
+    }
+}
+
+impl fmt::Display for ProposalStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ProposalStatus {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "accepted" => Ok(Self::Accepted),
+            "rejected" => Ok(Self::Rejected),
+            "overridden" => Ok(Self::Overridden),
+            other => Err(crate::Error::InvalidData(format!(
+                "Unknown proposal status: {other}"
+            ))),
+        }
+    }
+}
+
+/// A harmonization proposal persisted for review.
+///
+/// Wraps a [`ProposedTag`] from the rules engine with the entity it applies
+/// to and the state of the review decision.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Proposal {
+    /// Database row ID (0 until inserted).
+    pub id: i64,
+
+    /// The item this proposal is about, by its ID.
+    pub entity_id: String,
+
+    /// The metadata field: "genre", "form", "period", or "instrumentation";
+    /// "performer" for a performer credit; or "expression" for a link to
+    /// the recording whose ID is the value.
+    pub field: String,
+
+    /// The canonical value proposed by the rule.
+    pub value: String,
+
+    /// The name of the rule that produced this proposal.
+    pub rule_name: String,
+
+    /// Which source's assertion triggered this proposal.
+    pub source: Source,
+
+    /// Combined confidence score.
+    pub confidence: f64,
+
+    /// Conflicting values from other sources.
+    pub alternatives: Vec<Alternative>,
+
+    /// Review status.
+    pub status: ProposalStatus,
+
+    /// When the proposal was generated.
+    pub created_at: DateTime<Utc>,
+
+    /// When the proposal was last decided (`None` while pending).
+    pub decided_at: Option<DateTime<Utc>>,
+
+    /// Row ID of the [`Source::User`] assertion recorded by overriding the
+    /// proposal, if it is overridden.
+    pub override_assertion_id: Option<i64>,
+}
+
+impl Proposal {
+    /// Create a pending proposal for an entity from a rules engine result.
+    #[must_use]
+    pub fn new(entity_id: impl Into<String>, tag: ProposedTag) -> Self {
+        Self {
+            id: 0,
+            entity_id: entity_id.into(),
+            field: tag.field,
+            value: tag.value,
+            rule_name: tag.rule_name,
+            source: tag.source,
+            confidence: tag.confidence,
+            alternatives: tag.alternatives,
+            status: ProposalStatus::Pending,
+            created_at: Utc::now(),
+            decided_at: None,
+            override_assertion_id: None,
+        }
+    }
+
+    /// Whether other sources proposed conflicting values.
+    #[must_use]
+    pub fn has_conflicts(&self) -> bool {
+        !self.alternatives.is_empty()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,4 +222,39 @@ mod tests {
 
         assert_eq!(assertion.confidence, Some(0.95));
     }
+
+    #[test]
+    fn test_proposal_status_round_trip() {
+        for status in [
+            ProposalStatus::Pending,
+            ProposalStatus::Accepted,
+            ProposalStatus::Rejected,
+            ProposalStatus::Overridden,
+        ] {
+            assert_eq!(status.as_str().parse::<ProposalStatus>().unwrap(), status);
+        }
+        assert!("maybe".parse::<ProposalStatus>().is_err());
+    }
+
+    #[test]
+    fn test_proposal_from_tag() {
+        let tag = ProposedTag {
+            field: "form".to_string(),
+            value: "Symphony".to_string(),
+            source: Source::MusicBrainz,
+            rule_name: "symphony".to_string(),
+            confidence: 0.9,
+            alternatives: vec![Alternative {
+                value: "Sinfonietta".to_string(),
+                source: Source::LastFm,
+                confidence: 0.4,
+            }],
+        };
+        let proposal = Proposal::new("item-1", tag);
+
+        assert_eq!(proposal.entity_id, "item-1");
+        assert_eq!(proposal.status, ProposalStatus::Pending);
+        assert!(proposal.decided_at.is_none());
+        assert!(proposal.has_conflicts());
+    }
 }
@@ -1,26 +1,120 @@
 use rusqlite::Connection;
+use std::collections::HashMap;
+use std::ops::Deref;
 use std::path::Path;
+use std::time::Duration;
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::model::{
     Artist, ArtistId, ArtistRole, Expression, ExpressionId, Item, ItemId, Manifestation,
-    ManifestationId, Work, WorkId,
+    ManifestationId, Performer, PerformerCredit, PerformerKind, ReleaseTrack, Work, WorkCredit,
+    WorkId, WorkRole,
 };
-use crate::provenance::Assertion;
+use crate::provenance::{Assertion, Proposal, ProposalStatus, Source};
 use crate::taxonomy::{LcgftTerm, LcmptTerm};
 
 use super::migrations::MIGRATIONS;
 
+/// How long a connection waits for another connection's write lock before
+/// failing with `SQLITE_BUSY`.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// A database connection with CRUD methods for FRBR entities.
 #[derive(Debug)]
 pub struct Database {
     conn: Connection,
 }
 
+/// A transaction on a [`Database`].
+///
+/// Derefs to the database, so every CRUD method called through it runs
+/// inside the transaction. Nothing is written until [`commit`](Self::commit);
+/// dropping the handle rolls the transaction back.
+#[derive(Debug)]
+pub struct Transaction<'a> {
+    db: &'a Database,
+    tx: rusqlite::Transaction<'a>,
+}
+
+impl Deref for Transaction<'_> {
+    type Target = Database;
+
+    fn deref(&self) -> &Database {
+        self.db
+    }
+}
+
+impl Transaction<'_> {
+    /// Commit the transaction.
+    pub fn commit(self) -> Result<()> {
+        self.tx.commit()?;
+        Ok(())
+    }
+
+    /// Roll the transaction back, discarding its changes.
+    pub fn rollback(self) -> Result<()> {
+        self.tx.rollback()?;
+        Ok(())
+    }
+}
+
+/// The state of the catalog when a derived index (such as the similarity
+/// index or the cached search facets) was built. The index is stale once
+/// [`Database::catalog_stamp`] differs from the stamp it recorded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CatalogStamp {
+    /// [`Database::latest_change`], which dates inserts and updates.
+    pub latest_change: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// [`Database::catalog_row_count`], which drops when rows are deleted.
+    pub rows: u64,
+}
+
+/// A window of rows in a listing, for paging through large tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page {
+    /// Number of rows skipped before the window.
+    pub offset: usize,
+
+    /// Largest number of rows returned.
+    pub limit: usize,
+}
+
+impl Page {
+    /// The first `limit` rows.
+    #[must_use]
+    pub const fn first(limit: usize) -> Self {
+        Self { offset: 0, limit }
+    }
+
+    /// The window following this one.
+    #[must_use]
+    pub const fn next(self) -> Self {
+        Self {
+            offset: self.offset + self.limit,
+            limit: self.limit,
+        }
+    }
+
+    /// The `LIMIT` and `OFFSET` values, saturated to what SQLite accepts.
+    fn sql(self) -> (i64, i64) {
+        (
+            i64::try_from(self.limit).unwrap_or(i64::MAX),
+            i64::try_from(self.offset).unwrap_or(i64::MAX),
+        )
+    }
+}
+
 impl Database {
     /// Open (or create) a database at the given path and apply migrations.
+    ///
+    /// The database is switched to WAL mode, so readers do not block the
+    /// writer, and connections wait up to [`BUSY_TIMEOUT`] for each other's
+    /// locks.
     pub fn open(path: impl AsRef<Path>) -> Result<Self> {
         let conn = Connection::open(path)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
         let db = Self { conn };
         db.apply_migrations()?;
         Ok(db)
@@ -39,6 +133,26 @@ impl Database {
         &self.conn
     }
 
+    /// Begin a transaction. Transactions do not nest.
+    pub fn transaction(&self) -> Result<Transaction<'_>> {
+        Ok(Transaction {
+            db: self,
+            tx: self.conn.unchecked_transaction()?,
+        })
+    }
+
+    /// Run `f` in a transaction, committing if it succeeds. Inside an open
+    /// transaction, `f` simply joins it.
+    fn in_transaction<T>(&self, f: impl FnOnce(&Self) -> Result<T>) -> Result<T> {
+        if !self.conn.is_autocommit() {
+            return f(self);
+        }
+        let tx = self.transaction()?;
+        let value = f(&tx)?;
+        tx.commit()?;
+        Ok(value)
+    }
+
     fn apply_migrations(&self) -> Result<()> {
         // Create migrations table if it doesn't exist
         self.conn.execute(
@@ -88,8 +202,8 @@ impl Database {
                 file_size, file_mtime, file_hash, fingerprint, fingerprint_score,
                 tag_title, tag_artist, tag_album, tag_album_artist,
                 tag_track_number, tag_disc_number, tag_year, tag_genre,
-                duration_secs, created_at, updated_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)",
+                duration_secs, created_at, updated_at, missing_since, audio_hash
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)",
             rusqlite::params![
                 item.id.to_string(),
                 item.expression_id.map(|id| id.to_string()),
@@ -112,11 +226,18 @@ impl Database {
                 item.duration_secs,
                 item.created_at.to_rfc3339(),
                 item.updated_at.to_rfc3339(),
+                item.missing_since.map(|t| t.to_rfc3339()),
+                item.audio_hash,
             ],
         )?;
         Ok(())
     }
 
+    /// Insert many new items in one transaction.
+    pub fn insert_items(&self, items: &[Item]) -> Result<()> {
+        self.in_transaction(|db| items.iter().try_for_each(|item| db.insert_item(item)))
+    }
+
     /// Update an existing item.
     pub fn update_item(&self, item: &Item) -> Result<()> {
         self.conn.execute(
@@ -127,7 +248,8 @@ impl Database {
                 tag_title = ?11, tag_artist = ?12, tag_album = ?13,
                 tag_album_artist = ?14, tag_track_number = ?15,
                 tag_disc_number = ?16, tag_year = ?17, tag_genre = ?18,
-                duration_secs = ?19, updated_at = ?20
+                duration_secs = ?19, updated_at = ?20, missing_since = ?21,
+                audio_hash = ?22
              WHERE id = ?1",
             rusqlite::params![
                 item.id.to_string(),
@@ -150,6 +272,8 @@ impl Database {
                 item.tag_genre,
                 item.duration_secs,
                 item.updated_at.to_rfc3339(),
+                item.missing_since.map(|t| t.to_rfc3339()),
+                item.audio_hash,
             ],
         )?;
         Ok(())
@@ -190,9 +314,9 @@ impl Database {
                     file_size, file_mtime, file_hash, fingerprint, fingerprint_score,
                     tag_title, tag_artist, tag_album, tag_album_artist,
                     tag_track_number, tag_disc_number, tag_year, tag_genre,
-                    duration_secs, created_at, updated_at
+                    duration_secs, created_at, updated_at, missing_since, audio_hash
              FROM items
-             WHERE expression_id IS NULL
+             WHERE expression_id IS NULL AND missing_since IS NULL
              ORDER BY file_path",
         )?;
 
@@ -210,7 +334,7 @@ impl Database {
                     file_size, file_mtime, file_hash, fingerprint, fingerprint_score,
                     tag_title, tag_artist, tag_album, tag_album_artist,
                     tag_track_number, tag_disc_number, tag_year, tag_genre,
-                    duration_secs, created_at, updated_at
+                    duration_secs, created_at, updated_at, missing_since, audio_hash
              FROM items
              WHERE id = ?1",
         )?;
@@ -229,7 +353,7 @@ impl Database {
                     file_size, file_mtime, file_hash, fingerprint, fingerprint_score,
                     tag_title, tag_artist, tag_album, tag_album_artist,
                     tag_track_number, tag_disc_number, tag_year, tag_genre,
-                    duration_secs, created_at, updated_at
+                    duration_secs, created_at, updated_at, missing_since, audio_hash
              FROM items
              WHERE file_path = ?1",
         )?;
@@ -248,9 +372,9 @@ impl Database {
                     file_size, file_mtime, file_hash, fingerprint, fingerprint_score,
                     tag_title, tag_artist, tag_album, tag_album_artist,
                     tag_track_number, tag_disc_number, tag_year, tag_genre,
-                    duration_secs, created_at, updated_at
+                    duration_secs, created_at, updated_at, missing_since, audio_hash
              FROM items
-             WHERE expression_id IS NOT NULL
+             WHERE expression_id IS NOT NULL AND missing_since IS NULL
              ORDER BY file_path",
         )?;
 
@@ -268,7 +392,7 @@ impl Database {
                     file_size, file_mtime, file_hash, fingerprint, fingerprint_score,
                     tag_title, tag_artist, tag_album, tag_album_artist,
                     tag_track_number, tag_disc_number, tag_year, tag_genre,
-                    duration_secs, created_at, updated_at
+                    duration_secs, created_at, updated_at, missing_since, audio_hash
              FROM items
              ORDER BY file_path",
         )?;
@@ -287,19 +411,222 @@ impl Database {
                     file_size, file_mtime, file_hash, fingerprint, fingerprint_score,
                     tag_title, tag_artist, tag_album, tag_album_artist,
                     tag_track_number, tag_disc_number, tag_year, tag_genre,
-                    duration_secs, created_at, updated_at
+                    duration_secs, created_at, updated_at, missing_since, audio_hash
+             FROM items
+             WHERE fingerprint IS NULL AND missing_since IS NULL
+             ORDER BY file_path",
+        )?;
+
+        let items = stmt
+            .query_map([], Self::row_to_item)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(items)
+    }
+
+    /// List items whose files were missing at the last scan.
+    pub fn list_missing_items(&self) -> Result<Vec<Item>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, expression_id, manifestation_id, file_path, format,
+                    file_size, file_mtime, file_hash, fingerprint, fingerprint_score,
+                    tag_title, tag_artist, tag_album, tag_album_artist,
+                    tag_track_number, tag_disc_number, tag_year, tag_genre,
+                    duration_secs, created_at, updated_at, missing_since, audio_hash
+             FROM items
+             WHERE missing_since IS NOT NULL
+             ORDER BY file_path",
+        )?;
+
+        let items = stmt
+            .query_map([], Self::row_to_item)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(items)
+    }
+
+    /// Find items with the given fingerprint (e.g. to follow a moved file).
+    pub fn get_items_by_fingerprint(&self, fingerprint: &str) -> Result<Vec<Item>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, expression_id, manifestation_id, file_path, format,
+                    file_size, file_mtime, file_hash, fingerprint, fingerprint_score,
+                    tag_title, tag_artist, tag_album, tag_album_artist,
+                    tag_track_number, tag_disc_number, tag_year, tag_genre,
+                    duration_secs, created_at, updated_at, missing_since, audio_hash
+             FROM items
+             WHERE fingerprint = ?1
+             ORDER BY file_path",
+        )?;
+
+        let items = stmt
+            .query_map([fingerprint], Self::row_to_item)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(items)
+    }
+
+    /// Find items with the given content hash.
+    pub fn get_items_by_file_hash(&self, file_hash: &str) -> Result<Vec<Item>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, expression_id, manifestation_id, file_path, format,
+                    file_size, file_mtime, file_hash, fingerprint, fingerprint_score,
+                    tag_title, tag_artist, tag_album, tag_album_artist,
+                    tag_track_number, tag_disc_number, tag_year, tag_genre,
+                    duration_secs, created_at, updated_at, missing_since, audio_hash
+             FROM items
+             WHERE file_hash = ?1
+             ORDER BY file_path",
+        )?;
+
+        let items = stmt
+            .query_map([file_hash], Self::row_to_item)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(items)
+    }
+
+    /// Find items with the given audio stream hash.
+    pub fn get_items_by_audio_hash(&self, audio_hash: &str) -> Result<Vec<Item>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, expression_id, manifestation_id, file_path, format,
+                    file_size, file_mtime, file_hash, fingerprint, fingerprint_score,
+                    tag_title, tag_artist, tag_album, tag_album_artist,
+                    tag_track_number, tag_disc_number, tag_year, tag_genre,
+                    duration_secs, created_at, updated_at, missing_since, audio_hash
              FROM items
-             WHERE fingerprint IS NULL
+             WHERE audio_hash = ?1
              ORDER BY file_path",
         )?;
 
+        let items = stmt
+            .query_map([audio_hash], Self::row_to_item)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(items)
+    }
+
+    /// Groups of byte-identical files: present items sharing a `file_hash`.
+    pub fn list_duplicates_by_file_hash(&self) -> Result<Vec<Vec<Item>>> {
+        self.list_duplicates_by("file_hash", |item| item.file_hash.as_deref())
+    }
+
+    /// Groups of audio-identical files: present items sharing an
+    /// `audio_hash`, whatever their tags.
+    pub fn list_duplicates_by_audio_hash(&self) -> Result<Vec<Vec<Item>>> {
+        self.list_duplicates_by("audio_hash", |item| item.audio_hash.as_deref())
+    }
+
+    /// Group present items sharing a value in `column`, which `key` reads
+    /// back from an item.
+    fn list_duplicates_by(
+        &self,
+        column: &'static str,
+        key: fn(&Item) -> Option<&str>,
+    ) -> Result<Vec<Vec<Item>>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, expression_id, manifestation_id, file_path, format,
+                    file_size, file_mtime, file_hash, fingerprint, fingerprint_score,
+                    tag_title, tag_artist, tag_album, tag_album_artist,
+                    tag_track_number, tag_disc_number, tag_year, tag_genre,
+                    duration_secs, created_at, updated_at, missing_since, audio_hash
+             FROM items
+             WHERE missing_since IS NULL AND {column} IN (
+                 SELECT {column} FROM items
+                 WHERE {column} IS NOT NULL AND missing_since IS NULL
+                 GROUP BY {column} HAVING COUNT(*) > 1
+             )
+             ORDER BY {column}, file_path"
+        ))?;
+
         let items = stmt
             .query_map([], Self::row_to_item)?
             .collect::<rusqlite::Result<Vec<_>>>()?;
 
+        let mut groups: Vec<Vec<Item>> = Vec::new();
+        for item in items {
+            match groups.last_mut() {
+                Some(group) if group.first().and_then(key) == key(&item) => group.push(item),
+                _ => groups.push(vec![item]),
+            }
+        }
+        Ok(groups)
+    }
+
+    /// List one page of items, ordered by file path.
+    pub fn list_items_page(&self, page: Page) -> Result<Vec<Item>> {
+        let (limit, offset) = page.sql();
+        self.query_items(
+            "ORDER BY file_path LIMIT ?1 OFFSET ?2",
+            rusqlite::params![limit, offset],
+        )
+    }
+
+    /// List the items holding an expression, ordered by file path.
+    pub fn list_items_for_expression(&self, id: &ExpressionId) -> Result<Vec<Item>> {
+        self.query_items(
+            "WHERE expression_id = ?1 ORDER BY file_path",
+            rusqlite::params![id.to_string()],
+        )
+    }
+
+    /// List the items of a manifestation, in disc and track order.
+    pub fn list_items_for_manifestation(&self, id: &ManifestationId) -> Result<Vec<Item>> {
+        self.query_items(
+            "WHERE manifestation_id = ?1
+             ORDER BY tag_disc_number, tag_track_number, file_path",
+            rusqlite::params![id.to_string()],
+        )
+    }
+
+    /// Delete an item, with the assertions and proposals made about it.
+    ///
+    /// Returns whether the item existed.
+    pub fn delete_item(&self, id: &ItemId) -> Result<bool> {
+        self.in_transaction(|db| {
+            db.delete_entity_records(&id.to_string())?;
+            let deleted = db
+                .conn
+                .execute("DELETE FROM items WHERE id = ?1", [id.to_string()])?;
+            Ok(deleted > 0)
+        })
+    }
+
+    /// Select items with the given `WHERE`/`ORDER BY` clause.
+    fn query_items(&self, clause: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<Item>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, expression_id, manifestation_id, file_path, format,
+                    file_size, file_mtime, file_hash, fingerprint, fingerprint_score,
+                    tag_title, tag_artist, tag_album, tag_album_artist,
+                    tag_track_number, tag_disc_number, tag_year, tag_genre,
+                    duration_secs, created_at, updated_at, missing_since, audio_hash
+             FROM items
+             {clause}"
+        ))?;
+
+        let items = stmt
+            .query_map(params, Self::row_to_item)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
         Ok(items)
     }
 
+    /// Mark an item's file as missing from disk since `since`, or as present
+    /// again with `None`.
+    pub fn set_item_missing(
+        &self,
+        item_id: &ItemId,
+        since: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE items SET missing_since = ?2, updated_at = ?3 WHERE id = ?1",
+            rusqlite::params![
+                item_id.to_string(),
+                since.map(|t| t.to_rfc3339()),
+                chrono::Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
     #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
     fn row_to_item(row: &rusqlite::Row) -> rusqlite::Result<Item> {
         use crate::model::{AudioFormat, ExpressionId, ItemId, ManifestationId};
@@ -321,6 +648,7 @@ impl Database {
         let file_mtime_str: String = row.get(6)?;
         let created_at_str: String = row.get(19)?;
         let updated_at_str: String = row.get(20)?;
+        let missing_since_str: Option<String> = row.get(21)?;
 
         // Helper to parse optional UUID with proper error handling
         let parse_optional_uuid = |s: Option<String>, col: usize| -> rusqlite::Result<Option<Uuid>> {
@@ -354,6 +682,7 @@ impl Database {
                 ))?
                 .into(),
             file_hash: row.get(7)?,
+            audio_hash: row.get(22)?,
             fingerprint: row.get(8)?,
             fingerprint_score: row.get(9)?,
             tag_title: row.get(10)?,
@@ -375,14 +704,21 @@ impl Database {
                     20, rusqlite::types::Type::Text, Box::new(e)
                 ))?
                 .into(),
+            missing_since: missing_since_str
+                .map(|s| DateTime::parse_from_rfc3339(&s))
+                .transpose()
+                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(
+                    21, rusqlite::types::Type::Text, Box::new(e)
+                ))?
+                .map(Into::into),
         })
     }
 }
 
 // Assertion CRUD
 impl Database {
-    /// Insert a new assertion.
-    pub fn insert_assertion(&self, assertion: &Assertion) -> Result<()> {
+    /// Insert a new assertion, returning its row ID.
+    pub fn insert_assertion(&self, assertion: &Assertion) -> Result<i64> {
         self.conn.execute(
             "INSERT INTO assertions (entity_id, field, value, source, confidence, fetched_at)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
@@ -395,7 +731,48 @@ impl Database {
                 assertion.fetched_at.to_rfc3339(),
             ],
         )?;
-        Ok(())
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Insert many assertions in one transaction.
+    pub fn insert_assertions(&self, assertions: &[Assertion]) -> Result<()> {
+        self.in_transaction(|db| {
+            assertions
+                .iter()
+                .try_for_each(|a| db.insert_assertion(a).map(drop))
+        })
+    }
+
+    /// Delete an assertion by row ID, returning it if it existed.
+    pub fn delete_assertion_by_id(&self, id: i64) -> Result<Option<Assertion>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT entity_id, field, value, source, confidence, fetched_at
+             FROM assertions WHERE id = ?1",
+        )?;
+        let Some(assertion) = stmt.query_map([id], Self::row_to_assertion)?.next() else {
+            return Ok(None);
+        };
+        self.conn
+            .execute("DELETE FROM assertions WHERE id = ?1", [id])?;
+        Ok(Some(assertion?))
+    }
+
+    /// Delete an assertion, matching every stored column. Returns the number
+    /// of rows removed.
+    pub fn delete_assertion(&self, assertion: &Assertion) -> Result<usize> {
+        let deleted = self.conn.execute(
+            "DELETE FROM assertions
+             WHERE entity_id = ?1 AND field = ?2 AND value = ?3 AND source = ?4
+               AND fetched_at = ?5",
+            rusqlite::params![
+                assertion.entity_id,
+                assertion.field,
+                serde_json::to_string(&assertion.value)?,
+                format!("{:?}", assertion.source),
+                assertion.fetched_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(deleted)
     }
 
     /// Get all assertions for an entity.
@@ -415,7 +792,6 @@ impl Database {
     }
 
     fn row_to_assertion(row: &rusqlite::Row) -> rusqlite::Result<Assertion> {
-        use crate::provenance::Source;
         use chrono::DateTime;
 
         let entity_id: String = row.get(0)?;
@@ -427,23 +803,11 @@ impl Database {
 
         let value = serde_json::from_str(&value_str).unwrap_or(serde_json::Value::Null);
 
-        let source = match source_str.as_str() {
-            "EmbeddedTag" => Source::EmbeddedTag,
-            "AcoustId" => Source::AcoustId,
-            "MusicBrainz" => Source::MusicBrainz,
-            "Wikidata" => Source::Wikidata,
-            "LastFm" => Source::LastFm,
-            "Lcgft" => Source::Lcgft,
-            "Lcmpt" => Source::Lcmpt,
-            "Discogs" => Source::Discogs,
-            _ => Source::User,
-        };
-
         Ok(Assertion {
             entity_id,
             field,
             value,
-            source,
+            source: parse_source(&source_str),
             confidence,
             fetched_at: DateTime::parse_from_rfc3339(&fetched_at_str)
                 .map_err(|e| rusqlite::Error::FromSqlConversionFailure(
@@ -454,84 +818,535 @@ impl Database {
     }
 }
 
-// Work CRUD
+/// Parse a [`Source`] stored with its `Debug` name, defaulting to `User`.
+fn parse_source(s: &str) -> Source {
+    match s {
+        "EmbeddedTag" => Source::EmbeddedTag,
+        "AcoustId" => Source::AcoustId,
+        "Fingerprint" => Source::Fingerprint,
+        "MusicBrainz" => Source::MusicBrainz,
+        "Wikidata" => Source::Wikidata,
+        "LastFm" => Source::LastFm,
+        "Lcgft" => Source::Lcgft,
+        "Lcmpt" => Source::Lcmpt,
+        "Discogs" => Source::Discogs,
+        _ => Source::User,
+    }
+}
+
+// Proposal CRUD
 impl Database {
-    /// Insert a new work.
-    pub fn insert_work(&self, work: &Work) -> Result<()> {
+    /// Insert a proposal, returning its row ID.
+    pub fn insert_proposal(&self, proposal: &Proposal) -> Result<i64> {
         self.conn.execute(
-            "INSERT INTO works (
-                id, title, composer, musicbrainz_id, catalog_number,
-                key, composed_year, created_at, updated_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            "INSERT INTO proposals (entity_id, field, value, rule_name, source, confidence,
+                                    alternatives, status, created_at, decided_at,
+                                    override_assertion_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
             rusqlite::params![
-                work.id.to_string(),
-                work.title,
-                work.composer,
-                work.musicbrainz_id,
-                work.catalog_number,
-                work.key,
-                work.composed_year.map(i64::from),
-                work.created_at.to_rfc3339(),
-                work.updated_at.to_rfc3339(),
+                proposal.entity_id,
+                proposal.field,
+                proposal.value,
+                proposal.rule_name,
+                format!("{:?}", proposal.source),
+                proposal.confidence,
+                serde_json::to_string(&proposal.alternatives)?,
+                proposal.status.as_str(),
+                proposal.created_at.to_rfc3339(),
+                proposal.decided_at.map(|t| t.to_rfc3339()),
+                proposal.override_assertion_id,
             ],
         )?;
-        Ok(())
+        Ok(self.conn.last_insert_rowid())
     }
 
-    /// Insert or replace a work (upsert by primary key).
-    pub fn upsert_work(&self, work: &Work) -> Result<()> {
-        self.conn.execute(
-            "INSERT OR REPLACE INTO works (
-                id, title, composer, musicbrainz_id, catalog_number,
-                key, composed_year, created_at, updated_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-            rusqlite::params![
-                work.id.to_string(),
-                work.title,
-                work.composer,
-                work.musicbrainz_id,
-                work.catalog_number,
-                work.key,
-                work.composed_year.map(i64::from),
-                work.created_at.to_rfc3339(),
-                work.updated_at.to_rfc3339(),
-            ],
+    /// Replace the pending proposals for an entity with a fresh set.
+    ///
+    /// Decided proposals are kept, and a new proposal is skipped when the
+    /// same field and value has already been decided, so re-running
+    /// harmonization does not re-open settled reviews. Returns the number
+    /// of proposals inserted.
+    pub fn replace_pending_proposals(
+        &self,
+        entity_id: &str,
+        proposals: &[Proposal],
+    ) -> Result<usize> {
+        self.in_transaction(|db| {
+            db.conn.execute(
+                "DELETE FROM proposals WHERE entity_id = ?1 AND status = 'pending'",
+                [entity_id],
+            )?;
+
+            let mut inserted = 0;
+            for proposal in proposals {
+                if db.insert_new_proposal(proposal)? {
+                    inserted += 1;
+                }
+            }
+            Ok(inserted)
+        })
+    }
+
+    /// Insert a proposal unless the entity already has one, pending or
+    /// decided, for the same field and value. Returns whether it was
+    /// inserted.
+    pub fn insert_new_proposal(&self, proposal: &Proposal) -> Result<bool> {
+        let exists: bool = self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM proposals
+                           WHERE entity_id = ?1 AND field = ?2 AND value = ?3)",
+            rusqlite::params![proposal.entity_id, proposal.field, proposal.value],
+            |row| row.get(0),
         )?;
-        Ok(())
+        if !exists {
+            self.insert_proposal(proposal)?;
+        }
+        Ok(!exists)
     }
 
-    /// Look up a work by its `MusicBrainz` ID.
-    pub fn get_work_by_musicbrainz_id(&self, mbid: &str) -> Result<Option<Work>> {
+    /// Look up a proposal by row ID.
+    pub fn get_proposal(&self, id: i64) -> Result<Option<Proposal>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, title, composer, musicbrainz_id, catalog_number,
-                    key, composed_year, created_at, updated_at
-             FROM works
-             WHERE musicbrainz_id = ?1",
+            "SELECT id, entity_id, field, value, rule_name, source, confidence,
+                    alternatives, status, created_at, decided_at, override_assertion_id
+             FROM proposals WHERE id = ?1",
         )?;
 
-        let mut rows = stmt.query_map(rusqlite::params![mbid], Self::row_to_work)?;
+        let mut rows = stmt.query_map([id], Self::row_to_proposal)?;
+        Ok(rows.next().transpose()?)
+    }
 
-        match rows.next() {
-            Some(row) => Ok(Some(row?)),
-            None => Ok(None),
-        }
+    /// Get all proposals for an entity, ordered by field and descending
+    /// confidence.
+    pub fn get_proposals_for_entity(&self, entity_id: &str) -> Result<Vec<Proposal>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, entity_id, field, value, rule_name, source, confidence,
+                    alternatives, status, created_at, decided_at, override_assertion_id
+             FROM proposals
+             WHERE entity_id = ?1
+             ORDER BY field, confidence DESC, id",
+        )?;
+
+        let proposals = stmt
+            .query_map([entity_id], Self::row_to_proposal)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(proposals)
     }
 
-    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-    fn row_to_work(row: &rusqlite::Row) -> rusqlite::Result<Work> {
-        use chrono::DateTime;
-        use uuid::Uuid;
+    /// List all proposals with the given status.
+    pub fn list_proposals_by_status(&self, status: ProposalStatus) -> Result<Vec<Proposal>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, entity_id, field, value, rule_name, source, confidence,
+                    alternatives, status, created_at, decided_at, override_assertion_id
+             FROM proposals
+             WHERE status = ?1
+             ORDER BY entity_id, field, confidence DESC, id",
+        )?;
 
-        let id_str: String = row.get(0)?;
-        let created_at_str: String = row.get(7)?;
-        let updated_at_str: String = row.get(8)?;
+        let proposals = stmt
+            .query_map([status.as_str()], Self::row_to_proposal)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
 
-        Ok(Work {
-            id: WorkId::from_uuid(
-                Uuid::parse_str(&id_str)
-                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(
-                        0, rusqlite::types::Type::Text, Box::new(e)
-                    ))?
+        Ok(proposals)
+    }
+
+    /// Count proposals by status.
+    pub fn count_proposals_by_status(&self) -> Result<Vec<(ProposalStatus, usize)>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT status, COUNT(*) FROM proposals GROUP BY status ORDER BY status")?;
+
+        let counts = stmt
+            .query_map([], |row| {
+                let status: String = row.get(0)?;
+                let count: i64 = row.get(1)?;
+                Ok((status, count))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        counts
+            .into_iter()
+            .map(|(status, count)| {
+                Ok((status.parse()?, usize::try_from(count).unwrap_or_default()))
+            })
+            .collect()
+    }
+
+    /// Count proposals by entity and status, for callers that only report
+    /// on some entities.
+    pub fn count_proposals_by_entity_and_status(
+        &self,
+    ) -> Result<Vec<(String, ProposalStatus, usize)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT entity_id, status, COUNT(*) FROM proposals
+             GROUP BY entity_id, status ORDER BY entity_id, status",
+        )?;
+
+        let counts = stmt
+            .query_map([], |row| {
+                let entity_id: String = row.get(0)?;
+                let status: String = row.get(1)?;
+                let count: i64 = row.get(2)?;
+                Ok((entity_id, status, count))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        counts
+            .into_iter()
+            .map(|(entity_id, status, count)| {
+                Ok((
+                    entity_id,
+                    status.parse()?,
+                    usize::try_from(count).unwrap_or_default(),
+                ))
+            })
+            .collect()
+    }
+
+    /// Record a review decision on a proposal.
+    ///
+    /// Setting a proposal back to [`ProposalStatus::Pending`] clears its
+    /// decision time.
+    pub fn set_proposal_status(&self, id: i64, status: ProposalStatus) -> Result<()> {
+        let decided_at =
+            (status != ProposalStatus::Pending).then(|| chrono::Utc::now().to_rfc3339());
+        let updated = self.conn.execute(
+            "UPDATE proposals SET status = ?1, decided_at = ?2 WHERE id = ?3",
+            rusqlite::params![status.as_str(), decided_at, id],
+        )?;
+        if updated == 0 {
+            return Err(crate::Error::NotFound {
+                entity: "proposal",
+                id: id.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Write a proposal's whole review state: its status, decision time and
+    /// the user assertion recording an override. Used to apply a decision
+    /// and to restore the previous state when it is undone.
+    pub fn set_proposal_decision(
+        &self,
+        id: i64,
+        status: ProposalStatus,
+        decided_at: Option<chrono::DateTime<chrono::Utc>>,
+        override_assertion_id: Option<i64>,
+    ) -> Result<()> {
+        let updated = self.conn.execute(
+            "UPDATE proposals SET status = ?1, decided_at = ?2, override_assertion_id = ?3
+             WHERE id = ?4",
+            rusqlite::params![
+                status.as_str(),
+                decided_at.map(|t| t.to_rfc3339()),
+                override_assertion_id,
+                id
+            ],
+        )?;
+        if updated == 0 {
+            return Err(crate::Error::NotFound {
+                entity: "proposal",
+                id: id.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Delete all proposals for an entity.
+    pub fn delete_proposals_for_entity(&self, entity_id: &str) -> Result<usize> {
+        let deleted = self
+            .conn
+            .execute("DELETE FROM proposals WHERE entity_id = ?1", [entity_id])?;
+        Ok(deleted)
+    }
+
+    /// Delete the assertions and proposals about an entity that is being
+    /// deleted.
+    fn delete_entity_records(&self, entity_id: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM assertions WHERE entity_id = ?1", [entity_id])?;
+        self.delete_proposals_for_entity(entity_id)?;
+        Ok(())
+    }
+
+    fn row_to_proposal(row: &rusqlite::Row) -> rusqlite::Result<Proposal> {
+        use chrono::DateTime;
+
+        let parse_time = |idx: usize, value: &str| {
+            DateTime::parse_from_rfc3339(value)
+                .map(Into::into)
+                .map_err(|e| {
+                    rusqlite::Error::FromSqlConversionFailure(
+                        idx,
+                        rusqlite::types::Type::Text,
+                        Box::new(e),
+                    )
+                })
+        };
+
+        let source_str: String = row.get(5)?;
+        let alternatives_str: String = row.get(7)?;
+        let status_str: String = row.get(8)?;
+        let created_at_str: String = row.get(9)?;
+        let decided_at_str: Option<String> = row.get(10)?;
+
+        Ok(Proposal {
+            id: row.get(0)?,
+            entity_id: row.get(1)?,
+            field: row.get(2)?,
+            value: row.get(3)?,
+            rule_name: row.get(4)?,
+            source: parse_source(&source_str),
+            confidence: row.get(6)?,
+            alternatives: serde_json::from_str(&alternatives_str).unwrap_or_default(),
+            status: status_str.parse().map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    8,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                )
+            })?,
+            created_at: parse_time(9, &created_at_str)?,
+            decided_at: decided_at_str.map(|s| parse_time(10, &s)).transpose()?,
+            override_assertion_id: row.get(11)?,
+        })
+    }
+}
+
+// Work CRUD
+impl Database {
+    /// Insert a new work.
+    pub fn insert_work(&self, work: &Work) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO works (
+                id, title, composer, musicbrainz_id, catalog_number,
+                key, composed_year, created_at, updated_at,
+                parent_work_id, part_number, movement_label
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            rusqlite::params![
+                work.id.to_string(),
+                work.title,
+                work.composer,
+                work.musicbrainz_id,
+                work.catalog_number,
+                work.key,
+                work.composed_year.map(i64::from),
+                work.created_at.to_rfc3339(),
+                work.updated_at.to_rfc3339(),
+                work.parent_work_id.map(|id| id.to_string()),
+                work.part_number,
+                work.movement_label,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Insert or replace a work (upsert by primary key).
+    pub fn upsert_work(&self, work: &Work) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO works (
+                id, title, composer, musicbrainz_id, catalog_number,
+                key, composed_year, created_at, updated_at,
+                parent_work_id, part_number, movement_label
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            rusqlite::params![
+                work.id.to_string(),
+                work.title,
+                work.composer,
+                work.musicbrainz_id,
+                work.catalog_number,
+                work.key,
+                work.composed_year.map(i64::from),
+                work.created_at.to_rfc3339(),
+                work.updated_at.to_rfc3339(),
+                work.parent_work_id.map(|id| id.to_string()),
+                work.part_number,
+                work.movement_label,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Look up a work by its `MusicBrainz` ID.
+    pub fn get_work_by_musicbrainz_id(&self, mbid: &str) -> Result<Option<Work>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, composer, musicbrainz_id, catalog_number,
+                    key, composed_year, created_at, updated_at,
+                    parent_work_id, part_number, movement_label
+             FROM works
+             WHERE musicbrainz_id = ?1",
+        )?;
+
+        let mut rows = stmt.query_map(rusqlite::params![mbid], Self::row_to_work)?;
+
+        match rows.next() {
+            Some(row) => Ok(Some(row?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Look up a work by ID.
+    pub fn get_work_by_id(&self, id: &WorkId) -> Result<Option<Work>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, composer, musicbrainz_id, catalog_number,
+                    key, composed_year, created_at, updated_at,
+                    parent_work_id, part_number, movement_label
+             FROM works
+             WHERE id = ?1",
+        )?;
+
+        let mut rows = stmt.query_map(rusqlite::params![id.to_string()], Self::row_to_work)?;
+
+        match rows.next() {
+            Some(row) => Ok(Some(row?)),
+            None => Ok(None),
+        }
+    }
+
+    /// List all works, ordered by title.
+    pub fn list_works(&self) -> Result<Vec<Work>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, composer, musicbrainz_id, catalog_number,
+                    key, composed_year, created_at, updated_at,
+                    parent_work_id, part_number, movement_label
+             FROM works
+             ORDER BY title",
+        )?;
+
+        let works = stmt
+            .query_map([], Self::row_to_work)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(works)
+    }
+
+    /// List one page of works, ordered by title.
+    pub fn list_works_page(&self, page: Page) -> Result<Vec<Work>> {
+        let (limit, offset) = page.sql();
+        self.query_works(
+            "ORDER BY title LIMIT ?1 OFFSET ?2",
+            rusqlite::params![limit, offset],
+        )
+    }
+
+    /// List the works by a composer, matched case-insensitively, ordered by
+    /// title.
+    pub fn list_works_by_composer(&self, composer: &str) -> Result<Vec<Work>> {
+        self.query_works(
+            "WHERE composer = ?1 COLLATE NOCASE ORDER BY title",
+            rusqlite::params![composer],
+        )
+    }
+
+    /// List the movements and other parts of a work, in order.
+    ///
+    /// Parts without a part number come last, ordered by title.
+    pub fn list_movements(&self, parent: &WorkId) -> Result<Vec<Work>> {
+        self.query_works(
+            "WHERE parent_work_id = ?1 ORDER BY part_number IS NULL, part_number, title",
+            rusqlite::params![parent.to_string()],
+        )
+    }
+
+    /// List the works that are not part of another work, ordered by title.
+    pub fn list_top_level_works(&self) -> Result<Vec<Work>> {
+        self.query_works("WHERE parent_work_id IS NULL ORDER BY title", &[])
+    }
+
+    /// The work at the top of a work's hierarchy: the work itself unless it
+    /// is a movement, else its parent's root.
+    ///
+    /// A parent that no longer exists, or a cycle, ends the walk at the
+    /// last work reached. Returns `None` if the work itself does not exist.
+    pub fn get_root_work(&self, id: &WorkId) -> Result<Option<Work>> {
+        let Some(mut work) = self.get_work_by_id(id)? else {
+            return Ok(None);
+        };
+        let mut seen = std::collections::HashSet::from([work.id]);
+        while let Some(parent_id) = work.parent_work_id {
+            if !seen.insert(parent_id) {
+                break;
+            }
+            match self.get_work_by_id(&parent_id)? {
+                Some(parent) => work = parent,
+                None => break,
+            }
+        }
+        Ok(Some(work))
+    }
+
+    /// Delete a work together with its credits and expressions (see
+    /// [`delete_expression`](Self::delete_expression)).
+    ///
+    /// Its movements are kept, as works of their own.
+    ///
+    /// Returns whether the work existed.
+    pub fn delete_work(&self, id: &WorkId) -> Result<bool> {
+        self.in_transaction(|db| {
+            for expr in db.list_expressions_for_work(id)? {
+                db.delete_expression(&expr.id)?;
+            }
+            db.conn.execute(
+                "UPDATE works SET parent_work_id = NULL, updated_at = ?2
+                 WHERE parent_work_id = ?1",
+                [id.to_string(), chrono::Utc::now().to_rfc3339()],
+            )?;
+            db.conn.execute(
+                "DELETE FROM work_credits WHERE work_id = ?1",
+                [id.to_string()],
+            )?;
+            db.delete_entity_records(&id.to_string())?;
+            let deleted = db
+                .conn
+                .execute("DELETE FROM works WHERE id = ?1", [id.to_string()])?;
+            Ok(deleted > 0)
+        })
+    }
+
+    /// Select works with the given `WHERE`/`ORDER BY` clause.
+    fn query_works(&self, clause: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<Work>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, title, composer, musicbrainz_id, catalog_number,
+                    key, composed_year, created_at, updated_at,
+                    parent_work_id, part_number, movement_label
+             FROM works
+             {clause}"
+        ))?;
+
+        let works = stmt
+            .query_map(params, Self::row_to_work)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(works)
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn row_to_work(row: &rusqlite::Row) -> rusqlite::Result<Work> {
+        use chrono::DateTime;
+        use uuid::Uuid;
+
+        let id_str: String = row.get(0)?;
+        let created_at_str: String = row.get(7)?;
+        let updated_at_str: String = row.get(8)?;
+        let parent_work_id = row
+            .get::<_, Option<String>>(9)?
+            .map(|id| {
+                Uuid::parse_str(&id).map(WorkId::from_uuid).map_err(|e| {
+                    rusqlite::Error::FromSqlConversionFailure(
+                        9,
+                        rusqlite::types::Type::Text,
+                        Box::new(e),
+                    )
+                })
+            })
+            .transpose()?;
+
+        Ok(Work {
+            id: WorkId::from_uuid(
+                Uuid::parse_str(&id_str)
+                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(
+                        0, rusqlite::types::Type::Text, Box::new(e)
+                    ))?
             ),
             title: row.get(1)?,
             composer: row.get(2)?,
@@ -549,6 +1364,9 @@ impl Database {
                     8, rusqlite::types::Type::Text, Box::new(e)
                 ))?
                 .into(),
+            parent_work_id,
+            part_number: row.get(10)?,
+            movement_label: row.get(11)?,
         })
     }
 }
@@ -560,8 +1378,9 @@ impl Database {
         self.conn.execute(
             "INSERT INTO expressions (
                 id, work_id, title, musicbrainz_id, conductor_id,
-                recorded_year, duration_secs, created_at, updated_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                recorded_year, duration_secs, created_at, updated_at,
+                recorded_from, recorded_until, venue, live
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
             rusqlite::params![
                 expr.id.to_string(),
                 expr.work_id.to_string(),
@@ -572,18 +1391,14 @@ impl Database {
                 expr.duration_secs,
                 expr.created_at.to_rfc3339(),
                 expr.updated_at.to_rfc3339(),
+                expr.recorded_from,
+                expr.recorded_until,
+                expr.venue,
+                expr.live,
             ],
         )?;
 
-        for performer_id in &expr.performer_ids {
-            self.conn.execute(
-                "INSERT INTO expression_performers (expression_id, artist_id)
-                 VALUES (?1, ?2)",
-                rusqlite::params![expr.id.to_string(), performer_id.to_string()],
-            )?;
-        }
-
-        Ok(())
+        self.insert_performers(expr)
     }
 
     /// Insert or replace an expression and update its performer associations.
@@ -591,8 +1406,9 @@ impl Database {
         self.conn.execute(
             "INSERT OR REPLACE INTO expressions (
                 id, work_id, title, musicbrainz_id, conductor_id,
-                recorded_year, duration_secs, created_at, updated_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                recorded_year, duration_secs, created_at, updated_at,
+                recorded_from, recorded_until, venue, live
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
             rusqlite::params![
                 expr.id.to_string(),
                 expr.work_id.to_string(),
@@ -603,6 +1419,10 @@ impl Database {
                 expr.duration_secs,
                 expr.created_at.to_rfc3339(),
                 expr.updated_at.to_rfc3339(),
+                expr.recorded_from,
+                expr.recorded_until,
+                expr.venue,
+                expr.live,
             ],
         )?;
 
@@ -612,79 +1432,142 @@ impl Database {
             rusqlite::params![expr.id.to_string()],
         )?;
 
-        for performer_id in &expr.performer_ids {
+        self.insert_performers(expr)
+    }
+
+    /// Insert the performer credits of an expression, in credit order.
+    fn insert_performers(&self, expr: &Expression) -> Result<()> {
+        for performer in &expr.performers {
             self.conn.execute(
-                "INSERT INTO expression_performers (expression_id, artist_id)
-                 VALUES (?1, ?2)",
-                rusqlite::params![expr.id.to_string(), performer_id.to_string()],
+                "INSERT INTO expression_performers (
+                    expression_id, artist_id, role, lcmpt_uri, kind, credited_as
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                rusqlite::params![
+                    expr.id.to_string(),
+                    performer.artist_id.to_string(),
+                    performer.role,
+                    performer.lcmpt_uri,
+                    performer.kind.map(PerformerKind::as_str),
+                    performer.credited_as,
+                ],
             )?;
         }
-
         Ok(())
     }
 
-    /// List all expressions, including their performer IDs.
+    /// List all expressions, including their performers.
     pub fn list_expressions(&self) -> Result<Vec<Expression>> {
-        use std::collections::HashMap;
+        self.query_expressions("ORDER BY title", &[])
+    }
 
-        // Query 1: Get all expressions
-        let mut stmt = self.conn.prepare(
+    /// List one page of expressions, ordered by title.
+    pub fn list_expressions_page(&self, page: Page) -> Result<Vec<Expression>> {
+        let (limit, offset) = page.sql();
+        self.query_expressions(
+            "ORDER BY title LIMIT ?1 OFFSET ?2",
+            rusqlite::params![limit, offset],
+        )
+    }
+
+    /// List the expressions (recordings) of a work, oldest first.
+    pub fn list_expressions_for_work(&self, id: &WorkId) -> Result<Vec<Expression>> {
+        self.query_expressions(
+            "WHERE work_id = ?1 ORDER BY recorded_year, title",
+            rusqlite::params![id.to_string()],
+        )
+    }
+
+    /// List the expressions an artist performs on or conducts, ordered by
+    /// title.
+    pub fn list_expressions_for_artist(&self, id: &ArtistId) -> Result<Vec<Expression>> {
+        self.query_expressions(
+            "WHERE conductor_id = ?1 OR id IN (
+                 SELECT expression_id FROM expression_performers WHERE artist_id = ?1
+             )
+             ORDER BY title",
+            rusqlite::params![id.to_string()],
+        )
+    }
+
+    /// Select expressions with the given `WHERE`/`ORDER BY` clause, filling
+    /// in their performers with one more query rather than one per
+    /// expression.
+    fn query_expressions(
+        &self,
+        clause: &str,
+        params: &[&dyn rusqlite::ToSql],
+    ) -> Result<Vec<Expression>> {
+        let mut stmt = self.conn.prepare(&format!(
             "SELECT id, work_id, title, musicbrainz_id, conductor_id,
-                    recorded_year, duration_secs, created_at, updated_at
+                    recorded_year, duration_secs, created_at, updated_at,
+                    recorded_from, recorded_until, venue, live
              FROM expressions
-             ORDER BY title",
-        )?;
+             {clause}"
+        ))?;
 
         let mut expressions = stmt
-            .query_map([], Self::row_to_expression)?
+            .query_map(params, Self::row_to_expression)?
             .collect::<rusqlite::Result<Vec<_>>>()?;
+        if expressions.is_empty() {
+            return Ok(expressions);
+        }
 
-        // Query 2: Get ALL performers for ALL expressions in one query (fixes N+1)
-        let mut perf_stmt = self.conn.prepare(
-            "SELECT expression_id, artist_id
-             FROM expression_performers
-             ORDER BY expression_id",
+        let credits = self.query_performer_credits(
+            &format!(
+                "WHERE expression_id IN (SELECT id FROM expressions {clause})
+                 ORDER BY expression_id, rowid"
+            ),
+            params,
         )?;
-
-        // Build a map: expression_id -> Vec<artist_id>
-        let mut performers_map: HashMap<ExpressionId, Vec<ArtistId>> = HashMap::new();
-        let rows = perf_stmt.query_map([], |row| {
-            let expr_id_str: String = row.get(0)?;
-            let artist_id_str: String = row.get(1)?;
-            Ok((expr_id_str, artist_id_str))
-        })?;
-
-        for row_result in rows {
-            let (expr_id_str, artist_id_str) = row_result?;
-
-            let expr_id = uuid::Uuid::parse_str(&expr_id_str)
-                .map(ExpressionId::from_uuid)
-                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(
-                    0, rusqlite::types::Type::Text, Box::new(e)
-                ))?;
-
-            let artist_id = uuid::Uuid::parse_str(&artist_id_str)
-                .map(ArtistId::from_uuid)
-                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(
-                    1, rusqlite::types::Type::Text, Box::new(e)
-                ))?;
-
-            performers_map.entry(expr_id).or_default().push(artist_id);
+        let mut performers_map: HashMap<ExpressionId, Vec<Performer>> = HashMap::new();
+        for credit in credits {
+            performers_map
+                .entry(credit.expression_id)
+                .or_default()
+                .push(credit.performer);
         }
 
-        // Populate performer IDs from the map
         for expr in &mut expressions {
-            expr.performer_ids = performers_map.remove(&expr.id).unwrap_or_default();
+            expr.performers = performers_map.remove(&expr.id).unwrap_or_default();
         }
 
         Ok(expressions)
     }
 
-    /// Look up an expression by its `MusicBrainz` ID, including performer IDs.
+    /// Delete an expression and its performer credits and release track
+    /// links. Items holding it are kept but no longer identified.
+    ///
+    /// Returns whether the expression existed.
+    pub fn delete_expression(&self, id: &ExpressionId) -> Result<bool> {
+        self.in_transaction(|db| {
+            let id = id.to_string();
+            db.conn.execute(
+                "DELETE FROM expression_performers WHERE expression_id = ?1",
+                [&id],
+            )?;
+            db.conn.execute(
+                "DELETE FROM manifestation_expressions WHERE expression_id = ?1",
+                [&id],
+            )?;
+            db.conn.execute(
+                "UPDATE items SET expression_id = NULL, updated_at = ?2
+                 WHERE expression_id = ?1",
+                rusqlite::params![id, chrono::Utc::now().to_rfc3339()],
+            )?;
+            db.delete_entity_records(&id)?;
+            let deleted = db
+                .conn
+                .execute("DELETE FROM expressions WHERE id = ?1", [&id])?;
+            Ok(deleted > 0)
+        })
+    }
+
+    /// Look up an expression by its `MusicBrainz` ID, including performers.
     pub fn get_expression_by_musicbrainz_id(&self, mbid: &str) -> Result<Option<Expression>> {
         let mut stmt = self.conn.prepare(
             "SELECT id, work_id, title, musicbrainz_id, conductor_id,
-                    recorded_year, duration_secs, created_at, updated_at
+                    recorded_year, duration_secs, created_at, updated_at,
+                    recorded_from, recorded_until, venue, live
              FROM expressions
              WHERE musicbrainz_id = ?1",
         )?;
@@ -694,28 +1577,96 @@ impl Database {
         match rows.next() {
             Some(row) => {
                 let mut expr: Expression = row?;
-                // Fetch performer IDs from junction table
-                let mut perf_stmt = self.conn.prepare(
-                    "SELECT artist_id FROM expression_performers
-                     WHERE expression_id = ?1",
-                )?;
-                let performer_ids = perf_stmt
-                    .query_map(rusqlite::params![expr.id.to_string()], |row| {
-                        let id_str: String = row.get(0)?;
-                        uuid::Uuid::parse_str(&id_str)
-                            .map(ArtistId::from_uuid)
-                            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(
-                                0, rusqlite::types::Type::Text, Box::new(e)
-                            ))
-                    })?
-                    .collect::<rusqlite::Result<Vec<_>>>()?;
-                expr.performer_ids = performer_ids;
+                expr.performers = self.get_performers(&expr.id)?;
+                Ok(Some(expr))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Look up an expression by ID, including performers.
+    pub fn get_expression_by_id(&self, id: &ExpressionId) -> Result<Option<Expression>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, work_id, title, musicbrainz_id, conductor_id,
+                    recorded_year, duration_secs, created_at, updated_at,
+                    recorded_from, recorded_until, venue, live
+             FROM expressions
+             WHERE id = ?1",
+        )?;
+
+        let mut rows =
+            stmt.query_map(rusqlite::params![id.to_string()], Self::row_to_expression)?;
+
+        match rows.next() {
+            Some(row) => {
+                let mut expr: Expression = row?;
+                expr.performers = self.get_performers(&expr.id)?;
                 Ok(Some(expr))
             }
             None => Ok(None),
         }
     }
 
+    /// Fetch the performers of one expression from the junction table.
+    fn get_performers(&self, id: &ExpressionId) -> Result<Vec<Performer>> {
+        let credits = self.query_performer_credits(
+            "WHERE expression_id = ?1 ORDER BY rowid",
+            rusqlite::params![id.to_string()],
+        )?;
+        Ok(credits.into_iter().map(|c| c.performer).collect())
+    }
+
+    /// List every performer credit (the `expression_performers` junction).
+    pub fn list_expression_performers(&self) -> Result<Vec<PerformerCredit>> {
+        self.query_performer_credits("ORDER BY expression_id, rowid", &[])
+    }
+
+    /// Select performer credits with the given `WHERE`/`ORDER BY` clause.
+    fn query_performer_credits(
+        &self,
+        clause: &str,
+        params: &[&dyn rusqlite::ToSql],
+    ) -> Result<Vec<PerformerCredit>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT expression_id, artist_id, role, lcmpt_uri, kind, credited_as
+             FROM expression_performers
+             {clause}"
+        ))?;
+        let rows = stmt
+            .query_map(params, |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                    row.get::<_, Option<String>>(5)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        rows.into_iter()
+            .map(
+                |(expression_id, artist_id, role, lcmpt_uri, kind, credited_as)| {
+                    let parse = |id: &str| {
+                        uuid::Uuid::parse_str(id)
+                            .map_err(|e| Error::InvalidData(format!("Invalid ID '{id}': {e}")))
+                    };
+                    Ok(PerformerCredit {
+                        expression_id: ExpressionId::from_uuid(parse(&expression_id)?),
+                        performer: Performer {
+                            artist_id: ArtistId::from_uuid(parse(&artist_id)?),
+                            role,
+                            lcmpt_uri,
+                            kind: kind.as_deref().map(str::parse).transpose()?,
+                            credited_as,
+                        },
+                    })
+                },
+            )
+            .collect()
+    }
+
     #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
     fn row_to_expression(row: &rusqlite::Row) -> rusqlite::Result<Expression> {
         use chrono::DateTime;
@@ -753,9 +1704,13 @@ impl Database {
             ),
             title: row.get(2)?,
             musicbrainz_id: row.get(3)?,
-            performer_ids: Vec::new(), // populated after query
+            performers: Vec::new(), // populated after query
             conductor_id: parse_optional_artist_uuid(conductor_id_str, 4)?,
             recorded_year: row.get::<_, Option<i64>>(5)?.map(|v| v as i32),
+            recorded_from: row.get(9)?,
+            recorded_until: row.get(10)?,
+            venue: row.get(11)?,
+            live: row.get(12)?,
             duration_secs: row.get(6)?,
             created_at: DateTime::parse_from_rfc3339(&created_at_str)
                 .map_err(|e| rusqlite::Error::FromSqlConversionFailure(
@@ -841,6 +1796,112 @@ impl Database {
         }
     }
 
+    /// Look up a manifestation by ID.
+    pub fn get_manifestation_by_id(&self, id: &ManifestationId) -> Result<Option<Manifestation>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, musicbrainz_id, label, catalog_number,
+                    release_year, track_count, disc_count, format,
+                    created_at, updated_at
+             FROM manifestations
+             WHERE id = ?1",
+        )?;
+
+        let mut rows =
+            stmt.query_map(rusqlite::params![id.to_string()], Self::row_to_manifestation)?;
+
+        match rows.next() {
+            Some(row) => Ok(Some(row?)),
+            None => Ok(None),
+        }
+    }
+
+    /// List all manifestations, ordered by title.
+    pub fn list_manifestations(&self) -> Result<Vec<Manifestation>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, musicbrainz_id, label, catalog_number,
+                    release_year, track_count, disc_count, format,
+                    created_at, updated_at
+             FROM manifestations
+             ORDER BY title",
+        )?;
+
+        let manifestations = stmt
+            .query_map([], Self::row_to_manifestation)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(manifestations)
+    }
+
+    /// List one page of manifestations, ordered by title.
+    pub fn list_manifestations_page(&self, page: Page) -> Result<Vec<Manifestation>> {
+        let (limit, offset) = page.sql();
+        self.query_manifestations(
+            "ORDER BY title LIMIT ?1 OFFSET ?2",
+            rusqlite::params![limit, offset],
+        )
+    }
+
+    /// List the manifestations (releases) an expression appears on, oldest
+    /// first.
+    pub fn list_manifestations_for_expression(
+        &self,
+        id: &ExpressionId,
+    ) -> Result<Vec<Manifestation>> {
+        self.query_manifestations(
+            "WHERE id IN (
+                 SELECT manifestation_id FROM manifestation_expressions
+                 WHERE expression_id = ?1
+             )
+             ORDER BY release_year, title",
+            rusqlite::params![id.to_string()],
+        )
+    }
+
+    /// Delete a manifestation and its release track links. Items of it are
+    /// kept but no longer linked to a release.
+    ///
+    /// Returns whether the manifestation existed.
+    pub fn delete_manifestation(&self, id: &ManifestationId) -> Result<bool> {
+        self.in_transaction(|db| {
+            let id = id.to_string();
+            db.conn.execute(
+                "DELETE FROM manifestation_expressions WHERE manifestation_id = ?1",
+                [&id],
+            )?;
+            db.conn.execute(
+                "UPDATE items SET manifestation_id = NULL, updated_at = ?2
+                 WHERE manifestation_id = ?1",
+                rusqlite::params![id, chrono::Utc::now().to_rfc3339()],
+            )?;
+            db.delete_entity_records(&id)?;
+            let deleted = db
+                .conn
+                .execute("DELETE FROM manifestations WHERE id = ?1", [&id])?;
+            Ok(deleted > 0)
+        })
+    }
+
+    /// Select manifestations with the given `WHERE`/`ORDER BY` clause.
+    fn query_manifestations(
+        &self,
+        clause: &str,
+        params: &[&dyn rusqlite::ToSql],
+    ) -> Result<Vec<Manifestation>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, title, musicbrainz_id, label, catalog_number,
+                    release_year, track_count, disc_count, format,
+                    created_at, updated_at
+             FROM manifestations
+             {clause}"
+        ))?;
+
+        let manifestations = stmt
+            .query_map(params, Self::row_to_manifestation)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(manifestations)
+    }
+
     #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
     fn row_to_manifestation(row: &rusqlite::Row) -> rusqlite::Result<Manifestation> {
         use chrono::DateTime;
@@ -952,30 +2013,203 @@ impl Database {
         match rows.next() {
             Some(row) => {
                 let mut artist: Artist = row?;
-                // Fetch roles from junction table
-                let mut role_stmt = self
-                    .conn
-                    .prepare("SELECT role FROM artist_roles WHERE artist_id = ?1")?;
-                let roles = role_stmt
-                    .query_map(rusqlite::params![artist.id.to_string()], |row| {
-                        let role_str: String = row.get(0)?;
-                        Ok(match role_str.as_str() {
-                            "Composer" => ArtistRole::Composer,
-                            "Performer" => ArtistRole::Performer,
-                            "Conductor" => ArtistRole::Conductor,
-                            "Ensemble" => ArtistRole::Ensemble,
-                            "Producer" => ArtistRole::Producer,
-                            _ => ArtistRole::Other,
-                        })
-                    })?
-                    .collect::<rusqlite::Result<Vec<_>>>()?;
-                artist.roles = roles;
+                artist.roles = self.get_artist_roles(&artist.id)?;
+                Ok(Some(artist))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Look up an artist by its Discogs artist ID, recorded as a
+    /// [`Source::Discogs`] `discogs_id` assertion on the artist, including
+    /// roles.
+    pub fn get_artist_by_discogs_id(&self, discogs_id: u64) -> Result<Option<Artist>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.id, a.name, a.sort_name, a.musicbrainz_id, a.created_at, a.updated_at
+             FROM artists a
+             JOIN assertions s ON s.entity_id = a.id
+             WHERE s.field = 'discogs_id' AND s.source = 'Discogs' AND s.value = ?1
+             ORDER BY a.created_at
+             LIMIT 1",
+        )?;
+
+        let mut rows = stmt.query_map([discogs_id.to_string()], Self::row_to_artist)?;
+
+        match rows.next() {
+            Some(row) => {
+                let mut artist: Artist = row?;
+                artist.roles = self.get_artist_roles(&artist.id)?;
+                Ok(Some(artist))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Look up an artist by name, ignoring case, including roles. If several
+    /// share the name, the earliest added is returned.
+    pub fn get_artist_by_name(&self, name: &str) -> Result<Option<Artist>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, sort_name, musicbrainz_id, created_at, updated_at
+             FROM artists
+             WHERE name = ?1 COLLATE NOCASE
+             ORDER BY created_at
+             LIMIT 1",
+        )?;
+
+        let mut rows = stmt.query_map(rusqlite::params![name], Self::row_to_artist)?;
+
+        match rows.next() {
+            Some(row) => {
+                let mut artist: Artist = row?;
+                artist.roles = self.get_artist_roles(&artist.id)?;
+                Ok(Some(artist))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Look up an artist by ID, including roles.
+    pub fn get_artist_by_id(&self, id: &ArtistId) -> Result<Option<Artist>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, sort_name, musicbrainz_id, created_at, updated_at
+             FROM artists
+             WHERE id = ?1",
+        )?;
+
+        let mut rows = stmt.query_map(rusqlite::params![id.to_string()], Self::row_to_artist)?;
+
+        match rows.next() {
+            Some(row) => {
+                let mut artist: Artist = row?;
+                artist.roles = self.get_artist_roles(&artist.id)?;
                 Ok(Some(artist))
             }
             None => Ok(None),
         }
     }
 
+    /// Fetch the roles of one artist from the junction table.
+    fn get_artist_roles(&self, id: &ArtistId) -> Result<Vec<ArtistRole>> {
+        let mut role_stmt = self
+            .conn
+            .prepare("SELECT role FROM artist_roles WHERE artist_id = ?1")?;
+        let roles = role_stmt
+            .query_map(rusqlite::params![id.to_string()], |row| {
+                let role_str: String = row.get(0)?;
+                Ok(Self::parse_artist_role(&role_str))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(roles)
+    }
+
+    /// List all artists, including their roles, ordered by name.
+    pub fn list_artists(&self) -> Result<Vec<Artist>> {
+        self.query_artists("ORDER BY name", &[])
+    }
+
+    /// List one page of artists, ordered by name.
+    pub fn list_artists_page(&self, page: Page) -> Result<Vec<Artist>> {
+        let (limit, offset) = page.sql();
+        self.query_artists(
+            "ORDER BY name LIMIT ?1 OFFSET ?2",
+            rusqlite::params![limit, offset],
+        )
+    }
+
+    /// List the artists credited as performers of an expression, ordered by
+    /// name. The conductor is not included unless also credited.
+    pub fn list_performers_for_expression(&self, id: &ExpressionId) -> Result<Vec<Artist>> {
+        self.query_artists(
+            "WHERE id IN (
+                 SELECT artist_id FROM expression_performers WHERE expression_id = ?1
+             )
+             ORDER BY name",
+            rusqlite::params![id.to_string()],
+        )
+    }
+
+    /// Delete an artist with their roles and their performer and work
+    /// credits. Expressions they conducted are kept without a conductor.
+    ///
+    /// Returns whether the artist existed.
+    pub fn delete_artist(&self, id: &ArtistId) -> Result<bool> {
+        self.in_transaction(|db| {
+            let id = id.to_string();
+            db.conn
+                .execute("DELETE FROM artist_roles WHERE artist_id = ?1", [&id])?;
+            db.conn.execute(
+                "DELETE FROM expression_performers WHERE artist_id = ?1",
+                [&id],
+            )?;
+            db.conn
+                .execute("DELETE FROM work_credits WHERE artist_id = ?1", [&id])?;
+            db.conn.execute(
+                "UPDATE expressions SET conductor_id = NULL, updated_at = ?2
+                 WHERE conductor_id = ?1",
+                rusqlite::params![id, chrono::Utc::now().to_rfc3339()],
+            )?;
+            db.delete_entity_records(&id)?;
+            let deleted = db
+                .conn
+                .execute("DELETE FROM artists WHERE id = ?1", [&id])?;
+            Ok(deleted > 0)
+        })
+    }
+
+    /// Select artists with the given `WHERE`/`ORDER BY` clause, fetching
+    /// their roles in one more query rather than one per artist.
+    fn query_artists(&self, clause: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<Artist>> {
+        use std::collections::HashMap;
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, name, sort_name, musicbrainz_id, created_at, updated_at
+             FROM artists
+             {clause}"
+        ))?;
+
+        let mut artists = stmt
+            .query_map(params, Self::row_to_artist)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        if artists.is_empty() {
+            return Ok(artists);
+        }
+
+        let mut role_stmt = self.conn.prepare(&format!(
+            "SELECT artist_id, role FROM artist_roles
+             WHERE artist_id IN (SELECT id FROM artists {clause})
+             ORDER BY artist_id, role"
+        ))?;
+        let mut roles_map: HashMap<String, Vec<ArtistRole>> = HashMap::new();
+        let rows = role_stmt.query_map(params, |row| {
+            let artist_id: String = row.get(0)?;
+            let role_str: String = row.get(1)?;
+            Ok((artist_id, Self::parse_artist_role(&role_str)))
+        })?;
+        for row_result in rows {
+            let (artist_id, role) = row_result?;
+            roles_map.entry(artist_id).or_default().push(role);
+        }
+
+        for artist in &mut artists {
+            artist.roles = roles_map
+                .remove(&artist.id.to_string())
+                .unwrap_or_default();
+        }
+
+        Ok(artists)
+    }
+
+    fn parse_artist_role(role: &str) -> ArtistRole {
+        match role {
+            "Composer" => ArtistRole::Composer,
+            "Performer" => ArtistRole::Performer,
+            "Conductor" => ArtistRole::Conductor,
+            "Ensemble" => ArtistRole::Ensemble,
+            "Producer" => ArtistRole::Producer,
+            _ => ArtistRole::Other,
+        }
+    }
+
     fn row_to_artist(row: &rusqlite::Row) -> rusqlite::Result<Artist> {
         use chrono::DateTime;
         use uuid::Uuid;
@@ -1009,6 +2243,492 @@ impl Database {
     }
 }
 
+// Manifestation-Expression links
+impl Database {
+    /// Link an expression to a manifestation, replacing any existing
+    /// track/disc position for that pair.
+    pub fn link_release_track(&self, track: &ReleaseTrack) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO manifestation_expressions (
+                manifestation_id, expression_id, track_number, disc_number
+            ) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![
+                track.manifestation_id.to_string(),
+                track.expression_id.to_string(),
+                track.track_number.map(i64::from),
+                track.disc_number.map(i64::from),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// List every manifestation-expression link.
+    pub fn list_release_tracks(&self) -> Result<Vec<ReleaseTrack>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT manifestation_id, expression_id, track_number, disc_number
+             FROM manifestation_expressions
+             ORDER BY manifestation_id, disc_number, track_number",
+        )?;
+
+        let tracks = stmt
+            .query_map([], Self::row_to_release_track)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(tracks)
+    }
+
+    /// List the tracks of a manifestation, in disc and track order.
+    pub fn list_tracks_for_manifestation(&self, id: &ManifestationId) -> Result<Vec<ReleaseTrack>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT manifestation_id, expression_id, track_number, disc_number
+             FROM manifestation_expressions
+             WHERE manifestation_id = ?1
+             ORDER BY disc_number, track_number",
+        )?;
+
+        let tracks = stmt
+            .query_map([id.to_string()], Self::row_to_release_track)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(tracks)
+    }
+
+    /// Remove an expression from a manifestation. Returns whether they were
+    /// linked.
+    pub fn unlink_release_track(
+        &self,
+        manifestation_id: &ManifestationId,
+        expression_id: &ExpressionId,
+    ) -> Result<bool> {
+        let deleted = self.conn.execute(
+            "DELETE FROM manifestation_expressions
+             WHERE manifestation_id = ?1 AND expression_id = ?2",
+            [manifestation_id.to_string(), expression_id.to_string()],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn row_to_release_track(row: &rusqlite::Row) -> rusqlite::Result<ReleaseTrack> {
+        use uuid::Uuid;
+
+        let man_id_str: String = row.get(0)?;
+        let expr_id_str: String = row.get(1)?;
+
+        Ok(ReleaseTrack {
+            manifestation_id: Uuid::parse_str(&man_id_str)
+                .map(ManifestationId::from_uuid)
+                .map_err(|e| {
+                    rusqlite::Error::FromSqlConversionFailure(
+                        0,
+                        rusqlite::types::Type::Text,
+                        Box::new(e),
+                    )
+                })?,
+            expression_id: Uuid::parse_str(&expr_id_str)
+                .map(ExpressionId::from_uuid)
+                .map_err(|e| {
+                    rusqlite::Error::FromSqlConversionFailure(
+                        1,
+                        rusqlite::types::Type::Text,
+                        Box::new(e),
+                    )
+                })?,
+            track_number: row.get::<_, Option<i64>>(2)?.map(|v| v as u32),
+            disc_number: row.get::<_, Option<i64>>(3)?.map(|v| v as u32),
+        })
+    }
+}
+
+// Work credits
+impl Database {
+    /// Credit an artist on a work. Crediting them again in the same role
+    /// does nothing.
+    pub fn link_work_credit(&self, credit: &WorkCredit) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO work_credits (work_id, artist_id, role)
+             VALUES (?1, ?2, ?3)",
+            rusqlite::params![
+                credit.work_id.to_string(),
+                credit.artist_id.to_string(),
+                credit.role.as_str(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Remove a credit. Returns whether it existed.
+    pub fn unlink_work_credit(&self, credit: &WorkCredit) -> Result<bool> {
+        let deleted = self.conn.execute(
+            "DELETE FROM work_credits WHERE work_id = ?1 AND artist_id = ?2 AND role = ?3",
+            rusqlite::params![
+                credit.work_id.to_string(),
+                credit.artist_id.to_string(),
+                credit.role.as_str(),
+            ],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// List every work credit.
+    pub fn list_work_credits(&self) -> Result<Vec<WorkCredit>> {
+        self.query_work_credits("ORDER BY work_id, role, artist_id", &[])
+    }
+
+    /// List the credits on a work, ordered by role.
+    pub fn list_credits_for_work(&self, id: &WorkId) -> Result<Vec<WorkCredit>> {
+        self.query_work_credits(
+            "WHERE work_id = ?1 ORDER BY role, artist_id",
+            rusqlite::params![id.to_string()],
+        )
+    }
+
+    /// List the artists credited on a work in a role, ordered by name.
+    pub fn list_credited_artists(&self, id: &WorkId, role: WorkRole) -> Result<Vec<Artist>> {
+        self.query_artists(
+            "WHERE id IN (
+                 SELECT artist_id FROM work_credits WHERE work_id = ?1 AND role = ?2
+             )
+             ORDER BY name",
+            rusqlite::params![id.to_string(), role.as_str()],
+        )
+    }
+
+    /// List the works an artist is credited on, in any role, ordered by
+    /// title.
+    pub fn list_works_credited_to(&self, id: &ArtistId) -> Result<Vec<Work>> {
+        self.query_works(
+            "WHERE id IN (SELECT work_id FROM work_credits WHERE artist_id = ?1)
+             ORDER BY title",
+            rusqlite::params![id.to_string()],
+        )
+    }
+
+    /// Select work credits with the given `WHERE`/`ORDER BY` clause.
+    fn query_work_credits(
+        &self,
+        clause: &str,
+        params: &[&dyn rusqlite::ToSql],
+    ) -> Result<Vec<WorkCredit>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT work_id, artist_id, role FROM work_credits {clause}"
+        ))?;
+        let rows = stmt
+            .query_map(params, |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        rows.into_iter()
+            .map(|(work_id, artist_id, role)| {
+                let parse = |id: &str| {
+                    uuid::Uuid::parse_str(id)
+                        .map_err(|e| Error::InvalidData(format!("Invalid ID '{id}': {e}")))
+                };
+                Ok(WorkCredit {
+                    work_id: WorkId::from_uuid(parse(&work_id)?),
+                    artist_id: ArtistId::from_uuid(parse(&artist_id)?),
+                    role: role.parse()?,
+                })
+            })
+            .collect()
+    }
+}
+
+// Merges
+impl Database {
+    /// Merge the `duplicate` work into `survivor`.
+    ///
+    /// The duplicate's expressions, movements, credits, assertions and
+    /// proposals move to the survivor, which also takes any field it lacks
+    /// from the duplicate. The duplicate is then deleted, and the merge is
+    /// recorded as a `merged_from` assertion on the survivor. Works with
+    /// different `MusicBrainz` IDs are distinct and cannot be merged.
+    pub fn merge_works(&self, survivor: &WorkId, duplicate: &WorkId) -> Result<()> {
+        let (keep, dup) = self.merge_pair("work", survivor, duplicate, Self::get_work_by_id)?;
+        check_musicbrainz_ids(
+            "work",
+            keep.musicbrainz_id.as_ref(),
+            dup.musicbrainz_id.as_ref(),
+        )?;
+
+        self.in_transaction(|db| {
+            let (keep_id, dup_id) = (keep.id.to_string(), dup.id.to_string());
+            let now = chrono::Utc::now().to_rfc3339();
+            db.conn.execute(
+                "UPDATE expressions SET work_id = ?1, updated_at = ?3 WHERE work_id = ?2",
+                [&keep_id, &dup_id, &now],
+            )?;
+            // The duplicate's movements become the survivor's; neither
+            // can end up a part of itself.
+            db.conn.execute(
+                "UPDATE works SET
+                    parent_work_id = CASE WHEN id = ?1 THEN NULL ELSE ?1 END,
+                    updated_at = ?3
+                 WHERE parent_work_id = ?2",
+                [&keep_id, &dup_id, &now],
+            )?;
+            db.conn.execute(
+                "INSERT OR IGNORE INTO work_credits (work_id, artist_id, role)
+                 SELECT ?1, artist_id, role FROM work_credits WHERE work_id = ?2",
+                [&keep_id, &dup_id],
+            )?;
+            db.conn
+                .execute("DELETE FROM work_credits WHERE work_id = ?1", [&dup_id])?;
+            db.move_entity_records(&dup_id, &keep_id)?;
+            db.conn
+                .execute("DELETE FROM works WHERE id = ?1", [&dup_id])?;
+            let dup_parent = dup.parent_work_id.filter(|id| *id != keep.id);
+            db.conn.execute(
+                "UPDATE works SET
+                    composer = COALESCE(composer, ?2),
+                    musicbrainz_id = COALESCE(musicbrainz_id, ?3),
+                    catalog_number = COALESCE(catalog_number, ?4),
+                    key = COALESCE(key, ?5),
+                    composed_year = COALESCE(composed_year, ?6),
+                    parent_work_id = COALESCE(parent_work_id, ?7),
+                    part_number = COALESCE(part_number, ?8),
+                    movement_label = COALESCE(movement_label, ?9),
+                    updated_at = ?10
+                 WHERE id = ?1",
+                rusqlite::params![
+                    keep_id,
+                    dup.composer,
+                    dup.musicbrainz_id,
+                    dup.catalog_number,
+                    dup.key,
+                    dup.composed_year.map(i64::from),
+                    dup_parent.map(|id| id.to_string()),
+                    dup.part_number,
+                    dup.movement_label,
+                    now,
+                ],
+            )?;
+            db.record_merge(&keep_id, &dup_id, dup.musicbrainz_id.as_deref(), &dup.title)
+        })
+    }
+
+    /// Merge the `duplicate` expression into `survivor`.
+    ///
+    /// The duplicate's items, performer credits, release tracks, assertions
+    /// and proposals move to the survivor, which also takes any field it
+    /// lacks from the duplicate. Where both already had the same performer
+    /// or release, the survivor's row is kept. The duplicate is then
+    /// deleted and the merge recorded as for [`merge_works`](Self::merge_works).
+    pub fn merge_expressions(
+        &self,
+        survivor: &ExpressionId,
+        duplicate: &ExpressionId,
+    ) -> Result<()> {
+        let (keep, dup) = self.merge_pair(
+            "expression",
+            survivor,
+            duplicate,
+            Self::get_expression_by_id,
+        )?;
+        check_musicbrainz_ids(
+            "expression",
+            keep.musicbrainz_id.as_ref(),
+            dup.musicbrainz_id.as_ref(),
+        )?;
+
+        self.in_transaction(|db| {
+            let (keep_id, dup_id) = (keep.id.to_string(), dup.id.to_string());
+            let now = chrono::Utc::now().to_rfc3339();
+            db.conn.execute(
+                "UPDATE items SET expression_id = ?1, updated_at = ?3 WHERE expression_id = ?2",
+                [&keep_id, &dup_id, &now],
+            )?;
+            db.conn.execute(
+                "INSERT OR IGNORE INTO expression_performers (
+                    expression_id, artist_id, role, lcmpt_uri, kind, credited_as
+                 )
+                 SELECT ?1, artist_id, role, lcmpt_uri, kind, credited_as
+                 FROM expression_performers
+                 WHERE expression_id = ?2",
+                [&keep_id, &dup_id],
+            )?;
+            db.conn.execute(
+                "INSERT OR IGNORE INTO manifestation_expressions (
+                    manifestation_id, expression_id, track_number, disc_number
+                 )
+                 SELECT manifestation_id, ?1, track_number, disc_number
+                 FROM manifestation_expressions
+                 WHERE expression_id = ?2",
+                [&keep_id, &dup_id],
+            )?;
+            db.move_entity_records(&dup_id, &keep_id)?;
+            db.delete_expression(&dup.id)?;
+            db.conn.execute(
+                "UPDATE expressions SET
+                    title = COALESCE(title, ?2),
+                    musicbrainz_id = COALESCE(musicbrainz_id, ?3),
+                    conductor_id = COALESCE(conductor_id, ?4),
+                    recorded_year = COALESCE(recorded_year, ?5),
+                    duration_secs = COALESCE(duration_secs, ?6),
+                    updated_at = ?7,
+                    recorded_from = COALESCE(recorded_from, ?8),
+                    recorded_until = COALESCE(recorded_until, ?9),
+                    venue = COALESCE(venue, ?10),
+                    live = COALESCE(live, ?11)
+                 WHERE id = ?1",
+                rusqlite::params![
+                    keep_id,
+                    dup.title,
+                    dup.musicbrainz_id,
+                    dup.conductor_id.map(|id| id.to_string()),
+                    dup.recorded_year.map(i64::from),
+                    dup.duration_secs,
+                    now,
+                    dup.recorded_from,
+                    dup.recorded_until,
+                    dup.venue,
+                    dup.live,
+                ],
+            )?;
+            db.record_merge(
+                &keep_id,
+                &dup_id,
+                dup.musicbrainz_id.as_deref(),
+                dup.title.as_deref().unwrap_or_default(),
+            )
+        })
+    }
+
+    /// Merge the `duplicate` artist into `survivor`.
+    ///
+    /// The duplicate's roles, performer credits, conducted expressions,
+    /// assertions and proposals move to the survivor, which also takes a
+    /// sort name or `MusicBrainz` ID it lacks. Performer credits moved from
+    /// a differently named duplicate record its name as `credited_as`. The
+    /// duplicate is then deleted and the merge recorded as for
+    /// [`merge_works`](Self::merge_works).
+    pub fn merge_artists(&self, survivor: &ArtistId, duplicate: &ArtistId) -> Result<()> {
+        let (keep, dup) = self.merge_pair("artist", survivor, duplicate, Self::get_artist_by_id)?;
+        check_musicbrainz_ids(
+            "artist",
+            keep.musicbrainz_id.as_ref(),
+            dup.musicbrainz_id.as_ref(),
+        )?;
+
+        self.in_transaction(|db| {
+            let (keep_id, dup_id) = (keep.id.to_string(), dup.id.to_string());
+            let now = chrono::Utc::now().to_rfc3339();
+            db.conn.execute(
+                "INSERT OR IGNORE INTO artist_roles (artist_id, role)
+                 SELECT ?1, role FROM artist_roles WHERE artist_id = ?2",
+                [&keep_id, &dup_id],
+            )?;
+            // Recordings credited under the duplicate's name keep it
+            let credited_as = (dup.name != keep.name).then_some(dup.name.as_str());
+            db.conn.execute(
+                "INSERT OR IGNORE INTO expression_performers (
+                    expression_id, artist_id, role, lcmpt_uri, kind, credited_as
+                 )
+                 SELECT expression_id, ?1, role, lcmpt_uri, kind, COALESCE(credited_as, ?3)
+                 FROM expression_performers
+                 WHERE artist_id = ?2",
+                rusqlite::params![keep_id, dup_id, credited_as],
+            )?;
+            db.conn.execute(
+                "INSERT OR IGNORE INTO work_credits (work_id, artist_id, role)
+                 SELECT work_id, ?1, role FROM work_credits WHERE artist_id = ?2",
+                [&keep_id, &dup_id],
+            )?;
+            db.conn.execute(
+                "UPDATE expressions SET conductor_id = ?1, updated_at = ?3
+                 WHERE conductor_id = ?2",
+                [&keep_id, &dup_id, &now],
+            )?;
+            db.move_entity_records(&dup_id, &keep_id)?;
+            db.delete_artist(&dup.id)?;
+            db.conn.execute(
+                "UPDATE artists SET
+                    sort_name = COALESCE(sort_name, ?2),
+                    musicbrainz_id = COALESCE(musicbrainz_id, ?3),
+                    updated_at = ?4
+                 WHERE id = ?1",
+                rusqlite::params![keep_id, dup.sort_name, dup.musicbrainz_id, now],
+            )?;
+            db.record_merge(&keep_id, &dup_id, dup.musicbrainz_id.as_deref(), &dup.name)
+        })
+    }
+
+    /// Look up both sides of a merge, which must exist and differ.
+    fn merge_pair<I: PartialEq + std::fmt::Display, T>(
+        &self,
+        entity: &'static str,
+        survivor: &I,
+        duplicate: &I,
+        get: impl Fn(&Self, &I) -> Result<Option<T>>,
+    ) -> Result<(T, T)> {
+        if survivor == duplicate {
+            return Err(Error::InvalidData(format!(
+                "cannot merge {entity} {survivor} into itself"
+            )));
+        }
+        let fetch = |id: &I| {
+            get(self, id)?.ok_or_else(|| Error::NotFound {
+                entity,
+                id: id.to_string(),
+            })
+        };
+        Ok((fetch(survivor)?, fetch(duplicate)?))
+    }
+
+    /// Point the assertions and proposals about one entity at another.
+    fn move_entity_records(&self, from: &str, to: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE assertions SET entity_id = ?2 WHERE entity_id = ?1",
+            [from, to],
+        )?;
+        self.conn.execute(
+            "UPDATE proposals SET entity_id = ?2 WHERE entity_id = ?1",
+            [from, to],
+        )?;
+        Ok(())
+    }
+
+    /// Record on the survivor which entity was merged into it.
+    fn record_merge(
+        &self,
+        survivor: &str,
+        duplicate: &str,
+        musicbrainz_id: Option<&str>,
+        label: &str,
+    ) -> Result<()> {
+        self.insert_assertion(&Assertion::new(
+            survivor,
+            "merged_from",
+            serde_json::json!({
+                "id": duplicate,
+                "musicbrainz_id": musicbrainz_id,
+                "label": label,
+            }),
+            Source::User,
+        ))
+        .map(drop)
+    }
+}
+
+/// Refuse to merge two entities that `MusicBrainz` knows to be different.
+fn check_musicbrainz_ids(
+    entity: &str,
+    survivor: Option<&String>,
+    duplicate: Option<&String>,
+) -> Result<()> {
+    match (survivor, duplicate) {
+        (Some(a), Some(b)) if a != b => Err(Error::InvalidData(format!(
+            "cannot merge {entity}s with different MusicBrainz IDs ({a} and {b})"
+        ))),
+        _ => Ok(()),
+    }
+}
+
 // LCGFT Vocabulary CRUD
 impl Database {
     /// Insert a single LCGFT term.
@@ -1145,6 +2865,104 @@ impl Database {
     }
 }
 
+// Wikidata label cache
+impl Database {
+    /// Cache the English label of a Wikidata entity.
+    pub fn cache_wikidata_label(&self, qid: &str, label: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO wikidata_labels (qid, label, fetched_at)
+             VALUES (?1, ?2, datetime('now'))",
+            rusqlite::params![qid, label],
+        )?;
+        Ok(())
+    }
+
+    /// The cached labels of the given Wikidata entities, keyed by QID.
+    /// Entities without a cached label are left out.
+    pub fn get_wikidata_labels(&self, qids: &[String]) -> Result<HashMap<String, String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT label FROM wikidata_labels WHERE qid = ?1")?;
+        let mut labels = HashMap::new();
+        for qid in qids {
+            let mut rows = stmt.query_map([qid], |row| row.get::<_, String>(0))?;
+            if let Some(label) = rows.next() {
+                labels.insert(qid.clone(), label?);
+            }
+        }
+        Ok(labels)
+    }
+}
+
+// Change tracking
+impl Database {
+    /// The most recent modification time across the catalog tables,
+    /// assertions, proposals and their review decisions, and cached Wikidata
+    /// labels, or `None` for an empty database.
+    ///
+    /// Derived indices (such as the similarity index) record it in a
+    /// [`CatalogStamp`] to decide whether they are stale.
+    pub fn latest_change(&self) -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+        let latest: Option<String> = self.conn.query_row(
+            "SELECT MAX(ts) FROM (
+                SELECT MAX(updated_at) AS ts FROM works
+                UNION ALL SELECT MAX(updated_at) FROM expressions
+                UNION ALL SELECT MAX(updated_at) FROM manifestations
+                UNION ALL SELECT MAX(updated_at) FROM items
+                UNION ALL SELECT MAX(updated_at) FROM artists
+                UNION ALL SELECT MAX(fetched_at) FROM assertions
+                UNION ALL SELECT MAX(created_at) FROM proposals
+                UNION ALL SELECT MAX(decided_at) FROM proposals
+                UNION ALL SELECT MAX(strftime('%Y-%m-%dT%H:%M:%S+00:00', fetched_at))
+                    FROM wikidata_labels
+             )",
+            [],
+            |row| row.get(0),
+        )?;
+
+        latest
+            .map(|ts| {
+                chrono::DateTime::parse_from_rfc3339(&ts)
+                    .map(|dt| dt.with_timezone(&chrono::Utc))
+                    .map_err(|e| crate::Error::InvalidData(format!("invalid timestamp {ts}: {e}")))
+            })
+            .transpose()
+    }
+
+    /// The total number of rows in the catalog tables, their links,
+    /// assertions, proposals and cached Wikidata labels.
+    ///
+    /// Deleting a row leaves [`Self::latest_change`] as it was, so derived
+    /// indices also record this count to notice deletions.
+    pub fn catalog_row_count(&self) -> Result<u64> {
+        let count: i64 = self.conn.query_row(
+            "SELECT (SELECT COUNT(*) FROM works)
+                  + (SELECT COUNT(*) FROM expressions)
+                  + (SELECT COUNT(*) FROM manifestations)
+                  + (SELECT COUNT(*) FROM items)
+                  + (SELECT COUNT(*) FROM artists)
+                  + (SELECT COUNT(*) FROM artist_roles)
+                  + (SELECT COUNT(*) FROM expression_performers)
+                  + (SELECT COUNT(*) FROM manifestation_expressions)
+                  + (SELECT COUNT(*) FROM work_credits)
+                  + (SELECT COUNT(*) FROM assertions)
+                  + (SELECT COUNT(*) FROM proposals)
+                  + (SELECT COUNT(*) FROM wikidata_labels)",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(u64::try_from(count).unwrap_or_default())
+    }
+
+    /// The current [`CatalogStamp`].
+    pub fn catalog_stamp(&self) -> Result<CatalogStamp> {
+        Ok(CatalogStamp {
+            latest_change: self.latest_change()?,
+            rows: self.catalog_row_count()?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1163,7 +2981,7 @@ mod tests {
                 row.get(0)
             })
             .unwrap();
-        assert_eq!(count, 2); // Two migrations applied
+        assert_eq!(count, 12); // Twelve migrations applied
     }
 
     #[test]
@@ -1207,6 +3025,9 @@ mod tests {
         assert_eq!(assertions[0].field, "genre");
         assert_eq!(assertions[0].source, Source::MusicBrainz);
         assert_eq!(assertions[0].confidence, Some(0.95));
+
+        assert_eq!(db.delete_assertion(&assertions[0]).unwrap(), 1);
+        assert!(db.get_assertions_for_entity("entity-123").unwrap().is_empty());
     }
 
     #[test]
@@ -1247,6 +3068,22 @@ mod tests {
         let found = found.unwrap();
         assert_eq!(found.name, "Takacs Quartet");
         assert_eq!(found.roles, vec![ArtistRole::Ensemble]);
+
+        let by_name = db.get_artist_by_name("takacs quartet").unwrap().unwrap();
+        assert_eq!(by_name.id, artist.id);
+        assert!(db.get_artist_by_name("Takacs").unwrap().is_none());
+
+        assert!(db.get_artist_by_discogs_id(283_487).unwrap().is_none());
+        db.insert_assertion(&Assertion::new(
+            artist.id.to_string(),
+            "discogs_id",
+            serde_json::json!(283_487),
+            Source::Discogs,
+        ))
+        .unwrap();
+        let by_discogs_id = db.get_artist_by_discogs_id(283_487).unwrap().unwrap();
+        assert_eq!(by_discogs_id.id, artist.id);
+        assert_eq!(by_discogs_id.roles, vec![ArtistRole::Ensemble]);
     }
 
     #[test]
@@ -1295,8 +3132,7 @@ mod tests {
         let found = found.unwrap();
         assert_eq!(found.title, Some("Test Recording".to_string()));
         assert_eq!(found.work_id, work.id);
-        assert_eq!(found.performer_ids.len(), 1);
-        assert_eq!(found.performer_ids[0], artist.id);
+        assert_eq!(found.performer_ids(), [artist.id]);
         assert_eq!(found.duration_secs, Some(300.5));
     }
 
@@ -1349,6 +3185,105 @@ mod tests {
         assert_eq!(items.len(), 2);
     }
 
+    #[test]
+    fn test_missing_items_and_fingerprint_lookup() {
+        let db = Database::open_in_memory().unwrap();
+        let mut item = Item::new(
+            PathBuf::from("/music/gone.flac"),
+            AudioFormat::Flac,
+            4096,
+            Utc::now(),
+        );
+        item.fingerprint = Some("AQAA-fp".to_string());
+        db.insert_item(&item).unwrap();
+        assert!(db.list_missing_items().unwrap().is_empty());
+
+        db.set_item_missing(&item.id, Some(Utc::now())).unwrap();
+        let missing = db.list_missing_items().unwrap();
+        assert_eq!(missing.len(), 1);
+        assert!(missing[0].is_missing());
+        // Missing files are left out of the pipeline work lists
+        assert!(db.list_unidentified_items().unwrap().is_empty());
+        assert_eq!(db.list_all_items().unwrap().len(), 1);
+
+        let found = db.get_items_by_fingerprint("AQAA-fp").unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, item.id);
+        assert!(db.get_items_by_file_hash("abc").unwrap().is_empty());
+
+        db.set_item_missing(&item.id, None).unwrap();
+        assert!(db.list_missing_items().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_transaction_commit_and_rollback() {
+        let db = Database::open_in_memory().unwrap();
+        let item = |name: &str| {
+            Item::new(
+                PathBuf::from(format!("/music/{name}.flac")),
+                AudioFormat::Flac,
+                1024,
+                Utc::now(),
+            )
+        };
+
+        let tx = db.transaction().unwrap();
+        tx.insert_items(&[item("a"), item("b")]).unwrap();
+        tx.rollback().unwrap();
+        assert!(db.list_all_items().unwrap().is_empty());
+
+        {
+            let tx = db.transaction().unwrap();
+            tx.insert_item(&item("c")).unwrap();
+            // Dropped without committing
+        }
+        assert!(db.list_all_items().unwrap().is_empty());
+
+        let tx = db.transaction().unwrap();
+        tx.insert_items(&[item("d"), item("e")]).unwrap();
+        tx.insert_assertions(&[Assertion::new(
+            "item-1",
+            "genre",
+            serde_json::json!("Opera"),
+            Source::LastFm,
+        )])
+        .unwrap();
+        tx.commit().unwrap();
+        assert_eq!(db.list_all_items().unwrap().len(), 2);
+        assert_eq!(db.get_assertions_for_entity("item-1").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_list_duplicates_by_hash() {
+        let db = Database::open_in_memory().unwrap();
+        let make = |path: &str, file_hash: &str, audio_hash: &str| {
+            let mut item = Item::new(PathBuf::from(path), AudioFormat::Flac, 1024, Utc::now());
+            item.file_hash = Some(file_hash.to_string());
+            item.audio_hash = Some(audio_hash.to_string());
+            db.insert_item(&item).unwrap();
+            item
+        };
+        make("/music/a.flac", "f1", "a1");
+        make("/music/copy/a.flac", "f1", "a1");
+        make("/music/retagged/a.flac", "f2", "a1");
+        make("/music/b.flac", "f3", "a2");
+        let gone = make("/music/gone/a.flac", "f1", "a1");
+        db.set_item_missing(&gone.id, Some(Utc::now())).unwrap();
+
+        let by_file = db.list_duplicates_by_file_hash().unwrap();
+        assert_eq!(by_file.len(), 1);
+        assert_eq!(by_file[0].len(), 2);
+
+        let by_audio = db.list_duplicates_by_audio_hash().unwrap();
+        assert_eq!(by_audio.len(), 1);
+        assert_eq!(by_audio[0].len(), 3);
+        assert_eq!(
+            db.get_items_by_audio_hash("a1").unwrap().len(),
+            4,
+            "lookups include missing items"
+        );
+    }
+
     #[test]
     fn test_get_item_by_id_found() {
         let db = Database::open_in_memory().unwrap();
@@ -1464,21 +3399,20 @@ mod tests {
         let expressions = db.list_expressions().unwrap();
         assert_eq!(expressions.len(), 1);
         assert_eq!(expressions[0].title, Some("Test Recording".to_string()));
-        assert_eq!(expressions[0].performer_ids.len(), 1);
-        assert_eq!(expressions[0].performer_ids[0], artist.id);
+        assert_eq!(expressions[0].performer_ids(), [artist.id]);
     }
 
     #[test]
     fn test_migration_002_creates_vocabulary_tables() {
         let db = Database::open_in_memory().unwrap();
-        // Verify migration count (should be 2 now)
+        // Verify migration count (should be 3 now)
         let count: i64 = db
             .conn()
             .query_row("SELECT COUNT(*) FROM schema_migrations", [], |row| {
                 row.get(0)
             })
             .unwrap();
-        assert_eq!(count, 2);
+        assert_eq!(count, 12);
     }
 
     #[test]
@@ -1618,4 +3552,811 @@ mod tests {
             .unwrap();
         assert_eq!(db.count_lcmpt_terms().unwrap(), 1);
     }
+
+    #[test]
+    fn test_list_works_and_manifestations() {
+        let db = Database::open_in_memory().unwrap();
+        db.insert_work(&Work::new("B Work")).unwrap();
+        db.insert_work(&Work::new("A Work")).unwrap();
+        db.insert_manifestation(&Manifestation::new("Some Release"))
+            .unwrap();
+
+        let works = db.list_works().unwrap();
+        assert_eq!(works.len(), 2);
+        assert_eq!(works[0].title, "A Work");
+        assert_eq!(db.list_manifestations().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_list_artists_with_roles() {
+        let db = Database::open_in_memory().unwrap();
+        let composer = Artist::new("Bela Bartok").with_role(ArtistRole::Composer);
+        let quartet = Artist::new("Takacs Quartet")
+            .with_role(ArtistRole::Ensemble)
+            .with_role(ArtistRole::Performer);
+        db.insert_artist(&composer).unwrap();
+        db.insert_artist(&quartet).unwrap();
+
+        let artists = db.list_artists().unwrap();
+        assert_eq!(artists.len(), 2);
+        assert_eq!(artists[0].name, "Bela Bartok");
+        assert_eq!(artists[0].roles, vec![ArtistRole::Composer]);
+        assert_eq!(artists[1].roles.len(), 2);
+    }
+
+    #[test]
+    fn test_release_track_round_trip() {
+        let db = Database::open_in_memory().unwrap();
+        let work = Work::new("Test Work");
+        db.insert_work(&work).unwrap();
+        let expr = Expression::new(work.id);
+        db.insert_expression(&expr).unwrap();
+        let man = Manifestation::new("Test Release");
+        db.insert_manifestation(&man).unwrap();
+
+        let track = ReleaseTrack::new(man.id, expr.id)
+            .with_track_number(4)
+            .with_disc_number(1);
+        db.link_release_track(&track).unwrap();
+        // Relinking the same pair replaces the position
+        db.link_release_track(&track.with_track_number(5)).unwrap();
+
+        let tracks = db.list_release_tracks().unwrap();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].track_number, Some(5));
+        assert_eq!(tracks[0].disc_number, Some(1));
+    }
+
+    #[test]
+    fn test_list_expression_performers() {
+        let db = Database::open_in_memory().unwrap();
+        let work = Work::new("Test Work");
+        db.insert_work(&work).unwrap();
+        let artist = Artist::new("Test Performer");
+        db.insert_artist(&artist).unwrap();
+        let expr = Expression::new(work.id).with_performer(artist.id);
+        db.insert_expression(&expr).unwrap();
+
+        let credits = db.list_expression_performers().unwrap();
+        assert_eq!(credits.len(), 1);
+        assert_eq!(credits[0].expression_id, expr.id);
+        assert_eq!(credits[0].performer.artist_id, artist.id);
+        assert_eq!(credits[0].performer.role, None);
+    }
+
+    #[test]
+    fn test_performer_roles() {
+        let db = Database::open_in_memory().unwrap();
+        let work = Work::new("Piano Concerto No. 1");
+        db.insert_work(&work).unwrap();
+        let pianist = Artist::new("Martha Argerich");
+        let orchestra = Artist::new("Royal Philharmonic Orchestra");
+        db.insert_artist(&pianist).unwrap();
+        db.insert_artist(&orchestra).unwrap();
+        let expr = Expression::new(work.id)
+            .with_credited_performer(
+                Performer::new(pianist.id)
+                    .with_role("piano")
+                    .with_lcmpt_uri("http://id.loc.gov/authorities/performanceMediums/mp2013015550")
+                    .with_kind(PerformerKind::Soloist)
+                    .with_credited_as("M. Argerich"),
+            )
+            .with_credited_performer(
+                Performer::new(orchestra.id).with_kind(PerformerKind::Orchestra),
+            );
+        db.insert_expression(&expr).unwrap();
+
+        let fetched = db.get_expression_by_id(&expr.id).unwrap().unwrap();
+        assert_eq!(fetched.performers, expr.performers);
+        let listed = db.list_expressions_for_work(&work.id).unwrap();
+        assert_eq!(listed[0].performers, expr.performers);
+        let credits = db.list_expression_performers().unwrap();
+        assert_eq!(credits.len(), 2);
+        assert_eq!(credits[0].performer, expr.performers[0]);
+
+        // Replacing the expression replaces its credits
+        let mut updated = fetched;
+        updated.performers.truncate(1);
+        updated.performers[0].add_role("harpsichord");
+        db.upsert_expression(&updated).unwrap();
+        let fetched = db.get_expression_by_id(&expr.id).unwrap().unwrap();
+        assert_eq!(fetched.performers.len(), 1);
+        assert_eq!(
+            fetched.performers[0].role.as_deref(),
+            Some("piano, harpsichord")
+        );
+    }
+
+    #[test]
+    fn test_recording_sessions() {
+        let db = Database::open_in_memory().unwrap();
+        let work = Work::new("Goldberg Variations");
+        db.insert_work(&work).unwrap();
+        let expr = Expression::new(work.id)
+            .with_recording_dates("1955-06-10", "1955-06-16")
+            .with_venue("CBS 30th Street Studio")
+            .with_live(false);
+        db.insert_expression(&expr).unwrap();
+
+        let fetched = db.get_expression_by_id(&expr.id).unwrap().unwrap();
+        assert_eq!(fetched.recorded_year, Some(1955));
+        assert_eq!(fetched.recorded_from.as_deref(), Some("1955-06-10"));
+        assert_eq!(fetched.recorded_until.as_deref(), Some("1955-06-16"));
+        assert_eq!(fetched.venue.as_deref(), Some("CBS 30th Street Studio"));
+        assert_eq!(fetched.live, Some(false));
+
+        // Merging fills in session details the kept expression lacks
+        let bare = Expression::new(work.id);
+        db.insert_expression(&bare).unwrap();
+        db.merge_expressions(&bare.id, &expr.id).unwrap();
+        let merged = db.get_expression_by_id(&bare.id).unwrap().unwrap();
+        assert_eq!(merged.venue, fetched.venue);
+        assert_eq!(merged.live, Some(false));
+    }
+
+    #[test]
+    fn test_wikidata_label_cache() {
+        let db = Database::open_in_memory().unwrap();
+        db.cache_wikidata_label("Q1344", "opera").unwrap();
+        db.cache_wikidata_label("Q5994", "pianoforte").unwrap();
+        db.cache_wikidata_label("Q5994", "piano").unwrap();
+
+        let qids = ["Q1344", "Q5994", "Q42"].map(String::from);
+        let labels = db.get_wikidata_labels(&qids).unwrap();
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels["Q1344"], "opera");
+        assert_eq!(labels["Q5994"], "piano");
+    }
+
+    #[test]
+    fn test_latest_change() {
+        let db = Database::open_in_memory().unwrap();
+        assert!(db.latest_change().unwrap().is_none());
+
+        let work = Work::new("Test Work");
+        db.insert_work(&work).unwrap();
+        let latest = db.latest_change().unwrap().unwrap();
+        assert_eq!(latest.timestamp(), work.updated_at.timestamp());
+        assert_eq!(db.catalog_row_count().unwrap(), 1);
+
+        let stamp = db.catalog_stamp().unwrap();
+        assert_eq!(stamp.latest_change, Some(latest));
+
+        // A deletion shows in the row count only
+        assert!(db.delete_work(&work.id).unwrap());
+        assert_eq!(db.catalog_row_count().unwrap(), 0);
+        assert_ne!(db.catalog_stamp().unwrap(), stamp);
+    }
+
+    #[test]
+    fn test_catalog_stamp_tracks_labels_and_review() {
+        use crate::taxonomy::rules::ProposedTag;
+
+        let db = Database::open_in_memory().unwrap();
+        let stamp = db.catalog_stamp().unwrap();
+
+        db.cache_wikidata_label("Q1344", "opera").unwrap();
+        let labelled = db.catalog_stamp().unwrap();
+        assert_ne!(labelled, stamp);
+        assert!(labelled.latest_change.is_some());
+
+        let tag = ProposedTag {
+            field: "genre".to_string(),
+            value: "Opera".to_string(),
+            source: Source::Wikidata,
+            rule_name: "opera".to_string(),
+            confidence: 0.9,
+            alternatives: Vec::new(),
+        };
+        let id = db.insert_proposal(&Proposal::new("item-1", tag)).unwrap();
+        let proposed = db.catalog_stamp().unwrap();
+        assert_eq!(proposed.rows, labelled.rows + 1);
+
+        let decided_at = Utc::now() + chrono::Duration::seconds(1);
+        db.set_proposal_decision(id, ProposalStatus::Accepted, Some(decided_at), None)
+            .unwrap();
+        let accepted = db.catalog_stamp().unwrap();
+        assert_eq!(accepted.rows, proposed.rows);
+        assert!(accepted.latest_change > proposed.latest_change);
+    }
+
+    #[test]
+    fn test_get_entities_by_id() {
+        let db = Database::open_in_memory().unwrap();
+        let artist = Artist::new("Test Performer").with_role(ArtistRole::Performer);
+        db.insert_artist(&artist).unwrap();
+        let work = Work::new("Test Work");
+        db.insert_work(&work).unwrap();
+        let expr = Expression::new(work.id).with_performer(artist.id);
+        db.insert_expression(&expr).unwrap();
+        let man = Manifestation::new("Test Album");
+        db.insert_manifestation(&man).unwrap();
+
+        assert_eq!(db.get_work_by_id(&work.id).unwrap().unwrap().title, "Test Work");
+        let fetched = db.get_expression_by_id(&expr.id).unwrap().unwrap();
+        assert_eq!(fetched.performer_ids(), vec![artist.id]);
+        assert_eq!(
+            db.get_manifestation_by_id(&man.id).unwrap().unwrap().title,
+            "Test Album"
+        );
+        let fetched = db.get_artist_by_id(&artist.id).unwrap().unwrap();
+        assert_eq!(fetched.roles, vec![ArtistRole::Performer]);
+
+        assert!(db.get_work_by_id(&WorkId::new()).unwrap().is_none());
+        assert!(db.get_expression_by_id(&ExpressionId::new()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_proposal_lifecycle() {
+        use crate::taxonomy::rules::ProposedTag;
+
+        let db = Database::open_in_memory().unwrap();
+        let tag = |field: &str, value: &str| ProposedTag {
+            field: field.to_string(),
+            value: value.to_string(),
+            source: Source::MusicBrainz,
+            rule_name: "rule".to_string(),
+            confidence: 0.8,
+            alternatives: Vec::new(),
+        };
+
+        let proposals = vec![
+            Proposal::new("item-1", tag("form", "Symphony")),
+            Proposal::new("item-1", tag("period", "Romantic")),
+        ];
+        assert_eq!(
+            db.replace_pending_proposals("item-1", &proposals).unwrap(),
+            2
+        );
+
+        let stored = db.get_proposals_for_entity("item-1").unwrap();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[0].field, "form");
+        assert_eq!(stored[0].source, Source::MusicBrainz);
+
+        db.set_proposal_status(stored[0].id, ProposalStatus::Accepted)
+            .unwrap();
+        let accepted = db.get_proposal(stored[0].id).unwrap().unwrap();
+        assert_eq!(accepted.status, ProposalStatus::Accepted);
+        assert!(accepted.decided_at.is_some());
+
+        // Re-harmonizing keeps decided proposals and does not re-propose them
+        assert_eq!(
+            db.replace_pending_proposals("item-1", &proposals).unwrap(),
+            1
+        );
+        assert_eq!(db.get_proposals_for_entity("item-1").unwrap().len(), 2);
+
+        let counts = db.count_proposals_by_status().unwrap();
+        assert!(counts.contains(&(ProposalStatus::Accepted, 1)));
+        assert!(counts.contains(&(ProposalStatus::Pending, 1)));
+        assert_eq!(
+            db.count_proposals_by_entity_and_status().unwrap(),
+            vec![
+                ("item-1".to_string(), ProposalStatus::Accepted, 1),
+                ("item-1".to_string(), ProposalStatus::Pending, 1),
+            ]
+        );
+        assert_eq!(
+            db.list_proposals_by_status(ProposalStatus::Pending)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // Undo returns a proposal to pending
+        db.set_proposal_status(stored[0].id, ProposalStatus::Pending)
+            .unwrap();
+        assert!(db
+            .get_proposal(stored[0].id)
+            .unwrap()
+            .unwrap()
+            .decided_at
+            .is_none());
+
+        assert!(db
+            .set_proposal_status(999, ProposalStatus::Rejected)
+            .is_err());
+
+        // Proposals recorded outside harmonization are not duplicated
+        assert!(!db.insert_new_proposal(&proposals[0]).unwrap());
+        assert!(db
+            .insert_new_proposal(&Proposal::new("item-2", tag("form", "Symphony")))
+            .unwrap());
+
+        // Overriding records the user assertion; restoring writes back the
+        // exact previous state
+        let previous = db.get_proposal(stored[0].id).unwrap().unwrap();
+        let assertion_id = db
+            .insert_assertion(&Assertion::new(
+                "item-1",
+                "form",
+                serde_json::json!("Sinfonia"),
+                Source::User,
+            ))
+            .unwrap();
+        db.set_proposal_decision(
+            stored[0].id,
+            ProposalStatus::Overridden,
+            Some(chrono::Utc::now()),
+            Some(assertion_id),
+        )
+        .unwrap();
+        let overridden = db.get_proposal(stored[0].id).unwrap().unwrap();
+        assert_eq!(overridden.override_assertion_id, Some(assertion_id));
+        let removed = db.delete_assertion_by_id(assertion_id).unwrap().unwrap();
+        assert_eq!(removed.value, serde_json::json!("Sinfonia"));
+        assert!(db.delete_assertion_by_id(assertion_id).unwrap().is_none());
+        db.set_proposal_decision(previous.id, previous.status, previous.decided_at, None)
+            .unwrap();
+        assert_eq!(db.get_proposal(stored[0].id).unwrap().unwrap(), previous);
+        assert_eq!(db.delete_proposals_for_entity("item-1").unwrap(), 2);
+    }
+
+    /// A work with two recordings, a performer and a conductor, one release
+    /// and a file of each recording on it.
+    struct Library {
+        work: Work,
+        early: Expression,
+        late: Expression,
+        pianist: Artist,
+        conductor: Artist,
+        release: Manifestation,
+        items: [Item; 2],
+    }
+
+    fn library(db: &Database) -> Library {
+        let work = Work::new("Piano Concerto No. 2").with_composer("Sergei Rachmaninoff");
+        db.insert_work(&work).unwrap();
+        db.insert_work(&Work::new("Vocalise").with_composer("Sergei Rachmaninoff"))
+            .unwrap();
+        db.insert_work(&Work::new("The Rite of Spring").with_composer("Igor Stravinsky"))
+            .unwrap();
+
+        let pianist = Artist::new("Sviatoslav Richter").with_role(ArtistRole::Performer);
+        let conductor = Artist::new("Stanislaw Wislocki").with_role(ArtistRole::Conductor);
+        db.insert_artist(&pianist).unwrap();
+        db.insert_artist(&conductor).unwrap();
+
+        let mut early = Expression::new(work.id)
+            .with_title("1959 recording")
+            .with_performer(pianist.id)
+            .with_conductor(conductor.id);
+        early.recorded_year = Some(1959);
+        let mut late = Expression::new(work.id).with_title("1965 recording");
+        late.recorded_year = Some(1965);
+        db.insert_expression(&early).unwrap();
+        db.insert_expression(&late).unwrap();
+
+        let release = Manifestation::new("Rachmaninoff: Concertos");
+        db.insert_manifestation(&release).unwrap();
+        db.link_release_track(&ReleaseTrack::new(release.id, late.id).with_track_number(2))
+            .unwrap();
+        db.link_release_track(&ReleaseTrack::new(release.id, early.id).with_track_number(1))
+            .unwrap();
+
+        let items = [early.id, late.id].map(|expression_id| {
+            let mut item = Item::new(
+                PathBuf::from(format!("/music/{expression_id}.flac")),
+                AudioFormat::Flac,
+                1024,
+                Utc::now(),
+            );
+            item.expression_id = Some(expression_id);
+            item.manifestation_id = Some(release.id);
+            db.insert_item(&item).unwrap();
+            item
+        });
+
+        Library {
+            work,
+            early,
+            late,
+            pianist,
+            conductor,
+            release,
+            items,
+        }
+    }
+
+    #[test]
+    fn test_relationship_queries() {
+        let db = Database::open_in_memory().unwrap();
+        let lib = library(&db);
+
+        let expressions = db.list_expressions_for_work(&lib.work.id).unwrap();
+        assert_eq!(
+            expressions.iter().map(|e| e.id).collect::<Vec<_>>(),
+            [lib.early.id, lib.late.id]
+        );
+        assert_eq!(expressions[0].performer_ids(), [lib.pianist.id]);
+
+        let performers = db.list_performers_for_expression(&lib.early.id).unwrap();
+        assert_eq!(performers.len(), 1);
+        assert_eq!(performers[0].roles, [ArtistRole::Performer]);
+        assert!(db
+            .list_performers_for_expression(&lib.late.id)
+            .unwrap()
+            .is_empty());
+
+        // Conducting counts as taking part in a recording
+        for artist in [&lib.pianist, &lib.conductor] {
+            let expressions = db.list_expressions_for_artist(&artist.id).unwrap();
+            assert_eq!(expressions.len(), 1);
+            assert_eq!(expressions[0].id, lib.early.id);
+        }
+
+        let works = db.list_works_by_composer("sergei rachmaninoff").unwrap();
+        assert_eq!(
+            works.iter().map(|w| w.title.as_str()).collect::<Vec<_>>(),
+            ["Piano Concerto No. 2", "Vocalise"]
+        );
+
+        let tracks = db.list_tracks_for_manifestation(&lib.release.id).unwrap();
+        assert_eq!(
+            tracks.iter().map(|t| t.expression_id).collect::<Vec<_>>(),
+            [lib.early.id, lib.late.id]
+        );
+        let releases = db.list_manifestations_for_expression(&lib.late.id).unwrap();
+        assert_eq!(releases.len(), 1);
+        assert_eq!(releases[0].id, lib.release.id);
+
+        assert_eq!(
+            db.list_items_for_manifestation(&lib.release.id)
+                .unwrap()
+                .len(),
+            2
+        );
+        let items = db.list_items_for_expression(&lib.late.id).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, lib.items[1].id);
+    }
+
+    #[test]
+    fn test_paged_listings() {
+        let db = Database::open_in_memory().unwrap();
+        library(&db);
+
+        let page = Page::first(2);
+        let first = db.list_works_page(page).unwrap();
+        let second = db.list_works_page(page.next()).unwrap();
+        assert_eq!(
+            first
+                .iter()
+                .chain(&second)
+                .map(|w| w.title.clone())
+                .collect::<Vec<_>>(),
+            db.list_works()
+                .unwrap()
+                .into_iter()
+                .map(|w| w.title)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(second.len(), 1);
+        assert!(db.list_works_page(page.next().next()).unwrap().is_empty());
+
+        // Performer IDs are filled in for the rows on the page only
+        let first = db.list_expressions_page(Page::first(1)).unwrap();
+        let second = db.list_expressions_page(Page::first(1).next()).unwrap();
+        assert_eq!(first[0].title.as_deref(), Some("1959 recording"));
+        assert_eq!(first[0].performers.len(), 1);
+        assert!(second[0].performers.is_empty());
+
+        let artists = db.list_artists_page(Page::first(1)).unwrap();
+        assert_eq!(artists.len(), 1);
+        assert_eq!(artists[0].roles, [ArtistRole::Conductor]);
+        assert_eq!(
+            db.list_manifestations_page(Page::first(10)).unwrap().len(),
+            1
+        );
+        assert_eq!(db.list_items_page(Page::first(10).next()).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_delete_work_cascades() {
+        let db = Database::open_in_memory().unwrap();
+        let lib = library(&db);
+        db.insert_assertion(&Assertion::new(
+            lib.work.id.to_string(),
+            "title",
+            serde_json::json!("Piano Concerto No. 2"),
+            Source::MusicBrainz,
+        ))
+        .unwrap();
+
+        assert!(db.delete_work(&lib.work.id).unwrap());
+        assert!(!db.delete_work(&lib.work.id).unwrap());
+
+        assert!(db.get_work_by_id(&lib.work.id).unwrap().is_none());
+        assert!(db.get_expression_by_id(&lib.early.id).unwrap().is_none());
+        assert!(db.list_expression_performers().unwrap().is_empty());
+        assert!(db.list_release_tracks().unwrap().is_empty());
+        assert!(db
+            .get_assertions_for_entity(&lib.work.id.to_string())
+            .unwrap()
+            .is_empty());
+
+        // The files are kept, no longer identified
+        let items = db.list_all_items().unwrap();
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(|i| i.expression_id.is_none()));
+        assert!(items
+            .iter()
+            .all(|i| i.manifestation_id == Some(lib.release.id)));
+        assert_eq!(
+            db.get_artist_by_id(&lib.pianist.id).unwrap().unwrap().name,
+            "Sviatoslav Richter"
+        );
+    }
+
+    #[test]
+    fn test_delete_artist_manifestation_and_item() {
+        let db = Database::open_in_memory().unwrap();
+        let lib = library(&db);
+
+        assert!(db.delete_artist(&lib.conductor.id).unwrap());
+        assert!(db.delete_artist(&lib.pianist.id).unwrap());
+        let early = db.get_expression_by_id(&lib.early.id).unwrap().unwrap();
+        assert!(early.conductor_id.is_none());
+        assert!(early.performers.is_empty());
+        assert!(db.list_artists().unwrap().is_empty());
+
+        assert!(db
+            .unlink_release_track(&lib.release.id, &lib.late.id)
+            .unwrap());
+        assert!(db
+            .list_manifestations_for_expression(&lib.late.id)
+            .unwrap()
+            .is_empty());
+        assert!(db.delete_manifestation(&lib.release.id).unwrap());
+        assert!(db.list_release_tracks().unwrap().is_empty());
+        assert!(db
+            .list_all_items()
+            .unwrap()
+            .iter()
+            .all(|i| i.manifestation_id.is_none()));
+
+        let item = &lib.items[0];
+        db.insert_assertion(&Assertion::new(
+            item.id.to_string(),
+            "genre",
+            serde_json::json!("Concerto"),
+            Source::LastFm,
+        ))
+        .unwrap();
+        assert!(db.delete_item(&item.id).unwrap());
+        assert!(db.get_item_by_id(&item.id).unwrap().is_none());
+        assert!(db
+            .get_assertions_for_entity(&item.id.to_string())
+            .unwrap()
+            .is_empty());
+        assert_eq!(db.list_all_items().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_merge_works() {
+        let db = Database::open_in_memory().unwrap();
+        let lib = library(&db);
+        // A second copy of the work, as identify creates from a recording title
+        let copy = Work::new("Piano Concerto No. 2")
+            .with_musicbrainz_id("mb-concerto-2")
+            .with_key("C minor");
+        db.insert_work(&copy).unwrap();
+        let mut expr = Expression::new(copy.id);
+        expr.recorded_year = Some(2001);
+        db.insert_expression(&expr).unwrap();
+        db.insert_assertion(&Assertion::new(
+            copy.id.to_string(),
+            "genre",
+            serde_json::json!("Concerto"),
+            Source::Wikidata,
+        ))
+        .unwrap();
+
+        db.merge_works(&lib.work.id, &copy.id).unwrap();
+
+        assert!(db.get_work_by_id(&copy.id).unwrap().is_none());
+        let work = db.get_work_by_id(&lib.work.id).unwrap().unwrap();
+        assert_eq!(work.composer.as_deref(), Some("Sergei Rachmaninoff"));
+        assert_eq!(work.musicbrainz_id.as_deref(), Some("mb-concerto-2"));
+        assert_eq!(work.key.as_deref(), Some("C minor"));
+        assert_eq!(db.list_expressions_for_work(&work.id).unwrap().len(), 3);
+
+        let assertions = db.get_assertions_for_entity(&work.id.to_string()).unwrap();
+        assert!(assertions.iter().any(|a| a.field == "genre"));
+        let merged = assertions
+            .iter()
+            .find(|a| a.field == "merged_from")
+            .unwrap();
+        assert_eq!(merged.source, Source::User);
+        assert_eq!(merged.value["id"], copy.id.to_string());
+
+        assert!(matches!(
+            db.merge_works(&lib.work.id, &copy.id),
+            Err(Error::NotFound { entity: "work", .. })
+        ));
+        assert!(db.merge_works(&lib.work.id, &lib.work.id).is_err());
+    }
+
+    #[test]
+    fn test_merge_works_with_different_musicbrainz_ids_fails() {
+        let db = Database::open_in_memory().unwrap();
+        let a = Work::new("Vocalise").with_musicbrainz_id("mb-a");
+        let b = Work::new("Vocalise").with_musicbrainz_id("mb-b");
+        db.insert_work(&a).unwrap();
+        db.insert_work(&b).unwrap();
+
+        assert!(matches!(
+            db.merge_works(&a.id, &b.id),
+            Err(Error::InvalidData(_))
+        ));
+        assert_eq!(db.list_works().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_work_credits() {
+        let db = Database::open_in_memory().unwrap();
+        let song = Work::new("Das Lied von der Erde").with_composer("Gustav Mahler");
+        let copy = Work::new("Das Lied von der Erde");
+        db.insert_work(&song).unwrap();
+        db.insert_work(&copy).unwrap();
+        let mahler = Artist::new("Gustav Mahler").with_role(ArtistRole::Composer);
+        let bethge = Artist::new("Hans Bethge");
+        let schoenberg = Artist::new("Arnold Schoenberg");
+        for artist in [&mahler, &bethge, &schoenberg] {
+            db.insert_artist(artist).unwrap();
+        }
+
+        let credits = [
+            WorkCredit::new(song.id, mahler.id, WorkRole::Composer),
+            WorkCredit::new(song.id, bethge.id, WorkRole::Lyricist),
+            WorkCredit::new(copy.id, schoenberg.id, WorkRole::Arranger),
+            WorkCredit::new(copy.id, mahler.id, WorkRole::Composer),
+        ];
+        for credit in &credits {
+            db.link_work_credit(credit).unwrap();
+        }
+        // Crediting twice is a no-op
+        db.link_work_credit(&credits[0]).unwrap();
+        assert_eq!(db.list_work_credits().unwrap().len(), 4);
+        assert_eq!(
+            db.list_credits_for_work(&song.id)
+                .unwrap()
+                .iter()
+                .map(|c| c.role)
+                .collect::<Vec<_>>(),
+            [WorkRole::Composer, WorkRole::Lyricist]
+        );
+        assert_eq!(
+            db.list_credited_artists(&song.id, WorkRole::Lyricist)
+                .unwrap()
+                .iter()
+                .map(|a| a.id)
+                .collect::<Vec<_>>(),
+            [bethge.id]
+        );
+
+        // The copy's credits move to the survivor, without duplicates
+        db.merge_works(&song.id, &copy.id).unwrap();
+        assert_eq!(db.list_credits_for_work(&song.id).unwrap().len(), 3);
+        assert_eq!(
+            db.list_works_credited_to(&schoenberg.id).unwrap(),
+            [db.get_work_by_id(&song.id).unwrap().unwrap()]
+        );
+
+        assert!(db.unlink_work_credit(&credits[1]).unwrap());
+        assert!(!db.unlink_work_credit(&credits[1]).unwrap());
+        assert!(db.delete_artist(&schoenberg.id).unwrap());
+        assert!(db.delete_work(&song.id).unwrap());
+        assert!(db.list_work_credits().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_work_movements() {
+        let db = Database::open_in_memory().unwrap();
+        let symphony =
+            Work::new("Symphony No. 5 in C minor, Op. 67").with_composer("Ludwig van Beethoven");
+        let second = Work::new("Symphony No. 5: II. Andante con moto")
+            .with_parent(symphony.id, Some(2))
+            .with_movement_label("Andante con moto");
+        let first = Work::new("Symphony No. 5: I. Allegro con brio")
+            .with_parent(symphony.id, Some(1))
+            .with_movement_label("Allegro con brio");
+        let sketch = Work::new("Symphony No. 5: sketch").with_parent(symphony.id, None);
+        // A part of a movement
+        let theme = Work::new("Andante con moto: theme").with_parent(second.id, Some(1));
+        for work in [&symphony, &second, &first, &sketch, &theme] {
+            db.insert_work(work).unwrap();
+        }
+
+        assert_eq!(db.get_work_by_id(&second.id).unwrap().unwrap(), second);
+        let movements = db.list_movements(&symphony.id).unwrap();
+        assert_eq!(
+            movements.iter().map(|w| w.id).collect::<Vec<_>>(),
+            [first.id, second.id, sketch.id]
+        );
+        assert_eq!(
+            db.list_top_level_works().unwrap(),
+            std::slice::from_ref(&symphony)
+        );
+        assert_eq!(
+            db.get_root_work(&theme.id).unwrap().map(|w| w.id),
+            Some(symphony.id)
+        );
+        assert_eq!(
+            db.get_root_work(&symphony.id).unwrap().map(|w| w.id),
+            Some(symphony.id)
+        );
+        assert!(db.get_root_work(&WorkId::new()).unwrap().is_none());
+
+        // Merging a copy of the symphony moves its movements
+        let copy = Work::new("Symphony No. 5").with_catalog_number("Op. 67");
+        db.insert_work(&copy).unwrap();
+        db.upsert_work(&first.clone().with_parent(copy.id, Some(1)))
+            .unwrap();
+        db.merge_works(&symphony.id, &copy.id).unwrap();
+        assert_eq!(db.list_movements(&symphony.id).unwrap().len(), 3);
+
+        // Deleting the symphony keeps its movements, as works of their own
+        assert!(db.delete_work(&symphony.id).unwrap());
+        let first = db.get_work_by_id(&first.id).unwrap().unwrap();
+        assert!(!first.is_movement());
+        assert_eq!(
+            db.get_root_work(&theme.id).unwrap().map(|w| w.id),
+            Some(second.id)
+        );
+    }
+
+    #[test]
+    fn test_merge_expressions_and_artists() {
+        let db = Database::open_in_memory().unwrap();
+        let lib = library(&db);
+
+        // The pianist again, without a MusicBrainz ID, credited on both takes
+        let copy = Artist::new("Richter, Sviatoslav")
+            .with_role(ArtistRole::Performer)
+            .with_role(ArtistRole::Conductor);
+        db.insert_artist(&copy).unwrap();
+        let mut late = lib.late.clone();
+        late.performers = vec![Performer::new(copy.id).with_role("piano")];
+        late.conductor_id = Some(copy.id);
+        db.upsert_expression(&late).unwrap();
+        let mut early = lib.early.clone();
+        early.performers.push(Performer::new(copy.id));
+        db.upsert_expression(&early).unwrap();
+
+        db.merge_artists(&lib.pianist.id, &copy.id).unwrap();
+        let pianist = db.get_artist_by_id(&lib.pianist.id).unwrap().unwrap();
+        assert_eq!(pianist.roles.len(), 2);
+        assert!(db.get_artist_by_id(&copy.id).unwrap().is_none());
+        let expressions = db.list_expressions_for_artist(&pianist.id).unwrap();
+        assert_eq!(expressions.len(), 2);
+        let late = db.get_expression_by_id(&lib.late.id).unwrap().unwrap();
+        assert_eq!(late.performer_ids(), [pianist.id]);
+        assert_eq!(late.performers[0].role.as_deref(), Some("piano"));
+        assert_eq!(
+            late.performers[0].credited_as.as_deref(),
+            Some("Richter, Sviatoslav")
+        );
+        assert_eq!(late.conductor_id, Some(pianist.id));
+        assert_eq!(
+            db.get_expression_by_id(&lib.early.id)
+                .unwrap()
+                .unwrap()
+                .performer_ids(),
+            [pianist.id]
+        );
+
+        db.merge_expressions(&lib.early.id, &lib.late.id).unwrap();
+        assert!(db.get_expression_by_id(&lib.late.id).unwrap().is_none());
+        assert_eq!(
+            db.list_items_for_expression(&lib.early.id).unwrap().len(),
+            2
+        );
+        // Both were on the release; the survivor keeps its own track
+        let tracks = db.list_tracks_for_manifestation(&lib.release.id).unwrap();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].expression_id, lib.early.id);
+        assert_eq!(tracks[0].track_number, Some(1));
+    }
 }
@@ -172,6 +172,101 @@ CREATE INDEX IF NOT EXISTS idx_lcmpt_label ON lcmpt_terms(label);
 CREATE INDEX IF NOT EXISTS idx_lcmpt_broader ON lcmpt_terms(broader_uri);
 ";
 
+const MIGRATION_003: &str = r"
+-- Harmonization proposals awaiting (or after) review
+CREATE TABLE IF NOT EXISTS proposals (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity_id TEXT NOT NULL,
+    field TEXT NOT NULL,
+    value TEXT NOT NULL,
+    rule_name TEXT NOT NULL,
+    source TEXT NOT NULL,
+    confidence REAL NOT NULL,
+    alternatives TEXT NOT NULL DEFAULT '[]',
+    status TEXT NOT NULL DEFAULT 'pending',
+    created_at TEXT NOT NULL,
+    decided_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_proposals_entity_id ON proposals(entity_id);
+CREATE INDEX IF NOT EXISTS idx_proposals_status ON proposals(status);
+";
+
+const MIGRATION_004: &str = r"
+-- Files no longer found on disk, and lookups for matching moved files
+ALTER TABLE items ADD COLUMN missing_since TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_items_fingerprint ON items(fingerprint);
+CREATE INDEX IF NOT EXISTS idx_items_file_hash ON items(file_hash);
+";
+
+const MIGRATION_005: &str = r"
+-- Hash of the audio stream alone, unchanged by retagging
+ALTER TABLE items ADD COLUMN audio_hash TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_items_audio_hash ON items(audio_hash);
+";
+
+const MIGRATION_006: &str = r"
+-- Reverse lookups through the junction tables
+CREATE INDEX IF NOT EXISTS idx_expression_performers_artist_id
+    ON expression_performers(artist_id);
+CREATE INDEX IF NOT EXISTS idx_manifestation_expressions_expression_id
+    ON manifestation_expressions(expression_id);
+CREATE INDEX IF NOT EXISTS idx_expressions_conductor_id ON expressions(conductor_id);
+";
+
+const MIGRATION_007: &str = r"
+-- Movements and other parts of a work
+ALTER TABLE works ADD COLUMN parent_work_id TEXT REFERENCES works(id);
+ALTER TABLE works ADD COLUMN part_number INTEGER;
+ALTER TABLE works ADD COLUMN movement_label TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_works_parent_work_id ON works(parent_work_id);
+";
+
+const MIGRATION_008: &str = r"
+-- Artists credited on a work, by role
+CREATE TABLE IF NOT EXISTS work_credits (
+    work_id TEXT NOT NULL REFERENCES works(id),
+    artist_id TEXT NOT NULL REFERENCES artists(id),
+    role TEXT NOT NULL,
+    PRIMARY KEY (work_id, artist_id, role)
+);
+
+CREATE INDEX IF NOT EXISTS idx_work_credits_artist_id ON work_credits(artist_id);
+";
+
+const MIGRATION_009: &str = r"
+-- How each performer is credited on a recording; role is the instrument or voice
+ALTER TABLE expression_performers ADD COLUMN lcmpt_uri TEXT;
+ALTER TABLE expression_performers ADD COLUMN kind TEXT;
+ALTER TABLE expression_performers ADD COLUMN credited_as TEXT;
+";
+
+const MIGRATION_010: &str = r"
+-- When, where and how a recording was made
+ALTER TABLE expressions ADD COLUMN recorded_from TEXT;
+ALTER TABLE expressions ADD COLUMN recorded_until TEXT;
+ALTER TABLE expressions ADD COLUMN venue TEXT;
+ALTER TABLE expressions ADD COLUMN live INTEGER;
+";
+
+const MIGRATION_011: &str = r"
+-- English labels of Wikidata entities, cached between enrichment runs
+CREATE TABLE IF NOT EXISTS wikidata_labels (
+    qid TEXT PRIMARY KEY,
+    label TEXT NOT NULL,
+    fetched_at TEXT NOT NULL
+);
+";
+
+const MIGRATION_012: &str = r"
+-- The user assertion recorded by overriding a proposal, so reverting the
+-- override removes only that value
+ALTER TABLE proposals ADD COLUMN override_assertion_id INTEGER;
+";
+
 pub const MIGRATIONS: &[Migration] = &[
     Migration {
         version: 1,
@@ -183,4 +278,54 @@ pub const MIGRATIONS: &[Migration] = &[
         name: "vocabulary_tables",
         sql: MIGRATION_002,
     },
+    Migration {
+        version: 3,
+        name: "proposals",
+        sql: MIGRATION_003,
+    },
+    Migration {
+        version: 4,
+        name: "item_missing",
+        sql: MIGRATION_004,
+    },
+    Migration {
+        version: 5,
+        name: "item_audio_hash",
+        sql: MIGRATION_005,
+    },
+    Migration {
+        version: 6,
+        name: "junction_indexes",
+        sql: MIGRATION_006,
+    },
+    Migration {
+        version: 7,
+        name: "work_parts",
+        sql: MIGRATION_007,
+    },
+    Migration {
+        version: 8,
+        name: "work_credits",
+        sql: MIGRATION_008,
+    },
+    Migration {
+        version: 9,
+        name: "performer_roles",
+        sql: MIGRATION_009,
+    },
+    Migration {
+        version: 10,
+        name: "recording_sessions",
+        sql: MIGRATION_010,
+    },
+    Migration {
+        version: 11,
+        name: "wikidata_labels",
+        sql: MIGRATION_011,
+    },
+    Migration {
+        version: 12,
+        name: "proposal_overrides",
+        sql: MIGRATION_012,
+    },
 ];
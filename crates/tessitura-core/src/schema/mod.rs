@@ -1,4 +1,6 @@
 pub mod db;
 pub mod migrations;
+pub mod pool;
 
-pub use db::Database;
+pub use db::{CatalogStamp, Database, Page, Transaction};
+pub use pool::DatabasePool;
@@ -0,0 +1,133 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::error::Error;
+
+use super::db::Database;
+
+/// Number of idle connections a pool keeps by default.
+pub const DEFAULT_POOL_SIZE: usize = 4;
+
+/// A small pool of connections to one database file.
+///
+/// Cloning the pool is cheap and shares its connections, so one pool can be
+/// handed to every stage and async task. Connections are opened on demand,
+/// lent to a closure by [`with`](Self::with) and returned to the pool when
+/// it finishes; at most `size` idle connections are kept.
+#[derive(Debug, Clone)]
+pub struct DatabasePool {
+    inner: Arc<PoolInner>,
+}
+
+#[derive(Debug)]
+struct PoolInner {
+    path: PathBuf,
+    size: usize,
+    idle: Mutex<Vec<Database>>,
+}
+
+impl DatabasePool {
+    /// Create a pool for the database at `path`, keeping
+    /// [`DEFAULT_POOL_SIZE`] idle connections. No connection is opened
+    /// until the first [`with`](Self::with).
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self::with_size(path, DEFAULT_POOL_SIZE)
+    }
+
+    /// Create a pool keeping at most `size` idle connections.
+    #[must_use]
+    pub fn with_size(path: impl Into<PathBuf>, size: usize) -> Self {
+        Self {
+            inner: Arc::new(PoolInner {
+                path: path.into(),
+                size,
+                idle: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// The path of the pooled database.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    /// Run `f` on an idle connection, or on a new one if none is idle,
+    /// then return the connection to the pool.
+    ///
+    /// Async callers should do their reads and writes in `f` and await
+    /// outside it, since a connection cannot be held across an await.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a new connection cannot be opened, or the error
+    /// `f` returns.
+    pub fn with<T, E: From<Error>>(
+        &self,
+        f: impl FnOnce(&Database) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let idle = self.inner.idle.lock().ok().and_then(|mut idle| idle.pop());
+        let db = match idle {
+            Some(db) => db,
+            None => Database::open(&self.inner.path)?,
+        };
+        let result = f(&db);
+        self.inner.release(db);
+        result
+    }
+}
+
+impl PoolInner {
+    /// Keep a connection for reuse, unless the pool is full or `f` left it
+    /// inside a transaction.
+    fn release(&self, db: Database) {
+        if !db.conn().is_autocommit() {
+            return;
+        }
+        if let Ok(mut idle) = self.idle.lock() {
+            if idle.len() < self.size {
+                idle.push(db);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{AudioFormat, Item};
+    use chrono::Utc;
+
+    #[test]
+    fn test_pool_reuses_connections() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = DatabasePool::with_size(dir.path().join("test.db"), 1);
+
+        let item = Item::new("/music/a.flac".into(), AudioFormat::Flac, 1, Utc::now());
+        pool.with(|first| {
+            first.insert_item(&item)?;
+            // A nested call opens a second connection, which sees the write
+            pool.clone()
+                .with(|second| second.get_item_by_id(&item.id))
+                .map(|found| assert!(found.is_some()))
+        })
+        .unwrap();
+
+        assert_eq!(pool.inner.idle.lock().unwrap().len(), 1);
+        assert!(pool
+            .with(|db| db.get_item_by_id(&item.id))
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn test_pool_drops_connections_left_in_a_transaction() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = DatabasePool::new(dir.path().join("test.db"));
+
+        pool.with(|db| db.conn().execute_batch("BEGIN").map_err(Error::from))
+            .unwrap();
+        assert!(pool.inner.idle.lock().unwrap().is_empty());
+    }
+}
@@ -18,9 +18,11 @@
 //! assert!(priority > 0);
 //! ```
 
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::OnceLock;
 
 use crate::error::{Error, Result};
 use crate::provenance::{Assertion, Source};
@@ -34,6 +36,7 @@ use crate::provenance::{Assertion, Source};
 const SOURCE_NAMES: &[(Source, &str)] = &[
     (Source::EmbeddedTag, "embedded_tag"),
     (Source::AcoustId, "acoustid"),
+    (Source::Fingerprint, "fingerprint"),
     (Source::MusicBrainz, "musicbrainz"),
     (Source::Wikidata, "wikidata"),
     (Source::LastFm, "lastfm"),
@@ -64,6 +67,12 @@ fn str_to_source(name: &str) -> Option<Source> {
     None
 }
 
+/// Assertion fields matched by genre rules.
+const GENRE_FIELDS: &[&str] = &["genre", "style", "form", "tag"];
+
+/// Assertion fields matched by instrument rules.
+const INSTRUMENT_FIELDS: &[&str] = &["instrumentation", "instrument", "ensemble"];
+
 // ---------------------------------------------------------------------------
 // Rule types
 // ---------------------------------------------------------------------------
@@ -73,7 +82,7 @@ fn str_to_source(name: &str) -> Option<Source> {
 /// The rules engine normalizes raw metadata assertions from multiple sources
 /// into canonical controlled vocabulary terms. Source priorities determine
 /// which source wins when assertions conflict.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MappingRules {
     /// Source name to priority (higher wins).
     #[serde(default)]
@@ -90,6 +99,10 @@ pub struct MappingRules {
     /// Rules for mapping instrumentation assertions to canonical instruments.
     #[serde(default)]
     pub instrument_rules: Vec<InstrumentRule>,
+
+    /// Canonical values for Wikidata entities, keyed by QID (e.g. "Q1344").
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub wikidata_mappings: HashMap<String, WikidataMapping>,
 }
 
 /// A rule for mapping genre, style, form, or tag assertions to canonical values.
@@ -102,10 +115,25 @@ pub struct GenreRule {
     #[serde(default)]
     pub description: Option<String>,
 
-    /// Match if the assertion value contains any of these strings (case-insensitive).
+    /// Match if the assertion value matches any of these patterns
+    /// (case-insensitive), as `match_mode` says.
     #[serde(default)]
     pub match_any: Vec<String>,
 
+    /// How `match_any` and `exclude_any` patterns are compared with values.
+    #[serde(default)]
+    pub match_mode: MatchMode,
+
+    /// Most edits (insertions, deletions, substitutions) a value may be from
+    /// a pattern in fuzzy mode.
+    #[serde(default = "default_max_edits")]
+    pub max_edits: usize,
+
+    /// Never match a value matching any of these patterns, even if it
+    /// matches `match_any`.
+    #[serde(default)]
+    pub exclude_any: Vec<String>,
+
     /// Only match assertions from these sources. Empty means match all sources.
     #[serde(default)]
     pub match_source: Vec<String>,
@@ -125,6 +153,71 @@ pub struct GenreRule {
     /// Confidence score for the rule output (0.0 to 1.0).
     #[serde(default = "default_confidence")]
     pub confidence: f64,
+
+    /// Conditions on the entity's other assertions that must hold too.
+    #[serde(flatten)]
+    pub conditions: Conditions,
+
+    /// The compiled patterns, built on first match or by
+    /// [`MappingRules::compile`].
+    #[serde(skip)]
+    matcher: OnceLock<Option<RuleMatcher>>,
+}
+
+/// Conditions over all of an entity's assertions, for rules that need
+/// several sources or fields to agree (e.g. a "chamber" tag from Last.fm
+/// and a violin in Wikidata's instrumentation).
+///
+/// A rule with conditions but no `match_any` fires once per entity when
+/// they hold; a rule with both fires for each matching assertion, but only
+/// when its conditions hold.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Conditions {
+    /// Every one of these must match some assertion.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub all_of: Vec<Condition>,
+
+    /// At least one of these must match some assertion (if any are given).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub any_of: Vec<Condition>,
+
+    /// None of these may match any assertion.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub none_of: Vec<Condition>,
+}
+
+/// A predicate on one assertion's field, source and value.
+///
+/// Besides the stored assertions, the period the period rules infer can be
+/// matched as the "period" field.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Condition {
+    /// The assertion field (e.g. "tag", "instrumentation"). Any field if
+    /// not given.
+    #[serde(default)]
+    pub field: Option<String>,
+
+    /// The assertion source (e.g. "lastfm"). Any source if not given.
+    #[serde(default)]
+    pub source: Option<String>,
+
+    /// Match if the value matches any of these patterns. Any value if
+    /// empty.
+    #[serde(default)]
+    pub match_any: Vec<String>,
+
+    /// How `match_any` patterns are compared with the value.
+    #[serde(default)]
+    pub match_mode: MatchMode,
+
+    /// Most edits a value may be from a pattern in fuzzy mode.
+    #[serde(default = "default_max_edits")]
+    pub max_edits: usize,
+
+    /// The compiled patterns, built on first match or by
+    /// [`MappingRules::compile`].
+    #[serde(skip)]
+    matcher: OnceLock<Option<RuleMatcher>>,
 }
 
 /// A rule for inferring musical period from composer name or composition year.
@@ -159,10 +252,23 @@ pub struct InstrumentRule {
     #[serde(default)]
     pub description: Option<String>,
 
-    /// Match if the assertion value contains any of these strings (case-insensitive).
+    /// Match if the assertion value matches any of these patterns
+    /// (case-insensitive), as `match_mode` says.
     #[serde(default)]
     pub match_any: Vec<String>,
 
+    /// How `match_any` and `exclude_any` patterns are compared with values.
+    #[serde(default)]
+    pub match_mode: MatchMode,
+
+    /// Most edits a value may be from a pattern in fuzzy mode.
+    #[serde(default = "default_max_edits")]
+    pub max_edits: usize,
+
+    /// Never match a value matching any of these patterns.
+    #[serde(default)]
+    pub exclude_any: Vec<String>,
+
     /// Canonical instrument names to produce.
     #[serde(default)]
     pub output_instruments: Vec<String>,
@@ -170,12 +276,90 @@ pub struct InstrumentRule {
     /// LCMPT labels to link.
     #[serde(default)]
     pub output_lcmpt_labels: Vec<String>,
+
+    /// The compiled patterns, built on first match or by
+    /// [`MappingRules::compile`].
+    #[serde(skip)]
+    matcher: OnceLock<Option<RuleMatcher>>,
+}
+
+/// Canonical values for a Wikidata entity that assertions refer to by QID
+/// (as `{"wikidata_qid": "Q…"}`), such as a work's form, instrumentation
+/// or period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WikidataMapping {
+    /// The entity's label, for readers of the rules file.
+    #[serde(default)]
+    pub label: Option<String>,
+
+    /// Canonical genre to produce.
+    #[serde(default)]
+    pub output_genre: Option<String>,
+
+    /// Canonical form to produce.
+    #[serde(default)]
+    pub output_form: Option<String>,
+
+    /// LCGFT label to link.
+    #[serde(default)]
+    pub output_lcgft_label: Option<String>,
+
+    /// Canonical instrument names to produce.
+    #[serde(default)]
+    pub output_instruments: Vec<String>,
+
+    /// Canonical period to produce.
+    #[serde(default)]
+    pub output_period: Option<String>,
+
+    /// Confidence score for the mapping output (0.0 to 1.0).
+    #[serde(default = "default_confidence")]
+    pub confidence: f64,
+}
+
+/// How a rule's patterns are compared with assertion values. Every mode
+/// ignores case.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchMode {
+    /// The value contains the pattern anywhere ("bop" matches "hip-hop").
+    #[default]
+    Substring,
+    /// The value contains the pattern's words as whole words ("bop" matches
+    /// "hard bop" but not "hip-hop").
+    Word,
+    /// The value is the pattern, ignoring surrounding whitespace.
+    Exact,
+    /// The pattern is a regular expression found anywhere in the value.
+    Regex,
+    /// Some run of the value's words is within `max_edits` edits of the
+    /// pattern ("string quartett" matches "string quartet").
+    Fuzzy,
+}
+
+/// A rule's `match_any` and `exclude_any` patterns, compiled for its
+/// [`MatchMode`].
+#[derive(Debug, Clone)]
+pub struct RuleMatcher {
+    mode: MatchMode,
+    max_edits: usize,
+    include: Patterns,
+    exclude: Patterns,
+}
+
+/// Lowercased text patterns, or compiled regular expressions.
+#[derive(Debug, Clone)]
+enum Patterns {
+    Text(Vec<String>),
+    Regex(Vec<Regex>),
 }
 
 /// A proposed metadata value produced by the rules engine.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProposedTag {
-    /// The metadata field: "genre", "form", "period", or "instrumentation".
+    /// The metadata field: "genre", "form", "period", or "instrumentation";
+    /// "performer" for a performer credit; or "expression" for a link to
+    /// the recording whose ID is the value.
     pub field: String,
 
     /// The canonical value proposed by the rule.
@@ -196,7 +380,7 @@ pub struct ProposedTag {
 }
 
 /// An alternative proposal that conflicted with the primary proposal.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Alternative {
     /// The alternative canonical value.
     pub value: String,
@@ -212,6 +396,293 @@ fn default_confidence() -> f64 {
     0.8
 }
 
+const fn default_max_edits() -> usize {
+    1
+}
+
+impl RuleMatcher {
+    /// Compile patterns for `mode`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if, in regex mode, a pattern is not a valid regular
+    /// expression.
+    pub fn new(
+        mode: MatchMode,
+        max_edits: usize,
+        match_any: &[String],
+        exclude_any: &[String],
+    ) -> Result<Self> {
+        Ok(Self {
+            mode,
+            max_edits,
+            include: Patterns::new(mode, match_any)?,
+            exclude: Patterns::new(mode, exclude_any)?,
+        })
+    }
+
+    /// Whether a lowercased value matches an included pattern and no
+    /// excluded one.
+    #[must_use]
+    pub fn matches(&self, value_lower: &str) -> bool {
+        self.any_matches(&self.include, value_lower)
+            && !self.any_matches(&self.exclude, value_lower)
+    }
+
+    fn any_matches(&self, patterns: &Patterns, value: &str) -> bool {
+        let texts = match patterns {
+            Patterns::Regex(regexes) => return regexes.iter().any(|r| r.is_match(value)),
+            Patterns::Text(texts) => texts,
+        };
+        match self.mode {
+            MatchMode::Substring => rule_matches_value(texts, value),
+            MatchMode::Exact => texts.iter().any(|t| t == value.trim()),
+            MatchMode::Word => {
+                let value_words = words(value);
+                texts.iter().any(|t| {
+                    let wanted = words(t);
+                    !wanted.is_empty() && value_words.windows(wanted.len()).any(|w| w == wanted)
+                })
+            }
+            MatchMode::Fuzzy => {
+                let value_words = words(value);
+                texts.iter().any(|t| {
+                    let wanted = words(t);
+                    !wanted.is_empty()
+                        && value_words.windows(wanted.len()).any(|w| {
+                            edit_distance(&w.join(" "), &wanted.join(" ")) <= self.max_edits
+                        })
+                })
+            }
+            // Regex patterns are compiled as `Patterns::Regex`
+            MatchMode::Regex => false,
+        }
+    }
+}
+
+impl Patterns {
+    fn new(mode: MatchMode, patterns: &[String]) -> Result<Self> {
+        if mode != MatchMode::Regex {
+            return Ok(Self::Text(
+                patterns.iter().map(|p| p.trim().to_lowercase()).collect(),
+            ));
+        }
+        patterns
+            .iter()
+            .map(|p| {
+                RegexBuilder::new(p)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| Error::InvalidData(format!("invalid regex '{p}': {e}")))
+            })
+            .collect::<Result<_>>()
+            .map(Self::Regex)
+    }
+}
+
+/// The words of a lowercased value, split at anything not a letter or digit.
+fn words(value: &str) -> Vec<&str> {
+    value
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Levenshtein distance between two strings, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+impl Default for GenreRule {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            description: None,
+            match_any: Vec::new(),
+            match_mode: MatchMode::default(),
+            max_edits: default_max_edits(),
+            exclude_any: Vec::new(),
+            match_source: Vec::new(),
+            output_genre: None,
+            output_form: None,
+            output_lcgft_label: None,
+            confidence: default_confidence(),
+            conditions: Conditions::default(),
+            matcher: OnceLock::new(),
+        }
+    }
+}
+
+impl GenreRule {
+    /// Produce a proposal for each of the rule's outputs.
+    fn propose(&self, source: Source, confidence: f64, proposals: &mut Vec<ProposedTag>) {
+        let outputs = [
+            ("genre", &self.output_genre),
+            ("form", &self.output_form),
+            ("genre", &self.output_lcgft_label),
+        ];
+        for (field, value) in outputs {
+            if let Some(value) = value {
+                proposals.push(ProposedTag {
+                    field: field.to_string(),
+                    value: value.clone(),
+                    source,
+                    rule_name: self.name.clone(),
+                    confidence,
+                    alternatives: Vec::new(),
+                });
+            }
+        }
+    }
+
+    /// Whether a lowercased assertion value matches this rule's patterns.
+    ///
+    /// The patterns are compiled on first use; invalid ones, which
+    /// [`MappingRules::compile`] reports, match nothing.
+    #[must_use]
+    pub fn matches_value(&self, value_lower: &str) -> bool {
+        self.matcher
+            .get_or_init(|| self.compile_matcher().ok())
+            .as_ref()
+            .is_some_and(|matcher| matcher.matches(value_lower))
+    }
+
+    fn compile_matcher(&self) -> Result<RuleMatcher> {
+        RuleMatcher::new(
+            self.match_mode,
+            self.max_edits,
+            &self.match_any,
+            &self.exclude_any,
+        )
+    }
+}
+
+impl Condition {
+    /// Whether an assertion satisfies this condition.
+    #[must_use]
+    pub fn matches(&self, assertion: &Assertion) -> bool {
+        if self
+            .field
+            .as_ref()
+            .is_some_and(|field| !field.eq_ignore_ascii_case(&assertion.field))
+        {
+            return false;
+        }
+        if self
+            .source
+            .as_ref()
+            .is_some_and(|source| !source.eq_ignore_ascii_case(source_to_str(assertion.source)))
+        {
+            return false;
+        }
+        if self.match_any.is_empty() {
+            return true;
+        }
+        let value = assertion_value_as_str(assertion).to_lowercase();
+        self.matcher
+            .get_or_init(|| self.compile_matcher().ok())
+            .as_ref()
+            .is_some_and(|matcher| matcher.matches(&value))
+    }
+
+    fn compile_matcher(&self) -> Result<RuleMatcher> {
+        RuleMatcher::new(self.match_mode, self.max_edits, &self.match_any, &[])
+    }
+}
+
+impl Conditions {
+    /// Whether no condition is given.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.all_of.is_empty() && self.any_of.is_empty() && self.none_of.is_empty()
+    }
+
+    /// Check the conditions against an entity's assertions, returning the
+    /// assertions that satisfy the `all_of` and `any_of` conditions (in
+    /// condition order) if they hold.
+    #[must_use]
+    pub fn evaluate<'a>(&self, assertions: &'a [Assertion]) -> Option<Vec<&'a Assertion>> {
+        let first_match = |condition: &Condition| assertions.iter().find(|a| condition.matches(a));
+        let mut witnesses = self
+            .all_of
+            .iter()
+            .map(first_match)
+            .collect::<Option<Vec<_>>>()?;
+        if !self.any_of.is_empty() {
+            let any: Vec<&Assertion> = self.any_of.iter().filter_map(first_match).collect();
+            if any.is_empty() {
+                return None;
+            }
+            witnesses.extend(any);
+        }
+        if self.none_of.iter().any(|c| first_match(c).is_some()) {
+            return None;
+        }
+        Some(witnesses)
+    }
+
+    fn compile(&mut self) -> Result<()> {
+        for condition in self
+            .all_of
+            .iter_mut()
+            .chain(&mut self.any_of)
+            .chain(&mut self.none_of)
+        {
+            condition.matcher = OnceLock::from(Some(condition.compile_matcher()?));
+        }
+        Ok(())
+    }
+}
+
+impl Default for InstrumentRule {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            description: None,
+            match_any: Vec::new(),
+            match_mode: MatchMode::default(),
+            max_edits: default_max_edits(),
+            exclude_any: Vec::new(),
+            output_instruments: Vec::new(),
+            output_lcmpt_labels: Vec::new(),
+            matcher: OnceLock::new(),
+        }
+    }
+}
+
+impl InstrumentRule {
+    /// Whether a lowercased assertion value matches this rule's patterns.
+    ///
+    /// The patterns are compiled on first use; invalid ones, which
+    /// [`MappingRules::compile`] reports, match nothing.
+    #[must_use]
+    pub fn matches_value(&self, value_lower: &str) -> bool {
+        self.matcher
+            .get_or_init(|| self.compile_matcher().ok())
+            .as_ref()
+            .is_some_and(|matcher| matcher.matches(value_lower))
+    }
+
+    fn compile_matcher(&self) -> Result<RuleMatcher> {
+        RuleMatcher::new(
+            self.match_mode,
+            self.max_edits,
+            &self.match_any,
+            &self.exclude_any,
+        )
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Implementation
 // ---------------------------------------------------------------------------
@@ -221,19 +692,74 @@ impl MappingRules {
     ///
     /// # Errors
     ///
-    /// Returns an error if the file cannot be read or parsed.
+    /// Returns an error if the file cannot be read or parsed, or a rule's
+    /// patterns are invalid.
     pub fn load(path: &Path) -> Result<Self> {
         let content = std::fs::read_to_string(path).map_err(Error::Io)?;
-        let rules: Self = toml::from_str(&content).map_err(|e| {
+        let mut rules: Self = toml::from_str(&content).map_err(|e| {
             Error::InvalidData(format!(
                 "failed to parse mapping rules from {}: {}",
                 path.display(),
                 e
             ))
         })?;
+        rules.compile().map_err(|e| {
+            Error::InvalidData(format!(
+                "invalid mapping rules in {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
         Ok(rules)
     }
 
+    /// Validate every genre and instrument rule and compile its patterns.
+    ///
+    /// [`MappingRules::load`] does this. Rules built in code compile their
+    /// patterns on first match instead, but only this reports invalid ones;
+    /// a rule whose patterns change after it has matched needs compiling
+    /// again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first rule with an invalid regex, or
+    /// with `none_of` conditions but nothing to match positively, or the
+    /// first Wikidata mapping not keyed by a QID.
+    pub fn compile(&mut self) -> Result<()> {
+        if let Some(key) = self.wikidata_mappings.keys().find(|key| !is_qid(key)) {
+            return Err(Error::InvalidData(format!(
+                "wikidata_mappings key '{key}' is not a QID (e.g. \"Q1344\")"
+            )));
+        }
+        let invalid = |name: &str, e: Error| match e {
+            Error::InvalidData(msg) => Error::InvalidData(format!("rule '{name}': {msg}")),
+            other => other,
+        };
+        for rule in &mut self.genre_rules {
+            let conditions = &rule.conditions;
+            if rule.match_any.is_empty()
+                && conditions.all_of.is_empty()
+                && conditions.any_of.is_empty()
+                && !conditions.none_of.is_empty()
+            {
+                return Err(Error::InvalidData(format!(
+                    "rule '{}': none_of needs match_any, all_of or any_of alongside",
+                    rule.name
+                )));
+            }
+            let matcher = rule.compile_matcher().map_err(|e| invalid(&rule.name, e))?;
+            rule.matcher = OnceLock::from(Some(matcher));
+            rule.conditions
+                .compile()
+                .map_err(|e| invalid(&rule.name, e))?;
+        }
+        for rule in &mut self.instrument_rules {
+            let matcher = rule.compile_matcher().map_err(|e| invalid(&rule.name, e))?;
+            rule.matcher = OnceLock::from(Some(matcher));
+        }
+        Ok(())
+    }
+
     /// Get the priority for a given source name.
     ///
     /// Returns 0 if the source is not found in the priority map.
@@ -255,16 +781,23 @@ impl MappingRules {
     /// Filters assertions by relevant fields ("genre", "style", "form", "tag"),
     /// then matches each against the configured genre rules in order. When a
     /// rule matches, a [`ProposedTag`] is created with the rule's output values.
+    /// Rules with [`Conditions`] only match when their conditions hold over
+    /// all of `assertions`; rules with conditions alone produce one proposal,
+    /// credited to the source of the assertion satisfying their first
+    /// positive condition.
     ///
     /// When multiple rules produce proposals for the same output field and value,
     /// the proposal from the highest-priority source wins, and lower-priority
     /// proposals are recorded as alternatives.
     pub fn apply_genre_rules(&self, assertions: &[Assertion]) -> Vec<ProposedTag> {
-        let relevant_fields = ["genre", "style", "form", "tag"];
-
         let relevant: Vec<&Assertion> = assertions
             .iter()
-            .filter(|a| relevant_fields.contains(&a.field.as_str()))
+            .filter(|a| GENRE_FIELDS.contains(&a.field.as_str()))
+            .collect();
+        let witnesses: Vec<Option<Vec<&Assertion>>> = self
+            .genre_rules
+            .iter()
+            .map(|rule| rule.conditions.evaluate(assertions))
             .collect();
 
         let mut raw_proposals: Vec<ProposedTag> = Vec::new();
@@ -274,50 +807,56 @@ impl MappingRules {
             let assertion_lower = assertion_value.to_lowercase();
             let source_name = source_to_str(assertion.source);
 
-            for rule in &self.genre_rules {
+            for (rule, witnesses) in self.genre_rules.iter().zip(&witnesses) {
+                if witnesses.is_none() {
+                    continue;
+                }
+
                 if !rule_matches_source(rule.match_source.as_slice(), source_name) {
                     continue;
                 }
 
-                if !rule_matches_value(rule.match_any.as_slice(), &assertion_lower) {
+                if !rule.matches_value(&assertion_lower) {
                     continue;
                 }
 
                 let assertion_confidence = assertion.confidence.unwrap_or(1.0);
-                let combined_confidence = rule.confidence * assertion_confidence;
-
-                // Produce a proposal for each non-None output field.
-                if let Some(ref genre) = rule.output_genre {
-                    raw_proposals.push(ProposedTag {
-                        field: "genre".to_string(),
-                        value: genre.clone(),
-                        source: assertion.source,
-                        rule_name: rule.name.clone(),
-                        confidence: combined_confidence,
-                        alternatives: Vec::new(),
-                    });
-                }
+                rule.propose(
+                    assertion.source,
+                    rule.confidence * assertion_confidence,
+                    &mut raw_proposals,
+                );
+            }
+        }
 
-                if let Some(ref form) = rule.output_form {
-                    raw_proposals.push(ProposedTag {
-                        field: "form".to_string(),
-                        value: form.clone(),
-                        source: assertion.source,
-                        rule_name: rule.name.clone(),
-                        confidence: combined_confidence,
-                        alternatives: Vec::new(),
-                    });
-                }
+        // Rules matching on their conditions alone
+        for (rule, witnesses) in self.genre_rules.iter().zip(&witnesses) {
+            if !rule.match_any.is_empty() {
+                continue;
+            }
+            let Some(witnesses) = witnesses.as_deref().filter(|w| !w.is_empty()) else {
+                continue;
+            };
+            let assertion_confidence = witnesses
+                .iter()
+                .map(|a| a.confidence.unwrap_or(1.0))
+                .fold(1.0, f64::min);
+            rule.propose(
+                witnesses[0].source,
+                rule.confidence * assertion_confidence,
+                &mut raw_proposals,
+            );
+        }
 
-                if let Some(ref lcgft) = rule.output_lcgft_label {
-                    raw_proposals.push(ProposedTag {
-                        field: "genre".to_string(),
-                        value: lcgft.clone(),
-                        source: assertion.source,
-                        rule_name: rule.name.clone(),
-                        confidence: combined_confidence,
-                        alternatives: Vec::new(),
-                    });
+        for (assertion, qid, mapping) in self.mapped_entities(&relevant) {
+            let outputs = [
+                ("genre", &mapping.output_genre),
+                ("form", &mapping.output_form),
+                ("genre", &mapping.output_lcgft_label),
+            ];
+            for (field, value) in outputs {
+                if let Some(value) = value {
+                    raw_proposals.push(mapping_proposal(assertion, qid, mapping, field, value));
                 }
             }
         }
@@ -390,9 +929,7 @@ impl MappingRules {
     pub fn apply_instrument_rules(&self, assertions: &[Assertion]) -> Vec<ProposedTag> {
         let relevant: Vec<&Assertion> = assertions
             .iter()
-            .filter(|a| {
-                a.field == "instrumentation" || a.field == "instrument" || a.field == "ensemble"
-            })
+            .filter(|a| INSTRUMENT_FIELDS.contains(&a.field.as_str()))
             .collect();
 
         let mut raw_proposals: Vec<ProposedTag> = Vec::new();
@@ -402,7 +939,7 @@ impl MappingRules {
             let assertion_lower = assertion_value.to_lowercase();
 
             for rule in &self.instrument_rules {
-                if !rule_matches_value(rule.match_any.as_slice(), &assertion_lower) {
+                if !rule.matches_value(&assertion_lower) {
                     continue;
                 }
 
@@ -422,9 +959,124 @@ impl MappingRules {
             }
         }
 
+        for (assertion, qid, mapping) in self.mapped_entities(&relevant) {
+            for instrument in &mapping.output_instruments {
+                raw_proposals.push(mapping_proposal(
+                    assertion,
+                    qid,
+                    mapping,
+                    "instrumentation",
+                    instrument,
+                ));
+            }
+        }
+
         deduplicate_proposals(&self.source_priority, &mut raw_proposals);
         raw_proposals
     }
+
+    /// The period a mapped Wikidata "period" or "school" assertion gives,
+    /// from the most confident one.
+    pub fn apply_wikidata_period(&self, assertions: &[Assertion]) -> Option<ProposedTag> {
+        let relevant: Vec<&Assertion> = assertions
+            .iter()
+            .filter(|a| a.field == "period" || a.field == "school")
+            .collect();
+        self.mapped_entities(&relevant)
+            .filter_map(|(assertion, qid, mapping)| {
+                let period = mapping.output_period.as_ref()?;
+                Some(mapping_proposal(assertion, qid, mapping, "period", period))
+            })
+            .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+    }
+
+    /// The assertions referring to a mapped Wikidata entity, with its QID
+    /// and mapping.
+    fn mapped_entities<'a>(
+        &'a self,
+        assertions: &'a [&'a Assertion],
+    ) -> impl Iterator<Item = (&'a Assertion, &'a str, &'a WikidataMapping)> + 'a {
+        assertions.iter().filter_map(|assertion| {
+            let qid = assertion_qid(assertion)?;
+            let mapping = self.wikidata_mappings.get(qid)?;
+            Some((*assertion, qid, mapping))
+        })
+    }
+
+    /// Apply every rule family to one entity's assertions.
+    ///
+    /// Combines genre, period and instrument proposals. The period comes
+    /// from a mapped Wikidata period or school, or else is inferred from the
+    /// "composer" and "composed_year"/"year" assertions; genre rule
+    /// conditions can match it as the "period" field.
+    pub fn harmonize(&self, assertions: &[Assertion]) -> Vec<ProposedTag> {
+        let composer = assertions
+            .iter()
+            .find(|a| a.field == "composer")
+            .and_then(|a| a.value.as_str());
+        #[allow(clippy::cast_possible_truncation)]
+        let year = assertions
+            .iter()
+            .find(|a| a.field == "composed_year" || a.field == "year")
+            .and_then(|a| a.value.as_i64())
+            .map(|y| y as i32);
+
+        let period = self
+            .apply_wikidata_period(assertions)
+            .or_else(|| self.apply_period_rules(composer, year));
+
+        // Let rule conditions match the inferred period
+        let mut facts = assertions.to_vec();
+        if let Some(period) = &period {
+            let entity_id = assertions.first().map_or("", |a| a.entity_id.as_str());
+            facts.push(
+                Assertion::new(
+                    entity_id,
+                    "period",
+                    serde_json::json!(period.value),
+                    period.source,
+                )
+                .with_confidence(period.confidence),
+            );
+        }
+
+        let mut proposals = self.apply_genre_rules(&facts);
+        proposals.extend(period);
+        proposals.extend(self.apply_instrument_rules(assertions));
+        proposals
+    }
+
+    /// Find the genre and instrumentation assertions that no rule matches.
+    ///
+    /// These are the values a rules file does not cover yet. Assertions in
+    /// fields the rules never look at are not included.
+    pub fn unmatched<'a>(&self, assertions: &'a [Assertion]) -> Vec<&'a Assertion> {
+        assertions
+            .iter()
+            .filter(|assertion| {
+                let qid = assertion_qid(assertion);
+                if qid.is_some_and(|q| self.wikidata_mappings.contains_key(q)) {
+                    return false;
+                }
+                let field = assertion.field.as_str();
+                let value = assertion_value_as_str(assertion).to_lowercase();
+                if GENRE_FIELDS.contains(&field) {
+                    let source_name = source_to_str(assertion.source);
+                    !self.genre_rules.iter().any(|rule| {
+                        rule_matches_source(&rule.match_source, source_name)
+                            && rule.matches_value(&value)
+                    })
+                } else if INSTRUMENT_FIELDS.contains(&field) {
+                    !self
+                        .instrument_rules
+                        .iter()
+                        .any(|rule| rule.matches_value(&value))
+                } else {
+                    false
+                }
+            })
+            .collect()
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -433,15 +1085,50 @@ impl MappingRules {
 
 /// Extract the assertion value as a string, handling both JSON strings and
 /// other JSON value types by converting to their display representation.
+/// Objects with a "label" (such as resolved Wikidata entities) give their
+/// label.
 ///
 /// Returns a `Cow` to avoid cloning when the value is already a string.
 fn assertion_value_as_str(assertion: &Assertion) -> std::borrow::Cow<'_, str> {
     match &assertion.value {
         serde_json::Value::String(s) => std::borrow::Cow::Borrowed(s),
+        serde_json::Value::Object(map) => match map.get("label") {
+            Some(serde_json::Value::String(label)) => std::borrow::Cow::Borrowed(label),
+            _ => std::borrow::Cow::Owned(assertion.value.to_string()),
+        },
         other => std::borrow::Cow::Owned(other.to_string()),
     }
 }
 
+/// Whether a string is a Wikidata item ID: "Q" followed by digits.
+fn is_qid(s: &str) -> bool {
+    s.strip_prefix('Q')
+        .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// The Wikidata QID an assertion value refers to, if any.
+fn assertion_qid(assertion: &Assertion) -> Option<&str> {
+    assertion.value.get("wikidata_qid")?.as_str()
+}
+
+/// A proposal for one output of a Wikidata mapping, named after its QID.
+fn mapping_proposal(
+    assertion: &Assertion,
+    qid: &str,
+    mapping: &WikidataMapping,
+    field: &str,
+    value: &str,
+) -> ProposedTag {
+    ProposedTag {
+        field: field.to_string(),
+        value: value.to_string(),
+        source: assertion.source,
+        rule_name: format!("wikidata:{qid}"),
+        confidence: mapping.confidence * assertion.confidence.unwrap_or(1.0),
+        alternatives: Vec::new(),
+    }
+}
+
 /// Check if the assertion's source is allowed by the rule's `match_source` list.
 /// An empty `match_source` list means all sources are allowed.
 ///
@@ -568,6 +1255,7 @@ mod tests {
     }
 
     /// Helper to build a minimal MappingRules for testing.
+    #[allow(clippy::too_many_lines)] // Literal rule set shared by the tests
     fn sample_rules() -> MappingRules {
         MappingRules {
             source_priority: HashMap::from([
@@ -589,6 +1277,7 @@ mod tests {
                     output_form: None,
                     output_lcgft_label: None,
                     confidence: 0.8,
+                    ..Default::default()
                 },
                 GenreRule {
                     name: "jazz-general".to_string(),
@@ -599,6 +1288,7 @@ mod tests {
                     output_form: None,
                     output_lcgft_label: None,
                     confidence: 0.8,
+                    ..Default::default()
                 },
                 GenreRule {
                     name: "string-quartet-form".to_string(),
@@ -609,6 +1299,7 @@ mod tests {
                     output_form: Some("String quartet".to_string()),
                     output_lcgft_label: Some("String quartets".to_string()),
                     confidence: 0.9,
+                    ..Default::default()
                 },
             ],
             period_rules: vec![
@@ -648,10 +1339,39 @@ mod tests {
                     "viola".to_string(),
                     "violoncello".to_string(),
                 ],
+                ..Default::default()
             }],
+            wikidata_mappings: HashMap::new(),
         }
     }
 
+    // -----------------------------------------------------------------------
+    // Combined harmonization tests
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_harmonize_combines_rule_families() {
+        let rules = sample_rules();
+        let assertions = vec![
+            make_assertion("form", "String Quartet", Source::MusicBrainz),
+            make_assertion("instrumentation", "string quartet", Source::Wikidata),
+            Assertion::new(
+                "test-entity",
+                "composer",
+                json!("Joseph Haydn"),
+                Source::MusicBrainz,
+            ),
+        ];
+
+        let proposals = rules.harmonize(&assertions);
+        let fields: Vec<&str> = proposals.iter().map(|p| p.field.as_str()).collect();
+        assert!(fields.contains(&"form"));
+        assert!(fields.contains(&"instrumentation"));
+        assert!(proposals
+            .iter()
+            .any(|p| p.field == "period" && p.value == "Classical"));
+    }
+
     // -----------------------------------------------------------------------
     // TOML loading tests
     // -----------------------------------------------------------------------
@@ -997,6 +1717,7 @@ match_any = ["rock"]
             output_form: None,
             output_lcgft_label: None,
             confidence: 0.8,
+            ..Default::default()
         }];
 
         let assertions = vec![make_assertion("genre", "anything", Source::MusicBrainz)];
@@ -1257,6 +1978,7 @@ match_any = ["rock"]
     fn test_source_to_str_all_variants() {
         assert_eq!(source_to_str(Source::EmbeddedTag), "embedded_tag");
         assert_eq!(source_to_str(Source::AcoustId), "acoustid");
+        assert_eq!(source_to_str(Source::Fingerprint), "fingerprint");
         assert_eq!(source_to_str(Source::MusicBrainz), "musicbrainz");
         assert_eq!(source_to_str(Source::Wikidata), "wikidata");
         assert_eq!(source_to_str(Source::LastFm), "lastfm");
@@ -1270,6 +1992,7 @@ match_any = ["rock"]
     fn test_str_to_source_all_variants() {
         assert_eq!(str_to_source("embedded_tag"), Some(Source::EmbeddedTag));
         assert_eq!(str_to_source("acoustid"), Some(Source::AcoustId));
+        assert_eq!(str_to_source("fingerprint"), Some(Source::Fingerprint));
         assert_eq!(str_to_source("musicbrainz"), Some(Source::MusicBrainz));
         assert_eq!(str_to_source("wikidata"), Some(Source::Wikidata));
         assert_eq!(str_to_source("lastfm"), Some(Source::LastFm));
@@ -1334,6 +2057,34 @@ match_any = ["rock"]
         assert!(period_proposal.is_none());
     }
 
+    #[test]
+    fn test_unmatched_lists_uncovered_assertions() {
+        let rules = sample_rules();
+        let assertions = vec![
+            make_assertion("genre", "Classical", Source::MusicBrainz),
+            make_assertion("genre", "polka", Source::MusicBrainz),
+            // The form rule only accepts MusicBrainz
+            make_assertion("form", "string quartet", Source::LastFm),
+            make_assertion("instrumentation", "string quartet", Source::Wikidata),
+            make_assertion("instrumentation", "theremin", Source::Wikidata),
+            make_assertion("title", "Quartet No. 1", Source::MusicBrainz),
+        ];
+
+        let unmatched: Vec<(&str, &str)> = rules
+            .unmatched(&assertions)
+            .into_iter()
+            .map(|a| (a.field.as_str(), a.value.as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            unmatched,
+            vec![
+                ("genre", "polka"),
+                ("form", "string quartet"),
+                ("instrumentation", "theremin"),
+            ]
+        );
+    }
+
     // -----------------------------------------------------------------------
     // ProposedTag field values
     // -----------------------------------------------------------------------
@@ -1391,6 +2142,7 @@ match_any = ["rock"]
             output_form: None,
             output_lcgft_label: Some("Fugues".to_string()),
             confidence: 0.85,
+            ..Default::default()
         }];
 
         let assertions = vec![make_assertion("genre", "fugue", Source::Lcgft)];
@@ -1433,6 +2185,7 @@ match_any = ["rock"]
             output_form: Some("String quartet".to_string()),
             output_lcgft_label: Some("String quartets".to_string()),
             confidence: 0.9,
+            ..Default::default()
         }];
 
         let assertions = vec![make_assertion(
@@ -1469,6 +2222,14 @@ match_any = ["rock"]
         let array_assertion =
             Assertion::new("test-entity", "tags", json!(["a", "b"]), Source::LastFm);
         assert_eq!(assertion_value_as_str(&array_assertion), "[\"a\",\"b\"]");
+
+        let labeled_assertion = Assertion::new(
+            "test-entity",
+            "form",
+            json!({ "wikidata_qid": "Q1344", "label": "opera" }),
+            Source::Wikidata,
+        );
+        assert_eq!(assertion_value_as_str(&labeled_assertion), "opera");
     }
 
     #[test]
@@ -1499,4 +2260,458 @@ match_any = ["rock"]
         ));
         assert!(!rule_matches_value(&patterns, "jazz"));
     }
+
+    // -----------------------------------------------------------------------
+    // Match modes
+    // -----------------------------------------------------------------------
+
+    fn matcher(mode: MatchMode, match_any: &[&str], exclude_any: &[&str]) -> RuleMatcher {
+        let owned = |patterns: &[&str]| -> Vec<String> {
+            patterns.iter().map(ToString::to_string).collect()
+        };
+        RuleMatcher::new(mode, 1, &owned(match_any), &owned(exclude_any)).unwrap()
+    }
+
+    #[test]
+    fn test_match_mode_word() {
+        let bop = matcher(MatchMode::Word, &["bop"], &[]);
+        assert!(bop.matches("hard bop"));
+        assert!(bop.matches("bop"));
+        assert!(!bop.matches("hip-hop"));
+        assert!(!bop.matches("bebop"));
+
+        let quartet = matcher(MatchMode::Word, &["String Quartet"], &[]);
+        assert!(quartet.matches("late string quartet, op. 131"));
+        assert!(!quartet.matches("string quartets"));
+    }
+
+    #[test]
+    fn test_match_mode_exact() {
+        let chamber = matcher(MatchMode::Exact, &["Chamber"], &[]);
+        assert!(chamber.matches(" chamber "));
+        assert!(!chamber.matches("chamber pop"));
+    }
+
+    #[test]
+    fn test_match_mode_regex() {
+        let opus = matcher(MatchMode::Regex, &[r"^op\.?\s*\d+$"], &[]);
+        assert!(opus.matches("op. 131"));
+        assert!(opus.matches("OP131"));
+        assert!(!opus.matches("opera 131"));
+    }
+
+    #[test]
+    fn test_match_mode_fuzzy() {
+        let quartet = matcher(MatchMode::Fuzzy, &["string quartet"], &[]);
+        assert!(quartet.matches("string quartett"));
+        assert!(quartet.matches("late strng quartet no. 3"));
+        assert!(!quartet.matches("string quintet"));
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_exclude_any() {
+        let bop = matcher(MatchMode::Substring, &["bop"], &["hip-hop", "hip hop"]);
+        assert!(bop.matches("bebop"));
+        assert!(!bop.matches("hip-hop bop"));
+
+        let chamber = matcher(MatchMode::Word, &["chamber"], &["chamber pop"]);
+        assert!(chamber.matches("chamber music"));
+        assert!(!chamber.matches("baroque chamber pop"));
+    }
+
+    #[test]
+    fn test_genre_rule_match_mode_applied() {
+        let mut rules = sample_rules();
+        rules.genre_rules[1].match_any = vec!["bop".to_string()];
+        rules.genre_rules[1].match_mode = MatchMode::Word;
+        rules.genre_rules[1].exclude_any = vec!["hip hop".to_string()];
+        rules.compile().unwrap();
+
+        let assertions = vec![
+            make_assertion("genre", "hard bop", Source::LastFm),
+            make_assertion("genre", "hip hop bop", Source::LastFm),
+            make_assertion("genre", "bopping", Source::LastFm),
+        ];
+        let proposals = rules.apply_genre_rules(&assertions);
+        assert_eq!(proposals.len(), 1);
+        assert_eq!(proposals[0].value, "Jazz");
+        assert!(proposals[0].alternatives.is_empty());
+        assert_eq!(rules.unmatched(&assertions).len(), 2);
+    }
+
+    #[test]
+    fn test_load_compiles_match_modes() {
+        let toml_content = r#"
+[[genre_rules]]
+name = "jazz-bebop"
+match_any = ["bebop", "bop"]
+match_mode = "word"
+exclude_any = ["hip hop"]
+
+[[instrument_rules]]
+name = "violin"
+match_any = ["violin"]
+match_mode = "fuzzy"
+max_edits = 2
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test_modes.toml");
+        std::fs::write(&path, toml_content).unwrap();
+
+        let rules = MappingRules::load(&path).unwrap();
+        let genre = &rules.genre_rules[0];
+        assert_eq!(genre.match_mode, MatchMode::Word);
+        assert_eq!(genre.exclude_any, vec!["hip hop"]);
+        assert!(matches!(genre.matcher.get(), Some(Some(_))));
+        assert!(genre.matches_value("hard bop"));
+        let instrument = &rules.instrument_rules[0];
+        assert_eq!(instrument.max_edits, 2);
+        assert!(instrument.matches_value("vioiln"));
+    }
+
+    #[test]
+    fn test_load_rejects_invalid_regex() {
+        let toml_content = r#"
+[[genre_rules]]
+name = "broken"
+match_any = ["(unclosed"]
+match_mode = "regex"
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test_invalid.toml");
+        std::fs::write(&path, toml_content).unwrap();
+
+        let err = MappingRules::load(&path).unwrap_err().to_string();
+        assert!(err.contains("rule 'broken'"), "{err}");
+        assert!(err.contains("(unclosed"), "{err}");
+    }
+
+    // -----------------------------------------------------------------------
+    // Compound conditions
+    // -----------------------------------------------------------------------
+
+    fn condition(field: &str, source: Option<&str>, value: &str) -> Condition {
+        Condition {
+            field: Some(field.to_string()),
+            source: source.map(ToString::to_string),
+            match_any: vec![value.to_string()],
+            match_mode: MatchMode::Word,
+            ..Condition::default()
+        }
+    }
+
+    fn piano_trio_rules() -> MappingRules {
+        let mut rules = sample_rules();
+        rules.genre_rules.push(GenreRule {
+            name: "romantic-piano-trio".to_string(),
+            description: None,
+            match_any: vec![],
+            match_source: vec![],
+            output_genre: None,
+            output_form: Some("Piano trio".to_string()),
+            output_lcgft_label: None,
+            confidence: 0.7,
+            conditions: Conditions {
+                all_of: vec![
+                    condition("tag", Some("lastfm"), "chamber"),
+                    condition("instrumentation", Some("wikidata"), "violin"),
+                    condition("period", None, "romantic"),
+                ],
+                any_of: vec![],
+                none_of: vec![condition("instrumentation", None, "viola")],
+            },
+            ..Default::default()
+        });
+        rules
+    }
+
+    fn has_piano_trio(proposals: &[ProposedTag]) -> bool {
+        proposals
+            .iter()
+            .any(|p| p.field == "form" && p.value == "Piano trio")
+    }
+
+    #[test]
+    fn test_compound_rule_requires_all_conditions() {
+        let rules = piano_trio_rules();
+        let mut assertions = vec![
+            make_assertion("tag", "chamber", Source::LastFm),
+            make_assertion_with_confidence(
+                "instrumentation",
+                "violin, cello, piano",
+                Source::Wikidata,
+                0.9,
+            ),
+            Assertion::new(
+                "test-entity",
+                "composer",
+                json!("Frédéric Chopin"),
+                Source::Wikidata,
+            ),
+        ];
+
+        let proposals = rules.harmonize(&assertions);
+        let trio = proposals.iter().find(|p| p.value == "Piano trio").unwrap();
+        assert_eq!(trio.source, Source::LastFm);
+        assert_eq!(trio.rule_name, "romantic-piano-trio");
+        // Rule confidence times the weakest supporting assertion
+        assert!((trio.confidence - 0.7 * 0.9).abs() < 1e-9);
+
+        // Not Romantic without the composer
+        assert!(!has_piano_trio(&rules.harmonize(&assertions[..2])));
+        // Not from the wrong source
+        assertions[0].source = Source::Discogs;
+        assert!(!has_piano_trio(&rules.harmonize(&assertions)));
+    }
+
+    #[test]
+    fn test_compound_rule_none_of_and_any_of() {
+        let mut rules = piano_trio_rules();
+        let assertions = vec![
+            make_assertion("tag", "chamber", Source::LastFm),
+            make_assertion("instrumentation", "violin", Source::Wikidata),
+            make_assertion("instrumentation", "viola", Source::MusicBrainz),
+            make_assertion("period", "Romantic", Source::User),
+        ];
+        assert!(!has_piano_trio(&rules.apply_genre_rules(&assertions)));
+
+        let rule = rules.genre_rules.last_mut().unwrap();
+        rule.conditions.none_of.clear();
+        rule.conditions.any_of = vec![
+            condition("tag", None, "piano trio"),
+            condition("form", None, "trio"),
+        ];
+        assert!(!has_piano_trio(&rules.apply_genre_rules(&assertions)));
+
+        let mut with_form = assertions.clone();
+        with_form.push(make_assertion("form", "Trio", Source::MusicBrainz));
+        assert!(has_piano_trio(&rules.apply_genre_rules(&with_form)));
+    }
+
+    #[test]
+    fn test_compound_conditions_gate_value_matching() {
+        let mut rules = sample_rules();
+        rules.genre_rules[1].conditions.all_of =
+            vec![condition("instrumentation", None, "saxophone")];
+
+        let jazz = make_assertion("genre", "jazz", Source::MusicBrainz);
+        assert!(rules.apply_genre_rules(std::slice::from_ref(&jazz)).is_empty());
+
+        let sax = make_assertion("instrumentation", "alto saxophone", Source::Discogs);
+        let proposals = rules.apply_genre_rules(&[jazz, sax]);
+        assert_eq!(proposals.len(), 1);
+        assert_eq!(proposals[0].value, "Jazz");
+        assert_eq!(proposals[0].source, Source::MusicBrainz);
+    }
+
+    #[test]
+    fn test_load_compound_conditions() {
+        let toml_content = r#"
+[[genre_rules]]
+name = "romantic-piano-trio"
+output_form = "Piano trio"
+confidence = 0.7
+
+[[genre_rules.all_of]]
+field = "tag"
+source = "lastfm"
+match_any = ["chamber"]
+match_mode = "word"
+
+[[genre_rules.all_of]]
+field = "period"
+match_any = ["romantic"]
+
+[[genre_rules.none_of]]
+field = "instrumentation"
+match_any = ["viola"]
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test_compound.toml");
+        std::fs::write(&path, toml_content).unwrap();
+
+        let rules = MappingRules::load(&path).unwrap();
+        let conditions = &rules.genre_rules[0].conditions;
+        assert_eq!(conditions.all_of.len(), 2);
+        assert_eq!(conditions.all_of[0].source.as_deref(), Some("lastfm"));
+        assert_eq!(conditions.all_of[0].match_mode, MatchMode::Word);
+        assert!(matches!(conditions.all_of[1].matcher.get(), Some(Some(_))));
+        assert_eq!(conditions.none_of.len(), 1);
+        assert!(conditions.any_of.is_empty());
+
+        let toml_str = toml::to_string(&rules).unwrap();
+        let parsed: MappingRules = toml::from_str(&toml_str).unwrap();
+        assert_eq!(parsed.genre_rules[0].conditions.all_of.len(), 2);
+    }
+
+    #[test]
+    fn test_load_rejects_none_of_only_rule() {
+        let toml_content = r#"
+[[genre_rules]]
+name = "everything-but-jazz"
+output_genre = "Not jazz"
+
+[[genre_rules.none_of]]
+match_any = ["jazz"]
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test_none_of.toml");
+        std::fs::write(&path, toml_content).unwrap();
+
+        let err = MappingRules::load(&path).unwrap_err().to_string();
+        assert!(err.contains("rule 'everything-but-jazz'"), "{err}");
+    }
+
+    // -----------------------------------------------------------------------
+    // Wikidata entities
+    // -----------------------------------------------------------------------
+
+    fn wikidata_assertion(field: &str, qid: &str, label: Option<&str>) -> Assertion {
+        let value = match label {
+            Some(label) => json!({ "wikidata_qid": qid, "label": label }),
+            None => json!({ "wikidata_qid": qid }),
+        };
+        Assertion::new("test-entity", field, value, Source::Wikidata).with_confidence(0.9)
+    }
+
+    fn wikidata_rules() -> MappingRules {
+        let mut rules = sample_rules();
+        rules.wikidata_mappings = HashMap::from([
+            (
+                "Q1344".to_string(),
+                WikidataMapping {
+                    label: Some("opera".to_string()),
+                    output_genre: Some("Classical > Opera".to_string()),
+                    output_form: Some("Opera".to_string()),
+                    output_lcgft_label: Some("Operas".to_string()),
+                    output_instruments: vec![],
+                    output_period: None,
+                    confidence: 1.0,
+                },
+            ),
+            (
+                "Q5994".to_string(),
+                WikidataMapping {
+                    label: Some("piano".to_string()),
+                    output_genre: None,
+                    output_form: None,
+                    output_lcgft_label: None,
+                    output_instruments: vec!["Piano".to_string()],
+                    output_period: None,
+                    confidence: 0.8,
+                },
+            ),
+            (
+                "Q37068".to_string(),
+                WikidataMapping {
+                    label: Some("Romanticism".to_string()),
+                    output_genre: None,
+                    output_form: None,
+                    output_lcgft_label: None,
+                    output_instruments: vec![],
+                    output_period: Some("Romantic".to_string()),
+                    confidence: 1.0,
+                },
+            ),
+        ]);
+        rules
+    }
+
+    #[test]
+    fn test_wikidata_labels_match_rules() {
+        let rules = sample_rules();
+        let labeled = wikidata_assertion("instrumentation", "Q207338", Some("string quartet"));
+        assert_eq!(assertion_value_as_str(&labeled), "string quartet");
+
+        let proposals = rules.apply_instrument_rules(&[labeled]);
+        assert!(proposals.iter().any(|p| p.value == "Violin"));
+        assert_eq!(proposals[0].source, Source::Wikidata);
+
+        // Without a label the QID object matches nothing
+        let unlabeled = wikidata_assertion("instrumentation", "Q207338", None);
+        assert!(rules.apply_instrument_rules(&[unlabeled]).is_empty());
+    }
+
+    #[test]
+    fn test_wikidata_mappings() {
+        let rules = wikidata_rules();
+        let assertions = vec![
+            wikidata_assertion("form", "Q1344", None),
+            wikidata_assertion("instrumentation", "Q5994", Some("piano")),
+            wikidata_assertion("school", "Q37068", Some("Romanticism")),
+            Assertion::new(
+                "test-entity",
+                "composer",
+                json!("Johann Sebastian Bach"),
+                Source::MusicBrainz,
+            ),
+        ];
+
+        let proposals = rules.harmonize(&assertions);
+        let find = |field: &str, value: &str| {
+            proposals
+                .iter()
+                .find(|p| p.field == field && p.value == value)
+        };
+        let form = find("form", "Opera").unwrap();
+        assert_eq!(form.rule_name, "wikidata:Q1344");
+        assert!((form.confidence - 0.9).abs() < 1e-9);
+        assert!(find("genre", "Operas").is_some());
+        let piano = find("instrumentation", "Piano").unwrap();
+        assert!((piano.confidence - 0.72).abs() < 1e-9);
+        // Wikidata's school wins over the period inferred from the composer
+        let periods: Vec<&str> = proposals
+            .iter()
+            .filter(|p| p.field == "period")
+            .map(|p| p.value.as_str())
+            .collect();
+        assert_eq!(periods, ["Romantic"]);
+
+        assert!(rules.unmatched(&assertions).is_empty());
+        let unmapped = [wikidata_assertion("form", "Q42", None)];
+        assert_eq!(rules.unmatched(&unmapped).len(), 1);
+    }
+
+    #[test]
+    fn test_load_wikidata_mappings() {
+        let toml_content = r#"
+[wikidata_mappings.Q1344]
+label = "opera"
+output_form = "Opera"
+output_lcgft_label = "Operas"
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test_wikidata.toml");
+        std::fs::write(&path, toml_content).unwrap();
+
+        let rules = MappingRules::load(&path).unwrap();
+        let mapping = &rules.wikidata_mappings["Q1344"];
+        assert_eq!(mapping.output_form.as_deref(), Some("Opera"));
+        assert!(mapping.output_instruments.is_empty());
+        assert!((mapping.confidence - 0.8).abs() < f64::EPSILON);
+
+        std::fs::write(
+            &path,
+            "[wikidata_mappings.opera]\noutput_form = \"Opera\"\n",
+        )
+        .unwrap();
+        let err = MappingRules::load(&path).unwrap_err().to_string();
+        assert!(err.contains("'opera' is not a QID"), "{err}");
+    }
+
+    #[test]
+    fn test_load_rejects_unknown_match_mode() {
+        let toml_content = r#"
+[[genre_rules]]
+name = "typo"
+match_any = ["jazz"]
+match_mode = "wholeword"
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test_unknown_mode.toml");
+        std::fs::write(&path, toml_content).unwrap();
+
+        assert!(MappingRules::load(&path).is_err());
+    }
 }
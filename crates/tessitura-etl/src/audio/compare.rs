@@ -0,0 +1,121 @@
+//! Offline comparison of chromaprint fingerprints.
+//!
+//! Two fingerprints of the same recording differ in a small fraction of
+//! their bits, whatever the encoding, and may be shifted by leading silence
+//! or a different track split. The comparison slides one against the other
+//! and keeps the alignment with the fewest bit errors.
+
+/// Largest alignment shift tried, in fingerprint frames (about 15 seconds).
+pub const MAX_OFFSET: usize = 120;
+
+/// Fewest overlapping frames a comparison is based on (about 5 seconds).
+pub const MIN_OVERLAP: usize = 40;
+
+/// Fraction of the longer fingerprint the overlap must cover.
+pub const MIN_COVERAGE: f64 = 0.8;
+
+/// The best alignment of two fingerprints.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FingerprintMatch {
+    /// Fraction of equal bits over the overlap, from 0.0 to 1.0. Unrelated
+    /// audio scores around 0.5 to 0.65.
+    pub similarity: f64,
+
+    /// Frames by which the audio starts later in the second fingerprint
+    /// than in the first (negative if earlier).
+    pub offset: isize,
+
+    /// Number of overlapping frames compared.
+    pub overlap: usize,
+}
+
+/// Compare two fingerprints at every shift up to [`MAX_OFFSET`] frames.
+///
+/// Returns the alignment with the highest similarity, or `None` when no
+/// alignment overlaps by at least [`MIN_OVERLAP`] frames and
+/// [`MIN_COVERAGE`] of the longer fingerprint.
+#[must_use]
+pub fn compare_fingerprints(a: &[u32], b: &[u32]) -> Option<FingerprintMatch> {
+    let longest = a.len().max(b.len());
+    let mut best: Option<FingerprintMatch> = None;
+    let mut consider = |candidate: Option<FingerprintMatch>| {
+        if let Some(candidate) = candidate {
+            if best.is_none_or(|best| candidate.similarity > best.similarity) {
+                best = Some(candidate);
+            }
+        }
+    };
+
+    for shift in 0..=MAX_OFFSET {
+        let offset = isize::try_from(shift).ok()?;
+        if let Some(b_part) = b.get(shift..) {
+            consider(score(a, b_part, offset, longest));
+        }
+        if shift > 0 {
+            if let Some(a_part) = a.get(shift..) {
+                consider(score(a_part, b, -offset, longest));
+            }
+        }
+    }
+    best
+}
+
+/// Score two aligned fingerprints frame by frame.
+#[allow(clippy::cast_precision_loss)]
+fn score(a: &[u32], b: &[u32], offset: isize, longest: usize) -> Option<FingerprintMatch> {
+    let overlap = a.len().min(b.len());
+    if overlap < MIN_OVERLAP || (overlap as f64) < longest as f64 * MIN_COVERAGE {
+        return None;
+    }
+
+    let errors: u64 = a
+        .iter()
+        .zip(b)
+        .map(|(x, y)| u64::from((x ^ y).count_ones()))
+        .sum();
+    Some(FingerprintMatch {
+        similarity: 1.0 - errors as f64 / (32 * overlap) as f64,
+        offset,
+        overlap,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::fingerprint;
+
+    #[test]
+    fn test_identical_fingerprints_match_exactly() {
+        let fp = fingerprint(1, 500);
+        let m = compare_fingerprints(&fp, &fp).unwrap();
+        assert!((m.similarity - 1.0).abs() < f64::EPSILON);
+        assert_eq!(m.offset, 0);
+        assert_eq!(m.overlap, 500);
+    }
+
+    #[test]
+    fn test_shifted_fingerprint_is_aligned() {
+        let fp = fingerprint(2, 500);
+        // Ten frames of leading silence, and one bit flipped in every frame
+        let mut shifted = vec![0; 10];
+        shifted.extend(fp.iter().map(|v| v ^ 1));
+
+        let m = compare_fingerprints(&fp, &shifted).unwrap();
+        assert_eq!(m.offset, 10);
+        assert!((m.similarity - 31.0 / 32.0).abs() < 1e-9);
+
+        let m = compare_fingerprints(&shifted, &fp).unwrap();
+        assert_eq!(m.offset, -10);
+    }
+
+    #[test]
+    fn test_unrelated_and_short_fingerprints() {
+        let m = compare_fingerprints(&fingerprint(3, 500), &fingerprint(4, 500)).unwrap();
+        assert!(m.similarity < 0.6, "similarity {}", m.similarity);
+
+        assert!(compare_fingerprints(&fingerprint(5, 10), &fingerprint(5, 10)).is_none());
+        // The overlap covers too little of the longer fingerprint
+        assert!(compare_fingerprints(&fingerprint(6, 100), &fingerprint(6, 500)).is_none());
+    }
+}
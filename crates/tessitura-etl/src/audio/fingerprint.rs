@@ -1,6 +1,6 @@
 use anyhow::{Context, Result};
 use rusty_chromaprint::{Configuration, Fingerprinter};
-use std::io::Write;
+use std::io::{Read, Write};
 use std::path::Path;
 
 use super::decoder::decode_audio;
@@ -8,7 +8,7 @@ use super::decoder::decode_audio;
 /// Encode a chromaprint fingerprint vector into a compressed base64 string.
 ///
 /// AcoustID expects fingerprints in this format for lookup.
-fn encode_fingerprint(fp: &[u32]) -> Result<String> {
+pub(crate) fn encode_fingerprint(fp: &[u32]) -> Result<String> {
     // Convert u32 array to bytes (little-endian)
     let mut bytes = Vec::with_capacity(fp.len() * 4);
     for &val in fp {
@@ -27,6 +27,25 @@ fn encode_fingerprint(fp: &[u32]) -> Result<String> {
     Ok(base64::Engine::encode(&base64::engine::general_purpose::STANDARD, compressed))
 }
 
+/// Decode a fingerprint string produced by [`generate_fingerprint`] back
+/// into its raw chromaprint frames.
+pub fn decode_fingerprint(encoded: &str) -> Result<Vec<u32>> {
+    let compressed = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, encoded)
+        .context("Fingerprint is not valid base64")?;
+    let mut bytes = Vec::new();
+    flate2::read::ZlibDecoder::new(compressed.as_slice())
+        .read_to_end(&mut bytes)
+        .context("Failed to decompress fingerprint")?;
+    if bytes.len() % 4 != 0 {
+        anyhow::bail!("Fingerprint length {} is not a multiple of 4", bytes.len());
+    }
+
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect())
+}
+
 /// Generate a chromaprint fingerprint for an audio file.
 ///
 /// Returns the fingerprint string and duration in seconds.
@@ -82,6 +101,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_decode_fingerprint_roundtrip() {
+        let frames = vec![0, 1, 0xdead_beef, u32::MAX];
+        let encoded = encode_fingerprint(&frames).unwrap();
+        assert_eq!(decode_fingerprint(&encoded).unwrap(), frames);
+        assert!(decode_fingerprint("not a fingerprint!").is_err());
+    }
+
     // Note: Real audio file tests would require test fixtures
     // For now, we test error handling
 }
@@ -0,0 +1,114 @@
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// SHA-256 of the whole file, as lowercase hex.
+///
+/// Identical for byte-identical files; any tag edit changes it.
+pub fn file_hash(path: &Path) -> Result<String> {
+    let mut file = std::fs::File::open(path)
+        .with_context(|| format!("Failed to open file: {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// SHA-256 of the encoded audio packets of the default track, as lowercase
+/// hex.
+///
+/// Tag blocks (ID3, Vorbis comments, RIFF INFO, ...) are not part of the
+/// packets, so retagging a file leaves this hash unchanged. Re-encoding the
+/// audio changes it. Fails for a file with no audio packets.
+pub fn audio_hash(path: &Path) -> Result<String> {
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("Failed to open audio file: {}", path.display()))?;
+    let mss = MediaSourceStream::new(
+        Box::new(file),
+        symphonia::core::io::MediaSourceStreamOptions::default(),
+    );
+
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .context("Failed to probe audio format")?;
+    let mut format = probed.format;
+    let track_id = format
+        .default_track()
+        .context("No default audio track found")?
+        .id;
+
+    let mut hasher = Sha256::new();
+    let mut packets = 0_usize;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(symphonia::core::errors::Error::IoError(e))
+                if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+            {
+                break;
+            }
+            Err(e) => return Err(e).context("Failed to read packet"),
+        };
+        if packet.track_id() == track_id {
+            hasher.update(&packet.data);
+            packets += 1;
+        }
+    }
+    if packets == 0 {
+        anyhow::bail!("No audio packets in {}", path.display());
+    }
+
+    Ok(to_hex(&hasher.finalize()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for &b in bytes {
+        hex.push(char::from(DIGITS[usize::from(b >> 4)]));
+        hex.push(char::from(DIGITS[usize::from(b & 0x0f)]));
+    }
+    hex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_hash_of_known_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("abc.bin");
+        std::fs::write(&path, b"abc").unwrap();
+
+        assert_eq!(
+            file_hash(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_audio_hash_rejects_non_audio() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, b"not audio").unwrap();
+
+        assert!(audio_hash(&path).is_err());
+        assert!(file_hash(Path::new("/nonexistent/file.flac")).is_err());
+    }
+}
@@ -1,5 +1,9 @@
+pub mod compare;
 pub mod decoder;
 pub mod fingerprint;
+pub mod hash;
 
+pub use compare::{compare_fingerprints, FingerprintMatch};
 pub use decoder::{decode_audio, DecodedAudio};
-pub use fingerprint::generate_fingerprint;
+pub use fingerprint::{decode_fingerprint, generate_fingerprint};
+pub use hash::{audio_hash, file_hash};
@@ -3,6 +3,8 @@ use confyg::{env, Confygery};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+use crate::export::ExportConfig;
+
 /// Configuration for tessitura.
 ///
 /// Configuration is loaded from multiple sources with the following priority:
@@ -69,6 +71,16 @@ pub struct Config {
     /// - Default: Info level, colored output to stdout
     #[serde(default = "default_logging")]
     pub logging: twyg::Opts,
+
+    /// Settings for `tessitura export`: sources written without review, and
+    /// tag key overrides.
+    ///
+    /// Can be set via:
+    /// - Config: [export] section, with [export.vorbis] and [export.id3v2]
+    /// - Default: only values approved in review, written to standard Vorbis
+    ///   comment keys and ID3v2 frames
+    #[serde(default)]
+    pub export: ExportConfig,
 }
 
 impl Default for Config {
@@ -80,6 +92,7 @@ impl Default for Config {
             database_path: default_db_path(),
             rules_path: default_rules_path(),
             logging: default_logging(),
+            export: ExportConfig::default(),
         }
     }
 }
@@ -248,6 +261,29 @@ acoustid_api_key = "your-acoustid-api-key-here"
 # Default: {config_dir}/tessitura/taxonomy.toml
 #rules_path = "/path/to/taxonomy.toml"
 
+# Tag export
+#
+# 'tessitura export' only writes values approved in review. Values from a
+# source listed in auto_accept (works and recordings identified from it, and
+# its pending proposals) are written without review. Sources: MusicBrainz,
+# Wikidata, LastFm, Discogs.
+#[export]
+#auto_accept = ["MusicBrainz"]
+#
+# Export writes these fields: work, movement, movement_name,
+# composer, arranger, lyricist, librettist, writer, catalog_number, key,
+# performer, conductor, genre, form, period, instrumentation,
+# musicbrainz_work_id, musicbrainz_recording_id, musicbrainz_release_id.
+#
+# Override the Vorbis comment key (FLAC, Ogg) or ID3v2 frame (MP3) a field
+# is written to; use "TXXX:Description" for user-defined ID3v2 frames and
+# "" to disable a field.
+#[export.vorbis]
+#catalog_number = "CATALOGNUMBER"
+#
+#[export.id3v2]
+#work = "TXXX:WORK"
+
 # Logging configuration
 #
 # All options can also be set via environment variables with TESS_LOGGING_* prefix
@@ -0,0 +1,357 @@
+//! Suggesting duplicate works, expressions and artists to merge.
+//!
+//! Identify creates a `Work` from the recording title whenever
+//! `MusicBrainz` has no work relation, and an artist whenever it has no
+//! `MusicBrainz` ID to match, so the same entity can end up in several rows.
+//! The suggestions here group rows by normalized titles, names and catalog
+//! numbers and pick the row to keep; the merge itself is done by
+//! `Database::merge_works` and friends.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use tessitura_core::model::{Artist, Expression, Work};
+
+use crate::recordings::UnionFind;
+
+/// Why rows were suggested as duplicates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchReason {
+    /// Same composer and catalog number.
+    CatalogNumber,
+    /// Same title, and no conflicting composer.
+    TitleAndComposer,
+    /// Same name, in any word order.
+    Name,
+    /// Same work, title, year and performers.
+    Recording,
+}
+
+impl fmt::Display for MatchReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::CatalogNumber => "same composer and catalog number",
+            Self::TitleAndComposer => "same title and composer",
+            Self::Name => "same name",
+            Self::Recording => "same work, title, year and performers",
+        })
+    }
+}
+
+/// Rows that look like the same entity, with the one to keep.
+#[derive(Debug, Clone)]
+pub struct MergeSuggestion<T> {
+    /// The row to keep: one with a `MusicBrainz` ID if any, else the oldest.
+    pub survivor: T,
+
+    /// The rows to merge into the survivor.
+    pub duplicates: Vec<T>,
+
+    pub reason: MatchReason,
+}
+
+/// The parts of an entity that decide which row survives a merge.
+trait Mergeable: Clone {
+    fn musicbrainz_id(&self) -> Option<&str>;
+    fn created_at(&self) -> DateTime<Utc>;
+}
+
+impl Mergeable for Work {
+    fn musicbrainz_id(&self) -> Option<&str> {
+        self.musicbrainz_id.as_deref()
+    }
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+impl Mergeable for Expression {
+    fn musicbrainz_id(&self) -> Option<&str> {
+        self.musicbrainz_id.as_deref()
+    }
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+impl Mergeable for Artist {
+    fn musicbrainz_id(&self) -> Option<&str> {
+        self.musicbrainz_id.as_deref()
+    }
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+/// Suggest works to merge.
+///
+/// Works match on composer and catalog number, in any spelling or catalog
+/// edition ("Op. 18, No. 1" and "op.18/1", "K. 331" and "KV 300i" are the
+/// same), or on composer and title. A work without a composer, as identify
+/// creates from a recording title, takes the composer of the works with
+/// the same title when they all have the same one, and is not matched on
+/// title when they have several. When no work of that title has a
+/// composer, the works match on title only if they also have the same
+/// catalog number, as generic titles ("Allegro", "Adagio") say nothing on
+/// their own.
+#[must_use]
+pub fn suggest_work_merges(works: &[Work]) -> Vec<MergeSuggestion<Work>> {
+    // Composers seen for each title, to place the works without one
+    let mut composers: HashMap<String, HashSet<String>> = HashMap::new();
+    for work in works {
+        let composer = work.composer.as_deref().map(normalize);
+        let entry = composers.entry(normalize(&work.title)).or_default();
+        entry.extend(composer.filter(|c| !c.is_empty()));
+    }
+
+    let by_catalog = |w: &Work| {
+        let composer = normalize(w.composer.as_deref()?);
+        let catalog = w.catalog().map(|c| c.normalized().to_string())?;
+        (!composer.is_empty()).then(|| format!("{composer}\0{catalog}"))
+    };
+    let by_title = |w: &Work| {
+        let title = normalize(&w.title);
+        let composer = match w.composer.as_deref().map(normalize) {
+            Some(composer) if !composer.is_empty() => composer,
+            _ => {
+                let known = &composers[&title];
+                match known.len() {
+                    0 => {
+                        let catalog = w.catalog().map(|c| c.normalized().to_string())?;
+                        return (!title.is_empty()).then(|| format!("{title}\0\0{catalog}"));
+                    }
+                    1 => known.iter().next()?.clone(),
+                    _ => return None,
+                }
+            }
+        };
+        (!title.is_empty()).then(|| format!("{title}\0{composer}"))
+    };
+    suggest(
+        works,
+        &[
+            (MatchReason::CatalogNumber, &by_catalog),
+            (MatchReason::TitleAndComposer, &by_title),
+        ],
+    )
+}
+
+/// Suggest expressions to merge: those of the same work with the same
+/// title, recording year and performers. Expressions with no performer
+/// credited are not compared.
+#[must_use]
+pub fn suggest_expression_merges(expressions: &[Expression]) -> Vec<MergeSuggestion<Expression>> {
+    let by_recording = |e: &Expression| {
+        if e.performers.is_empty() {
+            return None;
+        }
+        let mut performers: Vec<String> = e
+            .performers
+            .iter()
+            .map(|p| p.artist_id.to_string())
+            .collect();
+        performers.sort();
+        Some(format!(
+            "{}\0{}\0{:?}\0{}",
+            e.work_id,
+            normalize(e.title.as_deref().unwrap_or_default()),
+            e.recorded_year,
+            performers.join(",")
+        ))
+    };
+    suggest(expressions, &[(MatchReason::Recording, &by_recording)])
+}
+
+/// Suggest artists to merge: those with the same name, ignoring case,
+/// punctuation and word order ("Richter, Sviatoslav" matches
+/// "Sviatoslav Richter").
+#[must_use]
+pub fn suggest_artist_merges(artists: &[Artist]) -> Vec<MergeSuggestion<Artist>> {
+    let by_name = |a: &Artist| {
+        let name = normalize(&a.name);
+        let mut words: Vec<&str> = name.split(' ').filter(|w| !w.is_empty()).collect();
+        words.sort_unstable();
+        (!words.is_empty()).then(|| words.join(" "))
+    };
+    suggest(artists, &[(MatchReason::Name, &by_name)])
+}
+
+/// A way of matching rows: rows with the same key match.
+type Matcher<'a, T> = (MatchReason, &'a dyn Fn(&T) -> Option<String>);
+
+/// Group the rows matching by any of `matchers`, transitively, suggesting
+/// a merge for each group of two or more. A group's reason is that of the
+/// first matcher joining it. Groups holding rows with different
+/// `MusicBrainz` IDs are distinct entities and are left out.
+fn suggest<T: Mergeable>(rows: &[T], matchers: &[Matcher<'_, T>]) -> Vec<MergeSuggestion<T>> {
+    let mut sets = UnionFind::new(rows.len());
+    let mut reasons: HashMap<usize, MatchReason> = HashMap::new();
+    for (reason, key) in matchers {
+        let mut first_by_key: HashMap<String, usize> = HashMap::new();
+        for (idx, row) in rows.iter().enumerate() {
+            let Some(key) = key(row) else {
+                continue;
+            };
+            let Some(&first) = first_by_key.get(&key) else {
+                first_by_key.insert(key, idx);
+                continue;
+            };
+            let roots = [sets.find(first), sets.find(idx)];
+            if roots[0] != roots[1] {
+                let reason = [reasons.remove(&roots[0]), reasons.remove(&roots[1])]
+                    .into_iter()
+                    .flatten()
+                    .next()
+                    .unwrap_or(*reason);
+                reasons.insert(sets.union(roots[0], roots[1]), reason);
+            }
+        }
+    }
+
+    let mut groups: BTreeMap<usize, Vec<&T>> = BTreeMap::new();
+    for (idx, row) in rows.iter().enumerate() {
+        groups.entry(sets.find(idx)).or_default().push(row);
+    }
+
+    let mut suggestions = Vec::new();
+    for (root, mut group) in groups {
+        let mbids: HashSet<&str> = group.iter().filter_map(|r| r.musicbrainz_id()).collect();
+        let Some(&reason) = reasons.get(&root) else {
+            continue;
+        };
+        if mbids.len() > 1 {
+            continue;
+        }
+        group.sort_by_key(|r| (r.musicbrainz_id().is_none(), r.created_at()));
+        suggestions.push(MergeSuggestion {
+            survivor: group[0].clone(),
+            duplicates: group[1..].iter().map(|r| (*r).clone()).collect(),
+            reason,
+        });
+    }
+    suggestions
+}
+
+/// Lower-case and reduce to words of letters and digits.
+fn normalize(value: &str) -> String {
+    value
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tessitura_core::model::{ArtistId, ExpressionId};
+
+    #[test]
+    fn test_suggest_work_merges() {
+        let mb = Work::new("String Quartet No. 1 in F major, Op. 18 No. 1")
+            .with_composer("Ludwig van Beethoven")
+            .with_musicbrainz_id("mb-op18-1")
+            .with_catalog_number("Op. 18, No. 1");
+        let by_catalog = Work::new("Quartet in F")
+            .with_composer("Ludwig van Beethoven")
+            .with_catalog_number("op.18 no.1");
+        // Created by identify from a recording title
+        let from_recording = Work::new("String quartet no. 1 in F major, op. 18 no. 1");
+        let other_composer = Work::new("String Quartet No. 1").with_composer("Béla Bartók");
+        let other_mbid = Work::new("Quartet in F")
+            .with_composer("Ludwig van Beethoven")
+            .with_musicbrainz_id("mb-other")
+            .with_catalog_number("Op. 18 No. 1");
+
+        let works = vec![
+            by_catalog.clone(),
+            from_recording.clone(),
+            mb.clone(),
+            other_composer,
+        ];
+        let suggestions = suggest_work_merges(&works);
+        assert_eq!(suggestions.len(), 1);
+        let suggestion = &suggestions[0];
+        assert_eq!(suggestion.reason, MatchReason::CatalogNumber);
+        assert_eq!(suggestion.survivor.id, mb.id);
+        let mut duplicates: Vec<_> = suggestion.duplicates.iter().map(|w| w.id).collect();
+        duplicates.sort_by_key(ToString::to_string);
+        let mut expected = vec![by_catalog.id, from_recording.id];
+        expected.sort_by_key(ToString::to_string);
+        assert_eq!(duplicates, expected);
+
+        // Two MusicBrainz works are distinct whatever their titles
+        assert!(suggest_work_merges(&[mb, other_mbid]).is_empty());
+        assert!(suggest_work_merges(&[from_recording]).is_empty());
+    }
+
+    #[test]
+    fn test_suggest_work_merges_across_catalog_spellings() {
+        let mozart = |title: &str, catalog: &str| {
+            Work::new(title)
+                .with_composer("Wolfgang Amadeus Mozart")
+                .with_catalog_number(catalog)
+        };
+        let works = vec![
+            mozart("Symphony No. 40", "K. 550"),
+            mozart("Sinfonie g-Moll", "KV 550"),
+            mozart("Piano Sonata No. 11", "K. 331"),
+            mozart("Klaviersonate A-Dur", "KV 300i"),
+            mozart("Piano Sonata No. 12", "K. 332"),
+            // Catalog number only in the title
+            Work::new("Klaviersonate F-Dur, KV 332").with_composer("Wolfgang Amadeus Mozart"),
+        ];
+        let suggestions = suggest_work_merges(&works);
+        assert_eq!(suggestions.len(), 3);
+        assert!(suggestions
+            .iter()
+            .all(|s| s.reason == MatchReason::CatalogNumber && s.duplicates.len() == 1));
+    }
+
+    #[test]
+    fn test_suggest_work_merges_needs_more_than_a_generic_title() {
+        // No composer and no catalog number: nothing ties these together
+        let allegro = Work::new("Allegro");
+        let works = vec![allegro.clone(), Work::new("Allegro")];
+        assert!(suggest_work_merges(&works).is_empty());
+
+        // The same catalog number does
+        let works = vec![
+            Work::new("Allegro").with_catalog_number("K. 3"),
+            Work::new("Allegro").with_catalog_number("KV 3"),
+            Work::new("Allegro").with_catalog_number("K. 4"),
+            allegro,
+        ];
+        let suggestions = suggest_work_merges(&works);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].reason, MatchReason::TitleAndComposer);
+        assert_eq!(suggestions[0].duplicates.len(), 1);
+    }
+
+    #[test]
+    fn test_suggest_artist_and_expression_merges() {
+        let richter = Artist::new("Sviatoslav Richter").with_musicbrainz_id("mb-richter");
+        let reversed = Artist::new("Richter, Sviatoslav");
+        let other = Artist::new("Emil Gilels");
+        let suggestions = suggest_artist_merges(&[reversed.clone(), richter.clone(), other]);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].survivor.id, richter.id);
+        assert_eq!(suggestions[0].duplicates[0].id, reversed.id);
+
+        let work = Work::new("Piano Concerto No. 2");
+        let pianist = ArtistId::new();
+        let take = Expression::new(work.id)
+            .with_title("Piano Concerto No. 2")
+            .with_performer(pianist);
+        let mut copy = take.clone();
+        copy.id = ExpressionId::new();
+        copy.title = Some("Piano concerto no 2".to_string());
+        let uncredited = Expression::new(work.id).with_title("Piano Concerto No. 2");
+        let suggestions = suggest_expression_merges(&[take, copy, uncredited.clone(), uncredited]);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].reason, MatchReason::Recording);
+        assert_eq!(suggestions[0].duplicates.len(), 1);
+    }
+}
@@ -4,6 +4,8 @@
 //! a known catalog number or Discogs release ID. The enricher gathers label,
 //! catalog number, release year, genre, style, format, and personnel credit
 //! information. All findings are stored as provenance-tracked [`Assertion`]s.
+//! Performers among the personnel are recorded with their instruments or
+//! voices, and credited on the item's recording once approved in review.
 //!
 //! Rate limits are enforced internally: authenticated requests are capped at
 //! 4 req/sec (240/min) and unauthenticated requests at 1 req/sec (60/min),
@@ -11,15 +13,17 @@
 //!
 //! [`Assertion`]: tessitura_core::provenance::Assertion
 
-use std::path::Path;
+use std::collections::HashSet;
 use std::time::Duration;
 
 use reqwest::Client;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use tessitura_core::provenance::{Assertion, Source};
-use tessitura_core::schema::Database;
+use tessitura_core::model::{Artist, ArtistRole, ItemId, Performer, PerformerKind};
+use tessitura_core::provenance::{Assertion, ProposalStatus, Source};
+use tessitura_core::schema::{Database, DatabasePool};
 
+use crate::enrich::lcgft::lcmpt_term_for_role;
 use crate::enrich::resilience::RateLimiter;
 use crate::error::{EnrichError, EnrichResult};
 
@@ -105,10 +109,153 @@ pub struct DiscogsFormat {
 /// An extra artist credit on a Discogs release.
 #[derive(Debug, Clone, Deserialize)]
 pub struct DiscogsExtraArtist {
-    /// Artist name.
+    /// Discogs artist ID, which tells apart artists of the same name.
+    #[serde(default)]
+    pub id: Option<u64>,
+    /// Artist name, with a number in parentheses if Discogs has several
+    /// artists of that name (e.g. "John Williams (4)").
     pub name: String,
-    /// Credit role (e.g. "Producer", "Mastered By").
+    /// Artist name variation: the name credited on this release, if
+    /// different (empty otherwise).
+    #[serde(default)]
+    pub anv: String,
+    /// Credit role (e.g. "Producer", "Mastered By", "Piano, Harpsichord").
     pub role: String,
+    /// Tracks the credit is limited to (empty if it covers the release).
+    #[serde(default)]
+    pub tracks: String,
+}
+
+/// Instruments recognised in Discogs credit roles without the LCMPT
+/// vocabulary. A role naming one of these as its last word ("Baroque
+/// Violin", "Tenor Saxophone") is an instrument credit too.
+const COMMON_INSTRUMENTS: [&str; 38] = [
+    "accordion",
+    "bandoneon",
+    "bass",
+    "bassoon",
+    "celesta",
+    "cello",
+    "clarinet",
+    "contrabass",
+    "cornet",
+    "drums",
+    "flugelhorn",
+    "flute",
+    "fortepiano",
+    "glockenspiel",
+    "guitar",
+    "harmonium",
+    "harp",
+    "harpsichord",
+    "horn",
+    "keyboards",
+    "lute",
+    "mandolin",
+    "marimba",
+    "oboe",
+    "organ",
+    "percussion",
+    "piano",
+    "recorder",
+    "saxophone",
+    "synthesizer",
+    "theorbo",
+    "timpani",
+    "trombone",
+    "trumpet",
+    "tuba",
+    "vibraphone",
+    "viola",
+    "violin",
+];
+
+impl DiscogsExtraArtist {
+    /// The instruments or voices, and the kind of performer, the credit
+    /// names, if it is a performance credit. Roles are separated by commas
+    /// and may carry a qualifier in brackets ("Violin [Solo]"); production
+    /// and other non-performing roles are skipped. An instrumentalist is
+    /// only a soloist if the credit says so.
+    #[must_use]
+    pub fn performance(&self) -> Option<(Vec<String>, Option<PerformerKind>)> {
+        let mut roles = Vec::new();
+        let mut kind = None;
+        for part in self.role.split(',') {
+            let (name, qualifier) = match part.split_once('[') {
+                Some((name, qualifier)) => (name, qualifier.to_lowercase()),
+                None => (part, String::new()),
+            };
+            let name = name.trim().to_lowercase();
+            match name.as_str() {
+                "orchestra" => kind = Some(PerformerKind::Orchestra),
+                "choir" | "chorus" => kind = Some(PerformerKind::Chorus),
+                "ensemble" => kind = Some(PerformerKind::Ensemble),
+                _ => {
+                    let voice = name.strip_suffix("vocals").map(str::trim);
+                    let role = match voice {
+                        Some("" | "lead" | "backing") => "voice".to_string(),
+                        Some(voice) => voice.to_string(),
+                        None if is_common_instrument(&name) => name,
+                        None => continue,
+                    };
+                    roles.push(role);
+                    if qualifier.contains("solo") {
+                        kind = Some(PerformerKind::Soloist);
+                    }
+                }
+            }
+        }
+        (kind.is_some() || !roles.is_empty()).then_some((roles, kind))
+    }
+
+    /// The performer credit this names, if it is a performance credit
+    /// covering the whole release. Credits limited to some tracks cannot be
+    /// matched to an item.
+    #[must_use]
+    pub fn credit(&self) -> Option<DiscogsCredit> {
+        if !self.tracks.is_empty() {
+            return None;
+        }
+        let (roles, kind) = self.performance()?;
+        Some(DiscogsCredit {
+            name: self.artist_name().to_string(),
+            discogs_id: self.id,
+            roles,
+            kind,
+            credited_as: Some(self.anv.clone()).filter(|anv| !anv.is_empty()),
+        })
+    }
+
+    /// The artist's name without Discogs' disambiguating number.
+    #[must_use]
+    pub fn artist_name(&self) -> &str {
+        self.name
+            .strip_suffix(')')
+            .and_then(|rest| rest.rsplit_once(" ("))
+            .filter(|(_, number)| number.chars().all(|c| c.is_ascii_digit()))
+            .map_or(&self.name, |(name, _)| name)
+    }
+}
+
+/// A performer credited on a release, as recorded in a `performer`
+/// assertion for review.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiscogsCredit {
+    /// The artist's name, without Discogs' disambiguating number.
+    pub name: String,
+    pub discogs_id: Option<u64>,
+    /// Instruments or voices, lower-cased.
+    #[serde(default)]
+    pub roles: Vec<String>,
+    pub kind: Option<PerformerKind>,
+    /// The name credited on the release, if different.
+    pub credited_as: Option<String>,
+}
+
+/// Whether a lowercase role names a common instrument.
+fn is_common_instrument(role: &str) -> bool {
+    let last = role.rsplit(' ').next().unwrap_or(role);
+    COMMON_INSTRUMENTS.contains(&role) || COMMON_INSTRUMENTS.contains(&last)
 }
 
 // ---------------------------------------------------------------------------
@@ -261,7 +408,7 @@ impl DiscogsEnricher {
         &self,
         catno: &str,
         entity_id: &str,
-        db_path: &Path,
+        db: &DatabasePool,
     ) -> EnrichResult<Vec<Assertion>> {
         let results = self.client.search_release(catno).await?;
 
@@ -271,7 +418,7 @@ impl DiscogsEnricher {
         };
         let release_id = result.id;
 
-        self.enrich_release(release_id, entity_id, db_path).await
+        self.enrich_release(release_id, entity_id, db).await
     }
 
     /// Enrich an entity from a specific Discogs release ID.
@@ -283,7 +430,7 @@ impl DiscogsEnricher {
         &self,
         release_id: u64,
         entity_id: &str,
-        db_path: &Path,
+        db: &DatabasePool,
     ) -> EnrichResult<Vec<Assertion>> {
         let release = self.client.get_release(release_id).await?;
         let mut assertions = Vec::new();
@@ -384,16 +531,114 @@ impl DiscogsEnricher {
             );
         }
 
+        // Performers, proposed for review by harmonization
+        assertions.extend(release.extraartists.iter().filter_map(|extra| {
+            let credit = serde_json::json!(extra.credit()?);
+            Some(
+                Assertion::new(entity_id, "performer", credit, Source::Discogs)
+                    .with_confidence(0.85),
+            )
+        }));
+
         // Persist all assertions to the database
-        let db = Database::open(db_path)?;
-        for assertion in &assertions {
-            db.insert_assertion(assertion)?;
-        }
+        db.with(|conn| conn.insert_assertions(&assertions))?;
 
         Ok(assertions)
     }
 }
 
+/// Credit the Discogs performers approved in review on the recording of the
+/// item `entity_id`.
+///
+/// Enrichment records each performer credited on the item's release as a
+/// `performer` assertion, which harmonization proposes for review; once
+/// the proposal is accepted, the performer is added to the recording.
+/// Artists are found by their Discogs ID, so homonyms Discogs tells apart
+/// stay distinct, and created with that ID when not known yet. Credits
+/// without a Discogs ID are skipped. Returns the number of credits that
+/// changed the recording's performers.
+///
+/// # Errors
+/// Returns an error if the database cannot be read or updated.
+pub fn credit_approved_performers(db: &Database, entity_id: &str) -> EnrichResult<usize> {
+    let Ok(item_id) = entity_id.parse::<ItemId>() else {
+        return Ok(0);
+    };
+    let Some(expression_id) = db.get_item_by_id(&item_id)?.and_then(|i| i.expression_id) else {
+        return Ok(0);
+    };
+    let Some(mut expression) = db.get_expression_by_id(&expression_id)? else {
+        return Ok(0);
+    };
+
+    let approved: HashSet<String> = db
+        .get_proposals_for_entity(entity_id)?
+        .into_iter()
+        .filter(|p| {
+            p.field == "performer"
+                && p.source == Source::Discogs
+                && p.status == ProposalStatus::Accepted
+        })
+        .map(|p| p.value)
+        .collect();
+    if approved.is_empty() {
+        return Ok(0);
+    }
+    let credits: Vec<DiscogsCredit> = db
+        .get_assertions_for_entity(entity_id)?
+        .into_iter()
+        .filter(|a| a.field == "performer" && a.source == Source::Discogs)
+        .filter_map(|a| serde_json::from_value::<DiscogsCredit>(a.value).ok())
+        .filter(|credit| approved.contains(&credit.name))
+        .collect();
+
+    let tx = db.transaction()?;
+    let mut credited = 0;
+    for credit in credits {
+        let Some(discogs_id) = credit.discogs_id else {
+            continue;
+        };
+        let artist_id = if let Some(artist) = tx.get_artist_by_discogs_id(discogs_id)? {
+            artist.id
+        } else {
+            let role = match credit.kind {
+                Some(PerformerKind::Soloist) | None => ArtistRole::Performer,
+                Some(_) => ArtistRole::Ensemble,
+            };
+            let artist = Artist::new(&credit.name).with_role(role);
+            tx.insert_artist(&artist)?;
+            tx.insert_assertion(&Assertion::new(
+                artist.id.to_string(),
+                "discogs_id",
+                serde_json::json!(discogs_id),
+                Source::Discogs,
+            ))?;
+            artist.id
+        };
+
+        let mut performer = Performer::new(artist_id);
+        for role in &credit.roles {
+            performer.add_role(role);
+            if performer.lcmpt_uri.is_none() {
+                performer.lcmpt_uri = lcmpt_term_for_role(&tx, role)?.map(|t| t.uri);
+            }
+        }
+        performer.kind = credit.kind;
+        performer.credited_as = credit.credited_as;
+        let before = expression.performers.clone();
+        expression.add_performer(performer);
+        if expression.performers != before {
+            credited += 1;
+        }
+    }
+    if credited > 0 {
+        expression.updated_at = chrono::Utc::now();
+        tx.upsert_expression(&expression)?;
+    }
+    tx.commit()?;
+    Ok(credited)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -517,6 +762,162 @@ mod tests {
         assert_eq!(release.extraartists[0].role, "Producer");
     }
 
+    #[test]
+    fn test_extra_artist_performance() {
+        let extra = |name: &str, role: &str| DiscogsExtraArtist {
+            id: None,
+            name: name.to_string(),
+            anv: String::new(),
+            role: role.to_string(),
+            tracks: String::new(),
+        };
+
+        let (roles, kind) = extra("Martha Argerich", "Piano, Harpsichord [Solo]")
+            .performance()
+            .unwrap();
+        assert_eq!(roles, ["piano", "harpsichord"]);
+        assert_eq!(kind, Some(PerformerKind::Soloist));
+        let (roles, _) = extra("Jessye Norman", "Soprano Vocals")
+            .performance()
+            .unwrap();
+        assert_eq!(roles, ["soprano"]);
+        let (roles, kind) = extra("Gidon Kremer", "Baroque Violin")
+            .performance()
+            .unwrap();
+        assert_eq!(roles, ["baroque violin"]);
+        // Not said to be a soloist
+        assert_eq!(kind, None);
+        let (roles, kind) = extra("Berliner Philharmoniker", "Orchestra")
+            .performance()
+            .unwrap();
+        assert!(roles.is_empty());
+        assert_eq!(kind, Some(PerformerKind::Orchestra));
+        assert!(extra("Teo Macero", "Producer").performance().is_none());
+        assert!(extra("Someone", "Liner Notes, Photography By")
+            .performance()
+            .is_none());
+
+        assert_eq!(
+            extra("John Williams (4)", "Guitar").artist_name(),
+            "John Williams"
+        );
+        assert_eq!(
+            extra("Sweet (Band)", "Guitar").artist_name(),
+            "Sweet (Band)"
+        );
+    }
+
+    #[test]
+    fn test_credit_approved_performers_on_item_recording() {
+        use chrono::Utc;
+        use std::path::PathBuf;
+        use tessitura_core::model::{AudioFormat, Expression, Item, Work};
+        use tessitura_core::provenance::Proposal;
+        use tessitura_core::taxonomy::rules::ProposedTag;
+
+        let db = Database::open_in_memory().unwrap();
+        let work = Work::new("Concierto de Aranjuez");
+        db.insert_work(&work).unwrap();
+        // A homonym of the guitarist credited below
+        let composer = Artist::new("John Williams").with_role(ArtistRole::Composer);
+        db.insert_artist(&composer).unwrap();
+        let expr = Expression::new(work.id);
+        db.insert_expression(&expr).unwrap();
+        let mut item = Item::new(
+            PathBuf::from("/music/01.flac"),
+            AudioFormat::Flac,
+            1,
+            Utc::now(),
+        );
+        item.expression_id = Some(expr.id);
+        db.insert_item(&item).unwrap();
+        let entity_id = item.id.to_string();
+
+        let release: DiscogsRelease = serde_json::from_str(
+            r#"{
+                "id": 1,
+                "title": "Rodrigo: Concierto de Aranjuez",
+                "extraartists": [
+                    {"id": 254741, "name": "John Williams (4)", "anv": "J. Williams",
+                     "role": "Guitar", "tracks": ""},
+                    {"id": 87001, "name": "Philharmonia Orchestra", "role": "Orchestra",
+                     "tracks": ""},
+                    {"id": 12, "name": "Louis Frémaux", "role": "Conductor", "tracks": ""},
+                    {"id": 99, "name": "Jaime Laredo", "role": "Violin", "tracks": "3"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let credits: Vec<DiscogsCredit> = release
+            .extraartists
+            .iter()
+            .filter_map(DiscogsExtraArtist::credit)
+            .collect();
+        assert_eq!(credits.len(), 2);
+        for credit in &credits {
+            db.insert_assertion(&Assertion::new(
+                &entity_id,
+                "performer",
+                serde_json::json!(credit),
+                Source::Discogs,
+            ))
+            .unwrap();
+        }
+
+        // Nothing is credited before review
+        assert_eq!(credit_approved_performers(&db, &entity_id).unwrap(), 0);
+
+        let proposal = |name: &str| {
+            Proposal::new(
+                &entity_id,
+                ProposedTag {
+                    field: "performer".to_string(),
+                    value: name.to_string(),
+                    source: Source::Discogs,
+                    rule_name: "performer_credit".to_string(),
+                    confidence: 0.85,
+                    alternatives: Vec::new(),
+                },
+            )
+        };
+        let accepted = db.insert_proposal(&proposal("John Williams")).unwrap();
+        db.set_proposal_status(accepted, ProposalStatus::Accepted)
+            .unwrap();
+        let rejected = db
+            .insert_proposal(&proposal("Philharmonia Orchestra"))
+            .unwrap();
+        db.set_proposal_status(rejected, ProposalStatus::Rejected)
+            .unwrap();
+
+        assert_eq!(credit_approved_performers(&db, &entity_id).unwrap(), 1);
+        let performers = db
+            .get_expression_by_id(&expr.id)
+            .unwrap()
+            .unwrap()
+            .performers;
+        assert_eq!(performers.len(), 1);
+        // A new artist, not the composer of the same name
+        assert_ne!(performers[0].artist_id, composer.id);
+        assert_eq!(performers[0].role.as_deref(), Some("guitar"));
+        assert_eq!(performers[0].kind, None);
+        assert_eq!(performers[0].credited_as.as_deref(), Some("J. Williams"));
+
+        // Applying again finds the artist by Discogs ID and changes nothing
+        assert_eq!(credit_approved_performers(&db, &entity_id).unwrap(), 0);
+        assert_eq!(db.list_artists().unwrap().len(), 2);
+        assert_eq!(
+            db.get_expression_by_id(&expr.id)
+                .unwrap()
+                .unwrap()
+                .performers
+                .len(),
+            1
+        );
+
+        // Not an item ID
+        assert_eq!(credit_approved_performers(&db, "release-1").unwrap(), 0);
+    }
+
     #[test]
     fn test_release_deserialize_minimal() {
         let json = r#"{"id": 1, "title": "Test"}"#;
@@ -9,14 +9,13 @@
 //! [`Assertion`]: tessitura_core::provenance::Assertion
 //! [`Source::LastFm`]: tessitura_core::provenance::Source::LastFm
 
-use std::path::Path;
 use std::time::Duration;
 
 use reqwest::Client;
 use serde::Deserialize;
 
 use tessitura_core::provenance::{Assertion, Source};
-use tessitura_core::schema::Database;
+use tessitura_core::schema::DatabasePool;
 
 use crate::enrich::resilience::RateLimiter;
 use crate::error::{EnrichError, EnrichResult};
@@ -188,7 +187,7 @@ impl LastFmEnricher {
         artist: &str,
         track: &str,
         entity_id: &str,
-        db_path: &Path,
+        db: &DatabasePool,
     ) -> EnrichResult<Vec<Assertion>> {
         let mut assertions = Vec::new();
 
@@ -220,10 +219,7 @@ impl LastFmEnricher {
         }
 
         // Persist all assertions to the database
-        let db = Database::open(db_path)?;
-        for assertion in &assertions {
-            db.insert_assertion(assertion)?;
-        }
+        db.with(|conn| conn.insert_assertions(&assertions))?;
 
         Ok(assertions)
     }
@@ -151,6 +151,20 @@ pub fn load_lcmpt(db: &Database, snapshot_path: &Path) -> Result<usize> {
     Ok(count)
 }
 
+/// Find the LCMPT term for an instrument or voice as credited on a
+/// recording: "piano" is the term of that label, and a voice such as
+/// "soprano" the term "soprano voice".
+///
+/// # Errors
+///
+/// Returns an error if the database lookup fails.
+pub fn lcmpt_term_for_role(db: &Database, role: &str) -> tessitura_core::Result<Option<LcmptTerm>> {
+    match db.get_lcmpt_by_label(role)? {
+        Some(term) => Ok(Some(term)),
+        None => db.get_lcmpt_by_label(&format!("{role} voice")),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -268,6 +282,20 @@ mod tests {
         assert_eq!(narrower.len(), 2);
     }
 
+    #[test]
+    fn test_lcmpt_term_for_role() {
+        let db = Database::open_in_memory().unwrap();
+        db.insert_lcmpt_term(&LcmptTerm::new("http://example.com/mp1", "piano"))
+            .unwrap();
+        db.insert_lcmpt_term(&LcmptTerm::new("http://example.com/mp2", "soprano voice"))
+            .unwrap();
+
+        let uri = |role| lcmpt_term_for_role(&db, role).unwrap().map(|t| t.uri);
+        assert_eq!(uri("Piano").as_deref(), Some("http://example.com/mp1"));
+        assert_eq!(uri("soprano").as_deref(), Some("http://example.com/mp2"));
+        assert_eq!(uri("theremin"), None);
+    }
+
     #[test]
     fn test_load_lcgft_invalid_json() {
         let db = Database::open_in_memory().unwrap();
@@ -9,10 +9,8 @@
 //!
 //! [`Assertion`]: tessitura_core::provenance::Assertion
 
-use std::path::Path;
-
 use tessitura_core::provenance::{Assertion, Source};
-use tessitura_core::schema::Database;
+use tessitura_core::schema::DatabasePool;
 
 use crate::enrich::resilience::RateLimiter;
 use crate::error::EnrichResult;
@@ -61,7 +59,7 @@ impl MusicBrainzEnricher {
         &self,
         recording_mbid: &str,
         entity_id: &str,
-        db_path: &Path,
+        db: &DatabasePool,
     ) -> EnrichResult<Vec<Assertion>> {
         let mut assertions = Vec::new();
 
@@ -111,10 +109,7 @@ impl MusicBrainzEnricher {
         }
 
         // 4. Persist all assertions to the database
-        let db = Database::open(db_path)?;
-        for assertion in &assertions {
-            db.insert_assertion(assertion)?;
-        }
+        db.with(|conn| conn.insert_assertions(&assertions))?;
 
         Ok(assertions)
     }
@@ -142,20 +137,17 @@ impl MusicBrainzEnricher {
             Source::MusicBrainz,
         ));
 
-        // Extract key from attributes (e.g. "A minor", "D major")
-        for attr in &work.attributes {
-            let lower = attr.to_lowercase();
-            if lower.contains("major") || lower.contains("minor") {
-                assertions.push(
-                    Assertion::new(
-                        entity_id,
-                        "key",
-                        serde_json::json!(attr),
-                        Source::MusicBrainz,
-                    )
-                    .with_confidence(0.9),
-                );
-            }
+        // Key from attributes (e.g. "A minor", "D major"), in canonical form
+        if let Some(key) = work.key() {
+            assertions.push(
+                Assertion::new(
+                    entity_id,
+                    "key",
+                    serde_json::json!(key.to_string()),
+                    Source::MusicBrainz,
+                )
+                .with_confidence(0.9),
+            );
         }
 
         // Extract composer from relations
@@ -29,12 +29,10 @@ impl RateLimiter {
     /// the configured interval to enforce the rate limit.
     pub async fn acquire(&self) {
         // `acquire` only returns `Err` when the semaphore is closed, which
-        // we never do, so `expect` is safe here.
-        let _permit = self
-            .semaphore
-            .acquire()
-            .await
-            .expect("rate-limiter semaphore unexpectedly closed");
+        // we never do; if it ever happens there is nothing left to throttle.
+        let Ok(_permit) = self.semaphore.acquire().await else {
+            return;
+        };
         sleep(self.interval).await;
     }
 }
@@ -6,6 +6,7 @@
 
 use std::path::PathBuf;
 
+use serde::{Deserialize, Serialize};
 use treadle::{Stage, StageContext, StageOutcome, SubTask};
 use uuid::Uuid;
 
@@ -14,8 +15,29 @@ use crate::enrich::discogs::DiscogsEnricher;
 use crate::enrich::lastfm::LastFmEnricher;
 use crate::enrich::musicbrainz::MusicBrainzEnricher;
 use crate::enrich::wikidata::WikidataEnricher;
+use crate::error::{EnrichError, EnrichResult};
 use tessitura_core::model::ItemId;
-use tessitura_core::schema::Database;
+use tessitura_core::provenance::Assertion;
+use tessitura_core::schema::{Database, DatabasePool};
+
+/// Every enrichment source, in fan-out order.
+pub const ENRICH_SOURCES: [&str; 4] = ["musicbrainz", "wikidata", "lastfm", "discogs"];
+
+/// The result of enriching one item from one source.
+///
+/// Stored in the subtask's context metadata under `enrich_result`. Failures
+/// are returned as errors instead, so treadle records them on the subtask.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceOutcome {
+    /// The source returned data, stored as this many assertions.
+    Enriched { assertions: usize },
+    /// The source has no data for the item.
+    NotFound,
+    /// The item lacks the identifier this source is queried by (for
+    /// example, a recording MBID or a catalog number).
+    Skipped,
+}
 
 /// Parse an item ID string into an `ItemId`.
 fn parse_item_id(item_id: &str) -> Result<ItemId, treadle::TreadleError> {
@@ -25,6 +47,33 @@ fn parse_item_id(item_id: &str) -> Result<ItemId, treadle::TreadleError> {
     Ok(ItemId::from_uuid(uuid))
 }
 
+/// Classify an enricher's result, treating an empty result as not found.
+fn classify(
+    source: &str,
+    item_id: &str,
+    result: EnrichResult<Vec<Assertion>>,
+) -> Result<SourceOutcome, treadle::TreadleError> {
+    match result {
+        Ok(assertions) if assertions.is_empty() => Ok(SourceOutcome::NotFound),
+        Ok(assertions) => {
+            log::info!(
+                "{source} enrichment: {} assertions for {item_id}",
+                assertions.len()
+            );
+            Ok(SourceOutcome::Enriched {
+                assertions: assertions.len(),
+            })
+        }
+        Err(EnrichError::NotFound { .. }) => Ok(SourceOutcome::NotFound),
+        Err(e) => {
+            log::warn!("{source} enrichment failed for {item_id}: {e}");
+            Err(treadle::TreadleError::StageExecution(format!(
+                "{source} enrichment failed: {e}"
+            )))
+        }
+    }
+}
+
 /// The Enrich stage: fan-out to multiple metadata sources.
 ///
 /// Each source runs as an independent subtask. If one source fails,
@@ -36,7 +85,7 @@ pub struct EnrichStage {
     wikidata: Option<WikidataEnricher>,
     lastfm: Option<LastFmEnricher>,
     discogs: Option<DiscogsEnricher>,
-    db_path: PathBuf,
+    pool: DatabasePool,
 }
 
 impl EnrichStage {
@@ -81,7 +130,7 @@ impl EnrichStage {
             wikidata,
             lastfm,
             discogs,
-            db_path,
+            pool: DatabasePool::new(db_path),
         }
     }
 
@@ -104,17 +153,29 @@ impl EnrichStage {
         sources
     }
 
-    async fn enrich_from_musicbrainz(&self, item_id: &str) -> Result<(), treadle::TreadleError> {
+    /// Read from a pooled connection, which goes back to the pool before
+    /// the caller awaits anything.
+    fn read<T>(
+        &self,
+        f: impl FnOnce(&Database) -> Result<T, treadle::TreadleError>,
+    ) -> Result<T, treadle::TreadleError> {
+        self.pool
+            .with(|db| Ok::<_, tessitura_core::Error>(f(db)))
+            .map_err(|e| {
+                treadle::TreadleError::StageExecution(format!("Failed to open database: {e}"))
+            })?
+    }
+
+    async fn enrich_from_musicbrainz(
+        &self,
+        item_id: &str,
+    ) -> Result<SourceOutcome, treadle::TreadleError> {
         let enricher = self.musicbrainz.as_ref().ok_or_else(|| {
             treadle::TreadleError::StageExecution("MusicBrainz enricher not available".to_string())
         })?;
 
-        // Read phase: open DB, extract needed data, then drop DB before async work.
-        let recording_mbid = {
-            let db = Database::open(&self.db_path).map_err(|e| {
-                treadle::TreadleError::StageExecution(format!("Failed to open database: {e}"))
-            })?;
-
+        // Read phase: extract needed data, returning the connection before async work.
+        let recording_mbid = self.read(|db| {
             let item_id_parsed = parse_item_id(item_id)?;
             let item = db
                 .get_item_by_id(&item_id_parsed)
@@ -125,53 +186,37 @@ impl EnrichStage {
                     treadle::TreadleError::StageExecution(format!("Item not found: {item_id}"))
                 })?;
 
-            item.expression_id.and_then(|expr_id| {
-                match db.list_expressions() {
-                    Ok(expressions) => expressions
-                        .into_iter()
-                        .find(|e| e.id == expr_id)
-                        .and_then(|expr| expr.musicbrainz_id),
+            Ok(item.expression_id.and_then(|expr_id| {
+                match db.get_expression_by_id(&expr_id) {
+                    Ok(expr) => expr.and_then(|expr| expr.musicbrainz_id),
                     Err(e) => {
-                        log::warn!("Failed to list expressions for MusicBrainz lookup: {e}");
+                        log::warn!("Failed to get expression for MusicBrainz lookup: {e}");
                         None
                     }
                 }
-            })
-        };
-        // `db` is now dropped -- safe for Send futures.
-
-        if let Some(ref mbid) = recording_mbid {
-            match enricher
-                .enrich_recording(mbid, item_id, &self.db_path)
-                .await
-            {
-                Ok(assertions) => {
-                    log::info!(
-                        "MusicBrainz enrichment: {} assertions for {}",
-                        assertions.len(),
-                        item_id
-                    );
-                }
-                Err(e) => {
-                    log::warn!("MusicBrainz enrichment failed for {}: {}", item_id, e);
-                }
-            }
-        }
+            }))
+        })?;
 
-        Ok(())
+        let Some(mbid) = recording_mbid else {
+            log::debug!("Skipping MusicBrainz enrichment for {item_id} (no recording MBID)");
+            return Ok(SourceOutcome::Skipped);
+        };
+        let result = enricher
+            .enrich_recording(&mbid, item_id, &self.pool)
+            .await;
+        classify("MusicBrainz", item_id, result)
     }
 
-    async fn enrich_from_wikidata(&self, item_id: &str) -> Result<(), treadle::TreadleError> {
+    async fn enrich_from_wikidata(
+        &self,
+        item_id: &str,
+    ) -> Result<SourceOutcome, treadle::TreadleError> {
         let enricher = self.wikidata.as_ref().ok_or_else(|| {
             treadle::TreadleError::StageExecution("Wikidata enricher not available".to_string())
         })?;
 
-        // Read phase: open DB, extract needed data, then drop DB before async work.
-        let work_mbid = {
-            let db = Database::open(&self.db_path).map_err(|e| {
-                treadle::TreadleError::StageExecution(format!("Failed to open database: {e}"))
-            })?;
-
+        // Read phase: extract needed data, returning the connection before async work.
+        let work_mbid = self.read(|db| {
             let item_id_parsed = parse_item_id(item_id)?;
             let item = db
                 .get_item_by_id(&item_id_parsed)
@@ -182,11 +227,11 @@ impl EnrichStage {
                     treadle::TreadleError::StageExecution(format!("Item not found: {item_id}"))
                 })?;
 
-            item.expression_id.and_then(|expr_id| {
-                match db.list_expressions() {
-                    Ok(expressions) => {
-                        let expr = expressions.into_iter().find(|e| e.id == expr_id)?;
-                        match db.get_work_by_musicbrainz_id(&expr.work_id.to_string()) {
+            Ok(item.expression_id.and_then(|expr_id| {
+                match db.get_expression_by_id(&expr_id) {
+                    Ok(expr) => {
+                        let expr = expr?;
+                        match db.get_work_by_id(&expr.work_id) {
                             Ok(work_opt) => work_opt.and_then(|w| w.musicbrainz_id),
                             Err(e) => {
                                 log::warn!("Failed to get work for Wikidata lookup: {e}");
@@ -195,43 +240,31 @@ impl EnrichStage {
                         }
                     }
                     Err(e) => {
-                        log::warn!("Failed to list expressions for Wikidata lookup: {e}");
+                        log::warn!("Failed to get expression for Wikidata lookup: {e}");
                         None
                     }
                 }
-            })
-        };
-        // `db` is now dropped -- safe for Send futures.
-
-        if let Some(ref mbid) = work_mbid {
-            match enricher.enrich(mbid, item_id, &self.db_path).await {
-                Ok(assertions) => {
-                    log::info!(
-                        "Wikidata enrichment: {} assertions for {}",
-                        assertions.len(),
-                        item_id
-                    );
-                }
-                Err(e) => {
-                    log::warn!("Wikidata enrichment failed for {}: {}", item_id, e);
-                }
-            }
-        }
+            }))
+        })?;
 
-        Ok(())
+        let Some(mbid) = work_mbid else {
+            log::debug!("Skipping Wikidata enrichment for {item_id} (no work MBID)");
+            return Ok(SourceOutcome::Skipped);
+        };
+        let result = enricher.enrich(&mbid, item_id, &self.pool).await;
+        classify("Wikidata", item_id, result)
     }
 
-    async fn enrich_from_lastfm(&self, item_id: &str) -> Result<(), treadle::TreadleError> {
+    async fn enrich_from_lastfm(
+        &self,
+        item_id: &str,
+    ) -> Result<SourceOutcome, treadle::TreadleError> {
         let enricher = self.lastfm.as_ref().ok_or_else(|| {
             treadle::TreadleError::StageExecution("Last.fm enricher not available".to_string())
         })?;
 
-        // Read phase: open DB, extract needed data, then drop DB before async work.
-        let artist_and_title = {
-            let db = Database::open(&self.db_path).map_err(|e| {
-                treadle::TreadleError::StageExecution(format!("Failed to open database: {e}"))
-            })?;
-
+        // Read phase: extract needed data, returning the connection before async work.
+        let artist_and_title = self.read(|db| {
             let item_id_parsed = parse_item_id(item_id)?;
             let item = db
                 .get_item_by_id(&item_id_parsed)
@@ -242,47 +275,32 @@ impl EnrichStage {
                     treadle::TreadleError::StageExecution(format!("Item not found: {item_id}"))
                 })?;
 
-            item.tag_artist.zip(item.tag_title)
-        };
-        // `db` is now dropped -- safe for Send futures.
-
-        if let Some((artist, title)) = artist_and_title {
-            match enricher
-                .enrich(&artist, &title, item_id, &self.db_path)
-                .await
-            {
-                Ok(assertions) => {
-                    log::info!(
-                        "Last.fm enrichment: {} assertions for {}",
-                        assertions.len(),
-                        item_id
-                    );
-                }
-                Err(e) => {
-                    log::warn!("Last.fm enrichment failed for {}: {}", item_id, e);
-                }
-            }
-        } else {
+            Ok(item.tag_artist.zip(item.tag_title))
+        })?;
+
+        let Some((artist, title)) = artist_and_title else {
             log::debug!(
                 "Skipping Last.fm enrichment for {} (no artist/title tags)",
                 item_id
             );
-        }
-
-        Ok(())
+            return Ok(SourceOutcome::Skipped);
+        };
+        let result = enricher
+            .enrich(&artist, &title, item_id, &self.pool)
+            .await;
+        classify("Last.fm", item_id, result)
     }
 
-    async fn enrich_from_discogs(&self, item_id: &str) -> Result<(), treadle::TreadleError> {
+    async fn enrich_from_discogs(
+        &self,
+        item_id: &str,
+    ) -> Result<SourceOutcome, treadle::TreadleError> {
         let enricher = self.discogs.as_ref().ok_or_else(|| {
             treadle::TreadleError::StageExecution("Discogs enricher not available".to_string())
         })?;
 
-        // Read phase: open DB, extract needed data, then drop DB before async work.
-        let catalog_number = {
-            let db = Database::open(&self.db_path).map_err(|e| {
-                treadle::TreadleError::StageExecution(format!("Failed to open database: {e}"))
-            })?;
-
+        // Read phase: extract needed data, returning the connection before async work.
+        let catalog_number = self.read(|db| {
             let item_id_parsed = parse_item_id(item_id)?;
             let item = db
                 .get_item_by_id(&item_id_parsed)
@@ -293,42 +311,28 @@ impl EnrichStage {
                     treadle::TreadleError::StageExecution(format!("Item not found: {item_id}"))
                 })?;
 
-            item.manifestation_id.and_then(|man_id| {
-                match db.get_manifestation_by_musicbrainz_id(&man_id.to_string()) {
+            Ok(item.manifestation_id.and_then(|man_id| {
+                match db.get_manifestation_by_id(&man_id) {
                     Ok(man_opt) => man_opt.and_then(|man| man.catalog_number),
                     Err(e) => {
                         log::warn!("Failed to get manifestation for Discogs lookup: {e}");
                         None
                     }
                 }
-            })
-        };
-        // `db` is now dropped -- safe for Send futures.
-
-        if let Some(ref catno) = catalog_number {
-            match enricher
-                .enrich_by_catno(catno, item_id, &self.db_path)
-                .await
-            {
-                Ok(assertions) => {
-                    log::info!(
-                        "Discogs enrichment: {} assertions for {}",
-                        assertions.len(),
-                        item_id
-                    );
-                }
-                Err(e) => {
-                    log::warn!("Discogs enrichment failed for {}: {}", item_id, e);
-                }
-            }
-        } else {
+            }))
+        })?;
+
+        let Some(catno) = catalog_number else {
             log::debug!(
                 "Skipping Discogs enrichment for {} (no catalog number)",
                 item_id
             );
-        }
-
-        Ok(())
+            return Ok(SourceOutcome::Skipped);
+        };
+        let result = enricher
+            .enrich_by_catno(&catno, item_id, &self.pool)
+            .await;
+        classify("Discogs", item_id, result)
     }
 }
 
@@ -376,26 +380,27 @@ impl Stage for EnrichStage {
             }
 
             // Subtask dispatching
-            Some("musicbrainz") => {
-                self.enrich_from_musicbrainz(item.id()).await?;
-                Ok(StageOutcome::Complete)
-            }
-            Some("wikidata") => {
-                self.enrich_from_wikidata(item.id()).await?;
-                Ok(StageOutcome::Complete)
-            }
-            Some("lastfm") => {
-                self.enrich_from_lastfm(item.id()).await?;
-                Ok(StageOutcome::Complete)
-            }
-            Some("discogs") => {
-                self.enrich_from_discogs(item.id()).await?;
+            Some(source) => {
+                let outcome = match source {
+                    "musicbrainz" => self.enrich_from_musicbrainz(item.id()).await?,
+                    "wikidata" => self.enrich_from_wikidata(item.id()).await?,
+                    "lastfm" => self.enrich_from_lastfm(item.id()).await?,
+                    "discogs" => self.enrich_from_discogs(item.id()).await?,
+                    other => {
+                        return Err(treadle::TreadleError::StageExecution(format!(
+                            "Unknown enrichment subtask: {other}"
+                        )))
+                    }
+                };
+                let outcome_json = serde_json::to_value(outcome).map_err(|e| {
+                    treadle::TreadleError::StageExecution(format!(
+                        "Failed to serialize enrichment result: {e}"
+                    ))
+                })?;
+                ctx.metadata
+                    .insert("enrich_result".to_string(), outcome_json);
                 Ok(StageOutcome::Complete)
             }
-
-            Some(other) => Err(treadle::TreadleError::StageExecution(format!(
-                "Unknown enrichment subtask: {other}"
-            ))),
         }
     }
 }
@@ -497,4 +502,63 @@ mod tests {
         let result = stage.execute(&item, &mut ctx).await;
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_classify_results() {
+        let assertion = Assertion::new(
+            "item",
+            "genre",
+            serde_json::json!("Classical"),
+            tessitura_core::provenance::Source::LastFm,
+        );
+        assert_eq!(
+            classify("Last.fm", "item", Ok(vec![assertion])).unwrap(),
+            SourceOutcome::Enriched { assertions: 1 }
+        );
+        assert_eq!(
+            classify("Last.fm", "item", Ok(Vec::new())).unwrap(),
+            SourceOutcome::NotFound
+        );
+        let not_found = EnrichError::NotFound {
+            entity: "Q1".to_string(),
+            source_name: "Wikidata".to_string(),
+        };
+        assert_eq!(
+            classify("Wikidata", "item", Err(not_found)).unwrap(),
+            SourceOutcome::NotFound
+        );
+        let rate_limited = EnrichError::RateLimited {
+            source_name: "Discogs".to_string(),
+        };
+        assert!(classify("Discogs", "item", Err(rate_limited)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_enrich_subtask_skips_unidentified_item() {
+        use chrono::Utc;
+        use tessitura_core::model::{AudioFormat, Item};
+        use tessitura_core::schema::Database;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db = Database::open(&db_path).unwrap();
+        let item = Item::new(
+            dir.path().join("track.flac"),
+            AudioFormat::Flac,
+            0,
+            Utc::now(),
+        );
+        db.insert_item(&item).unwrap();
+        drop(db);
+
+        let stage = EnrichStage::new(&test_config(), db_path);
+        let work_item = crate::MusicFile::new(item.id.to_string(), item.file_path.clone());
+        let mut ctx = StageContext::new("enrich".to_string()).with_subtask("musicbrainz");
+
+        let outcome = stage.execute(&work_item, &mut ctx).await.unwrap();
+        assert!(matches!(outcome, StageOutcome::Complete));
+        let result: SourceOutcome =
+            serde_json::from_value(ctx.metadata["enrich_result"].clone()).unwrap();
+        assert_eq!(result, SourceOutcome::Skipped);
+    }
 }
@@ -4,19 +4,21 @@
 //! metadata for musical works that have been linked via a MusicBrainz work
 //! ID. The enricher extracts properties such as tonality (key), form,
 //! catalog code, instrumentation, time period, and artistic movement
-//! (school). All findings are stored as provenance-tracked [`Assertion`]s.
+//! (school). Entity references are stored with their English labels,
+//! fetched in batches and cached in the database, so mapping rules can match
+//! them. All findings are stored as provenance-tracked [`Assertion`]s.
 //!
 //! [`Assertion`]: tessitura_core::provenance::Assertion
 
 use std::collections::HashMap;
-use std::path::Path;
 use std::time::Duration;
 
 use reqwest::Client;
 use serde::Deserialize;
 
+use tessitura_core::model::{CatalogNumber, MusicalKey};
 use tessitura_core::provenance::{Assertion, Source};
-use tessitura_core::schema::Database;
+use tessitura_core::schema::{Database, DatabasePool};
 
 use crate::enrich::resilience::RateLimiter;
 use crate::error::{EnrichError, EnrichResult};
@@ -43,6 +45,9 @@ const PROP_PERIOD: &str = "P2348";
 /// Movement (artistic school) -- entity reference.
 const PROP_MOVEMENT: &str = "P135";
 
+/// Most entities the `wbgetentities` API returns per request.
+const LABEL_BATCH_SIZE: usize = 50;
+
 // ---------------------------------------------------------------------------
 // SPARQL response types (private)
 // ---------------------------------------------------------------------------
@@ -72,6 +77,25 @@ struct EntityDataWrapper {
     entities: HashMap<String, WikidataEntity>,
 }
 
+/// Wrapper for the `wbgetentities` labels response.
+#[derive(Debug, Deserialize)]
+struct LabelsWrapper {
+    #[serde(default)]
+    entities: HashMap<String, LabeledEntity>,
+}
+
+/// An entity's labels by language; missing entities have none.
+#[derive(Debug, Deserialize)]
+struct LabeledEntity {
+    #[serde(default)]
+    labels: HashMap<String, WikidataLabel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WikidataLabel {
+    value: String,
+}
+
 /// A Wikidata entity with its claims (property-value pairs).
 #[derive(Debug, Clone, Deserialize)]
 pub struct WikidataEntity {
@@ -295,6 +319,114 @@ impl WikidataClient {
                 source_name: "Wikidata".to_string(),
             })
     }
+
+    /// Fetch the English labels of Wikidata entities, keyed by QID.
+    ///
+    /// Uses the `wbgetentities` API, up to 50 entities per request.
+    /// Entities that do not exist or have no English label are left out.
+    ///
+    /// # Errors
+    /// Returns an error on HTTP failure or if a response cannot be parsed.
+    pub async fn get_labels(&self, qids: &[String]) -> EnrichResult<HashMap<String, String>> {
+        let mut labels = HashMap::new();
+        for batch in qids.chunks(LABEL_BATCH_SIZE) {
+            self.rate_limiter.acquire().await;
+
+            let ids = batch.join("|");
+            let response = self
+                .http
+                .get("https://www.wikidata.org/w/api.php")
+                .query(&[
+                    ("action", "wbgetentities"),
+                    ("ids", ids.as_str()),
+                    ("props", "labels"),
+                    ("languages", "en"),
+                    ("format", "json"),
+                ])
+                .send()
+                .await?
+                .error_for_status()
+                .map_err(|e| EnrichError::Http {
+                    source_name: "Wikidata".to_string(),
+                    message: e.to_string(),
+                })?;
+
+            let wrapper: LabelsWrapper = response.json().await.map_err(|e| EnrichError::Parse {
+                source_name: "Wikidata".to_string(),
+                message: e.to_string(),
+            })?;
+            labels.extend(english_labels(wrapper));
+        }
+        Ok(labels)
+    }
+}
+
+/// The English labels in a `wbgetentities` response, keyed by QID.
+fn english_labels(wrapper: LabelsWrapper) -> impl Iterator<Item = (String, String)> {
+    wrapper
+        .entities
+        .into_iter()
+        .filter_map(|(qid, mut entity)| Some((qid, entity.labels.remove("en")?.value)))
+}
+
+/// An assertion value referring to a Wikidata entity, with its label when
+/// known.
+fn entity_value(qid: &str, labels: &HashMap<String, String>) -> serde_json::Value {
+    match labels.get(qid) {
+        Some(label) => serde_json::json!({ "wikidata_qid": qid, "label": label }),
+        None => serde_json::json!({ "wikidata_qid": qid }),
+    }
+}
+
+/// A tonality assertion value, labelled with the key in canonical form when
+/// the entity's label names one ("E♭ major" becomes "E-flat major").
+fn key_value(qid: &str, labels: &HashMap<String, String>) -> serde_json::Value {
+    let mut value = entity_value(qid, labels);
+    if let Some(key) = labels
+        .get(qid)
+        .and_then(|label| MusicalKey::canonical(label))
+    {
+        value["label"] = serde_json::json!(key);
+    }
+    value
+}
+
+/// Label the Wikidata entity references among `assertions` that were
+/// stored with only their QID, from the database's label cache.
+///
+/// Assertions recorded before their entity's label was cached would
+/// otherwise match no rule. Only the given values are changed, not the
+/// stored assertions. Returns the number of assertions labelled.
+///
+/// # Errors
+/// Returns an error if the cache cannot be read.
+pub fn label_from_cache(db: &Database, assertions: &mut [Assertion]) -> EnrichResult<usize> {
+    let unlabelled = |a: &Assertion| {
+        a.value
+            .get("label")
+            .is_none()
+            .then(|| a.value.get("wikidata_qid")?.as_str().map(str::to_string))
+            .flatten()
+    };
+    let qids: Vec<String> = assertions.iter().filter_map(unlabelled).collect();
+    if qids.is_empty() {
+        return Ok(0);
+    }
+
+    let labels = db.get_wikidata_labels(&qids)?;
+    let mut labelled = 0;
+    for assertion in assertions.iter_mut() {
+        let Some(qid) = unlabelled(assertion).filter(|qid| labels.contains_key(qid)) else {
+            continue;
+        };
+        assertion.value = if assertion.field == "key" {
+            key_value(&qid, &labels)
+        } else {
+            entity_value(&qid, &labels)
+        };
+        labelled += 1;
+    }
+    Ok(labelled)
 }
 
 // ---------------------------------------------------------------------------
@@ -331,8 +463,9 @@ impl WikidataEnricher {
     ///
     /// Looks up the Wikidata entity linked to the MB work ID (via property
     /// P435), then extracts key, form, catalog code, instrumentation,
-    /// period, and school. All findings are stored as provenance-tracked
-    /// assertions in the database.
+    /// period, and school, resolving the entities they refer to to English
+    /// labels. All findings are stored as provenance-tracked assertions in
+    /// the database.
     ///
     /// Returns the list of assertions that were created, or an empty `Vec`
     /// when no Wikidata entity is linked to the given work ID.
@@ -344,7 +477,7 @@ impl WikidataEnricher {
         &self,
         mb_work_id: &str,
         entity_id: &str,
-        db_path: &Path,
+        db: &DatabasePool,
     ) -> EnrichResult<Vec<Assertion>> {
         // 1. Find Wikidata QID via MusicBrainz work ID (P435)
         let Some(qid) = self.client.find_by_mb_work_id(mb_work_id).await? else {
@@ -354,8 +487,19 @@ impl WikidataEnricher {
 
         log::info!("Found Wikidata entity {} for MB work {}", qid, mb_work_id);
 
-        // 2. Fetch entity data
+        // 2. Fetch entity data, and the labels of the entities it refers to
         let entity = self.client.get_entity(&qid).await?;
+        let referenced: Vec<String> = [
+            PROP_TONALITY,
+            PROP_FORM,
+            PROP_INSTRUMENTATION,
+            PROP_PERIOD,
+            PROP_MOVEMENT,
+        ]
+        .iter()
+        .flat_map(|property| entity.get_entity_refs(property))
+        .collect();
+        let labels = self.resolve_labels(&referenced, db).await?;
 
         // 3. Extract properties into assertions
         let mut assertions = Vec::new();
@@ -366,7 +510,7 @@ impl WikidataEnricher {
                 Assertion::new(
                     entity_id,
                     "key",
-                    serde_json::json!({ "wikidata_qid": key_ref }),
+                    key_value(&key_ref, &labels),
                     Source::Wikidata,
                 )
                 .with_confidence(0.9),
@@ -379,15 +523,16 @@ impl WikidataEnricher {
                 Assertion::new(
                     entity_id,
                     "form",
-                    serde_json::json!({ "wikidata_qid": form_ref }),
+                    entity_value(&form_ref, &labels),
                     Source::Wikidata,
                 )
                 .with_confidence(0.9),
             );
         }
 
-        // P528 -- Catalog code
+        // P528 -- Catalog code, in canonical form where it parses
         for catalog in entity.get_string_values(PROP_CATALOG) {
+            let catalog = CatalogNumber::canonical(&catalog).unwrap_or(catalog);
             assertions.push(
                 Assertion::new(
                     entity_id,
@@ -405,7 +550,7 @@ impl WikidataEnricher {
                 Assertion::new(
                     entity_id,
                     "instrumentation",
-                    serde_json::json!({ "wikidata_qid": instrument_ref }),
+                    entity_value(&instrument_ref, &labels),
                     Source::Wikidata,
                 )
                 .with_confidence(0.9),
@@ -418,7 +563,7 @@ impl WikidataEnricher {
                 Assertion::new(
                     entity_id,
                     "period",
-                    serde_json::json!({ "wikidata_qid": period_ref }),
+                    entity_value(&period_ref, &labels),
                     Source::Wikidata,
                 )
                 .with_confidence(0.85),
@@ -431,7 +576,7 @@ impl WikidataEnricher {
                 Assertion::new(
                     entity_id,
                     "school",
-                    serde_json::json!({ "wikidata_qid": movement_ref }),
+                    entity_value(&movement_ref, &labels),
                     Source::Wikidata,
                 )
                 .with_confidence(0.85),
@@ -439,13 +584,47 @@ impl WikidataEnricher {
         }
 
         // 4. Persist all assertions to the database
-        let db = Database::open(db_path)?;
-        for assertion in &assertions {
-            db.insert_assertion(assertion)?;
-        }
+        db.with(|conn| conn.insert_assertions(&assertions))?;
 
         Ok(assertions)
     }
+
+    /// The English labels of the given entities, from the database's cache
+    /// or else fetched (and cached). Labels that cannot be fetched are left
+    /// out, so the assertions keep only their QIDs.
+    ///
+    /// # Errors
+    /// Returns an error if the cache cannot be read or written.
+    async fn resolve_labels(
+        &self,
+        qids: &[String],
+        db: &DatabasePool,
+    ) -> EnrichResult<HashMap<String, String>> {
+        let mut labels = db.with(|conn| conn.get_wikidata_labels(qids))?;
+        let mut missing: Vec<String> = qids
+            .iter()
+            .filter(|qid| !labels.contains_key(*qid))
+            .cloned()
+            .collect();
+        missing.sort();
+        missing.dedup();
+        if missing.is_empty() {
+            return Ok(labels);
+        }
+
+        match self.client.get_labels(&missing).await {
+            Ok(fetched) => {
+                db.with(|conn| {
+                    fetched
+                        .iter()
+                        .try_for_each(|(qid, label)| conn.cache_wikidata_label(qid, label))
+                })?;
+                labels.extend(fetched);
+            }
+            Err(err) => log::warn!("Failed to fetch Wikidata labels: {err}"),
+        }
+        Ok(labels)
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -690,6 +869,102 @@ mod tests {
         assert_eq!(wrapper.entities["Q12345"].id, "Q12345");
     }
 
+    #[test]
+    fn test_labels_wrapper_deserialize() {
+        let json = r#"{
+            "entities": {
+                "Q1344": {
+                    "type": "item",
+                    "id": "Q1344",
+                    "labels": { "en": { "language": "en", "value": "opera" } }
+                },
+                "Q5994": {
+                    "type": "item",
+                    "id": "Q5994",
+                    "labels": {}
+                },
+                "Q999999999": { "id": "Q999999999", "missing": "" }
+            },
+            "success": 1
+        }"#;
+
+        let wrapper: LabelsWrapper = serde_json::from_str(json).unwrap();
+        let labels: HashMap<String, String> = english_labels(wrapper).collect();
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels["Q1344"], "opera");
+    }
+
+    #[test]
+    fn test_entity_value_includes_known_label() {
+        let labels = HashMap::from([("Q1344".to_string(), "opera".to_string())]);
+        assert_eq!(
+            entity_value("Q1344", &labels),
+            serde_json::json!({ "wikidata_qid": "Q1344", "label": "opera" })
+        );
+        assert_eq!(
+            entity_value("Q5994", &labels),
+            serde_json::json!({ "wikidata_qid": "Q5994" })
+        );
+    }
+
+    #[test]
+    fn test_key_value_canonicalizes_label() {
+        let labels = HashMap::from([
+            ("Q1".to_string(), "E♭ major".to_string()),
+            ("Q2".to_string(), "twelve-tone technique".to_string()),
+        ]);
+        assert_eq!(key_value("Q1", &labels)["label"], "E-flat major");
+        assert_eq!(key_value("Q2", &labels)["label"], "twelve-tone technique");
+    }
+
+    #[test]
+    fn test_label_from_cache() {
+        let db = Database::open_in_memory().unwrap();
+        db.cache_wikidata_label("Q1344", "opera").unwrap();
+        db.cache_wikidata_label("Q1", "E♭ major").unwrap();
+
+        let entity = |field: &str, qid: &str| {
+            Assertion::new(
+                "w",
+                field,
+                serde_json::json!({ "wikidata_qid": qid }),
+                Source::Wikidata,
+            )
+        };
+        let mut assertions = vec![
+            entity("form", "Q1344"),
+            entity("key", "Q1"),
+            entity("instrumentation", "Q5994"),
+            Assertion::new("w", "genre", serde_json::json!("opera"), Source::LastFm),
+        ];
+        assert_eq!(label_from_cache(&db, &mut assertions).unwrap(), 2);
+        assert_eq!(assertions[0].value["label"], "opera");
+        assert_eq!(assertions[1].value["label"], "E-flat major");
+        assert!(assertions[2].value.get("label").is_none());
+        assert_eq!(assertions[3].value, "opera");
+
+        // Already labelled assertions are left alone
+        assert_eq!(label_from_cache(&db, &mut assertions).unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_labels_uses_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = DatabasePool::new(dir.path().join("test.db"));
+        pool.with(|conn| {
+            conn.cache_wikidata_label("Q1344", "opera")?;
+            conn.cache_wikidata_label("Q5994", "piano")
+        })
+        .unwrap();
+
+        // Everything is cached, so nothing is fetched
+        let enricher = WikidataEnricher::new().unwrap();
+        let qids = ["Q1344", "Q5994", "Q1344"].map(String::from);
+        let labels = enricher.resolve_labels(&qids, &pool).await.unwrap();
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels["Q5994"], "piano");
+    }
+
     #[test]
     fn test_quantity_datavalue_deserialize() {
         let json = r#"{
@@ -1,4 +1,6 @@
-//! Enrichment error types for the ETL pipeline.
+//! Error types for the ETL pipeline.
+
+use std::path::PathBuf;
 
 use thiserror::Error;
 
@@ -55,3 +57,29 @@ impl EnrichError {
 
 /// Convenience alias for enrichment results.
 pub type EnrichResult<T> = std::result::Result<T, EnrichError>;
+
+/// Errors that can occur while exporting tags to audio files.
+#[derive(Debug, Error)]
+pub enum ExportError {
+    /// Reading or writing a file's tags failed.
+    #[error("tag error for {}: {source}", path.display())]
+    Tag {
+        path: PathBuf,
+        source: lofty::error::LoftyError,
+    },
+
+    /// The file's format has no supported tag type (Vorbis comments or ID3v2).
+    #[error("unsupported format for tag export: {}", .0.display())]
+    UnsupportedFormat(PathBuf),
+
+    /// An I/O error opening the file.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// An error propagated from the core domain layer.
+    #[error("database error: {0}")]
+    Database(#[from] tessitura_core::Error),
+}
+
+/// Convenience alias for export results.
+pub type ExportResult<T> = std::result::Result<T, ExportError>;
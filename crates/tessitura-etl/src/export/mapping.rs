@@ -0,0 +1,322 @@
+//! Mapping from exported FRBR fields to tag keys.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use tessitura_core::provenance::Source;
+
+/// A metadata field projected from the FRBR model into file tags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportField {
+    /// The work's title; for a movement, the title of the work it is part
+    /// of.
+    Work,
+    /// A movement's number within its work.
+    Movement,
+    /// A movement's name, without the work's title.
+    MovementName,
+    /// Every credited composer.
+    Composer,
+    /// Credited arrangers and orchestrators.
+    Arranger,
+    Lyricist,
+    Librettist,
+    /// Credited writers of both music and words.
+    Writer,
+    CatalogNumber,
+    Key,
+    /// Credited performers (one value each).
+    Performer,
+    Conductor,
+    /// Canonical genre, approved in review.
+    Genre,
+    /// Canonical form, approved in review.
+    Form,
+    /// Canonical period, approved in review.
+    Period,
+    /// Canonical instrumentation, approved in review.
+    Instrumentation,
+    #[serde(rename = "musicbrainz_work_id")]
+    MusicBrainzWorkId,
+    #[serde(rename = "musicbrainz_recording_id")]
+    MusicBrainzRecordingId,
+    #[serde(rename = "musicbrainz_release_id")]
+    MusicBrainzReleaseId,
+}
+
+impl ExportField {
+    /// Every exportable field, in tag-writing order.
+    pub const ALL: [Self; 19] = [
+        Self::Work,
+        Self::Movement,
+        Self::MovementName,
+        Self::Composer,
+        Self::Arranger,
+        Self::Lyricist,
+        Self::Librettist,
+        Self::Writer,
+        Self::CatalogNumber,
+        Self::Key,
+        Self::Performer,
+        Self::Conductor,
+        Self::Genre,
+        Self::Form,
+        Self::Period,
+        Self::Instrumentation,
+        Self::MusicBrainzWorkId,
+        Self::MusicBrainzRecordingId,
+        Self::MusicBrainzReleaseId,
+    ];
+
+    /// The field name used by proposals and user assertions, as in the
+    /// configuration ("work", "catalog_number", "genre", ...).
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Work => "work",
+            Self::Movement => "movement",
+            Self::MovementName => "movement_name",
+            Self::Composer => "composer",
+            Self::Arranger => "arranger",
+            Self::Lyricist => "lyricist",
+            Self::Librettist => "librettist",
+            Self::Writer => "writer",
+            Self::CatalogNumber => "catalog_number",
+            Self::Key => "key",
+            Self::Performer => "performer",
+            Self::Conductor => "conductor",
+            Self::Genre => "genre",
+            Self::Form => "form",
+            Self::Period => "period",
+            Self::Instrumentation => "instrumentation",
+            Self::MusicBrainzWorkId => "musicbrainz_work_id",
+            Self::MusicBrainzRecordingId => "musicbrainz_recording_id",
+            Self::MusicBrainzReleaseId => "musicbrainz_release_id",
+        }
+    }
+
+    /// The harmonized field name used by mapping rules ("genre", "form",
+    /// "period", "instrumentation"), if this field is harmonized rather
+    /// than projected from the FRBR model.
+    #[must_use]
+    pub const fn harmonized_name(self) -> Option<&'static str> {
+        match self {
+            Self::Genre => Some("genre"),
+            Self::Form => Some("form"),
+            Self::Period => Some("period"),
+            Self::Instrumentation => Some("instrumentation"),
+            _ => None,
+        }
+    }
+
+    /// Default Vorbis comment key (FLAC, Ogg Vorbis, Opus).
+    #[must_use]
+    pub const fn default_vorbis_key(self) -> &'static str {
+        match self {
+            Self::Work => "WORK",
+            Self::Movement => "MOVEMENT",
+            Self::MovementName => "MOVEMENTNAME",
+            Self::Composer => "COMPOSER",
+            Self::Arranger => "ARRANGER",
+            Self::Lyricist => "LYRICIST",
+            Self::Librettist => "LIBRETTIST",
+            Self::Writer => "WRITER",
+            Self::CatalogNumber => "OPUS",
+            Self::Key => "KEY",
+            Self::Performer => "PERFORMER",
+            Self::Conductor => "CONDUCTOR",
+            Self::Genre => "GENRE",
+            Self::Form => "FORM",
+            Self::Period => "PERIOD",
+            Self::Instrumentation => "INSTRUMENTATION",
+            Self::MusicBrainzWorkId => "MUSICBRAINZ_WORKID",
+            Self::MusicBrainzRecordingId => "MUSICBRAINZ_TRACKID",
+            Self::MusicBrainzReleaseId => "MUSICBRAINZ_ALBUMID",
+        }
+    }
+
+    /// Default ID3v2 frame: a frame ID such as `TCOM`, or `TXXX:<description>`
+    /// for a user-defined text frame.
+    #[must_use]
+    pub const fn default_id3v2_key(self) -> &'static str {
+        match self {
+            Self::Work => "TIT1",
+            Self::Movement => "MVIN",
+            Self::MovementName => "MVNM",
+            Self::Composer => "TCOM",
+            Self::Arranger => "TXXX:ARRANGER",
+            Self::Lyricist => "TEXT",
+            Self::Librettist => "TXXX:LIBRETTIST",
+            Self::Writer => "TXXX:WRITER",
+            Self::CatalogNumber => "TXXX:OPUS",
+            Self::Key => "TKEY",
+            Self::Performer => "TXXX:PERFORMER",
+            Self::Conductor => "TPE3",
+            Self::Genre => "TCON",
+            Self::Form => "TXXX:FORM",
+            Self::Period => "TXXX:PERIOD",
+            Self::Instrumentation => "TXXX:INSTRUMENTATION",
+            Self::MusicBrainzWorkId => "TXXX:MusicBrainz Work Id",
+            Self::MusicBrainzRecordingId => "TXXX:MusicBrainz Track Id",
+            Self::MusicBrainzReleaseId => "TXXX:MusicBrainz Album Id",
+        }
+    }
+}
+
+/// The tag format written to a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagFormat {
+    /// Vorbis comments (FLAC, Ogg Vorbis, Opus).
+    Vorbis,
+    /// ID3v2 (MP3).
+    Id3v2,
+}
+
+/// Configurable field → tag key mapping.
+///
+/// Only overrides are stored; unmapped fields use the defaults from
+/// [`ExportField::default_vorbis_key`] and [`ExportField::default_id3v2_key`].
+/// Mapping a field to an empty string disables it.
+///
+/// ```toml
+/// [export.vorbis]
+/// catalog_number = "CATALOGNUMBER"
+/// period = ""
+///
+/// [export.id3v2]
+/// work = "TXXX:WORK"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldMapping {
+    #[serde(default)]
+    pub vorbis: BTreeMap<ExportField, String>,
+    #[serde(default)]
+    pub id3v2: BTreeMap<ExportField, String>,
+}
+
+/// The `[export]` configuration section.
+///
+/// Values projected from the FRBR model are only written once approved in
+/// review, unless the source they were identified from is listed in
+/// `auto_accept`:
+///
+/// ```toml
+/// [export]
+/// auto_accept = ["MusicBrainz"]
+///
+/// [export.vorbis]
+/// catalog_number = "CATALOGNUMBER"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportConfig {
+    /// Sources whose identified values are written without review.
+    #[serde(default)]
+    pub auto_accept: Vec<Source>,
+    #[serde(flatten)]
+    pub mapping: FieldMapping,
+}
+
+impl FieldMapping {
+    /// The tag key a field is written to, or `None` if it is disabled.
+    #[must_use]
+    pub fn key(&self, format: TagFormat, field: ExportField) -> Option<&str> {
+        let (overrides, default) = match format {
+            TagFormat::Vorbis => (&self.vorbis, field.default_vorbis_key()),
+            TagFormat::Id3v2 => (&self.id3v2, field.default_id3v2_key()),
+        };
+        let key = overrides.get(&field).map_or(default, String::as_str);
+        (!key.is_empty()).then_some(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_keys() {
+        let mapping = FieldMapping::default();
+        assert_eq!(
+            mapping.key(TagFormat::Vorbis, ExportField::Composer),
+            Some("COMPOSER")
+        );
+        assert_eq!(
+            mapping.key(TagFormat::Id3v2, ExportField::Composer),
+            Some("TCOM")
+        );
+    }
+
+    #[test]
+    fn test_overrides_and_disabled_fields() {
+        let mapping: FieldMapping = toml::from_str(
+            r#"
+            [vorbis]
+            catalog_number = "CATALOGNUMBER"
+            period = ""
+
+            [id3v2]
+            musicbrainz_work_id = "TXXX:MB Work"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            mapping.key(TagFormat::Vorbis, ExportField::CatalogNumber),
+            Some("CATALOGNUMBER")
+        );
+        assert_eq!(mapping.key(TagFormat::Vorbis, ExportField::Period), None);
+        assert_eq!(
+            mapping.key(TagFormat::Id3v2, ExportField::Period),
+            Some("TXXX:PERIOD")
+        );
+        assert_eq!(
+            mapping.key(TagFormat::Id3v2, ExportField::MusicBrainzWorkId),
+            Some("TXXX:MB Work")
+        );
+    }
+
+    #[test]
+    fn test_export_config() {
+        let config: ExportConfig = toml::from_str(
+            r#"
+            auto_accept = ["MusicBrainz"]
+
+            [vorbis]
+            catalog_number = "CATALOGNUMBER"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.auto_accept, [Source::MusicBrainz]);
+        assert_eq!(
+            config
+                .mapping
+                .key(TagFormat::Vorbis, ExportField::CatalogNumber),
+            Some("CATALOGNUMBER")
+        );
+        assert!(toml::from_str::<ExportConfig>("")
+            .unwrap()
+            .auto_accept
+            .is_empty());
+    }
+
+    #[test]
+    fn test_field_names_match_config_keys() {
+        for field in ExportField::ALL {
+            let name = toml::Value::try_from(field).unwrap();
+            assert_eq!(name.as_str(), Some(field.name()));
+        }
+    }
+
+    #[test]
+    fn test_harmonized_fields() {
+        let harmonized: Vec<_> = ExportField::ALL
+            .iter()
+            .filter_map(|f| f.harmonized_name())
+            .collect();
+        assert_eq!(
+            harmonized,
+            vec!["genre", "form", "period", "instrumentation"]
+        );
+    }
+}
@@ -0,0 +1,14 @@
+//! Export stage components.
+//!
+//! Projects the FRBR model for each item into Vorbis comments or ID3v2
+//! frames and writes them back to the audio files.
+
+pub mod mapping;
+pub mod stage;
+pub mod tags;
+pub mod values;
+
+pub use mapping::{ExportConfig, ExportField, FieldMapping, TagFormat};
+pub use stage::ExportStage;
+pub use tags::{export_tags, TagChange};
+pub use values::ExportValues;
@@ -0,0 +1,249 @@
+//! Export stage: write approved metadata back into audio file tags.
+
+use std::path::PathBuf;
+
+use treadle::{Stage, StageContext, StageOutcome};
+use uuid::Uuid;
+
+use tessitura_core::model::{Item, ItemId};
+use tessitura_core::provenance::Source;
+use tessitura_core::schema::{Database, DatabasePool};
+
+use super::mapping::FieldMapping;
+use super::tags::export_tags;
+use super::values::ExportValues;
+use crate::error::ExportError;
+
+/// The Export stage: project the FRBR model for an item into its tags.
+///
+/// Writes Vorbis comments (FLAC, Ogg Vorbis, Opus) or ID3v2 frames (MP3)
+/// using a configurable [`FieldMapping`]. Only values approved in review are
+/// written, unless their source is auto-accepted. The resulting changes are stored
+/// in the stage context metadata under `export_changes`; in dry-run mode
+/// they are computed but not written.
+#[derive(Debug)]
+pub struct ExportStage {
+    pool: DatabasePool,
+    mapping: FieldMapping,
+    auto_accept: Vec<Source>,
+    dry_run: bool,
+}
+
+impl ExportStage {
+    #[must_use]
+    pub fn new(db_path: PathBuf, mapping: FieldMapping) -> Self {
+        Self {
+            pool: DatabasePool::new(db_path),
+            mapping,
+            auto_accept: Vec::new(),
+            dry_run: false,
+        }
+    }
+
+    /// Write values from these sources without review.
+    #[must_use]
+    pub fn with_auto_accept(mut self, sources: Vec<Source>) -> Self {
+        self.auto_accept = sources;
+        self
+    }
+
+    /// Compute changes without writing them.
+    #[must_use]
+    pub const fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    fn load(
+        &self,
+        db: &Database,
+        item: &dyn treadle::WorkItem,
+    ) -> treadle::Result<(Item, ExportValues)> {
+        // 1. Load the item
+        let item_id = Uuid::parse_str(item.id())
+            .map(ItemId::from_uuid)
+            .map_err(|_| {
+                treadle::TreadleError::StageExecution(format!(
+                    "Invalid item ID (not a UUID): {}",
+                    item.id()
+                ))
+            })?;
+        let db_item = db
+            .get_item_by_id(&item_id)
+            .map_err(|e| treadle::TreadleError::StageExecution(format!("Failed to get item: {e}")))?
+            .ok_or_else(|| {
+                treadle::TreadleError::StageExecution(format!("Item not found: {}", item.id()))
+            })?;
+
+        // 2. Project the FRBR model and approved values
+        let values = ExportValues::collect(db, &db_item, &self.auto_accept).map_err(|e| {
+            treadle::TreadleError::StageExecution(format!("Failed to collect metadata: {e}"))
+        })?;
+        Ok((db_item, values))
+    }
+}
+
+#[async_trait::async_trait]
+impl Stage for ExportStage {
+    fn name(&self) -> &str {
+        "export"
+    }
+
+    async fn execute(
+        &self,
+        item: &dyn treadle::WorkItem,
+        ctx: &mut StageContext,
+    ) -> treadle::Result<StageOutcome> {
+        // 1-2. Load the item and its approved values
+        let (db_item, values) = self
+            .pool
+            .with(|db| Ok::<_, tessitura_core::Error>(self.load(db, item)))
+            .map_err(|e| {
+                treadle::TreadleError::StageExecution(format!("Failed to open database: {e}"))
+            })??;
+
+        if values.is_empty() {
+            log::info!("Nothing to export for {}", item.id());
+            return Ok(StageOutcome::Complete);
+        }
+
+        // 3. Diff against the file's tags and write the changes
+        let changes = match export_tags(&db_item.file_path, &values, &self.mapping, self.dry_run) {
+            Ok(changes) => changes,
+            Err(ExportError::UnsupportedFormat(path)) => {
+                log::info!(
+                    "Skipping export for {} (unsupported format)",
+                    path.display()
+                );
+                return Ok(StageOutcome::Complete);
+            }
+            Err(e) => {
+                return Err(treadle::TreadleError::StageExecution(format!(
+                    "Failed to export tags: {e}"
+                )))
+            }
+        };
+
+        log::info!(
+            "{} {} tag change(s) for {}",
+            if self.dry_run { "Planned" } else { "Wrote" },
+            changes.len(),
+            db_item.file_path.display()
+        );
+
+        // 4. Store the changes in context metadata for reporting
+        let changes_json = serde_json::to_value(&changes).map_err(|e| {
+            treadle::TreadleError::StageExecution(format!("Failed to serialize changes: {e}"))
+        })?;
+        ctx.metadata
+            .insert("export_changes".to_string(), changes_json);
+
+        Ok(StageOutcome::Complete)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::write_empty_flac;
+    use crate::MusicFile;
+    use chrono::Utc;
+    use tessitura_core::model::{AudioFormat, Expression, Item, Work};
+    use tessitura_core::schema::Database;
+
+    #[tokio::test]
+    async fn test_export_stage_records_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let file_path = dir.path().join("track.flac");
+        write_empty_flac(&file_path);
+
+        let db = Database::open(&db_path).unwrap();
+        let work = Work::new("Sonata")
+            .with_composer("Scarlatti")
+            .with_musicbrainz_id("work-mbid");
+        db.insert_work(&work).unwrap();
+        let expr = Expression::new(work.id);
+        db.insert_expression(&expr).unwrap();
+        let mut item = Item::new(file_path.clone(), AudioFormat::Flac, 0, Utc::now());
+        item.expression_id = Some(expr.id);
+        db.insert_item(&item).unwrap();
+        drop(db);
+
+        let stage = ExportStage::new(db_path, FieldMapping::default())
+            .with_auto_accept(vec![Source::MusicBrainz])
+            .with_dry_run(true);
+        let work_item = MusicFile::new(item.id.to_string(), file_path);
+        let mut ctx = StageContext::new("export".to_string());
+
+        let outcome = stage.execute(&work_item, &mut ctx).await.unwrap();
+        assert!(matches!(outcome, StageOutcome::Complete));
+        let changes: Vec<crate::export::TagChange> =
+            serde_json::from_value(ctx.metadata["export_changes"].clone()).unwrap();
+        let keys: Vec<_> = changes.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(keys, ["WORK", "COMPOSER", "MUSICBRAINZ_WORKID"]);
+        assert_eq!(changes[1].after, ["Scarlatti"]);
+    }
+
+    #[tokio::test]
+    async fn test_export_stage_fails_for_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let file_path = dir.path().join("missing.mp3");
+
+        let db = Database::open(&db_path).unwrap();
+        let work = Work::new("Sonata")
+            .with_composer("Scarlatti")
+            .with_musicbrainz_id("work-mbid");
+        db.insert_work(&work).unwrap();
+        let expr = Expression::new(work.id);
+        db.insert_expression(&expr).unwrap();
+        let mut item = Item::new(file_path.clone(), AudioFormat::Mp3, 0, Utc::now());
+        item.expression_id = Some(expr.id);
+        db.insert_item(&item).unwrap();
+        drop(db);
+
+        let stage = ExportStage::new(db_path, FieldMapping::default())
+            .with_auto_accept(vec![Source::MusicBrainz])
+            .with_dry_run(true);
+        let work_item = MusicFile::new(item.id.to_string(), file_path);
+        let mut ctx = StageContext::new("export".to_string());
+
+        // The file does not exist, so reading its tags fails
+        let result = stage.execute(&work_item, &mut ctx).await;
+        assert!(result.is_err());
+        assert!(!ctx.metadata.contains_key("export_changes"));
+    }
+
+    #[tokio::test]
+    async fn test_export_stage_skips_items_without_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db = Database::open(&db_path).unwrap();
+        let item = Item::new(
+            dir.path().join("unknown.flac"),
+            AudioFormat::Flac,
+            0,
+            Utc::now(),
+        );
+        db.insert_item(&item).unwrap();
+        drop(db);
+
+        let stage = ExportStage::new(db_path, FieldMapping::default());
+        let work_item = MusicFile::new(item.id.to_string(), item.file_path.clone());
+        let mut ctx = StageContext::new("export".to_string());
+
+        let outcome = stage.execute(&work_item, &mut ctx).await.unwrap();
+        assert!(matches!(outcome, StageOutcome::Complete));
+    }
+
+    #[tokio::test]
+    async fn test_export_stage_invalid_item_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let stage = ExportStage::new(dir.path().join("test.db"), FieldMapping::default());
+        let work_item = MusicFile::new("not-a-uuid", dir.path().join("x.flac"));
+        let mut ctx = StageContext::new("export".to_string());
+
+        assert!(stage.execute(&work_item, &mut ctx).await.is_err());
+    }
+}
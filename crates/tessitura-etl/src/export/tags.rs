@@ -0,0 +1,258 @@
+//! Reading and writing Vorbis comments and ID3v2 frames via lofty.
+
+use std::borrow::Cow;
+use std::fs::File;
+use std::path::Path;
+
+use lofty::config::{ParseOptions, WriteOptions};
+use lofty::file::{AudioFile, FileType};
+use lofty::flac::FlacFile;
+use lofty::id3::v2::{Frame, FrameId, Id3v2Tag, TextInformationFrame};
+use lofty::mpeg::MpegFile;
+use lofty::ogg::{OpusFile, VorbisComments, VorbisFile};
+use lofty::probe::Probe;
+use lofty::tag::TagExt;
+use lofty::TextEncoding;
+use serde::{Deserialize, Serialize};
+
+use super::mapping::{FieldMapping, TagFormat};
+use super::values::ExportValues;
+use crate::error::{ExportError, ExportResult};
+
+/// Separator for multiple values in an ID3v2.4 text frame.
+const ID3V2_MULTI_VALUE_SEPARATOR: char = '\0';
+
+/// A change to a single tag key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagChange {
+    pub key: String,
+    /// Values currently in the file (empty if the key is absent).
+    pub before: Vec<String>,
+    /// Values that will be written.
+    pub after: Vec<String>,
+}
+
+/// The writable tag of a supported file.
+#[derive(Debug)]
+enum FileTags {
+    Vorbis(VorbisComments),
+    Id3v2(Id3v2Tag),
+}
+
+impl FileTags {
+    fn read(path: &Path) -> ExportResult<Self> {
+        let tag_error = |source| ExportError::Tag {
+            path: path.to_path_buf(),
+            source,
+        };
+
+        let file_type = Probe::open(path)
+            .and_then(|probe| Ok(probe.guess_file_type()?))
+            .map_err(tag_error)?
+            .file_type();
+        let mut file = File::open(path)?;
+        let options = ParseOptions::new();
+
+        match file_type {
+            Some(FileType::Flac) => {
+                let flac = FlacFile::read_from(&mut file, options).map_err(tag_error)?;
+                Ok(Self::Vorbis(
+                    flac.vorbis_comments().cloned().unwrap_or_default(),
+                ))
+            }
+            Some(FileType::Vorbis) => {
+                let ogg = VorbisFile::read_from(&mut file, options).map_err(tag_error)?;
+                Ok(Self::Vorbis(ogg.vorbis_comments().clone()))
+            }
+            Some(FileType::Opus) => {
+                let opus = OpusFile::read_from(&mut file, options).map_err(tag_error)?;
+                Ok(Self::Vorbis(opus.vorbis_comments().clone()))
+            }
+            Some(FileType::Mpeg) => {
+                let mpeg = MpegFile::read_from(&mut file, options).map_err(tag_error)?;
+                Ok(Self::Id3v2(mpeg.id3v2().cloned().unwrap_or_default()))
+            }
+            _ => Err(ExportError::UnsupportedFormat(path.to_path_buf())),
+        }
+    }
+
+    const fn format(&self) -> TagFormat {
+        match self {
+            Self::Vorbis(_) => TagFormat::Vorbis,
+            Self::Id3v2(_) => TagFormat::Id3v2,
+        }
+    }
+
+    fn get(&self, key: &str) -> Vec<String> {
+        match self {
+            Self::Vorbis(tag) => tag.get_all(key).map(str::to_string).collect(),
+            Self::Id3v2(tag) => {
+                let text = match key.strip_prefix("TXXX:") {
+                    Some(description) => tag.get_user_text(description),
+                    None => FrameId::new(key).ok().and_then(|id| tag.get_text(&id)),
+                };
+                text.map(|t| {
+                    t.split(ID3V2_MULTI_VALUE_SEPARATOR)
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default()
+            }
+        }
+    }
+
+    fn set(&mut self, key: &str, values: &[String]) {
+        match self {
+            Self::Vorbis(tag) => {
+                tag.remove(key).for_each(drop);
+                for value in values {
+                    tag.push(key.to_string(), value.clone());
+                }
+            }
+            Self::Id3v2(tag) => {
+                let joined = values.join(&ID3V2_MULTI_VALUE_SEPARATOR.to_string());
+                if let Some(description) = key.strip_prefix("TXXX:") {
+                    tag.insert_user_text(description.to_string(), joined);
+                } else {
+                    match FrameId::new(Cow::Owned(key.to_string())) {
+                        Ok(id) => {
+                            tag.insert(Frame::Text(TextInformationFrame::new(
+                                id,
+                                TextEncoding::UTF8,
+                                joined,
+                            )));
+                        }
+                        Err(e) => log::warn!("Skipping invalid ID3v2 frame ID '{key}': {e}"),
+                    }
+                }
+            }
+        }
+    }
+
+    fn save(&self, path: &Path) -> ExportResult<()> {
+        let result = match self {
+            Self::Vorbis(tag) => tag.save_to_path(path, WriteOptions::default()),
+            Self::Id3v2(tag) => tag.save_to_path(path, WriteOptions::default()),
+        };
+        result.map_err(|source| ExportError::Tag {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+/// Compare the values to export with a file's current tags and, unless
+/// `dry_run` is set, write the differences.
+///
+/// Returns the changes, which are empty when the file is already up to
+/// date. Keys whose field has no value are left untouched.
+pub fn export_tags(
+    path: &Path,
+    values: &ExportValues,
+    mapping: &FieldMapping,
+    dry_run: bool,
+) -> ExportResult<Vec<TagChange>> {
+    let mut tags = FileTags::read(path)?;
+    let changes = diff(&tags, values, mapping);
+
+    if !dry_run && !changes.is_empty() {
+        for change in &changes {
+            tags.set(&change.key, &change.after);
+        }
+        tags.save(path)?;
+    }
+
+    Ok(changes)
+}
+
+fn diff(tags: &FileTags, values: &ExportValues, mapping: &FieldMapping) -> Vec<TagChange> {
+    values
+        .iter()
+        .filter_map(|(field, after)| {
+            let key = mapping.key(tags.format(), field)?;
+            let before = tags.get(key);
+            (before != after).then(|| TagChange {
+                key: key.to_string(),
+                before,
+                after: after.to_vec(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::mapping::ExportField;
+    use crate::test_support::write_empty_flac;
+
+    fn sample_values() -> ExportValues {
+        let mut values = ExportValues::new();
+        values.push(ExportField::Composer, "Béla Bartók");
+        values.push(ExportField::Performer, "Zoltán Székely");
+        values.push(ExportField::Performer, "Alexandre Moskowsky");
+        values
+    }
+
+    #[test]
+    fn test_flac_dry_run_then_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("track.flac");
+        write_empty_flac(&path);
+        let mapping = FieldMapping::default();
+
+        let changes = export_tags(&path, &sample_values(), &mapping, true).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].key, "COMPOSER");
+        assert!(changes[0].before.is_empty());
+        assert_eq!(changes[1].after.len(), 2);
+
+        // Dry run leaves the file untouched
+        let changes = export_tags(&path, &sample_values(), &mapping, true).unwrap();
+        assert_eq!(changes.len(), 2);
+
+        export_tags(&path, &sample_values(), &mapping, false).unwrap();
+        let changes = export_tags(&path, &sample_values(), &mapping, true).unwrap();
+        assert!(changes.is_empty());
+
+        let FileTags::Vorbis(tag) = FileTags::read(&path).unwrap() else {
+            panic!("expected Vorbis comments");
+        };
+        assert_eq!(tag.get("COMPOSER"), Some("Béla Bartók"));
+        assert_eq!(tag.get_all("PERFORMER").count(), 2);
+    }
+
+    #[test]
+    fn test_id3v2_keys_round_trip() {
+        let mut tags = FileTags::Id3v2(Id3v2Tag::default());
+        let performers = vec!["A".to_string(), "B".to_string()];
+        tags.set("TCOM", &["Béla Bartók".to_string()]);
+        tags.set("TXXX:PERFORMER", &performers);
+        tags.set("bogus", &["ignored".to_string()]);
+
+        assert_eq!(tags.get("TCOM"), vec!["Béla Bartók".to_string()]);
+        assert_eq!(tags.get("TXXX:PERFORMER"), performers);
+        assert!(tags.get("TCON").is_empty());
+    }
+
+    #[test]
+    fn test_diff_respects_disabled_fields() {
+        let tags = FileTags::Vorbis(VorbisComments::default());
+        let mut mapping = FieldMapping::default();
+        mapping.vorbis.insert(ExportField::Performer, String::new());
+
+        let changes = diff(&tags, &sample_values(), &mapping);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].key, "COMPOSER");
+    }
+
+    #[test]
+    fn test_unsupported_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, b"not audio").unwrap();
+
+        let result = export_tags(&path, &sample_values(), &FieldMapping::default(), true);
+        assert!(result.is_err());
+    }
+}
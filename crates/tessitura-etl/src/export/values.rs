@@ -0,0 +1,535 @@
+//! Projection of the FRBR model onto the exportable fields of one item.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use tessitura_core::model::{Item, Work, WorkId, WorkRole};
+use tessitura_core::provenance::{Assertion, Proposal, ProposalStatus, Source};
+use tessitura_core::schema::Database;
+
+use super::mapping::ExportField;
+use crate::error::ExportResult;
+
+/// The values to write for each field of an item, in tag order.
+///
+/// Fields with no values are absent and leave existing tags untouched.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ExportValues {
+    fields: BTreeMap<ExportField, Vec<String>>,
+}
+
+impl ExportValues {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collect the exportable metadata for an item.
+    ///
+    /// Only values approved in review are included: an accepted proposal
+    /// for the field, or a value chosen by the user, which is recorded as a
+    /// [`Source::User`] assertion on the item. Sources listed in
+    /// `auto_accept` skip review: their pending proposals are included, and
+    /// so are the work, recording and release fields of FRBR records
+    /// identified from them. A field approved in review is written instead
+    /// of the FRBR values.
+    pub fn collect(db: &Database, item: &Item, auto_accept: &[Source]) -> ExportResult<Self> {
+        let entity_id = item.id.to_string();
+        let proposals = db.get_proposals_for_entity(&entity_id)?;
+        let assertions = db.get_assertions_for_entity(&entity_id)?;
+        let mut values = Self::new();
+        values.add_approved(&proposals, &assertions, auto_accept);
+
+        let mut identified = Self::new();
+        let expression = match item.expression_id {
+            Some(id) => db.get_expression_by_id(&id)?,
+            None => None,
+        };
+        if let Some(expr) = &expression {
+            if let Some(work) = db.get_work_by_id(&expr.work_id)? {
+                identified.add_work(db, work, auto_accept)?;
+            }
+            if is_auto_accepted(expr.musicbrainz_id.as_deref(), auto_accept) {
+                for performer in &expr.performers {
+                    if let Some(artist) = db.get_artist_by_id(&performer.artist_id)? {
+                        identified.push(ExportField::Performer, artist.name);
+                    }
+                }
+                if let Some(conductor_id) = &expr.conductor_id {
+                    if let Some(artist) = db.get_artist_by_id(conductor_id)? {
+                        identified.push(ExportField::Conductor, artist.name);
+                    }
+                }
+                identified.push_opt(
+                    ExportField::MusicBrainzRecordingId,
+                    expr.musicbrainz_id.clone(),
+                );
+            }
+        }
+
+        if let Some(man_id) = &item.manifestation_id {
+            if let Some(man) = db.get_manifestation_by_id(man_id)? {
+                if is_auto_accepted(man.musicbrainz_id.as_deref(), auto_accept) {
+                    identified.push_opt(ExportField::MusicBrainzReleaseId, man.musicbrainz_id);
+                }
+            }
+        }
+
+        for (field, field_values) in identified.fields {
+            values.fields.entry(field).or_insert(field_values);
+        }
+        Ok(values)
+    }
+
+    /// Add the fields of a recording's work.
+    ///
+    /// A movement is tagged with the title of the work it is part of, its
+    /// number and its name, and takes the composer, catalog number and key
+    /// of that work where it has none of its own. Artists credited on the
+    /// work, or on the work a movement is part of, are added after any
+    /// composer recorded on the work itself. Each work's values are only
+    /// added if it was identified from an auto-accepted source.
+    fn add_work(&mut self, db: &Database, work: Work, auto_accept: &[Source]) -> ExportResult<()> {
+        if !is_auto_accepted(work.musicbrainz_id.as_deref(), auto_accept) {
+            return Ok(());
+        }
+        let root = match work.parent_work_id {
+            Some(_) => db
+                .get_root_work(&work.id)?
+                .filter(|root| root.id != work.id),
+            None => None,
+        };
+        let Some(root) = root else {
+            self.push(ExportField::Work, work.title);
+            self.push_opt(ExportField::Composer, work.composer);
+            self.add_credits(db, &work.id)?;
+            self.push_opt(ExportField::CatalogNumber, work.catalog_number);
+            self.push_opt(ExportField::Key, work.key);
+            self.push_opt(ExportField::MusicBrainzWorkId, work.musicbrainz_id);
+            return Ok(());
+        };
+
+        let root =
+            Some(root).filter(|root| is_auto_accepted(root.musicbrainz_id.as_deref(), auto_accept));
+        self.push_opt(ExportField::Work, root.as_ref().map(|r| r.title.clone()));
+        self.push_opt(
+            ExportField::Movement,
+            work.part_number.map(|n| n.to_string()),
+        );
+        self.push(
+            ExportField::MovementName,
+            work.movement_label.unwrap_or(work.title),
+        );
+        let (root_id, root_composer, root_catalog_number, root_key) = match root {
+            Some(root) => (Some(root.id), root.composer, root.catalog_number, root.key),
+            None => (None, None, None, None),
+        };
+        self.push_opt(ExportField::Composer, work.composer.or(root_composer));
+        self.add_credits(db, &work.id)?;
+        if let Some(root_id) = root_id {
+            self.add_credits(db, &root_id)?;
+        }
+        self.push_opt(
+            ExportField::CatalogNumber,
+            work.catalog_number.or(root_catalog_number),
+        );
+        self.push_opt(ExportField::Key, work.key.or(root_key));
+        self.push_opt(ExportField::MusicBrainzWorkId, work.musicbrainz_id);
+        Ok(())
+    }
+
+    /// Add the names of the artists credited on a work.
+    fn add_credits(&mut self, db: &Database, work_id: &WorkId) -> ExportResult<()> {
+        for (role, field) in [
+            (WorkRole::Composer, ExportField::Composer),
+            (WorkRole::Arranger, ExportField::Arranger),
+            (WorkRole::Orchestrator, ExportField::Arranger),
+            (WorkRole::Lyricist, ExportField::Lyricist),
+            (WorkRole::Librettist, ExportField::Librettist),
+            (WorkRole::Writer, ExportField::Writer),
+        ] {
+            for artist in db.list_credited_artists(work_id, role)? {
+                self.push(field, artist.name);
+            }
+        }
+        Ok(())
+    }
+
+    /// Add values approved in review, or proposed by an auto-accepted
+    /// source and still pending.
+    fn add_approved(
+        &mut self,
+        proposals: &[Proposal],
+        assertions: &[Assertion],
+        auto_accept: &[Source],
+    ) {
+        for field in ExportField::ALL {
+            let name = field.name();
+            for proposal in proposals.iter().filter(|p| {
+                p.field == name
+                    && match p.status {
+                        ProposalStatus::Accepted => true,
+                        ProposalStatus::Pending => auto_accept.contains(&p.source),
+                        ProposalStatus::Rejected | ProposalStatus::Overridden => false,
+                    }
+            }) {
+                self.push(field, proposal.value.clone());
+            }
+            for assertion in assertions
+                .iter()
+                .filter(|a| a.source == Source::User && a.field == name)
+            {
+                if let Some(value) = assertion.value.as_str() {
+                    self.push(field, value);
+                }
+            }
+        }
+    }
+
+    /// Append a value to a field, ignoring blanks and duplicates.
+    pub fn push(&mut self, field: ExportField, value: impl Into<String>) {
+        let value = value.into();
+        if value.trim().is_empty() {
+            return;
+        }
+        let values = self.fields.entry(field).or_default();
+        if !values.contains(&value) {
+            values.push(value);
+        }
+    }
+
+    fn push_opt(&mut self, field: ExportField, value: Option<String>) {
+        if let Some(value) = value {
+            self.push(field, value);
+        }
+    }
+
+    /// Values for a field (empty if none).
+    #[must_use]
+    pub fn get(&self, field: ExportField) -> &[String] {
+        self.fields.get(&field).map_or(&[], Vec::as_slice)
+    }
+
+    /// Fields with values, in tag order.
+    pub fn iter(&self) -> impl Iterator<Item = (ExportField, &[String])> {
+        self.fields.iter().map(|(f, v)| (*f, v.as_slice()))
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+/// Whether an FRBR record was identified from an auto-accepted source.
+///
+/// Works, recordings and releases are identified from `MusicBrainz`, and
+/// keep its ID; records without one were entered or merged locally and
+/// need review.
+fn is_auto_accepted(musicbrainz_id: Option<&str>, auto_accept: &[Source]) -> bool {
+    musicbrainz_id.is_some() && auto_accept.contains(&Source::MusicBrainz)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::path::PathBuf;
+    use tessitura_core::model::{Artist, AudioFormat, Expression, Manifestation, WorkCredit};
+    use tessitura_core::taxonomy::rules::ProposedTag;
+
+    #[test]
+    fn test_collect_projects_frbr_and_approved_values() {
+        let db = Database::open_in_memory().unwrap();
+
+        let performer = Artist::new("Takács Quartet");
+        db.insert_artist(&performer).unwrap();
+        let work = Work::new("String Quartet No. 4")
+            .with_composer("Béla Bartók")
+            .with_catalog_number("Sz. 91")
+            .with_key("C major")
+            .with_musicbrainz_id("work-mbid");
+        db.insert_work(&work).unwrap();
+        let expr = Expression::new(work.id)
+            .with_performer(performer.id)
+            .with_musicbrainz_id("recording-mbid");
+        db.insert_expression(&expr).unwrap();
+        let man = Manifestation::new("The String Quartets").with_musicbrainz_id("release-mbid");
+        db.insert_manifestation(&man).unwrap();
+
+        let mut item = Item::new(
+            PathBuf::from("/music/04.flac"),
+            AudioFormat::Flac,
+            1024,
+            Utc::now(),
+        );
+        item.expression_id = Some(expr.id);
+        item.manifestation_id = Some(man.id);
+        db.insert_item(&item).unwrap();
+
+        // Accepted in review
+        let accepted = Proposal::new(
+            item.id.to_string(),
+            ProposedTag {
+                field: "genre".to_string(),
+                value: "Chamber music".to_string(),
+                source: Source::MusicBrainz,
+                rule_name: "chamber".to_string(),
+                confidence: 0.9,
+                alternatives: Vec::new(),
+            },
+        );
+        let id = db.insert_proposal(&accepted).unwrap();
+        db.set_proposal_status(id, ProposalStatus::Accepted)
+            .unwrap();
+        // Still pending
+        db.insert_proposal(&Proposal::new(
+            item.id.to_string(),
+            ProposedTag {
+                field: "instrumentation".to_string(),
+                value: "String quartet".to_string(),
+                source: Source::Wikidata,
+                rule_name: "quartet".to_string(),
+                confidence: 0.7,
+                alternatives: Vec::new(),
+            },
+        ))
+        .unwrap();
+        // Custom value entered in review
+        db.insert_assertion(&Assertion::new(
+            item.id.to_string(),
+            "form",
+            serde_json::json!("String quartets"),
+            Source::User,
+        ))
+        .unwrap();
+        // Not approved: only a source assertion
+        db.insert_assertion(&Assertion::new(
+            item.id.to_string(),
+            "period",
+            serde_json::json!("20th Century"),
+            Source::Wikidata,
+        ))
+        .unwrap();
+
+        let values = ExportValues::collect(&db, &item, &[Source::MusicBrainz]).unwrap();
+        assert_eq!(values.get(ExportField::Work), ["String Quartet No. 4"]);
+        assert_eq!(values.get(ExportField::Composer), ["Béla Bartók"]);
+        assert_eq!(values.get(ExportField::CatalogNumber), ["Sz. 91"]);
+        assert_eq!(values.get(ExportField::Key), ["C major"]);
+        assert_eq!(values.get(ExportField::Performer), ["Takács Quartet"]);
+        assert_eq!(values.get(ExportField::MusicBrainzWorkId), ["work-mbid"]);
+        assert_eq!(
+            values.get(ExportField::MusicBrainzRecordingId),
+            ["recording-mbid"]
+        );
+        assert_eq!(
+            values.get(ExportField::MusicBrainzReleaseId),
+            ["release-mbid"]
+        );
+        assert_eq!(values.get(ExportField::Genre), ["Chamber music"]);
+        assert_eq!(values.get(ExportField::Form), ["String quartets"]);
+        assert!(values.get(ExportField::Instrumentation).is_empty());
+        assert!(values.get(ExportField::Period).is_empty());
+        assert!(values.get(ExportField::Conductor).is_empty());
+    }
+
+    #[test]
+    fn test_collect_rolls_movements_up_to_their_work() {
+        let db = Database::open_in_memory().unwrap();
+        let symphony = Work::new("Symphony No. 5 in C minor, Op. 67")
+            .with_musicbrainz_id("symphony-mbid")
+            .with_composer("Ludwig van Beethoven")
+            .with_catalog_number("Op. 67")
+            .with_key("C minor");
+        let movement = Work::new("Symphony No. 5 in C minor, Op. 67: II. Andante con moto")
+            .with_musicbrainz_id("movement-mbid")
+            .with_key("A-flat major")
+            .with_parent(symphony.id, Some(2))
+            .with_movement_label("Andante con moto");
+        db.insert_work(&symphony).unwrap();
+        db.insert_work(&movement).unwrap();
+        let expr = Expression::new(movement.id);
+        db.insert_expression(&expr).unwrap();
+        let mut item = Item::new(
+            PathBuf::from("/music/02.flac"),
+            AudioFormat::Flac,
+            1024,
+            Utc::now(),
+        );
+        item.expression_id = Some(expr.id);
+        db.insert_item(&item).unwrap();
+
+        let values = ExportValues::collect(&db, &item, &[Source::MusicBrainz]).unwrap();
+        assert_eq!(
+            values.get(ExportField::Work),
+            ["Symphony No. 5 in C minor, Op. 67"]
+        );
+        assert_eq!(values.get(ExportField::Movement), ["2"]);
+        assert_eq!(values.get(ExportField::MovementName), ["Andante con moto"]);
+        assert_eq!(values.get(ExportField::Composer), ["Ludwig van Beethoven"]);
+        assert_eq!(values.get(ExportField::CatalogNumber), ["Op. 67"]);
+        assert_eq!(values.get(ExportField::Key), ["A-flat major"]);
+        assert_eq!(
+            values.get(ExportField::MusicBrainzWorkId),
+            ["movement-mbid"]
+        );
+    }
+
+    #[test]
+    fn test_collect_credits() {
+        let db = Database::open_in_memory().unwrap();
+        let song = Work::new("Erlkönig, D. 328")
+            .with_composer("Franz Schubert")
+            .with_musicbrainz_id("song-mbid");
+        let movement = Work::new("Erlkönig: Wer reitet so spät")
+            .with_musicbrainz_id("part-mbid")
+            .with_parent(song.id, Some(1));
+        db.insert_work(&song).unwrap();
+        db.insert_work(&movement).unwrap();
+        let schubert = Artist::new("Franz Schubert");
+        let goethe = Artist::new("Johann Wolfgang von Goethe");
+        let berlioz = Artist::new("Hector Berlioz");
+        let liszt = Artist::new("Franz Liszt");
+        for artist in [&schubert, &goethe, &berlioz, &liszt] {
+            db.insert_artist(artist).unwrap();
+        }
+        for (artist, role) in [
+            (&schubert, WorkRole::Composer),
+            (&goethe, WorkRole::Lyricist),
+            (&berlioz, WorkRole::Orchestrator),
+        ] {
+            db.link_work_credit(&WorkCredit::new(song.id, artist.id, role))
+                .unwrap();
+        }
+        db.link_work_credit(&WorkCredit::new(movement.id, liszt.id, WorkRole::Arranger))
+            .unwrap();
+        let expr = Expression::new(movement.id);
+        db.insert_expression(&expr).unwrap();
+        let mut item = Item::new(
+            PathBuf::from("/music/01.flac"),
+            AudioFormat::Flac,
+            1024,
+            Utc::now(),
+        );
+        item.expression_id = Some(expr.id);
+        db.insert_item(&item).unwrap();
+
+        let values = ExportValues::collect(&db, &item, &[Source::MusicBrainz]).unwrap();
+        assert_eq!(values.get(ExportField::Composer), ["Franz Schubert"]);
+        assert_eq!(
+            values.get(ExportField::Arranger),
+            ["Franz Liszt", "Hector Berlioz"]
+        );
+        assert_eq!(
+            values.get(ExportField::Lyricist),
+            ["Johann Wolfgang von Goethe"]
+        );
+        assert!(values.get(ExportField::Librettist).is_empty());
+        assert!(values.get(ExportField::Writer).is_empty());
+    }
+
+    #[test]
+    fn test_collect_requires_review_unless_auto_accepted() {
+        let db = Database::open_in_memory().unwrap();
+        let work = Work::new("Magnificat")
+            .with_composer("Carl Philipp Emanuel Bach")
+            .with_key("D major")
+            .with_musicbrainz_id("work-mbid");
+        db.insert_work(&work).unwrap();
+        let expr = Expression::new(work.id).with_musicbrainz_id("recording-mbid");
+        db.insert_expression(&expr).unwrap();
+        let mut item = Item::new(
+            PathBuf::from("/music/01.flac"),
+            AudioFormat::Flac,
+            1024,
+            Utc::now(),
+        );
+        item.expression_id = Some(expr.id);
+        db.insert_item(&item).unwrap();
+
+        let proposal = |field: &str, value: &str, source: Source| {
+            Proposal::new(
+                item.id.to_string(),
+                ProposedTag {
+                    field: field.to_string(),
+                    value: value.to_string(),
+                    source,
+                    rule_name: "credits".to_string(),
+                    confidence: 0.8,
+                    alternatives: Vec::new(),
+                },
+            )
+        };
+        // Accepted in review
+        let id = db
+            .insert_proposal(&proposal("composer", "C. P. E. Bach", Source::MusicBrainz))
+            .unwrap();
+        db.set_proposal_status(id, ProposalStatus::Accepted)
+            .unwrap();
+        // Pending, from Discogs
+        db.insert_proposal(&proposal("performer", "RIAS Kammerchor", Source::Discogs))
+            .unwrap();
+        // Rejected, even though MusicBrainz is auto-accepted below
+        let id = db
+            .insert_proposal(&proposal("conductor", "Marcus Creed", Source::MusicBrainz))
+            .unwrap();
+        db.set_proposal_status(id, ProposalStatus::Rejected)
+            .unwrap();
+
+        // Nothing identified is written without review
+        let values = ExportValues::collect(&db, &item, &[]).unwrap();
+        assert_eq!(values.get(ExportField::Composer), ["C. P. E. Bach"]);
+        assert!(values.get(ExportField::Work).is_empty());
+        assert!(values.get(ExportField::Key).is_empty());
+        assert!(values.get(ExportField::Performer).is_empty());
+        assert!(values.get(ExportField::MusicBrainzRecordingId).is_empty());
+
+        // Auto-accepted sources skip review; approved values still win
+        let values =
+            ExportValues::collect(&db, &item, &[Source::MusicBrainz, Source::Discogs]).unwrap();
+        assert_eq!(values.get(ExportField::Work), ["Magnificat"]);
+        assert_eq!(values.get(ExportField::Composer), ["C. P. E. Bach"]);
+        assert_eq!(values.get(ExportField::Key), ["D major"]);
+        assert_eq!(values.get(ExportField::Performer), ["RIAS Kammerchor"]);
+        assert!(values.get(ExportField::Conductor).is_empty());
+        assert_eq!(
+            values.get(ExportField::MusicBrainzRecordingId),
+            ["recording-mbid"]
+        );
+
+        // Locally entered works are never auto-accepted
+        let local = Work::new("Magnificat (arr.)");
+        db.insert_work(&local).unwrap();
+        let local_expr = Expression::new(local.id);
+        db.insert_expression(&local_expr).unwrap();
+        item.expression_id = Some(local_expr.id);
+        let values = ExportValues::collect(&db, &item, &[Source::MusicBrainz]).unwrap();
+        assert!(values.get(ExportField::Work).is_empty());
+    }
+
+    #[test]
+    fn test_unidentified_item_has_no_values() {
+        let db = Database::open_in_memory().unwrap();
+        let item = Item::new(
+            PathBuf::from("/music/unknown.mp3"),
+            AudioFormat::Mp3,
+            512,
+            Utc::now(),
+        );
+        db.insert_item(&item).unwrap();
+
+        assert!(ExportValues::collect(&db, &item, &[Source::MusicBrainz])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_push_skips_blanks_and_duplicates() {
+        let mut values = ExportValues::new();
+        values.push(ExportField::Performer, "Martha Argerich");
+        values.push(ExportField::Performer, "Martha Argerich");
+        values.push(ExportField::Performer, "  ");
+        assert_eq!(values.get(ExportField::Performer), ["Martha Argerich"]);
+    }
+}
@@ -1,25 +1,30 @@
 //! Harmonize stage: apply mapping rules to enrichment assertions.
 //!
 //! Resolves conflicts between multiple sources using source priority,
-//! flags ambiguities, and returns `StageOutcome::NeedsReview` so the
-//! pipeline pauses for human approval.
+//! flags ambiguities, persists the resulting proposals, and returns
+//! `StageOutcome::NeedsReview` so the pipeline pauses for human approval.
 
+use std::collections::HashSet;
 use std::path::PathBuf;
 
-use treadle::{Stage, StageContext, StageOutcome};
+use treadle::{Stage, StageContext, StageOutcome, StageStatus, StateStore};
 
-use tessitura_core::schema::Database;
-use tessitura_core::taxonomy::rules::MappingRules;
+use tessitura_core::provenance::{Assertion, Proposal, ProposalStatus};
+use tessitura_core::schema::{Database, DatabasePool};
+use tessitura_core::taxonomy::rules::{MappingRules, ProposedTag};
 
 /// The Harmonize stage: apply mapping rules and resolve conflicts.
 ///
 /// Takes enrichment assertions from the database, applies genre/period/
 /// instrument rules, resolves conflicts using source priority, and
-/// stores proposed tags in the stage context metadata for review.
+/// stores the proposals in the `proposals` table for review, along with
+/// the performer credits enrichment found. They are also kept in the stage
+/// context metadata under `proposed_tags`, along with the assertions no
+/// rule matched under `unmatched_assertions`.
 #[derive(Debug)]
 pub struct HarmonizeStage {
     rules: MappingRules,
-    db_path: PathBuf,
+    pool: DatabasePool,
 }
 
 impl HarmonizeStage {
@@ -34,13 +39,16 @@ impl HarmonizeStage {
                 rules_path.display()
             )
         })?;
-        Ok(Self { rules, db_path })
+        Ok(Self::with_rules(rules, db_path))
     }
 
     /// Create a `HarmonizeStage` with pre-loaded rules (for testing).
     #[must_use]
     pub fn with_rules(rules: MappingRules, db_path: PathBuf) -> Self {
-        Self { rules, db_path }
+        Self {
+            rules,
+            pool: DatabasePool::new(db_path),
+        }
     }
 }
 
@@ -55,14 +63,29 @@ impl Stage for HarmonizeStage {
         item: &dyn treadle::WorkItem,
         ctx: &mut StageContext,
     ) -> treadle::Result<StageOutcome> {
-        let db = Database::open(&self.db_path).map_err(|e| {
-            treadle::TreadleError::StageExecution(format!("Failed to open database: {e}"))
-        })?;
+        self.pool
+            .with(|db| Ok::<_, tessitura_core::Error>(self.harmonize(db, item, ctx)))
+            .map_err(|e| {
+                treadle::TreadleError::StageExecution(format!("Failed to open database: {e}"))
+            })?
+    }
+}
 
-        // 1. Load all assertions for this item
-        let assertions = db.get_assertions_for_entity(item.id()).map_err(|e| {
+impl HarmonizeStage {
+    fn harmonize(
+        &self,
+        db: &Database,
+        item: &dyn treadle::WorkItem,
+        ctx: &mut StageContext,
+    ) -> treadle::Result<StageOutcome> {
+        // 1. Load all assertions for this item, labelling Wikidata entities
+        //    whose labels were cached after the assertion was recorded
+        let mut assertions = db.get_assertions_for_entity(item.id()).map_err(|e| {
             treadle::TreadleError::StageExecution(format!("Failed to get assertions: {e}"))
         })?;
+        crate::enrich::wikidata::label_from_cache(db, &mut assertions).map_err(|e| {
+            treadle::TreadleError::StageExecution(format!("Failed to read Wikidata labels: {e}"))
+        })?;
 
         if assertions.is_empty() {
             log::info!(
@@ -78,35 +101,33 @@ impl Stage for HarmonizeStage {
             item.id()
         );
 
-        // 2. Apply genre rules
-        let genre_proposals = self.rules.apply_genre_rules(&assertions);
+        // 2. Apply genre, period and instrument rules, and propose performer
+        //    credits and recording links as they are
+        let mut all_proposals = self.rules.harmonize(&assertions);
+        all_proposals.extend(performer_proposals(&assertions));
+        all_proposals.extend(crate::recordings::link_proposals(&assertions));
 
-        // 3. Apply period rules (extract composer and year from assertions)
-        let composer = assertions
-            .iter()
-            .find(|a| a.field == "composer")
-            .and_then(|a| a.value.as_str())
-            .map(String::from);
-        #[allow(clippy::cast_possible_truncation)]
-        let year = assertions
+        // 3. Persist proposals, replacing any still pending from a previous run
+        let records: Vec<Proposal> = all_proposals
             .iter()
-            .find(|a| a.field == "composed_year" || a.field == "year")
-            .and_then(|a| a.value.as_i64())
-            .map(|y| y as i32);
-
-        let period_proposal = self.rules.apply_period_rules(composer.as_deref(), year);
-
-        // 4. Apply instrument rules
-        let instrument_proposals = self.rules.apply_instrument_rules(&assertions);
-
-        // 5. Collect all proposals
-        let mut all_proposals = genre_proposals;
-        if let Some(period) = period_proposal {
-            all_proposals.push(period);
-        }
-        all_proposals.extend(instrument_proposals);
-
-        // 6. Store in context metadata for review
+            .cloned()
+            .map(|tag| Proposal::new(item.id(), tag))
+            .collect();
+        db.replace_pending_proposals(item.id(), &records)
+            .map_err(|e| {
+                treadle::TreadleError::StageExecution(format!("Failed to store proposals: {e}"))
+            })?;
+        let pending = db
+            .get_proposals_for_entity(item.id())
+            .map_err(|e| {
+                treadle::TreadleError::StageExecution(format!("Failed to get proposals: {e}"))
+            })?
+            .into_iter()
+            .filter(|p| p.status == ProposalStatus::Pending)
+            .count();
+
+        // 4. Store in context metadata for reporting, with the assertions the
+        //    rules do not cover
         let proposals_json = serde_json::to_value(&all_proposals).map_err(|e| {
             treadle::TreadleError::StageExecution(format!("Failed to serialize proposals: {e}"))
         })?;
@@ -114,31 +135,91 @@ impl Stage for HarmonizeStage {
         ctx.metadata
             .insert("proposed_tags".to_string(), proposals_json);
 
-        let has_conflicts = all_proposals.iter().any(|p| !p.alternatives.is_empty());
+        let unmatched = self.rules.unmatched(&assertions);
+        let unmatched_json = serde_json::to_value(&unmatched).map_err(|e| {
+            treadle::TreadleError::StageExecution(format!("Failed to serialize assertions: {e}"))
+        })?;
+        ctx.metadata
+            .insert("unmatched_assertions".to_string(), unmatched_json);
 
         log::info!(
-            "Harmonization complete for {}: {} proposals, {} with conflicts",
+            "Harmonization complete for {}: {} proposals, {} with conflicts, {} pending review",
             item.id(),
             all_proposals.len(),
             all_proposals
                 .iter()
                 .filter(|p| !p.alternatives.is_empty())
-                .count()
+                .count(),
+            pending
         );
 
-        if has_conflicts {
-            // Pause for human review when there are conflicts
-            Ok(StageOutcome::NeedsReview)
-        } else if all_proposals.is_empty() {
-            // Nothing to review
+        if pending == 0 {
+            // Nothing to review, or every proposal has already been decided
             Ok(StageOutcome::Complete)
         } else {
-            // Proposals without conflicts still need review
+            // Pause for human review, with or without conflicts
             Ok(StageOutcome::NeedsReview)
         }
     }
 }
 
+/// Proposals for the performers credited in `performer` assertions, one per
+/// name, so credits are reviewed before they reach the recording.
+fn performer_proposals(assertions: &[Assertion]) -> Vec<ProposedTag> {
+    let mut seen = HashSet::new();
+    assertions
+        .iter()
+        .filter(|a| a.field == "performer")
+        .filter_map(|a| {
+            let name = a.value.get("name")?.as_str()?;
+            seen.insert((a.source, name)).then(|| ProposedTag {
+                field: "performer".to_string(),
+                value: name.to_string(),
+                source: a.source,
+                rule_name: "performer_credit".to_string(),
+                confidence: a.confidence.unwrap_or(1.0),
+                alternatives: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/// Release the harmonize review gate for an item once none of its
+/// proposals are pending.
+///
+/// Marks a paused harmonize stage complete in the pipeline state store, so
+/// the workflow continues to export on its next advance. Returns whether
+/// the gate was released.
+///
+/// # Errors
+/// Returns an error if the proposals or the stage state cannot be read, or
+/// the new state cannot be saved.
+pub async fn release_review<S: StateStore>(
+    db: &Database,
+    store: &mut S,
+    item_id: &str,
+) -> treadle::Result<bool> {
+    let pending = db
+        .get_proposals_for_entity(item_id)
+        .map_err(|e| {
+            treadle::TreadleError::StageExecution(format!("Failed to get proposals: {e}"))
+        })?
+        .iter()
+        .any(|p| p.status == ProposalStatus::Pending);
+    if pending {
+        return Ok(false);
+    }
+
+    match store.get_stage_state(item_id, "harmonize").await? {
+        Some(mut state) if state.status == StageStatus::Paused => {
+            state.mark_complete();
+            store.save_stage_state(item_id, "harmonize", &state).await?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -153,18 +234,15 @@ mod tests {
         source_priority.insert("lastfm".to_string(), 2);
         source_priority.insert("wikidata".to_string(), 6);
 
+        let mut classical = GenreRule::default();
+        classical.name = "classical".to_string();
+        classical.match_any = vec!["classical".to_string()];
+        classical.output_genre = Some("Classical".to_string());
+        classical.confidence = 0.9;
+
         MappingRules {
             source_priority,
-            genre_rules: vec![GenreRule {
-                name: "classical".to_string(),
-                description: None,
-                match_any: vec!["classical".to_string()],
-                match_source: Vec::new(),
-                output_genre: Some("Classical".to_string()),
-                output_form: None,
-                output_lcgft_label: None,
-                confidence: 0.9,
-            }],
+            genre_rules: vec![classical],
             period_rules: vec![PeriodRule {
                 name: "romantic".to_string(),
                 description: None,
@@ -173,9 +251,37 @@ mod tests {
                 year_range: Some([1800, 1899]),
             }],
             instrument_rules: Vec::new(),
+            wikidata_mappings: HashMap::new(),
         }
     }
 
+    #[test]
+    fn test_performer_proposals() {
+        let credit = |name: &str, role: &str| {
+            Assertion::new(
+                "item-1",
+                "performer",
+                json!({ "name": name, "discogs_id": 1, "roles": [role] }),
+                Source::Discogs,
+            )
+            .with_confidence(0.85)
+        };
+        let assertions = vec![
+            credit("Martha Argerich", "piano"),
+            credit("Martha Argerich", "harpsichord"),
+            credit("Gidon Kremer", "violin"),
+            Assertion::new("item-1", "genre", json!("Classical"), Source::Discogs),
+        ];
+
+        let proposals = performer_proposals(&assertions);
+        let names: Vec<_> = proposals.iter().map(|p| p.value.as_str()).collect();
+        assert_eq!(names, ["Martha Argerich", "Gidon Kremer"]);
+        assert!(proposals
+            .iter()
+            .all(|p| p.field == "performer" && p.source == Source::Discogs));
+        assert!((proposals[0].confidence - 0.85).abs() < f64::EPSILON);
+    }
+
     #[test]
     fn test_harmonize_stage_with_rules() {
         let rules = sample_rules();
@@ -277,6 +383,10 @@ mod tests {
             .collect();
         assert!(fields.contains(&"genre"));
         assert!(fields.contains(&"period"));
+
+        // Both genre assertions matched a rule
+        let unmatched = ctx.metadata.get("unmatched_assertions").unwrap();
+        assert_eq!(unmatched.as_array().unwrap().len(), 0);
     }
 
     #[tokio::test]
@@ -324,4 +434,93 @@ mod tests {
         assert!(first.get("rule_name").is_some());
         assert!(first.get("confidence").is_some());
     }
+
+    #[tokio::test]
+    #[allow(clippy::items_after_statements)] // TestItem defined in test scope
+    async fn test_harmonize_persists_proposals() {
+        let rules = sample_rules();
+        let db_dir = tempfile::TempDir::new().unwrap();
+        let db_path = db_dir.path().join("test.db");
+        let db = Database::open(&db_path).unwrap();
+
+        let assertion = Assertion::new("entity-2", "genre", json!("classical"), Source::LastFm)
+            .with_confidence(0.8);
+        db.insert_assertion(&assertion).unwrap();
+
+        let stage = HarmonizeStage::with_rules(rules, db_path);
+
+        #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+        struct TestItem {
+            id: String,
+        }
+
+        impl treadle::WorkItem for TestItem {
+            fn id(&self) -> &str {
+                &self.id
+            }
+        }
+
+        let item = TestItem {
+            id: "entity-2".to_string(),
+        };
+
+        let mut ctx = StageContext::new("harmonize".to_string());
+        let outcome = stage.execute(&item, &mut ctx).await.unwrap();
+        assert_eq!(outcome, StageOutcome::NeedsReview);
+
+        let proposals = db.get_proposals_for_entity("entity-2").unwrap();
+        assert_eq!(proposals.len(), 1);
+        assert_eq!(proposals[0].field, "genre");
+        assert_eq!(proposals[0].value, "Classical");
+        assert_eq!(proposals[0].status, ProposalStatus::Pending);
+
+        // Once decided, re-running neither duplicates nor re-opens the review
+        db.set_proposal_status(proposals[0].id, ProposalStatus::Accepted)
+            .unwrap();
+        let mut ctx = StageContext::new("harmonize".to_string());
+        let outcome = stage.execute(&item, &mut ctx).await.unwrap();
+        assert_eq!(outcome, StageOutcome::Complete);
+        assert_eq!(db.get_proposals_for_entity("entity-2").unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_release_review_once_decided() {
+        let db = Database::open_in_memory().unwrap();
+        let mut store = treadle::MemoryStateStore::new();
+        let mut paused = treadle::StageState::new();
+        paused.mark_paused();
+        store
+            .save_stage_state("item-1", "harmonize", &paused)
+            .await
+            .unwrap();
+
+        let proposal = Proposal::new(
+            "item-1",
+            tessitura_core::taxonomy::rules::ProposedTag {
+                field: "genre".to_string(),
+                value: "Classical".to_string(),
+                source: Source::MusicBrainz,
+                rule_name: "classical".to_string(),
+                confidence: 0.9,
+                alternatives: Vec::new(),
+            },
+        );
+        let id = db.insert_proposal(&proposal).unwrap();
+
+        // Still pending: the gate stays closed
+        assert!(!release_review(&db, &mut store, "item-1").await.unwrap());
+
+        db.set_proposal_status(id, ProposalStatus::Rejected)
+            .unwrap();
+        assert!(release_review(&db, &mut store, "item-1").await.unwrap());
+        let state = store
+            .get_stage_state("item-1", "harmonize")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(state.status, StageStatus::Complete);
+
+        // Already released
+        assert!(!release_review(&db, &mut store, "item-1").await.unwrap());
+    }
 }
@@ -1,18 +1,23 @@
 use std::path::PathBuf;
-use tessitura_core::model::{Artist, ArtistRole, Expression, Manifestation, Work};
-use tessitura_core::schema::Database;
+use tessitura_core::model::{
+    Artist, ArtistId, ArtistRole, Expression, Manifestation, Performer, PerformerKind,
+    ReleaseTrack, Work, WorkCredit, WorkRole,
+};
+use tessitura_core::provenance::{Assertion, Source};
+use tessitura_core::schema::{Database, DatabasePool};
 use treadle::{Stage, StageContext, StageOutcome};
 
 use crate::acoustid::AcoustIdClient;
+use crate::enrich::lcgft::lcmpt_term_for_role;
 use crate::enrich::resilience::RateLimiter;
-use crate::musicbrainz::MusicBrainzClient;
+use crate::musicbrainz::{MbArtist, MbRecording, MbWork, MbWorkDetail, MusicBrainzClient};
 
 /// The Identify stage: match audio files to MusicBrainz recordings.
 #[derive(Debug)]
 pub struct IdentifyStage {
     acoustid: Option<AcoustIdClient>,
     musicbrainz: MusicBrainzClient,
-    db_path: PathBuf,
+    pool: DatabasePool,
     mb_rate_limiter: RateLimiter,
 }
 
@@ -36,7 +41,7 @@ impl IdentifyStage {
         Ok(Self {
             acoustid,
             musicbrainz,
-            db_path,
+            pool: DatabasePool::new(db_path),
             mb_rate_limiter: RateLimiter::new(1), // 1 req/sec for MusicBrainz
         })
     }
@@ -64,14 +69,176 @@ impl IdentifyStage {
         cleaned.trim().to_string()
     }
 
+    /// Find an artist by `MusicBrainz` ID, creating them with `role` if not
+    /// known yet.
+    fn find_or_create_artist(
+        db: &Database,
+        artist: &MbArtist,
+        role: ArtistRole,
+    ) -> Result<ArtistId, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(existing) = db.get_artist_by_musicbrainz_id(&artist.id)? {
+            return Ok(existing.id);
+        }
+        let created = Artist::new(&artist.name)
+            .with_musicbrainz_id(&artist.id)
+            .with_role(role);
+        db.insert_artist(&created)?;
+        Ok(created.id)
+    }
+
+    /// Credit the performers and conductor named in a recording's artist
+    /// relations, with their instruments or voices, linked to LCMPT terms
+    /// where the vocabulary has them.
+    fn credit_performers(
+        db: &Database,
+        expression: &mut Expression,
+        recording: &MbRecording,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        for relation in &recording.relations {
+            if let Some(conductor) = relation.conductor() {
+                if expression.conductor_id.is_none() {
+                    expression.conductor_id = Some(Self::find_or_create_artist(
+                        db,
+                        conductor,
+                        ArtistRole::Conductor,
+                    )?);
+                }
+                continue;
+            }
+            let Some(performance) = relation.performance() else {
+                continue;
+            };
+
+            let artist_role = match performance.kind {
+                Some(PerformerKind::Soloist) | None => ArtistRole::Performer,
+                Some(_) => ArtistRole::Ensemble,
+            };
+            let artist_id = Self::find_or_create_artist(db, performance.artist, artist_role)?;
+            let mut performer = Performer::new(artist_id);
+            for role in &performance.roles {
+                performer.add_role(role);
+                if performer.lcmpt_uri.is_none() {
+                    performer.lcmpt_uri = lcmpt_term_for_role(db, role)?.map(|t| t.uri);
+                }
+            }
+            performer.kind = performance.kind;
+            performer.credited_as = performance.credited_as.map(ToString::to_string);
+            expression.add_performer(performer);
+        }
+        Ok(())
+    }
+
+    /// Set the recording dates, venue and live/studio flag a recording's
+    /// relations give, where not known yet, returning the assertions that
+    /// record where they came from.
+    fn record_session(expression: &mut Expression, recording: &MbRecording) -> Vec<Assertion> {
+        let entity_id = expression.id.to_string();
+        let mut assertions = Vec::new();
+        if let Some((from, until)) = recording
+            .recording_dates()
+            .filter(|_| expression.recorded_from.is_none())
+        {
+            expression.set_recording_dates(from, until);
+            assertions.push(Assertion::new(
+                &entity_id,
+                "recording_date",
+                serde_json::json!({ "from": from, "until": until }),
+                Source::MusicBrainz,
+            ));
+        }
+        if let Some(venue) = recording.venue().filter(|_| expression.venue.is_none()) {
+            expression.venue = Some(venue.to_string());
+            assertions.push(Assertion::new(
+                &entity_id,
+                "venue",
+                serde_json::json!(venue),
+                Source::MusicBrainz,
+            ));
+        }
+        if let Some(live) = recording.live().filter(|_| expression.live.is_none()) {
+            expression.live = Some(live);
+            assertions.push(Assertion::new(
+                &entity_id,
+                "live",
+                serde_json::json!(live),
+                Source::MusicBrainz,
+            ));
+        }
+        assertions
+    }
+
+    /// Make `work` a part of the `MusicBrainz` work `parent`, at `position`,
+    /// creating the parent (with the movement's composer) if not known yet.
+    fn link_parent_work(
+        db: &Database,
+        work: &mut Work,
+        parent: &MbWork,
+        position: Option<u32>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let parent_id = if let Some(existing) = db.get_work_by_musicbrainz_id(&parent.id)? {
+            existing.id
+        } else {
+            let mut parent_work = Work::new(&parent.title).with_musicbrainz_id(&parent.id);
+            parent_work.composer.clone_from(&work.composer);
+            db.insert_work(&parent_work)?;
+            parent_work.id
+        };
+        work.parent_work_id = Some(parent_id);
+        work.part_number = position;
+        work.movement_label = movement_label(&parent.title, &work.title);
+        Ok(())
+    }
+
+    /// Fill in a work from its `MusicBrainz` details: credit its composers,
+    /// arrangers, lyricists, etc., and set its composer, key and parent
+    /// work where not known yet.
+    fn apply_work_detail(
+        db: &Database,
+        work: &mut Work,
+        detail: &MbWorkDetail,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        // The first composer is also the work's composer
+        let mut credits = Vec::new();
+        for relation in &detail.relations {
+            let (Some(role), Some(artist)) = (relation.work_role(), &relation.artist) else {
+                continue;
+            };
+            if role == WorkRole::Composer && work.composer.is_none() {
+                work.composer = Some(artist.name.clone());
+            }
+            let artist_role = if role == WorkRole::Composer {
+                ArtistRole::Composer
+            } else {
+                ArtistRole::Other
+            };
+            let artist_id = Self::find_or_create_artist(db, artist, artist_role)?;
+            credits.push(WorkCredit::new(work.id, artist_id, role));
+        }
+
+        // Extract key from attributes, in canonical form
+        if work.key.is_none() {
+            work.key = detail.key().map(|key| key.to_string());
+        }
+
+        // Link a movement to the work it is part of
+        if work.parent_work_id.is_none() {
+            if let Some((parent, position)) = detail.parent() {
+                Self::link_parent_work(db, work, parent, position)?;
+            }
+        }
+
+        db.upsert_work(work)?;
+        for credit in &credits {
+            db.link_work_credit(credit)?;
+        }
+        Ok(())
+    }
+
     /// Main identification orchestration: fingerprint matching → metadata fallback → FRBR entity creation.
     #[allow(clippy::too_many_lines)] // Main workflow orchestration
     async fn identify_items(&self) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
-        // Open database and get unidentified items (before any async)
-        let unidentified = {
-            let db = Database::open(&self.db_path)?;
-            db.list_unidentified_items()?
-        };
+        // Get unidentified items (before any async)
+        let unidentified = self.pool.with(Database::list_unidentified_items)?;
 
         log::info!("Found {} unidentified items", unidentified.len());
 
@@ -228,7 +395,6 @@ impl IdentifyStage {
     }
 
     /// Create FRBR entities (Work, Expression, Manifestation, Artist) from a MusicBrainz recording.
-    #[allow(clippy::too_many_lines)] // Complete FRBR entity creation workflow
     async fn create_frbr_entities(
         &self,
         item: &tessitura_core::model::Item,
@@ -239,26 +405,70 @@ impl IdentifyStage {
         self.mb_rate_limiter.acquire().await;
         let recording = self.musicbrainz.get_recording(recording_id).await?;
 
-        // Open database in a scoped block for thread safety
-        let db = Database::open(&self.db_path)?;
+        // Fetch detailed work info for composer, etc. A work identified
+        // before credits were recorded has none yet, so it is completed too
+        let work_mbid = recording
+            .relations
+            .iter()
+            .find(|r| r.relation_type == "performance")
+            .and_then(|r| r.work.as_ref())
+            .map(|work| work.id.clone());
+        let mut work_detail = None;
+        if let Some(work_mbid) = work_mbid {
+            if self
+                .pool
+                .with(|db| Self::needs_work_detail(db, &work_mbid))?
+            {
+                self.mb_rate_limiter.acquire().await;
+                work_detail = self.musicbrainz.get_work(&work_mbid).await.ok();
+            }
+        }
+
+        self.pool.with(|db| {
+            Self::record_frbr_entities(
+                db,
+                item,
+                recording_id,
+                &recording,
+                work_detail.as_ref(),
+                fingerprint_score,
+            )
+        })
+    }
 
-        // Step 1: Create/find artists
-        let mut artist_ids = Vec::new();
+    /// Whether a work needs its `MusicBrainz` details: it is not known yet,
+    /// or was identified before its credits were recorded.
+    fn needs_work_detail(db: &Database, mbid: &str) -> tessitura_core::Result<bool> {
+        Ok(match db.get_work_by_musicbrainz_id(mbid)? {
+            Some(work) => db.list_credits_for_work(&work.id)?.is_empty(),
+            None => true,
+        })
+    }
+
+    /// Record the FRBR entities for a recording identified for `item`, with
+    /// its work's details if they were fetched.
+    #[allow(clippy::too_many_lines)] // Complete FRBR entity creation workflow
+    fn record_frbr_entities(
+        db: &Database,
+        item: &tessitura_core::model::Item,
+        recording_id: &str,
+        recording: &MbRecording,
+        work_detail: Option<&MbWorkDetail>,
+        fingerprint_score: Option<f64>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        // Step 1: Create/find the credited artists
+        let mut performers = Vec::new();
         if let Some(artist_credit) = &recording.artist_credit {
             for credit in artist_credit {
-                // Check if artist already exists by MusicBrainz ID (FK fix!)
                 let artist_id =
-                    if let Some(existing) = db.get_artist_by_musicbrainz_id(&credit.artist.id)? {
-                        existing.id
-                    } else {
-                        // Create new artist
-                        let artist = Artist::new(&credit.artist.name)
-                            .with_musicbrainz_id(&credit.artist.id)
-                            .with_role(ArtistRole::Performer);
-                        db.insert_artist(&artist)?;
-                        artist.id
-                    };
-                artist_ids.push(artist_id);
+                    Self::find_or_create_artist(db, &credit.artist, ArtistRole::Performer)?;
+                let mut performer = Performer::new(artist_id);
+                performer.kind = credit.artist.performer_kind();
+                performer.credited_as = credit
+                    .name
+                    .clone()
+                    .filter(|name| *name != credit.artist.name);
+                performers.push(performer);
             }
         }
 
@@ -272,48 +482,19 @@ impl IdentifyStage {
                 // Check if work already exists
                 let existing_work = db.get_work_by_musicbrainz_id(&work_data.id)?;
 
-                if let Some(work) = existing_work {
-                    work.id
+                let mut work = if let Some(work) = existing_work {
+                    work
                 } else {
                     // Create new work
                     let work = Work::new(&work_data.title).with_musicbrainz_id(&work_data.id);
                     db.insert_work(&work)?;
+                    work
+                };
 
-                    // Fetch detailed work info for composer, etc.
-                    self.mb_rate_limiter.acquire().await;
-                    if let Ok(work_detail) = self.musicbrainz.get_work(&work_data.id).await {
-                        let mut updated_work = work;
-
-                        // Extract composer from relations
-                        if let Some(composer_rel) = work_detail
-                            .relations
-                            .iter()
-                            .find(|r| r.relation_type == "composer")
-                        {
-                            if let Some(ref artist) = composer_rel.artist {
-                                updated_work.composer = Some(artist.name.clone());
-
-                                // Create/update composer artist (FK fix!)
-                                if db.get_artist_by_musicbrainz_id(&artist.id)?.is_none() {
-                                    let composer_artist = Artist::new(&artist.name)
-                                        .with_musicbrainz_id(&artist.id)
-                                        .with_role(ArtistRole::Composer);
-                                    db.insert_artist(&composer_artist)?;
-                                }
-                            }
-                        }
-
-                        // Extract key from attributes
-                        if let Some(key) = work_detail.attributes.first() {
-                            updated_work.key = Some(key.clone());
-                        }
-
-                        db.upsert_work(&updated_work)?;
-                        updated_work.id
-                    } else {
-                        work.id
-                    }
+                if let Some(work_detail) = work_detail {
+                    Self::apply_work_detail(db, &mut work, work_detail)?;
                 }
+                work.id
             } else {
                 // No work relation, create a work from recording title
                 let work = Work::new(&recording.title);
@@ -327,10 +508,11 @@ impl IdentifyStage {
             work.id
         };
 
-        // Step 3: Create expression (recording)
+        // Step 3: Create expression (recording), or complete one identified
+        // before performers and sessions were recorded
         let existing_expression = db.get_expression_by_musicbrainz_id(recording_id)?;
-        let expression_id = if let Some(expr) = existing_expression {
-            expr.id
+        let (mut expression, before) = if let Some(expr) = existing_expression {
+            (expr.clone(), Some(expr))
         } else {
             let mut expression = Expression::new(work_id)
                 .with_title(&recording.title)
@@ -339,15 +521,27 @@ impl IdentifyStage {
             if let Some(duration) = item.duration_secs {
                 expression = expression.with_duration(duration);
             }
+            (expression, None)
+        };
 
-            // Add performers
-            for artist_id in &artist_ids {
-                expression = expression.with_performer(*artist_id);
+        // Add performers, with instruments and voices from the recording's
+        // artist relations
+        for performer in performers {
+            expression.add_performer(performer);
+        }
+        Self::credit_performers(db, &mut expression, recording)?;
+        let session = Self::record_session(&mut expression, &recording);
+
+        match before {
+            None => db.insert_expression(&expression)?,
+            Some(before) if before != expression => {
+                expression.updated_at = chrono::Utc::now();
+                db.upsert_expression(&expression)?;
             }
-
-            db.insert_expression(&expression)?;
-            expression.id
-        };
+            Some(_) => {}
+        }
+        db.insert_assertions(&session)?;
+        let expression_id = expression.id;
 
         // Step 4: Create manifestation (release) if available
         let manifestation_id = if let Some(releases) = &recording.releases {
@@ -369,7 +563,19 @@ impl IdentifyStage {
             None
         };
 
-        // Step 5: Link item to expression and manifestation, store fingerprint score
+        // Step 5: Record the recording's position on the release
+        if let Some(manifestation_id) = manifestation_id {
+            let mut track = ReleaseTrack::new(manifestation_id, expression_id);
+            if let Some(number) = item.tag_track_number {
+                track = track.with_track_number(number);
+            }
+            if let Some(disc) = item.tag_disc_number {
+                track = track.with_disc_number(disc);
+            }
+            db.link_release_track(&track)?;
+        }
+
+        // Step 6: Link item to expression and manifestation, store fingerprint score
         db.update_item_identification(
             &item.id,
             Some(expression_id),
@@ -406,6 +612,29 @@ impl Stage for IdentifyStage {
     }
 }
 
+/// The name of a movement alone, from its `MusicBrainz` title: the parent
+/// work's title and the movement number are dropped
+/// ("Symphony No. 5: II. Andante con moto" → "Andante con moto").
+fn movement_label(parent_title: &str, title: &str) -> Option<String> {
+    let rest = match title.strip_prefix(parent_title) {
+        Some(rest) => rest.trim_start_matches(|c: char| c == ':' || c.is_whitespace()),
+        None => title.split_once(": ")?.1,
+    };
+    let rest = match rest.split_once(". ") {
+        Some((number, name))
+            if !number.is_empty()
+                && number
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || "IVXLC".contains(c)) =>
+        {
+            name
+        }
+        _ => rest,
+    };
+    let rest = rest.trim();
+    (!rest.is_empty()).then(|| rest.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -462,4 +691,31 @@ mod tests {
             "Alive (Live)" // Keep live!
         );
     }
+
+    #[test]
+    fn test_movement_label() {
+        let parent = "Symphony No. 5 in C minor, Op. 67";
+        assert_eq!(
+            movement_label(
+                parent,
+                "Symphony No. 5 in C minor, Op. 67: II. Andante con moto"
+            )
+            .as_deref(),
+            Some("Andante con moto")
+        );
+        assert_eq!(
+            movement_label(
+                "Goldberg Variations",
+                "Goldberg-Variationen: 3. Canone all'Unisono"
+            )
+            .as_deref(),
+            Some("Canone all'Unisono")
+        );
+        assert_eq!(
+            movement_label(parent, "Symphony No. 5: Finale. Allegro").as_deref(),
+            Some("Finale. Allegro")
+        );
+        assert_eq!(movement_label(parent, "Andante con moto"), None);
+        assert_eq!(movement_label(parent, parent), None);
+    }
 }
@@ -9,19 +9,25 @@
 pub mod acoustid;
 pub mod audio;
 pub mod config;
+pub mod dedupe;
 pub mod enrich;
 pub mod error;
+pub mod export;
 pub mod harmonize;
 pub mod identify;
 pub mod musicbrainz;
 pub mod pipeline;
+pub mod recordings;
 pub mod scan;
+#[cfg(test)]
+mod test_support;
 pub mod work_item;
 
 pub use config::Config;
 pub use enrich::stage::EnrichStage;
-pub use error::{EnrichError, EnrichResult};
-pub use harmonize::HarmonizeStage;
+pub use error::{EnrichError, EnrichResult, ExportError, ExportResult};
+pub use export::ExportStage;
+pub use harmonize::{release_review, HarmonizeStage};
 pub use identify::IdentifyStage;
 pub use pipeline::{build_full_pipeline, build_pipeline};
 pub use scan::ScanStage;
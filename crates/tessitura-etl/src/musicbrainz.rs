@@ -1,6 +1,7 @@
 use reqwest::Client;
 use serde::Deserialize;
 use std::time::Duration;
+use tessitura_core::model::{MusicalKey, PerformerKind, WorkRole};
 
 // ---------------------------------------------------------------------------
 // Response types: Recording
@@ -18,9 +19,63 @@ pub struct MbRecording {
     pub relations: Vec<MbRelation>,
 }
 
+impl MbRecording {
+    /// The first and last day of the recording sessions, from the dates on
+    /// the "recorded at"/"recorded in", work and performer relations.
+    #[must_use]
+    pub fn recording_dates(&self) -> Option<(&str, &str)> {
+        let sessions = self
+            .relations
+            .iter()
+            .filter(|r| r.is_session())
+            .filter_map(|r| {
+                let begin = r.begin.as_deref()?;
+                Some((begin, r.end.as_deref().unwrap_or(begin)))
+            });
+        // Partial ISO dates ("1962", "1962-05") sort before the days they
+        // contain, so string order is date order.
+        sessions.reduce(|(from, until), (begin, end)| (from.min(begin), until.max(end)))
+    }
+
+    /// Where the recording was made: the place of a "recorded at" relation,
+    /// or failing that the area of a "recorded in" one.
+    #[must_use]
+    pub fn venue(&self) -> Option<&str> {
+        let named = |relation_type: &str| {
+            self.relations
+                .iter()
+                .filter(|r| r.relation_type == relation_type)
+                .find_map(|r| r.place.as_ref().or(r.area.as_ref()))
+                .map(|p| p.name.as_str())
+        };
+        named("recorded at").or_else(|| named("recorded in"))
+    }
+
+    /// Whether the recording is live: the "performance" relation to its
+    /// work is marked "live", or it was recorded at a studio (not live).
+    #[must_use]
+    pub fn live(&self) -> Option<bool> {
+        let performances = || {
+            self.relations
+                .iter()
+                .filter(|r| r.relation_type == "performance")
+        };
+        if performances().any(|r| r.attributes.iter().any(|a| a == "live")) {
+            return Some(true);
+        }
+        let in_studio = self.relations.iter().any(|r| {
+            r.relation_type == "recorded at"
+                && r.place.as_ref().and_then(|p| p.place_type.as_deref()) == Some("Studio")
+        });
+        in_studio.then_some(false)
+    }
+}
+
 /// An artist credit entry on a recording.
 #[derive(Debug, Deserialize)]
 pub struct MbArtistCredit {
+    /// The name the artist is credited under on the recording.
+    pub name: Option<String>,
     pub artist: MbArtist,
 }
 
@@ -29,6 +84,23 @@ pub struct MbArtistCredit {
 pub struct MbArtist {
     pub id: String,
     pub name: String,
+    /// "Person", "Group", "Orchestra", "Choir", etc.
+    #[serde(rename = "type")]
+    pub artist_type: Option<String>,
+}
+
+impl MbArtist {
+    /// The kind of performer the artist's type tells, if any.
+    #[must_use]
+    pub fn performer_kind(&self) -> Option<PerformerKind> {
+        match self.artist_type.as_deref()? {
+            "Person" => Some(PerformerKind::Soloist),
+            "Group" => Some(PerformerKind::Ensemble),
+            "Orchestra" => Some(PerformerKind::Orchestra),
+            "Choir" => Some(PerformerKind::Chorus),
+            _ => None,
+        }
+    }
 }
 
 /// A release (album) summary returned with a recording.
@@ -46,12 +118,121 @@ pub struct MbReleaseGroup {
     pub id: String,
 }
 
-/// A relation on a recording (e.g. "performance" linking to a work).
+/// A relation on a recording (e.g. "performance" linking to a work, or
+/// "instrument" linking to a performer).
 #[derive(Debug, Deserialize)]
 pub struct MbRelation {
     #[serde(rename = "type")]
     pub relation_type: String,
     pub work: Option<MbWork>,
+    pub artist: Option<MbArtist>,
+    /// Instruments, voices and modifiers such as "solo" or "guest".
+    #[serde(default)]
+    pub attributes: Vec<String>,
+    /// The name the artist is credited under, if not their own (empty
+    /// otherwise).
+    #[serde(rename = "target-credit")]
+    pub target_credit: Option<String>,
+    /// The place a "recorded at" relation names.
+    pub place: Option<MbPlace>,
+    /// The area (usually a city) a "recorded in" relation names.
+    pub area: Option<MbPlace>,
+    /// When the relation began and ended, as "YYYY", "YYYY-MM" or
+    /// "YYYY-MM-DD".
+    pub begin: Option<String>,
+    pub end: Option<String>,
+}
+
+/// A place or area named by a recording relation.
+#[derive(Debug, Deserialize)]
+pub struct MbPlace {
+    pub id: String,
+    pub name: String,
+    /// For places: "Studio", "Venue", "Religious building", etc.
+    #[serde(rename = "type")]
+    pub place_type: Option<String>,
+}
+
+/// Relation attributes that qualify a performance rather than name an
+/// instrument or voice.
+const PERFORMANCE_MODIFIERS: [&str; 4] = ["additional", "guest", "minor", "solo"];
+
+/// A performer credited by an artist relation on a recording.
+#[derive(Debug, Clone)]
+pub struct MbPerformance<'a> {
+    pub artist: &'a MbArtist,
+    /// Instruments or voices (e.g. "piano", "soprano").
+    pub roles: Vec<String>,
+    pub kind: Option<PerformerKind>,
+    pub credited_as: Option<&'a str>,
+}
+
+impl MbRelation {
+    /// Whether the relation's dates are those of the recording sessions.
+    fn is_session(&self) -> bool {
+        matches!(
+            self.relation_type.as_str(),
+            "recorded at" | "recorded in" | "performance" | "conductor"
+        ) || self.performance().is_some()
+    }
+
+    /// The performer an "instrument", "vocal", "performer" or "performing
+    /// orchestra" relation credits, with their instruments or voices.
+    #[must_use]
+    pub fn performance(&self) -> Option<MbPerformance<'_>> {
+        let artist = self.artist.as_ref()?;
+        let attributes = self
+            .attributes
+            .iter()
+            .map(String::as_str)
+            .filter(|a| !PERFORMANCE_MODIFIERS.contains(a));
+        let (roles, kind) = match self.relation_type.as_str() {
+            "instrument" => (
+                attributes.map(ToString::to_string).collect(),
+                artist.performer_kind().or(Some(PerformerKind::Soloist)),
+            ),
+            "vocal" => {
+                let voices: Vec<&str> = attributes.collect();
+                if voices.contains(&"choir vocals") {
+                    (Vec::new(), Some(PerformerKind::Chorus))
+                } else if voices.is_empty() {
+                    (vec!["voice".to_string()], artist.performer_kind())
+                } else {
+                    (
+                        voices.into_iter().map(voice_role).collect(),
+                        artist.performer_kind(),
+                    )
+                }
+            }
+            "performer" => (Vec::new(), artist.performer_kind()),
+            "performing orchestra" => (Vec::new(), Some(PerformerKind::Orchestra)),
+            _ => return None,
+        };
+        Some(MbPerformance {
+            artist,
+            roles,
+            kind,
+            credited_as: self.target_credit.as_deref().filter(|c| !c.is_empty()),
+        })
+    }
+
+    /// The artist a "conductor" relation names.
+    #[must_use]
+    pub fn conductor(&self) -> Option<&MbArtist> {
+        self.artist
+            .as_ref()
+            .filter(|_| self.relation_type == "conductor")
+    }
+}
+
+/// The voice a "vocal" relation attribute names: "soprano vocals" is a
+/// soprano, while lead, background and other vocals are just a voice.
+fn voice_role(attribute: &str) -> String {
+    match attribute.strip_suffix(" vocals") {
+        Some("lead" | "background" | "other") => "voice".to_string(),
+        Some(voice) => voice.to_string(),
+        None => attribute.to_string(),
+    }
 }
 
 /// A work summary returned in a recording relation.
@@ -76,16 +257,59 @@ pub struct MbWorkDetail {
     pub attributes: Vec<String>,
 }
 
-/// A relation on a work (e.g. "composer" linking to an artist).
+impl MbWorkDetail {
+    /// The work this one is a part of, with its position there if given:
+    /// the target of a backward "parts" relation.
+    #[must_use]
+    pub fn parent(&self) -> Option<(&MbWork, Option<u32>)> {
+        self.relations
+            .iter()
+            .filter(|r| r.relation_type == "parts" && r.direction.as_deref() == Some("backward"))
+            .find_map(|r| r.work.as_ref().map(|work| (work, r.ordering_key)))
+    }
+
+    /// The work's key: the first attribute naming one.
+    #[must_use]
+    pub fn key(&self) -> Option<MusicalKey> {
+        self.attributes.iter().find_map(|a| a.parse().ok())
+    }
+}
+
+/// A relation on a work (e.g. "composer" linking to an artist, or "parts"
+/// linking to a parent work or a movement).
 #[derive(Debug, Deserialize)]
 pub struct MbWorkRelation {
     #[serde(rename = "type")]
     pub relation_type: String,
+    /// "forward" or "backward": a backward "parts" relation points to the
+    /// work this one is a part of.
+    pub direction: Option<String>,
     pub artist: Option<MbArtist>,
+    pub work: Option<MbWork>,
+    #[serde(rename = "ordering-key")]
+    pub ordering_key: Option<u32>,
     #[serde(default)]
     pub attributes: Vec<String>,
 }
 
+impl MbWorkRelation {
+    /// The credit an artist relation gives on the work, if it is one
+    /// tracked in the catalog.
+    #[must_use]
+    pub fn work_role(&self) -> Option<WorkRole> {
+        self.artist.as_ref()?;
+        match self.relation_type.as_str() {
+            "composer" => Some(WorkRole::Composer),
+            "arranger" | "instrument arranger" | "vocal arranger" => Some(WorkRole::Arranger),
+            "orchestrator" => Some(WorkRole::Orchestrator),
+            "lyricist" => Some(WorkRole::Lyricist),
+            "librettist" => Some(WorkRole::Librettist),
+            "writer" => Some(WorkRole::Writer),
+            _ => None,
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Response types: Release detail
 // ---------------------------------------------------------------------------
@@ -160,7 +384,8 @@ impl MusicBrainzClient {
 
     /// Get recording details by MusicBrainz ID.
     ///
-    /// Includes artist credits, releases, and work relations.
+    /// Includes artist credits, releases, and artist (performer, conductor)
+    /// and work relations.
     ///
     /// Rate limit: 1 request/second (enforced by caller).
     ///
@@ -168,7 +393,7 @@ impl MusicBrainzClient {
     /// Returns an error if the API request fails or the response cannot be parsed.
     pub async fn get_recording(&self, mbid: &str) -> Result<MbRecording, reqwest::Error> {
         let url = format!(
-            "https://musicbrainz.org/ws/2/recording/{}?inc=releases+artists+work-rels&fmt=json",
+            "https://musicbrainz.org/ws/2/recording/{}?inc=releases+artists+artist-rels+work-rels+place-rels+area-rels&fmt=json",
             mbid
         );
 
@@ -178,8 +403,8 @@ impl MusicBrainzClient {
 
     /// Get work details by MusicBrainz ID.
     ///
-    /// Includes artist relations (composer, lyricist, etc.) and work
-    /// attributes (key, etc.).
+    /// Includes artist relations (composer, lyricist, etc.), work relations
+    /// (the parent work and movements) and work attributes (key, etc.).
     ///
     /// Rate limit: 1 request/second (enforced by caller).
     ///
@@ -187,7 +412,7 @@ impl MusicBrainzClient {
     /// Returns an error if the API request fails or the response cannot be parsed.
     pub async fn get_work(&self, mbid: &str) -> Result<MbWorkDetail, reqwest::Error> {
         let url = format!(
-            "https://musicbrainz.org/ws/2/work/{}?inc=artist-rels&fmt=json",
+            "https://musicbrainz.org/ws/2/work/{}?inc=artist-rels+work-rels&fmt=json",
             mbid
         );
 
@@ -228,19 +453,17 @@ impl MusicBrainzClient {
         title: &str,
         album: Option<&str>,
     ) -> Result<Vec<MbRecording>, reqwest::Error> {
-        use std::fmt::Write;
-
         #[derive(Deserialize)]
         struct SearchResult {
             recordings: Vec<MbRecording>,
         }
 
-        let mut query = format!("recording:\"{title}\" AND artist:\"{artist}\"");
-        if let Some(album) = album {
-            // write! to String never fails, but clippy requires handling the Result
-            write!(query, " AND release:\"{album}\"")
-                .expect("Writing to String cannot fail");
-        }
+        let query = match album {
+            Some(album) => format!(
+                "recording:\"{title}\" AND artist:\"{artist}\" AND release:\"{album}\""
+            ),
+            None => format!("recording:\"{title}\" AND artist:\"{artist}\""),
+        };
 
         let url = "https://musicbrainz.org/ws/2/recording/";
 
@@ -331,12 +554,143 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_mb_recording_performances() {
+        let json = r#"{
+            "id": "abc-123",
+            "title": "Piano Concerto No. 1",
+            "relations": [
+                {
+                    "type": "instrument",
+                    "artist": {"id": "a-1", "name": "Martha Argerich", "type": "Person"},
+                    "attributes": ["solo", "piano"],
+                    "target-credit": "M. Argerich"
+                },
+                {
+                    "type": "vocal",
+                    "artist": {"id": "a-2", "name": "Jessye Norman", "type": "Person"},
+                    "attributes": ["soprano vocals"],
+                    "target-credit": ""
+                },
+                {
+                    "type": "vocal",
+                    "artist": {"id": "a-3", "name": "Rundfunkchor Berlin", "type": "Choir"},
+                    "attributes": ["choir vocals"]
+                },
+                {
+                    "type": "performing orchestra",
+                    "artist": {"id": "a-4", "name": "Royal Philharmonic Orchestra"},
+                    "attributes": []
+                },
+                {
+                    "type": "conductor",
+                    "artist": {"id": "a-5", "name": "Charles Dutoit", "type": "Person"},
+                    "attributes": []
+                },
+                {
+                    "type": "performance",
+                    "work": {"id": "work-1", "title": "Piano Concerto No. 1"}
+                }
+            ]
+        }"#;
+
+        let recording: MbRecording = serde_json::from_str(json).unwrap();
+        let performances: Vec<MbPerformance<'_>> = recording
+            .relations
+            .iter()
+            .filter_map(MbRelation::performance)
+            .collect();
+        assert_eq!(performances.len(), 4);
+        assert_eq!(performances[0].artist.name, "Martha Argerich");
+        assert_eq!(performances[0].roles, ["piano"]);
+        assert_eq!(performances[0].kind, Some(PerformerKind::Soloist));
+        assert_eq!(performances[0].credited_as, Some("M. Argerich"));
+        assert_eq!(performances[1].roles, ["soprano"]);
+        assert_eq!(performances[1].credited_as, None);
+        assert!(performances[2].roles.is_empty());
+        assert_eq!(performances[2].kind, Some(PerformerKind::Chorus));
+        assert_eq!(performances[3].kind, Some(PerformerKind::Orchestra));
+
+        let conductors: Vec<&str> = recording
+            .relations
+            .iter()
+            .filter_map(MbRelation::conductor)
+            .map(|a| a.name.as_str())
+            .collect();
+        assert_eq!(conductors, ["Charles Dutoit"]);
+        assert_eq!(voice_role("lead vocals"), "voice");
+    }
+
+    #[test]
+    fn test_mb_recording_sessions() {
+        let json = r#"{
+            "id": "abc-123",
+            "title": "Goldberg Variations",
+            "relations": [
+                {
+                    "type": "recorded at",
+                    "place": {"id": "p-1", "name": "CBS 30th Street Studio", "type": "Studio"},
+                    "begin": "1955-06-10",
+                    "end": "1955-06-14"
+                },
+                {
+                    "type": "instrument",
+                    "artist": {"id": "a-1", "name": "Glenn Gould", "type": "Person"},
+                    "attributes": ["piano"],
+                    "begin": "1955-06-16",
+                    "end": "1955-06-16"
+                },
+                {
+                    "type": "recorded in",
+                    "area": {"id": "ar-1", "name": "New York"},
+                    "begin": "1955-06"
+                },
+                {
+                    "type": "mix",
+                    "artist": {"id": "a-2", "name": "Engineer"},
+                    "begin": "1956-01-01"
+                }
+            ]
+        }"#;
+
+        let recording: MbRecording = serde_json::from_str(json).unwrap();
+        assert_eq!(recording.recording_dates(), Some(("1955-06", "1955-06-16")));
+        assert_eq!(recording.venue(), Some("CBS 30th Street Studio"));
+        assert_eq!(recording.live(), Some(false));
+
+        let json = r#"{
+            "id": "def-456",
+            "title": "Piano Sonata",
+            "relations": [
+                {
+                    "type": "performance",
+                    "work": {"id": "work-1", "title": "Piano Sonata"},
+                    "attributes": ["live"],
+                    "begin": "1965-05-09",
+                    "end": "1965-05-09"
+                },
+                {
+                    "type": "recorded in",
+                    "area": {"id": "ar-1", "name": "New York"}
+                }
+            ]
+        }"#;
+
+        let recording: MbRecording = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            recording.recording_dates(),
+            Some(("1965-05-09", "1965-05-09"))
+        );
+        assert_eq!(recording.venue(), Some("New York"));
+        assert_eq!(recording.live(), Some(true));
+    }
+
     #[test]
     fn test_mb_work_detail_deserialize() {
         let json = r#"{
             "id": "work-1",
             "title": "Symphony No. 5",
-            "attributes": ["C minor"],
+            "attributes": ["Op. 67", "c-moll"],
             "relations": [
                 {
                     "type": "composer",
@@ -352,13 +706,46 @@ mod tests {
         let work: MbWorkDetail = serde_json::from_str(json).unwrap();
         assert_eq!(work.id, "work-1");
         assert_eq!(work.title, "Symphony No. 5");
-        assert_eq!(work.attributes, vec!["C minor"]);
+        assert_eq!(work.attributes, vec!["Op. 67", "c-moll"]);
+        assert_eq!(
+            work.key().map(|k| k.to_string()).as_deref(),
+            Some("C minor")
+        );
         assert_eq!(work.relations.len(), 1);
         assert_eq!(work.relations[0].relation_type, "composer");
         assert_eq!(
             work.relations[0].artist.as_ref().unwrap().name,
             "Ludwig van Beethoven"
         );
+        assert_eq!(work.relations[0].work_role(), Some(WorkRole::Composer));
+    }
+
+    #[test]
+    fn test_mb_work_detail_parent() {
+        let json = r#"{
+            "id": "work-2",
+            "title": "Symphony No. 5 in C minor, Op. 67: II. Andante con moto",
+            "relations": [
+                {
+                    "type": "parts",
+                    "direction": "forward",
+                    "work": {"id": "theme", "title": "Theme"},
+                    "ordering-key": 1
+                },
+                {
+                    "type": "parts",
+                    "direction": "backward",
+                    "work": {"id": "work-1", "title": "Symphony No. 5 in C minor, Op. 67"},
+                    "ordering-key": 2
+                }
+            ]
+        }"#;
+
+        let work: MbWorkDetail = serde_json::from_str(json).unwrap();
+        let (parent, position) = work.parent().unwrap();
+        assert_eq!(parent.id, "work-1");
+        assert_eq!(position, Some(2));
+        assert!(work.relations.iter().all(|r| r.work_role().is_none()));
     }
 
     #[test]
@@ -2,7 +2,7 @@ use std::path::PathBuf;
 use treadle::Workflow;
 
 use crate::config::Config;
-use crate::{EnrichStage, HarmonizeStage, IdentifyStage, ScanStage};
+use crate::{EnrichStage, ExportStage, HarmonizeStage, IdentifyStage, ScanStage};
 
 /// Build the scan + identify pipeline.
 ///
@@ -25,7 +25,10 @@ pub fn build_pipeline(
         .build()
 }
 
-/// Build the full 4-stage pipeline: scan → identify → enrich → harmonize.
+/// Build the full pipeline: scan → identify → enrich → harmonize → export.
+///
+/// Harmonize pauses each item for review, so export only runs once its
+/// proposals have been approved.
 ///
 /// # Errors
 /// Returns an error if the workflow or any stage cannot be built.
@@ -40,17 +43,24 @@ pub fn build_full_pipeline(
             treadle::TreadleError::InvalidWorkflow(format!("Failed to create identify stage: {e}"))
         })?;
     let enrich_stage = EnrichStage::new(config, db_path.clone());
-    let harmonize_stage = HarmonizeStage::new(&config.rules_path, db_path).map_err(|e| {
-        treadle::TreadleError::InvalidWorkflow(format!("Failed to create harmonize stage: {e}"))
-    })?;
+    let harmonize_stage =
+        HarmonizeStage::new(&config.rules_path, db_path.clone()).map_err(|e| {
+            treadle::TreadleError::InvalidWorkflow(format!(
+                "Failed to create harmonize stage: {e}"
+            ))
+        })?;
+    let export_stage = ExportStage::new(db_path, config.export.mapping.clone())
+        .with_auto_accept(config.export.auto_accept.clone());
 
     Workflow::builder()
         .stage("scan", scan_stage)
         .stage("identify", identify_stage)
         .stage("enrich", enrich_stage)
         .stage("harmonize", harmonize_stage)
+        .stage("export", export_stage)
         .dependency("identify", "scan")
         .dependency("enrich", "identify")
         .dependency("harmonize", "enrich")
+        .dependency("export", "harmonize")
         .build()
 }
@@ -0,0 +1,402 @@
+//! Finding the same recording in several files.
+//!
+//! Compares the stored chromaprint fingerprints of items with each other,
+//! entirely offline, to find files holding the same take — the same
+//! performance on a box set and on a compilation, or a lossless and a lossy
+//! copy — so they can be linked to one `Expression`. Links are proposed
+//! for review rather than made directly.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use tessitura_core::model::{ExpressionId, Item, ItemId};
+use tessitura_core::provenance::{Assertion, Proposal, ProposalStatus, Source};
+use tessitura_core::schema::Database;
+use tessitura_core::taxonomy::rules::ProposedTag;
+
+use crate::audio::compare::MAX_OFFSET;
+use crate::audio::{compare_fingerprints, decode_fingerprint};
+
+/// Default lowest fingerprint similarity taken as the same recording.
+pub const DEFAULT_MIN_SIMILARITY: f64 = 0.8;
+
+/// Items whose fingerprints match: the same recording in several files.
+#[derive(Debug, Clone)]
+pub struct RecordingGroup {
+    /// The matching items, ordered by path.
+    pub items: Vec<Item>,
+
+    /// Lowest similarity among the matches joining the group.
+    pub similarity: f64,
+}
+
+impl RecordingGroup {
+    /// The expression to link the whole group to: the one most of its items
+    /// are already linked to, preferring the first by path on a tie. `None`
+    /// if no item has been identified.
+    #[must_use]
+    pub fn proposed_expression(&self) -> Option<ExpressionId> {
+        let mut counts: HashMap<ExpressionId, usize> = HashMap::new();
+        for id in self.items.iter().filter_map(|i| i.expression_id) {
+            *counts.entry(id).or_default() += 1;
+        }
+        let most = counts.values().copied().max()?;
+        self.items
+            .iter()
+            .filter_map(|i| i.expression_id)
+            .find(|id| counts[id] == most)
+    }
+
+    /// The items not yet linked to the proposed expression.
+    #[must_use]
+    pub fn unlinked(&self) -> Vec<&Item> {
+        let Some(expression) = self.proposed_expression() else {
+            return self.items.iter().collect();
+        };
+        self.items
+            .iter()
+            .filter(|i| i.expression_id != Some(expression))
+            .collect()
+    }
+
+    /// Record a link to the proposed expression for each unlinked item, as
+    /// an `expression` assertion from fingerprint matching and a pending
+    /// proposal to review. Links already recorded are skipped. Returns the
+    /// number of proposals added.
+    ///
+    /// # Errors
+    /// Returns an error if the database cannot be read or updated.
+    pub fn propose_links(&self, db: &Database) -> tessitura_core::Result<usize> {
+        let Some(expression) = self.proposed_expression() else {
+            return Ok(0);
+        };
+        let value = serde_json::json!(expression.to_string());
+
+        let tx = db.transaction()?;
+        let mut proposed = 0;
+        for item in self.unlinked() {
+            let entity_id = item.id.to_string();
+            let recorded = tx.get_assertions_for_entity(&entity_id)?.iter().any(|a| {
+                a.field == "expression" && a.source == Source::Fingerprint && a.value == value
+            });
+            let assertion =
+                Assertion::new(&entity_id, "expression", value.clone(), Source::Fingerprint)
+                    .with_confidence(self.similarity);
+            if !recorded {
+                tx.insert_assertion(&assertion)?;
+            }
+            for tag in link_proposals(std::slice::from_ref(&assertion)) {
+                if tx.insert_new_proposal(&Proposal::new(&entity_id, tag))? {
+                    proposed += 1;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(proposed)
+    }
+}
+
+/// Proposals for the recording links found by fingerprint matching, one per
+/// expression, so they are reviewed before the item is relinked.
+#[must_use]
+pub fn link_proposals(assertions: &[Assertion]) -> Vec<ProposedTag> {
+    let mut seen = HashSet::new();
+    assertions
+        .iter()
+        .filter(|a| a.field == "expression" && a.source == Source::Fingerprint)
+        .filter_map(|a| {
+            let expression = a.value.as_str()?;
+            seen.insert(expression).then(|| ProposedTag {
+                field: "expression".to_string(),
+                value: expression.to_string(),
+                source: a.source,
+                rule_name: "same_recording".to_string(),
+                confidence: a.confidence.unwrap_or(1.0),
+                alternatives: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/// Link item `entity_id` to the expression of its accepted recording link
+/// proposal, if any. Returns whether the item was relinked.
+///
+/// # Errors
+/// Returns an error if the database cannot be read or updated.
+pub fn link_approved_recording(db: &Database, entity_id: &str) -> tessitura_core::Result<bool> {
+    let Ok(item_id) = entity_id.parse::<ItemId>() else {
+        return Ok(false);
+    };
+    let Some(item) = db.get_item_by_id(&item_id)? else {
+        return Ok(false);
+    };
+    let approved = db
+        .get_proposals_for_entity(entity_id)?
+        .into_iter()
+        .filter(|p| {
+            p.field == "expression"
+                && p.source == Source::Fingerprint
+                && p.status == ProposalStatus::Accepted
+        })
+        .max_by_key(|p| p.decided_at)
+        .and_then(|p| p.value.parse::<ExpressionId>().ok());
+    let Some(expression) = approved.filter(|id| item.expression_id != Some(*id)) else {
+        return Ok(false);
+    };
+    if db.get_expression_by_id(&expression)?.is_none() {
+        return Ok(false);
+    }
+    db.update_item_identification(
+        &item.id,
+        Some(expression),
+        item.manifestation_id,
+        item.fingerprint_score,
+    )?;
+    Ok(true)
+}
+
+/// Group the items whose fingerprints are at least `min_similarity` alike.
+///
+/// Items without a fingerprint, or whose file is missing, are left out.
+/// Fingerprints are bucketed by length, that is by duration, and only those
+/// whose lengths differ by at most [`MAX_OFFSET`] frames are compared: no
+/// larger shift is tried when aligning them, so copies padded with more
+/// silence than that do not match. Matches are transitive: if A matches B
+/// and B matches C, all three form one group.
+#[must_use]
+pub fn find_same_recordings(items: &[Item], min_similarity: f64) -> Vec<RecordingGroup> {
+    let mut buckets: BTreeMap<usize, Vec<(usize, Vec<u32>)>> = BTreeMap::new();
+    let fingerprints = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| !item.is_missing())
+        .filter_map(|(idx, item)| {
+            let encoded = item.fingerprint.as_deref()?;
+            match decode_fingerprint(encoded) {
+                Ok(frames) => Some((idx, frames)),
+                Err(e) => {
+                    log::debug!("Skipping {}: {e:#}", item.file_path.display());
+                    None
+                }
+            }
+        });
+    for (idx, frames) in fingerprints {
+        buckets
+            .entry(frames.len() / MAX_OFFSET)
+            .or_default()
+            .push((idx, frames));
+    }
+
+    let mut groups = UnionFind::new(items.len());
+    let mut similarity: HashMap<usize, f64> = HashMap::new();
+    for (bucket, members) in &buckets {
+        // Lengths within MAX_OFFSET of each other fall in the same or the
+        // next bucket
+        let next = buckets.get(&(bucket + 1)).map_or(&[][..], Vec::as_slice);
+        for (pos, (a_idx, a)) in members.iter().enumerate() {
+            for (b_idx, b) in members[pos + 1..].iter().chain(next) {
+                if a.len().abs_diff(b.len()) > MAX_OFFSET {
+                    continue;
+                }
+                let Some(m) = compare_fingerprints(a, b) else {
+                    continue;
+                };
+                if m.similarity >= min_similarity {
+                    let roots = [groups.find(*a_idx), groups.find(*b_idx)];
+                    let lowest = roots
+                        .iter()
+                        .filter_map(|root| similarity.remove(root))
+                        .fold(m.similarity, f64::min);
+                    similarity.insert(groups.union(roots[0], roots[1]), lowest);
+                }
+            }
+        }
+    }
+
+    let mut members: HashMap<usize, Vec<Item>> = HashMap::new();
+    for (idx, item) in items.iter().enumerate() {
+        members
+            .entry(groups.find(idx))
+            .or_default()
+            .push(item.clone());
+    }
+
+    let mut result: Vec<RecordingGroup> = members
+        .into_iter()
+        .filter(|(_, items)| items.len() > 1)
+        .map(|(root, mut items)| {
+            items.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+            RecordingGroup {
+                items,
+                similarity: similarity.get(&root).copied().unwrap_or(1.0),
+            }
+        })
+        .collect();
+    result.sort_by(|a, b| a.items[0].file_path.cmp(&b.items[0].file_path));
+    result
+}
+
+/// Disjoint sets over indices.
+#[derive(Debug)]
+pub(crate) struct UnionFind {
+    parent: Vec<usize>,
+}
+
+impl UnionFind {
+    pub(crate) fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+        }
+    }
+
+    pub(crate) fn find(&mut self, idx: usize) -> usize {
+        let mut root = idx;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut node = idx;
+        while self.parent[node] != root {
+            let next = self.parent[node];
+            self.parent[node] = root;
+            node = next;
+        }
+        root
+    }
+
+    /// Join the sets of `a` and `b`, returning the new root.
+    pub(crate) fn union(&mut self, a: usize, b: usize) -> usize {
+        let a_root = self.find(a);
+        let b_root = self.find(b);
+        self.parent[b_root] = a_root;
+        a_root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::fingerprint::encode_fingerprint;
+    use crate::test_support::fingerprint as frames;
+    use chrono::Utc;
+    use std::path::PathBuf;
+    use tessitura_core::model::{AudioFormat, Expression, Work};
+
+    fn item(path: &str, frames: &[u32]) -> Item {
+        let mut item = Item::new(PathBuf::from(path), AudioFormat::Flac, 1024, Utc::now());
+        item.fingerprint = Some(encode_fingerprint(frames).unwrap());
+        item
+    }
+
+    #[test]
+    fn test_find_same_recordings_groups_matching_fingerprints() {
+        let take = frames(1, 400);
+        // The same take on a compilation: two seconds later, slightly different
+        let mut compilation = vec![0; 16];
+        compilation.extend(take.iter().map(|v| v ^ 0b101));
+
+        let mut box_set = item("/music/box/cd3/04.flac", &take);
+        let expression = ExpressionId::new();
+        box_set.expression_id = Some(expression);
+        let items = vec![
+            item("/music/compilation/07.flac", &compilation),
+            box_set,
+            item("/music/other/01.flac", &frames(2, 400)),
+            Item::new(
+                PathBuf::from("/music/unfingerprinted.flac"),
+                AudioFormat::Flac,
+                0,
+                Utc::now(),
+            ),
+        ];
+
+        let groups = find_same_recordings(&items, DEFAULT_MIN_SIMILARITY);
+        assert_eq!(groups.len(), 1);
+        let group = &groups[0];
+        assert_eq!(
+            group
+                .items
+                .iter()
+                .map(|i| i.file_path.to_str().unwrap())
+                .collect::<Vec<_>>(),
+            ["/music/box/cd3/04.flac", "/music/compilation/07.flac"]
+        );
+        assert!(group.similarity > 0.9 && group.similarity < 1.0);
+        assert_eq!(group.proposed_expression(), Some(expression));
+        let unlinked = group.unlinked();
+        assert_eq!(unlinked.len(), 1);
+        assert_eq!(
+            unlinked[0].file_path,
+            PathBuf::from("/music/compilation/07.flac")
+        );
+    }
+
+    #[test]
+    fn test_find_same_recordings_compares_similar_durations_only() {
+        // Lengths 239 and 240 fall in neighbouring buckets
+        let take = frames(3, 239);
+        let mut padded = vec![0];
+        padded.extend(&take);
+        // The same take followed by more than MAX_OFFSET frames of applause
+        let long = frames(4, 1000);
+        let mut applause = long.clone();
+        applause.extend(frames(5, MAX_OFFSET + 1));
+
+        let items = vec![
+            item("/music/a.flac", &take),
+            item("/music/b.flac", &padded),
+            item("/music/c.flac", &long),
+            item("/music/d.flac", &applause),
+        ];
+        let groups = find_same_recordings(&items, DEFAULT_MIN_SIMILARITY);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].items[0].file_path, PathBuf::from("/music/a.flac"));
+        assert_eq!(groups[0].items[1].file_path, PathBuf::from("/music/b.flac"));
+    }
+
+    #[test]
+    fn test_links_are_proposed_and_applied_once_accepted() {
+        let db = Database::open_in_memory().unwrap();
+        let work = Work::new("Vocalise");
+        db.insert_work(&work).unwrap();
+        let expression = Expression::new(work.id);
+        db.insert_expression(&expression).unwrap();
+
+        let take = frames(6, 400);
+        let mut linked = item("/music/box/01.flac", &take);
+        linked.expression_id = Some(expression.id);
+        let unlinked = item("/music/compilation/01.flac", &take);
+        db.insert_item(&linked).unwrap();
+        db.insert_item(&unlinked).unwrap();
+
+        let groups = find_same_recordings(&[linked, unlinked.clone()], DEFAULT_MIN_SIMILARITY);
+        assert_eq!(groups[0].propose_links(&db).unwrap(), 1);
+        // Proposing again records nothing new
+        assert_eq!(groups[0].propose_links(&db).unwrap(), 0);
+
+        let entity_id = unlinked.id.to_string();
+        assert_eq!(db.get_assertions_for_entity(&entity_id).unwrap().len(), 1);
+        let proposals = db.get_proposals_for_entity(&entity_id).unwrap();
+        assert_eq!(proposals.len(), 1);
+        assert_eq!(proposals[0].value, expression.id.to_string());
+
+        // Nothing is linked until the proposal is accepted
+        assert!(!link_approved_recording(&db, &entity_id).unwrap());
+        assert!(db
+            .get_item_by_id(&unlinked.id)
+            .unwrap()
+            .unwrap()
+            .expression_id
+            .is_none());
+
+        db.set_proposal_status(proposals[0].id, ProposalStatus::Accepted)
+            .unwrap();
+        assert!(link_approved_recording(&db, &entity_id).unwrap());
+        assert_eq!(
+            db.get_item_by_id(&unlinked.id)
+                .unwrap()
+                .unwrap()
+                .expression_id,
+            Some(expression.id)
+        );
+        assert!(!link_approved_recording(&db, &entity_id).unwrap());
+    }
+}
@@ -1,12 +1,19 @@
+use chrono::{DateTime, Utc};
 use lofty::file::{AudioFile, TaggedFileExt};
 use lofty::tag::Accessor;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
 use tessitura_core::model::{AudioFormat, Item};
 use tessitura_core::schema::Database;
 use treadle::{Stage, StageContext, StageOutcome};
 use walkdir::WalkDir;
 
-use crate::audio::generate_fingerprint;
+use crate::audio::{audio_hash, file_hash, generate_fingerprint};
 
 /// Tags extracted from an audio file.
 #[derive(Debug, Default)]
@@ -22,17 +29,200 @@ struct TagData {
     duration_secs: Option<f64>,
 }
 
+/// Counts of what a scan found, stored in the stage context metadata under
+/// `scan_summary`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanSummary {
+    /// Audio files found on disk.
+    pub scanned: usize,
+    /// Files seen for the first time.
+    pub added: usize,
+    /// Known files whose size or modification time changed.
+    pub updated: usize,
+    /// Files matched to an item whose old path is gone.
+    pub moved: usize,
+    /// Files found again after being marked missing.
+    pub restored: usize,
+    /// Items newly marked missing because their file is gone.
+    pub missing: usize,
+    /// Files that could not be read.
+    #[serde(default)]
+    pub failed: usize,
+}
+
+/// Number of scanned files written to the database per transaction, and
+/// recorded in the checkpoint at once.
+pub const BATCH_SIZE: usize = 100;
+
+/// Progress of a scan, sent to the channel given to
+/// [`ScanStage::with_progress`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanEvent {
+    /// The directory walk found `total` audio files, of which `resumed`
+    /// were scanned before the previous scan was interrupted.
+    Started { total: usize, resumed: usize },
+    /// One more file has been scanned.
+    Scanned {
+        path: PathBuf,
+        done: usize,
+        total: usize,
+    },
+    /// The scan is complete.
+    Finished(ScanSummary),
+}
+
+/// A file read by a worker, ready to be recorded.
+#[derive(Debug)]
+struct AnalyzedFile {
+    item: Item,
+    existing: Option<Item>,
+    needs_update: bool,
+    audio_changed: bool,
+}
+
+/// Progress of a scan, kept on disk so an interrupted scan can resume.
+///
+/// The file holds one JSON line per committed batch, with the running
+/// summary and the paths of the batch. A line cut short by a crash is
+/// ignored.
+#[derive(Debug, Default)]
+struct ScanCheckpoint {
+    path: PathBuf,
+    music_dir: PathBuf,
+    summary: ScanSummary,
+    done: HashSet<PathBuf>,
+}
+
+/// One line of the checkpoint file.
+#[derive(Debug, Serialize, Deserialize)]
+struct CheckpointBatch {
+    music_dir: PathBuf,
+    summary: ScanSummary,
+    paths: Vec<PathBuf>,
+}
+
+impl ScanCheckpoint {
+    /// Load the checkpoint of an interrupted scan of `music_dir`, or start
+    /// a new one. A checkpoint left by a scan of another directory is
+    /// discarded.
+    fn load(path: &Path, music_dir: &Path) -> Self {
+        let mut checkpoint = Self {
+            path: path.to_path_buf(),
+            music_dir: music_dir.to_path_buf(),
+            ..Self::default()
+        };
+        let Ok(contents) = std::fs::read_to_string(path) else {
+            return checkpoint;
+        };
+        for line in contents.lines() {
+            let Ok(batch) = serde_json::from_str::<CheckpointBatch>(line) else {
+                continue;
+            };
+            if batch.music_dir != music_dir {
+                log::info!(
+                    "Discarding checkpoint of a scan of {}",
+                    batch.music_dir.display()
+                );
+                return Self {
+                    path: path.to_path_buf(),
+                    music_dir: music_dir.to_path_buf(),
+                    ..Self::default()
+                };
+            }
+            checkpoint.summary = batch.summary;
+            checkpoint.done.extend(batch.paths);
+        }
+        checkpoint
+    }
+
+    /// Append a committed batch, draining `paths`.
+    fn save(
+        &mut self,
+        summary: &ScanSummary,
+        paths: &mut Vec<PathBuf>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let batch = CheckpointBatch {
+            music_dir: self.music_dir.clone(),
+            summary: *summary,
+            paths: std::mem::take(paths),
+        };
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&batch)?)?;
+        self.done.extend(batch.paths);
+        Ok(())
+    }
+
+    /// Remove the checkpoint of a finished scan.
+    fn remove(path: &Path) -> std::io::Result<()> {
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// What scanning one file did to its item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileOutcome {
+    Added,
+    Updated,
+    Unchanged,
+    Moved,
+    Restored,
+}
+
 /// The Scan stage: walk directory, extract tags, create Item records.
+///
+/// Items whose files are no longer found under the directory are marked
+/// missing, and files that were moved or renamed keep their item. Files are
+/// read on a pool of worker threads, one per CPU unless set with
+/// [`with_workers`](Self::with_workers).
 #[derive(Debug)]
 pub struct ScanStage {
     music_dir: PathBuf,
     db_path: PathBuf,
+    workers: usize,
+    progress: Option<mpsc::Sender<ScanEvent>>,
 }
 
 impl ScanStage {
     #[must_use]
     pub fn new(music_dir: PathBuf, db_path: PathBuf) -> Self {
-        Self { music_dir, db_path }
+        Self {
+            music_dir,
+            db_path,
+            workers: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            progress: None,
+        }
+    }
+
+    /// Set the number of worker threads reading files (at least one).
+    #[must_use]
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    /// Send progress events to `progress` while scanning.
+    #[must_use]
+    pub fn with_progress(mut self, progress: mpsc::Sender<ScanEvent>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// The checkpoint file of an interrupted scan, next to the database.
+    fn checkpoint_path(&self) -> PathBuf {
+        self.db_path.with_file_name("scan-checkpoint.jsonl")
+    }
+
+    fn emit(&self, event: ScanEvent) {
+        if let Some(progress) = &self.progress {
+            // A closed receiver only means nobody is watching
+            progress.send(event).ok();
+        }
     }
 
     fn is_audio_file(path: &Path) -> bool {
@@ -79,121 +269,391 @@ impl ScanStage {
         Ok(tag_data)
     }
 
-    fn scan_directory(&self, db: &Database) -> Result<usize, Box<dyn std::error::Error>> {
-        let mut count = 0;
+    /// Walk the music directory and record every audio file, returning
+    /// what changed.
+    ///
+    /// Tag extraction, hashing and fingerprinting run on the worker pool;
+    /// this thread owns the database and writes the results in batches of
+    /// [`BATCH_SIZE`], each in one transaction. After every batch the scanned
+    /// paths are appended to the checkpoint, so a scan interrupted part way
+    /// resumes with the files it had not reached. Known files whose size,
+    /// modification time, hashes and fingerprint are all up to date are not
+    /// sent to the workers at all.
+    fn scan_directory(&self, db: &Database) -> Result<ScanSummary, Box<dyn std::error::Error>> {
+        let mut checkpoint = ScanCheckpoint::load(&self.checkpoint_path(), &self.music_dir);
+        let mut summary = checkpoint.summary;
+        let resumed = checkpoint.done.len();
+        if resumed > 0 {
+            log::info!("Resuming interrupted scan ({resumed} files already scanned)");
+        }
 
-        for entry in WalkDir::new(&self.music_dir)
+        let paths: Vec<PathBuf> = WalkDir::new(&self.music_dir)
             .follow_links(false)
             .into_iter()
             .filter_map(Result::ok)
-        {
-            let path = entry.path();
-            if !path.is_file() || !Self::is_audio_file(path) {
+            .map(walkdir::DirEntry::into_path)
+            .filter(|path| path.is_file() && Self::is_audio_file(path))
+            .collect();
+        let total = paths.len();
+        self.emit(ScanEvent::Started { total, resumed });
+
+        let mut known: HashMap<PathBuf, Item> = db
+            .list_all_items()?
+            .into_iter()
+            .map(|item| (item.file_path.clone(), item))
+            .collect();
+        let mut jobs = Vec::new();
+        let mut up_to_date = 0;
+        for path in &paths {
+            if checkpoint.done.contains(path) {
+                continue;
+            }
+            let existing = known.remove(path);
+            if existing.as_ref().is_some_and(Self::is_up_to_date) {
+                up_to_date += 1;
+                let done = resumed + up_to_date;
+                self.emit(ScanEvent::Scanned {
+                    path: path.clone(),
+                    done,
+                    total,
+                });
                 continue;
             }
+            jobs.push((path.clone(), existing));
+        }
+        let done = resumed + up_to_date;
+
+        let (job_tx, job_rx) = mpsc::sync_channel::<(PathBuf, Option<Item>)>(self.workers * 2);
+        let job_rx = Arc::new(Mutex::new(job_rx));
+        let (result_tx, result_rx) = mpsc::sync_channel(self.workers * 2);
+
+        thread::scope(|scope| {
+            for _ in 0..self.workers {
+                let job_rx = Arc::clone(&job_rx);
+                let result_tx = result_tx.clone();
+                scope.spawn(move || loop {
+                    let Ok(receiver) = job_rx.lock() else {
+                        break;
+                    };
+                    let Ok((path, existing)) = receiver.recv() else {
+                        break;
+                    };
+                    drop(receiver);
+                    let result = Self::analyze_file(&path, existing);
+                    if result_tx.send((path, result)).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(job_rx);
+            drop(result_tx);
 
-            log::debug!("Scanning: {}", path.display());
+            scope.spawn(move || {
+                for job in jobs {
+                    if job_tx.send(job).is_err() {
+                        break;
+                    }
+                }
+            });
 
-            // Extract format and metadata
-            let format = path
-                .extension()
-                .map(|ext| AudioFormat::from_extension(&ext.to_string_lossy()))
-                .unwrap_or(AudioFormat::Other);
+            self.write_results(db, result_rx, &mut checkpoint, &mut summary, done, total)
+        })?;
 
-            let metadata = std::fs::metadata(path)?;
-            let file_size = metadata.len();
-            let file_mtime = metadata.modified()?.into();
+        // Up-to-date files are left out of the checkpoint, which is only
+        // needed for files that took work, and so counted last
+        summary.scanned += up_to_date;
+        summary.missing = self.mark_missing(db, &paths.into_iter().collect::<HashSet<_>>())?;
+        ScanCheckpoint::remove(&self.checkpoint_path())?;
+        self.emit(ScanEvent::Finished(summary));
+        Ok(summary)
+    }
 
-            // Extract tags
-            let tags = match Self::extract_tags(path) {
-                Ok(t) => t,
+    /// Record the analyzed files coming from the workers, committing and
+    /// checkpointing them in batches.
+    fn write_results(
+        &self,
+        db: &Database,
+        results: mpsc::Receiver<(PathBuf, Result<AnalyzedFile, String>)>,
+        checkpoint: &mut ScanCheckpoint,
+        summary: &mut ScanSummary,
+        mut done: usize,
+        total: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+        let mut tx = db.transaction()?;
+        for (path, result) in results {
+            match result {
+                Ok(analyzed) => match Self::record_file(&tx, analyzed)? {
+                    FileOutcome::Added => summary.added += 1,
+                    FileOutcome::Updated => summary.updated += 1,
+                    FileOutcome::Unchanged => {}
+                    FileOutcome::Moved => summary.moved += 1,
+                    FileOutcome::Restored => summary.restored += 1,
+                },
                 Err(e) => {
-                    log::warn!("Failed to extract tags from {}: {}", path.display(), e);
-                    TagData::default()
+                    log::warn!("Failed to scan {}: {e}", path.display());
+                    summary.failed += 1;
                 }
-            };
+            }
+            summary.scanned += 1;
+            done += 1;
+            self.emit(ScanEvent::Scanned {
+                path: path.clone(),
+                done,
+                total,
+            });
+            batch.push(path);
+
+            if batch.len() >= BATCH_SIZE {
+                tx.commit()?;
+                checkpoint.save(summary, &mut batch)?;
+                tx = db.transaction()?;
+            }
+        }
+        tx.commit()?;
+        checkpoint.save(summary, &mut batch)?;
+        Ok(())
+    }
 
-            // Create Item record
-            let mut item = Item::new(path.to_path_buf(), format, file_size, file_mtime);
-            item.tag_title = tags.title;
-            item.tag_artist = tags.artist;
-            item.tag_album = tags.album;
-            item.tag_album_artist = tags.album_artist;
-            item.tag_track_number = tags.track_number;
-            item.tag_disc_number = tags.disc_number;
-            item.tag_year = tags.year;
-            item.tag_genre = tags.genre;
-            item.duration_secs = tags.duration_secs;
-
-            // Check if this item already exists in the database
-            let existing_item = db.get_item_by_path(path)?;
-
-            // Determine if we need to update or insert
-            let needs_update = if let Some(ref existing) = existing_item {
-                // File has changed if mtime or size differ
-                existing.file_mtime != file_mtime || existing.file_size != file_size
-            } else {
-                false
-            };
+    /// Whether a known file needs no work: present, unchanged on disk (same
+    /// size and modification time), and already hashed. A file whose audio
+    /// could not be hashed or fingerprinted is not retried until it changes.
+    fn is_up_to_date(existing: &Item) -> bool {
+        let Ok(metadata) = std::fs::metadata(&existing.file_path) else {
+            return false;
+        };
+        let Ok(mtime) = metadata.modified() else {
+            return false;
+        };
+        !existing.is_missing()
+            && existing.file_size == metadata.len()
+            && existing.file_mtime == DateTime::<Utc>::from(mtime)
+            && existing.file_hash.is_some()
+    }
 
-            // Generate fingerprint if:
-            // - This is a new item, OR
-            // - This is an existing item that has changed (updated file), OR
-            // - This is an existing item without a fingerprint (was 0-byte, now has content)
-            let should_fingerprint = existing_item.is_none()
-                || needs_update
-                || (existing_item.is_some() && existing_item.as_ref().unwrap().fingerprint.is_none());
-
-            if should_fingerprint && file_size > 0 {
-                match generate_fingerprint(path) {
-                    Ok((fingerprint, duration)) => {
-                        log::debug!(
-                            "  Fingerprint: {} ({}s)",
-                            &fingerprint[..20.min(fingerprint.len())],
-                            duration
-                        );
-                        item.fingerprint = Some(fingerprint);
-                        // Update duration if we got a more accurate one from decoding
-                        if item.duration_secs.is_none() {
-                            item.duration_secs = Some(duration);
-                        }
-                    }
-                    Err(e) => {
-                        log::warn!(
-                            "  Failed to generate fingerprint for {}: {}",
-                            path.display(),
-                            e
-                        );
-                        item.fingerprint = None;
+    /// Read the tags, hashes and fingerprint of one file, without touching
+    /// the database. Runs on a worker thread.
+    fn analyze_file(path: &Path, existing: Option<Item>) -> Result<AnalyzedFile, String> {
+        // Extract format and metadata
+        let format = path
+            .extension()
+            .map(|ext| AudioFormat::from_extension(&ext.to_string_lossy()))
+            .unwrap_or(AudioFormat::Other);
+
+        let metadata = std::fs::metadata(path).map_err(|e| e.to_string())?;
+        let file_size = metadata.len();
+        let file_mtime = metadata.modified().map_err(|e| e.to_string())?.into();
+
+        // Extract tags
+        let tags = match Self::extract_tags(path) {
+            Ok(t) => t,
+            Err(e) => {
+                log::warn!("Failed to extract tags from {}: {}", path.display(), e);
+                TagData::default()
+            }
+        };
+
+        // Create Item record
+        let mut item = Item::new(path.to_path_buf(), format, file_size, file_mtime);
+        item.tag_title = tags.title;
+        item.tag_artist = tags.artist;
+        item.tag_album = tags.album;
+        item.tag_album_artist = tags.album_artist;
+        item.tag_track_number = tags.track_number;
+        item.tag_disc_number = tags.disc_number;
+        item.tag_year = tags.year;
+        item.tag_genre = tags.genre;
+        item.duration_secs = tags.duration_secs;
+
+        // Determine if we need to update or insert
+        let needs_update = if let Some(ref existing) = existing {
+            // File has changed if mtime or size differ
+            existing.file_mtime != file_mtime || existing.file_size != file_size
+        } else {
+            false
+        };
+
+        let audio_changed = Self::update_hashes(&mut item, existing.as_ref(), needs_update);
+
+        // Generate fingerprint if:
+        // - This is a new item, OR
+        // - This is an existing item whose audio has changed, OR
+        // - This is an existing item without a fingerprint (was 0-byte, now has content)
+        let should_fingerprint = existing.is_none()
+            || audio_changed
+            || existing
+                .as_ref()
+                .is_some_and(|existing| existing.fingerprint.is_none());
+
+        if should_fingerprint && file_size > 0 {
+            match generate_fingerprint(path) {
+                Ok((fingerprint, duration)) => {
+                    log::debug!(
+                        "  Fingerprint: {} ({}s)",
+                        &fingerprint[..20.min(fingerprint.len())],
+                        duration
+                    );
+                    item.fingerprint = Some(fingerprint);
+                    // Update duration if we got a more accurate one from decoding
+                    if item.duration_secs.is_none() {
+                        item.duration_secs = Some(duration);
                     }
                 }
-            } else if let Some(ref existing) = existing_item {
-                // Preserve existing fingerprint if we're not re-fingerprinting
-                item.fingerprint = existing.fingerprint.clone();
+                Err(e) => {
+                    log::warn!(
+                        "  Failed to generate fingerprint for {}: {}",
+                        path.display(),
+                        e
+                    );
+                    item.fingerprint = None;
+                }
             }
+        } else if let Some(ref existing) = existing {
+            // Preserve existing fingerprint if we're not re-fingerprinting
+            item.fingerprint.clone_from(&existing.fingerprint);
+        }
 
-            // Insert or update in database
-            if let Some(existing) = existing_item {
-                // Preserve the existing ID and timestamps
-                item.id = existing.id;
-                item.created_at = existing.created_at;
-
-                // Preserve identification data if not changed
-                if !needs_update {
-                    item.expression_id = existing.expression_id;
-                    item.manifestation_id = existing.manifestation_id;
-                    item.fingerprint_score = existing.fingerprint_score;
-                }
+        Ok(AnalyzedFile {
+            item,
+            existing,
+            needs_update,
+            audio_changed,
+        })
+    }
+
+    /// Insert or update the item of an analyzed file, returning what changed.
+    fn record_file(
+        db: &Database,
+        analyzed: AnalyzedFile,
+    ) -> Result<FileOutcome, Box<dyn std::error::Error>> {
+        let AnalyzedFile {
+            mut item,
+            existing,
+            needs_update,
+            audio_changed,
+        } = analyzed;
+
+        if let Some(existing) = existing {
+            // Preserve the existing ID and timestamps
+            item.id = existing.id;
+            item.created_at = existing.created_at;
+
+            // Preserve identification data if the audio is unchanged
+            if !audio_changed {
+                item.expression_id = existing.expression_id;
+                item.manifestation_id = existing.manifestation_id;
+                item.fingerprint_score = existing.fingerprint_score;
+            }
 
-                db.update_item(&item)?;
-                log::debug!("  Updated existing item");
+            db.update_item(&item)?;
+            log::debug!("  Updated existing item");
+            Ok(if existing.is_missing() {
+                FileOutcome::Restored
+            } else if needs_update {
+                FileOutcome::Updated
             } else {
-                db.insert_item(&item)?;
-                log::debug!("  Inserted new item");
+                FileOutcome::Unchanged
+            })
+        } else if let Some(previous) = Self::find_moved(db, &item)? {
+            // Keep the identity, identification and review decisions
+            log::info!(
+                "Moved: {} -> {}",
+                previous.file_path.display(),
+                item.file_path.display()
+            );
+            item.id = previous.id;
+            item.created_at = previous.created_at;
+            item.expression_id = previous.expression_id;
+            item.manifestation_id = previous.manifestation_id;
+            item.fingerprint_score = previous.fingerprint_score;
+            db.update_item(&item)?;
+            Ok(FileOutcome::Moved)
+        } else {
+            db.insert_item(&item)?;
+            log::debug!("  Inserted new item");
+            Ok(FileOutcome::Added)
+        }
+    }
+
+    /// Record the file and audio stream hashes of an item, returning whether
+    /// its audio changed since `existing` was recorded.
+    ///
+    /// New and changed files are hashed, as are known files not hashed yet;
+    /// unchanged files keep their recorded hashes. A changed file whose audio
+    /// hash still matches was only retagged. A file whose audio cannot be
+    /// hashed keeps `None`, so it is never matched as a duplicate or a move
+    /// by audio hash, and is not hashed again until it changes.
+    fn update_hashes(item: &mut Item, existing: Option<&Item>, changed: bool) -> bool {
+        let should_hash = existing.is_none_or(|existing| changed || existing.file_hash.is_none());
+        if !should_hash {
+            if let Some(existing) = existing {
+                item.file_hash.clone_from(&existing.file_hash);
+                item.audio_hash.clone_from(&existing.audio_hash);
             }
-            count += 1;
+            return false;
         }
 
+        match file_hash(&item.file_path) {
+            Ok(hash) => item.file_hash = Some(hash),
+            Err(e) => log::warn!("  Failed to hash {}: {e:#}", item.file_path.display()),
+        }
+        match audio_hash(&item.file_path) {
+            Ok(hash) => item.audio_hash = Some(hash),
+            Err(e) => log::debug!("  No audio hash for {}: {e:#}", item.file_path.display()),
+        }
+
+        changed
+            && existing.is_none_or(|existing| {
+                existing.audio_hash.is_none() || existing.audio_hash != item.audio_hash
+            })
+    }
+
+    /// Find the item a new file was moved or renamed from: one with the
+    /// same file hash, the same audio hash (moved and retagged), or else
+    /// the same fingerprint and size, whose own file is gone.
+    fn find_moved(db: &Database, item: &Item) -> Result<Option<Item>, Box<dyn std::error::Error>> {
+        let mut candidates = match &item.file_hash {
+            Some(hash) => db.get_items_by_file_hash(hash)?,
+            None => Vec::new(),
+        };
+        if let Some(hash) = &item.audio_hash {
+            candidates.extend(db.get_items_by_audio_hash(hash)?);
+        }
+        if let Some(fingerprint) = &item.fingerprint {
+            candidates.extend(
+                db.get_items_by_fingerprint(fingerprint)?
+                    .into_iter()
+                    .filter(|c| c.file_size == item.file_size),
+            );
+        }
+
+        Ok(candidates
+            .into_iter()
+            .find(|c| c.file_path != item.file_path && !c.file_path.exists()))
+    }
+
+    /// Mark items under the music directory whose files were not found.
+    ///
+    /// Returns the number of items newly marked missing.
+    fn mark_missing(
+        &self,
+        db: &Database,
+        seen: &HashSet<PathBuf>,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let now = Utc::now();
+        let mut count = 0;
+        for item in db.list_all_items()? {
+            if item.is_missing()
+                || !item.file_path.starts_with(&self.music_dir)
+                || seen.contains(&item.file_path)
+                || item.file_path.exists()
+            {
+                continue;
+            }
+            log::info!("Missing: {}", item.file_path.display());
+            db.set_item_missing(&item.id, Some(now))?;
+            count += 1;
+        }
         Ok(count)
     }
 }
@@ -207,7 +667,7 @@ impl Stage for ScanStage {
     async fn execute(
         &self,
         _item: &dyn treadle::WorkItem,
-        _context: &mut StageContext,
+        context: &mut StageContext,
     ) -> treadle::Result<StageOutcome> {
         log::info!("Starting scan of {}", self.music_dir.display());
 
@@ -216,8 +676,24 @@ impl Stage for ScanStage {
         })?;
 
         match self.scan_directory(&db) {
-            Ok(count) => {
-                log::info!("Scan complete: {} files processed", count);
+            Ok(summary) => {
+                log::info!(
+                    "Scan complete: {} files processed ({} added, {} updated, {} moved, \
+                     {} restored, {} missing, {} failed)",
+                    summary.scanned,
+                    summary.added,
+                    summary.updated,
+                    summary.moved,
+                    summary.restored,
+                    summary.missing,
+                    summary.failed
+                );
+                let summary = serde_json::to_value(summary).map_err(|e| {
+                    treadle::TreadleError::StageExecution(format!(
+                        "Failed to serialize scan summary: {e}"
+                    ))
+                })?;
+                context.metadata.insert("scan_summary".to_string(), summary);
                 Ok(StageOutcome::Complete)
             }
             Err(e) => Err(treadle::TreadleError::StageExecution(format!(
@@ -230,6 +706,7 @@ impl Stage for ScanStage {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{write_empty_flac, write_flac};
     use std::fs;
     use tempfile::TempDir;
 
@@ -253,7 +730,7 @@ mod tests {
 
         let result = stage.scan_directory(&db);
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), 0);
+        assert_eq!(result.unwrap().scanned, 0);
     }
 
     #[tokio::test]
@@ -271,6 +748,223 @@ mod tests {
 
         let result = stage.scan_directory(&db);
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), 0); // No audio files found
+        assert_eq!(result.unwrap().scanned, 0); // No audio files found
+    }
+
+    #[test]
+    fn test_scan_marks_missing_and_restored_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let music_dir = temp_dir.path().join("music");
+        fs::create_dir(&music_dir).unwrap();
+        let db = Database::open(temp_dir.path().join("test.db")).unwrap();
+        let stage = ScanStage::new(music_dir.clone(), temp_dir.path().join("test.db"));
+
+        let track = music_dir.join("01.flac");
+        write_empty_flac(&track);
+        let summary = stage.scan_directory(&db).unwrap();
+        assert_eq!((summary.scanned, summary.added), (1, 1));
+        let id = db.get_item_by_path(&track).unwrap().unwrap().id;
+
+        fs::rename(&track, temp_dir.path().join("01.flac")).unwrap();
+        let summary = stage.scan_directory(&db).unwrap();
+        assert_eq!((summary.scanned, summary.missing), (0, 1));
+        assert_eq!(db.list_missing_items().unwrap()[0].id, id);
+
+        // Already marked: not counted again
+        assert_eq!(stage.scan_directory(&db).unwrap().missing, 0);
+
+        fs::rename(temp_dir.path().join("01.flac"), &track).unwrap();
+        let summary = stage.scan_directory(&db).unwrap();
+        assert_eq!(summary.restored, 1);
+        let item = db.get_item_by_path(&track).unwrap().unwrap();
+        assert_eq!(item.id, id);
+        assert!(!item.is_missing());
+    }
+
+    #[test]
+    fn test_scan_follows_renamed_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let music_dir = temp_dir.path().join("music");
+        fs::create_dir(&music_dir).unwrap();
+        let db = Database::open(temp_dir.path().join("test.db")).unwrap();
+        let stage = ScanStage::new(music_dir.clone(), temp_dir.path().join("test.db"));
+
+        let track = music_dir.join("01.flac");
+        write_empty_flac(&track);
+        stage.scan_directory(&db).unwrap();
+        let item = db.get_item_by_path(&track).unwrap().unwrap();
+        assert!(item.file_hash.is_some());
+
+        let renamed = music_dir.join("01 - Allegro.flac");
+        fs::rename(&track, &renamed).unwrap();
+        let summary = stage.scan_directory(&db).unwrap();
+        assert_eq!((summary.moved, summary.added, summary.missing), (1, 0, 0));
+        let moved = db.get_item_by_path(&renamed).unwrap().unwrap();
+        assert_eq!(moved.id, item.id);
+        assert_eq!(db.list_all_items().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_scan_keeps_identification_of_retagged_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let music_dir = temp_dir.path().join("music");
+        fs::create_dir(&music_dir).unwrap();
+        let db = Database::open(temp_dir.path().join("test.db")).unwrap();
+        let stage = ScanStage::new(music_dir.clone(), temp_dir.path().join("test.db"));
+
+        let track = music_dir.join("01.flac");
+        write_flac(&track, &[0, 1000, -1000]);
+        stage.scan_directory(&db).unwrap();
+        let mut item = db.get_item_by_path(&track).unwrap().unwrap();
+        assert!(item.audio_hash.is_some());
+        let work = tessitura_core::model::Work::new("String Quartet No. 4");
+        db.insert_work(&work).unwrap();
+        let expression = tessitura_core::model::Expression::new(work.id);
+        db.insert_expression(&expression).unwrap();
+        item.expression_id = Some(expression.id);
+        db.update_item(&item).unwrap();
+
+        let mut values = crate::export::ExportValues::new();
+        values.push(crate::export::ExportField::Composer, "Béla Bartók");
+        crate::export::export_tags(
+            &track,
+            &values,
+            &crate::export::FieldMapping::default(),
+            false,
+        )
+        .unwrap();
+        let summary = stage.scan_directory(&db).unwrap();
+        assert_eq!(summary.updated, 1);
+        let retagged = db.get_item_by_path(&track).unwrap().unwrap();
+        assert_ne!(retagged.file_hash, item.file_hash);
+        assert_eq!(retagged.audio_hash, item.audio_hash);
+        assert_eq!(retagged.expression_id, Some(expression.id));
+
+        // New audio is a different recording
+        write_flac(&track, &[0, 2000, -2000]);
+        stage.scan_directory(&db).unwrap();
+        let reencoded = db.get_item_by_path(&track).unwrap().unwrap();
+        assert_ne!(reencoded.audio_hash, item.audio_hash);
+        assert!(reencoded.expression_id.is_none());
+    }
+
+    #[test]
+    fn test_file_without_audio_hash_is_up_to_date() {
+        let temp_dir = TempDir::new().unwrap();
+        let track = temp_dir.path().join("01.flac");
+        write_empty_flac(&track);
+        let metadata = fs::metadata(&track).unwrap();
+        let mut item = Item::new(
+            track,
+            AudioFormat::Flac,
+            metadata.len(),
+            metadata.modified().unwrap().into(),
+        );
+        assert!(!ScanStage::is_up_to_date(&item));
+
+        // An empty stream has no audio to hash; it is not retried every scan
+        item.file_hash = Some("hash".to_string());
+        assert!(ScanStage::is_up_to_date(&item));
+        item.file_size += 1;
+        assert!(!ScanStage::is_up_to_date(&item));
+    }
+
+    #[test]
+    fn test_scan_reports_progress_from_workers() {
+        let temp_dir = TempDir::new().unwrap();
+        let music_dir = temp_dir.path().join("music");
+        fs::create_dir(&music_dir).unwrap();
+        for n in 1..=3 {
+            write_empty_flac(&music_dir.join(format!("0{n}.flac")));
+        }
+        let db = Database::open(temp_dir.path().join("test.db")).unwrap();
+        let (tx, rx) = mpsc::channel();
+        let stage = ScanStage::new(music_dir, temp_dir.path().join("test.db"))
+            .with_workers(2)
+            .with_progress(tx);
+
+        let summary = stage.scan_directory(&db).unwrap();
+        assert_eq!((summary.scanned, summary.added), (3, 3));
+
+        let events: Vec<ScanEvent> = rx.try_iter().collect();
+        assert_eq!(
+            events.first(),
+            Some(&ScanEvent::Started {
+                total: 3,
+                resumed: 0
+            })
+        );
+        assert_eq!(events.last(), Some(&ScanEvent::Finished(summary)));
+        let done: Vec<usize> = events
+            .iter()
+            .filter_map(|e| match e {
+                ScanEvent::Scanned { done, .. } => Some(*done),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(done, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_scan_resumes_from_checkpoint() {
+        let temp_dir = TempDir::new().unwrap();
+        let music_dir = temp_dir.path().join("music");
+        fs::create_dir(&music_dir).unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db = Database::open(&db_path).unwrap();
+        let stage = ScanStage::new(music_dir.clone(), db_path);
+
+        // A scan that recorded the first file and was then interrupted
+        let first = music_dir.join("01.flac");
+        write_empty_flac(&first);
+        stage.scan_directory(&db).unwrap();
+        let mut checkpoint = ScanCheckpoint::load(&stage.checkpoint_path(), &music_dir);
+        let interrupted = ScanSummary {
+            scanned: 1,
+            added: 1,
+            ..ScanSummary::default()
+        };
+        checkpoint.save(&interrupted, &mut vec![first]).unwrap();
+
+        write_empty_flac(&music_dir.join("02.flac"));
+        let summary = stage.scan_directory(&db).unwrap();
+        assert_eq!((summary.scanned, summary.added), (2, 2));
+        assert!(!stage.checkpoint_path().exists());
+        assert_eq!(db.list_all_items().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_find_moved_matches_fingerprint_and_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::open_in_memory().unwrap();
+
+        let mut old = Item::new(
+            temp_dir.path().join("old/01.flac"),
+            AudioFormat::Flac,
+            1000,
+            Utc::now(),
+        );
+        old.fingerprint = Some("AQAA-fp".to_string());
+        db.insert_item(&old).unwrap();
+
+        let mut moved = Item::new(
+            temp_dir.path().join("new/01.flac"),
+            AudioFormat::Flac,
+            1000,
+            Utc::now(),
+        );
+        moved.fingerprint = Some("AQAA-fp".to_string());
+        let found = ScanStage::find_moved(&db, &moved).unwrap().unwrap();
+        assert_eq!(found.id, old.id);
+
+        // A different size is a different file
+        moved.file_size = 2000;
+        assert!(ScanStage::find_moved(&db, &moved).unwrap().is_none());
+
+        // A copy whose original is still on disk is not a move
+        moved.file_size = 1000;
+        fs::create_dir_all(temp_dir.path().join("old")).unwrap();
+        fs::write(&old.file_path, b"still here").unwrap();
+        assert!(ScanStage::find_moved(&db, &moved).unwrap().is_none());
     }
 }
@@ -0,0 +1,75 @@
+//! Fixtures shared by the unit tests of several stages.
+
+use std::path::Path;
+
+/// A minimal FLAC stream: the marker, a STREAMINFO block describing an
+/// empty 44.1 kHz stereo stream, and a final PADDING block.
+pub fn write_empty_flac(path: &Path) {
+    write_flac(path, &[]);
+}
+
+/// A 44.1 kHz 16-bit stereo FLAC stream with one frame of 4096 samples at
+/// each of `levels`, stored as constant subframes, after a final PADDING
+/// block.
+pub fn write_flac(path: &Path, levels: &[i16]) {
+    let total = u32::try_from(levels.len() * 4096).unwrap();
+    let mut bytes = b"fLaC".to_vec();
+    bytes.extend_from_slice(&[0x00, 0x00, 0x00, 0x22]);
+    bytes.extend_from_slice(&[0x10, 0x00, 0x10, 0x00]); // block sizes
+    bytes.extend_from_slice(&[0x00; 6]); // frame sizes
+    bytes.extend_from_slice(&[0x0A, 0xC4, 0x42, 0xF0]); // rate, channels, bps
+    bytes.extend_from_slice(&total.to_be_bytes()); // total samples
+    bytes.extend_from_slice(&[0x00; 16]); // MD5
+    bytes.extend_from_slice(&[0x81, 0x00, 0x00, 0x08]);
+    bytes.extend_from_slice(&[0x00; 8]);
+
+    for (number, level) in levels.iter().enumerate() {
+        // Sync code, 4096 samples at 44.1 kHz, stereo, 16 bits, frame number
+        let mut frame = vec![0xFF, 0xF8, 0xC9, 0x18, u8::try_from(number).unwrap()];
+        frame.push(crc8(&frame));
+        for _ in 0..2 {
+            frame.push(0x00); // constant subframe
+            frame.extend_from_slice(&level.to_be_bytes());
+        }
+        frame.extend_from_slice(&crc16(&frame).to_be_bytes());
+        bytes.extend_from_slice(&frame);
+    }
+    std::fs::write(path, bytes).unwrap();
+}
+
+fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0, |crc, byte| {
+        (0..8).fold(crc ^ byte, |crc, _| {
+            if crc & 0x80 == 0 {
+                crc << 1
+            } else {
+                (crc << 1) ^ 0x07
+            }
+        })
+    })
+}
+
+fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0, |crc, byte| {
+        (0..8).fold(crc ^ (u16::from(*byte) << 8), |crc, _| {
+            if crc & 0x8000 == 0 {
+                crc << 1
+            } else {
+                (crc << 1) ^ 0x8005
+            }
+        })
+    })
+}
+
+/// A deterministic pseudo-random fingerprint of `len` frames.
+pub fn fingerprint(seed: u32, len: usize) -> Vec<u32> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        })
+        .collect()
+}
@@ -0,0 +1,65 @@
+use tessitura_core::model::WorkRole;
+
+/// A typed, directed relationship between two graph nodes.
+///
+/// Edges always point from the more specific entity to the more
+/// general one (Item → Expression → Work → Artist).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edge {
+    /// Work → Artist: the work's composer.
+    ComposedBy,
+    /// Work → Artist: an arranger, orchestrator, lyricist, librettist or
+    /// writer credited on the work. Composers are `ComposedBy`.
+    CreditedTo { role: WorkRole },
+    /// Work → Work: a movement or other part of the larger work.
+    PartOf,
+    /// Expression → Work: the recording is a performance of the work.
+    PerformanceOf,
+    /// Expression → Manifestation: the recording appears on the release.
+    ReleasedOn {
+        track_number: Option<u32>,
+        disc_number: Option<u32>,
+    },
+    /// Expression → Artist: a credited performer.
+    PerformedBy { role: Option<String> },
+    /// Expression → Artist: the conductor.
+    ConductedBy,
+    /// Item → Expression or Item → Manifestation: the file is a copy of it.
+    InstanceOf,
+}
+
+impl Edge {
+    /// The kebab-case relationship name (e.g., "composed-by").
+    #[must_use]
+    pub const fn label(&self) -> &'static str {
+        match self {
+            Self::ComposedBy => "composed-by",
+            Self::CreditedTo { .. } => "credited-to",
+            Self::PartOf => "part-of",
+            Self::PerformanceOf => "performance-of",
+            Self::ReleasedOn { .. } => "released-on",
+            Self::PerformedBy { .. } => "performed-by",
+            Self::ConductedBy => "conducted-by",
+            Self::InstanceOf => "instance-of",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edge_labels() {
+        assert_eq!(Edge::ComposedBy.label(), "composed-by");
+        assert_eq!(
+            Edge::ReleasedOn {
+                track_number: Some(1),
+                disc_number: None
+            }
+            .label(),
+            "released-on"
+        );
+        assert_eq!(Edge::PerformedBy { role: None }.label(), "performed-by");
+    }
+}
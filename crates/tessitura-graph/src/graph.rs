@@ -0,0 +1,703 @@
+use std::collections::{HashMap, HashSet};
+
+use petgraph::stable_graph::{NodeIndex, StableDiGraph};
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use tessitura_core::model::{
+    Artist, ArtistId, ArtistRole, Expression, ExpressionId, Item, Manifestation, Work, WorkId,
+    WorkRole,
+};
+use tessitura_core::schema::Database;
+
+use crate::edge::Edge;
+use crate::error::Result;
+use crate::node::{Node, NodeKey};
+
+/// Counts of what changed during a [`MusicGraph::refresh`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RefreshStats {
+    pub nodes_added: usize,
+    pub nodes_updated: usize,
+    pub nodes_removed: usize,
+    pub edges_added: usize,
+    pub edges_removed: usize,
+}
+
+impl RefreshStats {
+    /// Whether the refresh left the graph untouched.
+    #[must_use]
+    pub const fn is_unchanged(&self) -> bool {
+        self.nodes_added == 0
+            && self.nodes_updated == 0
+            && self.nodes_removed == 0
+            && self.edges_added == 0
+            && self.edges_removed == 0
+    }
+}
+
+/// An in-memory knowledge graph of the FRBR catalog.
+///
+/// Hydrated from the `SQLite` store with [`MusicGraph::load`] and kept in
+/// sync with [`MusicGraph::refresh`], which diffs the store against the
+/// graph and only touches nodes and edges that changed. Node indices are
+/// stable across refreshes.
+#[derive(Debug, Default)]
+pub struct MusicGraph {
+    graph: StableDiGraph<Node, Edge>,
+    index: HashMap<NodeKey, NodeIndex>,
+}
+
+impl MusicGraph {
+    /// Create an empty graph.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a graph from the current contents of the database.
+    pub fn load(db: &Database) -> Result<Self> {
+        let mut graph = Self::new();
+        graph.refresh(db)?;
+        Ok(graph)
+    }
+
+    /// Bring the graph up to date with the database.
+    ///
+    /// Entities that disappeared are removed (with their edges), changed
+    /// entities are replaced in place, and each node's outgoing edges are
+    /// reconciled against the junction tables.
+    pub fn refresh(&mut self, db: &Database) -> Result<RefreshStats> {
+        let mut stats = RefreshStats::default();
+
+        let artists = db.list_artists()?;
+        let composer_lookup = composer_lookup(&artists);
+
+        let nodes: Vec<Node> = db
+            .list_works()?
+            .into_iter()
+            .map(Node::Work)
+            .chain(db.list_expressions()?.into_iter().map(Node::Expression))
+            .chain(
+                db.list_manifestations()?
+                    .into_iter()
+                    .map(Node::Manifestation),
+            )
+            .chain(db.list_all_items()?.into_iter().map(Node::Item))
+            .chain(artists.into_iter().map(Node::Artist))
+            .collect();
+        self.sync_nodes(nodes, &mut stats);
+
+        let mut desired = self.entity_edges(&composer_lookup);
+
+        for credit in db.list_expression_performers()? {
+            let from = self.index.get(&NodeKey::Expression(credit.expression_id));
+            let to = self.index.get(&NodeKey::Artist(credit.performer.artist_id));
+            if let (Some(&from), Some(&to)) = (from, to) {
+                desired.entry(from).or_default().push((
+                    to,
+                    Edge::PerformedBy {
+                        role: credit.performer.role,
+                    },
+                ));
+            }
+        }
+
+        for credit in db.list_work_credits()? {
+            let from = self.index.get(&NodeKey::Work(credit.work_id));
+            let to = self.index.get(&NodeKey::Artist(credit.artist_id));
+            if let (Some(&from), Some(&to)) = (from, to) {
+                let edge = match credit.role {
+                    WorkRole::Composer => Edge::ComposedBy,
+                    role => Edge::CreditedTo { role },
+                };
+                // The composer may already be linked by name
+                let edges = desired.entry(from).or_default();
+                if !edges.iter().any(|(t, e)| *t == to && *e == edge) {
+                    edges.push((to, edge));
+                }
+            }
+        }
+
+        for track in db.list_release_tracks()? {
+            let from = self.index.get(&NodeKey::Expression(track.expression_id));
+            let to = self
+                .index
+                .get(&NodeKey::Manifestation(track.manifestation_id));
+            if let (Some(&from), Some(&to)) = (from, to) {
+                desired.entry(from).or_default().push((
+                    to,
+                    Edge::ReleasedOn {
+                        track_number: track.track_number,
+                        disc_number: track.disc_number,
+                    },
+                ));
+            }
+        }
+
+        self.sync_edges(desired, &mut stats);
+
+        log::debug!("Music graph refreshed: {stats:?}");
+        Ok(stats)
+    }
+
+    /// Remove stale nodes, replace changed ones and add new ones.
+    fn sync_nodes(&mut self, nodes: Vec<Node>, stats: &mut RefreshStats) {
+        let live: HashSet<NodeKey> = nodes.iter().map(Node::key).collect();
+        let stale: Vec<NodeKey> = self
+            .index
+            .keys()
+            .filter(|key| !live.contains(key))
+            .copied()
+            .collect();
+        for key in stale {
+            if let Some(idx) = self.index.remove(&key) {
+                stats.edges_removed += self.graph.edges(idx).count()
+                    + self.graph.edges_directed(idx, Direction::Incoming).count();
+                self.graph.remove_node(idx);
+                stats.nodes_removed += 1;
+            }
+        }
+
+        for node in nodes {
+            let key = node.key();
+            if let Some(&idx) = self.index.get(&key) {
+                if self.graph[idx] != node {
+                    self.graph[idx] = node;
+                    stats.nodes_updated += 1;
+                }
+            } else {
+                let idx = self.graph.add_node(node);
+                self.index.insert(key, idx);
+                stats.nodes_added += 1;
+            }
+        }
+    }
+
+    /// Outgoing edges implied by the entities' own foreign keys.
+    fn entity_edges(
+        &self,
+        composer_lookup: &HashMap<String, ArtistId>,
+    ) -> HashMap<NodeIndex, Vec<(NodeIndex, Edge)>> {
+        let mut desired = HashMap::new();
+        for idx in self.graph.node_indices() {
+            let edges: Vec<(NodeKey, Edge)> = match &self.graph[idx] {
+                Node::Work(work) => work
+                    .composer
+                    .as_deref()
+                    .and_then(|name| composer_lookup.get(&name.to_lowercase()))
+                    .map(|id| (NodeKey::Artist(*id), Edge::ComposedBy))
+                    .into_iter()
+                    .chain(
+                        work.parent_work_id
+                            .map(|id| (NodeKey::Work(id), Edge::PartOf)),
+                    )
+                    .collect(),
+                Node::Expression(expr) => {
+                    let mut edges = vec![(NodeKey::Work(expr.work_id), Edge::PerformanceOf)];
+                    if let Some(conductor) = expr.conductor_id {
+                        edges.push((NodeKey::Artist(conductor), Edge::ConductedBy));
+                    }
+                    edges
+                }
+                Node::Item(item) => item
+                    .expression_id
+                    .map(|id| (NodeKey::Expression(id), Edge::InstanceOf))
+                    .into_iter()
+                    .chain(
+                        item.manifestation_id
+                            .map(|id| (NodeKey::Manifestation(id), Edge::InstanceOf)),
+                    )
+                    .collect(),
+                Node::Manifestation(_) | Node::Artist(_) => Vec::new(),
+            };
+            let resolved = edges
+                .into_iter()
+                .filter_map(|(key, edge)| self.index.get(&key).map(|&to| (to, edge)))
+                .collect();
+            desired.insert(idx, resolved);
+        }
+        desired
+    }
+
+    /// Drop edges that are no longer wanted and add the missing ones.
+    fn sync_edges(
+        &mut self,
+        desired: HashMap<NodeIndex, Vec<(NodeIndex, Edge)>>,
+        stats: &mut RefreshStats,
+    ) {
+        for (from, wanted) in desired {
+            let existing: Vec<_> = self
+                .graph
+                .edges(from)
+                .map(|e| (e.id(), e.target(), e.weight().clone()))
+                .collect();
+
+            for (edge_id, to, edge) in &existing {
+                if !wanted.iter().any(|(t, e)| t == to && e == edge) {
+                    self.graph.remove_edge(*edge_id);
+                    stats.edges_removed += 1;
+                }
+            }
+
+            for (to, edge) in wanted {
+                if !existing.iter().any(|(_, t, e)| *t == to && *e == edge) {
+                    self.graph.add_edge(from, to, edge);
+                    stats.edges_added += 1;
+                }
+            }
+        }
+    }
+
+    /// Number of nodes in the graph.
+    #[must_use]
+    pub fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+
+    /// Number of edges in the graph.
+    #[must_use]
+    pub fn edge_count(&self) -> usize {
+        self.graph.edge_count()
+    }
+
+    /// Look up a node by its key.
+    #[must_use]
+    pub fn node(&self, key: NodeKey) -> Option<&Node> {
+        self.index.get(&key).map(|&idx| &self.graph[idx])
+    }
+
+    /// Iterate over all nodes.
+    pub fn nodes(&self) -> impl Iterator<Item = &Node> {
+        self.graph.node_weights()
+    }
+
+    /// Outgoing edges from a node, with their targets.
+    pub fn outgoing(&self, key: NodeKey) -> Vec<(&Edge, &Node)> {
+        self.index
+            .get(&key)
+            .map(|&idx| {
+                self.graph
+                    .edges(idx)
+                    .map(|e| (e.weight(), &self.graph[e.target()]))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Incoming edges to a node, with their sources.
+    pub fn incoming(&self, key: NodeKey) -> Vec<(&Edge, &Node)> {
+        self.index
+            .get(&key)
+            .map(|&idx| {
+                self.graph
+                    .edges_directed(idx, Direction::Incoming)
+                    .map(|e| (e.weight(), &self.graph[e.source()]))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The artist a work is composed by, if known.
+    #[must_use]
+    pub fn composer_of(&self, work: WorkId) -> Option<&Artist> {
+        self.outgoing(NodeKey::Work(work))
+            .into_iter()
+            .find(|(edge, _)| matches!(edge, Edge::ComposedBy))
+            .and_then(|(_, node)| node.as_artist())
+    }
+
+    /// Every composer of a work, where [`composer_of`](Self::composer_of)
+    /// gives only one.
+    pub fn composers_of(&self, work: WorkId) -> Vec<&Artist> {
+        self.outgoing(NodeKey::Work(work))
+            .into_iter()
+            .filter(|(edge, _)| matches!(edge, Edge::ComposedBy))
+            .filter_map(|(_, node)| node.as_artist())
+            .collect()
+    }
+
+    /// Every artist credited on a work, with their role.
+    pub fn credits_of(&self, work: WorkId) -> Vec<(&Artist, WorkRole)> {
+        self.outgoing(NodeKey::Work(work))
+            .into_iter()
+            .filter_map(|(edge, node)| {
+                let role = match edge {
+                    Edge::ComposedBy => WorkRole::Composer,
+                    Edge::CreditedTo { role } => *role,
+                    _ => return None,
+                };
+                node.as_artist().map(|artist| (artist, role))
+            })
+            .collect()
+    }
+
+    /// All works composed by an artist.
+    pub fn works_by_composer(&self, artist: ArtistId) -> Vec<&Work> {
+        self.incoming(NodeKey::Artist(artist))
+            .into_iter()
+            .filter(|(edge, _)| matches!(edge, Edge::ComposedBy))
+            .filter_map(|(_, node)| node.as_work())
+            .collect()
+    }
+
+    /// The work a movement is part of, if any.
+    #[must_use]
+    pub fn parent_of(&self, work: WorkId) -> Option<&Work> {
+        self.outgoing(NodeKey::Work(work))
+            .into_iter()
+            .find(|(edge, _)| matches!(edge, Edge::PartOf))
+            .and_then(|(_, node)| node.as_work())
+    }
+
+    /// The movements and other parts of a work, ordered by part number
+    /// (unnumbered parts last).
+    pub fn movements_of(&self, work: WorkId) -> Vec<&Work> {
+        let mut movements: Vec<&Work> = self
+            .incoming(NodeKey::Work(work))
+            .into_iter()
+            .filter(|(edge, _)| matches!(edge, Edge::PartOf))
+            .filter_map(|(_, node)| node.as_work())
+            .collect();
+        movements.sort_by_key(|w| (w.part_number.is_none(), w.part_number, w.title.clone()));
+        movements
+    }
+
+    /// The work at the top of a work's hierarchy: the work itself unless it
+    /// is a movement, else the root of the work it is part of.
+    #[must_use]
+    pub fn root_work_of(&self, work: WorkId) -> Option<&Work> {
+        let mut root = self.node(NodeKey::Work(work))?.as_work()?;
+        let mut seen = HashSet::from([root.id]);
+        while let Some(parent) = self.parent_of(root.id) {
+            if !seen.insert(parent.id) {
+                break;
+            }
+            root = parent;
+        }
+        Some(root)
+    }
+
+    /// A work followed by its movements, and theirs, at every depth.
+    pub fn work_and_movements(&self, work: WorkId) -> Vec<&Work> {
+        let mut found: Vec<&Work> = self
+            .node(NodeKey::Work(work))
+            .and_then(Node::as_work)
+            .into_iter()
+            .collect();
+        let mut seen: HashSet<WorkId> = found.iter().map(|w| w.id).collect();
+        let mut next = 0;
+        while next < found.len() {
+            for movement in self.movements_of(found[next].id) {
+                if seen.insert(movement.id) {
+                    found.push(movement);
+                }
+            }
+            next += 1;
+        }
+        found
+    }
+
+    /// All recorded performances of a work.
+    pub fn expressions_of(&self, work: WorkId) -> Vec<&Expression> {
+        self.incoming(NodeKey::Work(work))
+            .into_iter()
+            .filter(|(edge, _)| matches!(edge, Edge::PerformanceOf))
+            .filter_map(|(_, node)| node.as_expression())
+            .collect()
+    }
+
+    /// The work a recording is a performance of.
+    #[must_use]
+    pub fn work_of(&self, expression: ExpressionId) -> Option<&Work> {
+        self.outgoing(NodeKey::Expression(expression))
+            .into_iter()
+            .find(|(edge, _)| matches!(edge, Edge::PerformanceOf))
+            .and_then(|(_, node)| node.as_work())
+    }
+
+    /// Performers credited on a recording, with their roles.
+    pub fn performers_of(&self, expression: ExpressionId) -> Vec<(&Artist, Option<&str>)> {
+        self.outgoing(NodeKey::Expression(expression))
+            .into_iter()
+            .filter_map(|(edge, node)| match edge {
+                Edge::PerformedBy { role } => node.as_artist().map(|a| (a, role.as_deref())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The conductor of a recording, if any.
+    #[must_use]
+    pub fn conductor_of(&self, expression: ExpressionId) -> Option<&Artist> {
+        self.outgoing(NodeKey::Expression(expression))
+            .into_iter()
+            .find(|(edge, _)| matches!(edge, Edge::ConductedBy))
+            .and_then(|(_, node)| node.as_artist())
+    }
+
+    /// Recordings an artist performed on or conducted.
+    pub fn expressions_by_artist(&self, artist: ArtistId) -> Vec<&Expression> {
+        self.incoming(NodeKey::Artist(artist))
+            .into_iter()
+            .filter(|(edge, _)| matches!(edge, Edge::PerformedBy { .. } | Edge::ConductedBy))
+            .filter_map(|(_, node)| node.as_expression())
+            .collect()
+    }
+
+    /// Distinct works an artist performed or conducted.
+    pub fn works_performed_by(&self, artist: ArtistId) -> Vec<&Work> {
+        let mut seen = HashSet::new();
+        self.expressions_by_artist(artist)
+            .into_iter()
+            .filter_map(|expr| self.work_of(expr.id))
+            .filter(|work| seen.insert(work.id))
+            .collect()
+    }
+
+    /// Releases a recording appears on.
+    pub fn releases_of(&self, expression: ExpressionId) -> Vec<&Manifestation> {
+        self.outgoing(NodeKey::Expression(expression))
+            .into_iter()
+            .filter(|(edge, _)| matches!(edge, Edge::ReleasedOn { .. }))
+            .filter_map(|(_, node)| node.as_manifestation())
+            .collect()
+    }
+
+    /// Files that are copies of a recording.
+    pub fn items_of_expression(&self, expression: ExpressionId) -> Vec<&Item> {
+        self.incoming(NodeKey::Expression(expression))
+            .into_iter()
+            .filter(|(edge, _)| matches!(edge, Edge::InstanceOf))
+            .filter_map(|(_, node)| node.as_item())
+            .collect()
+    }
+
+    /// Files holding any recording of a work or of its movements.
+    pub fn items_of_work(&self, work: WorkId) -> Vec<&Item> {
+        self.work_and_movements(work)
+            .into_iter()
+            .flat_map(|w| self.expressions_of(w.id))
+            .flat_map(|expr| self.items_of_expression(expr.id))
+            .collect()
+    }
+}
+
+/// Map lower-cased artist names to IDs for resolving `Work.composer`,
+/// preferring artists that carry the Composer role.
+fn composer_lookup(artists: &[Artist]) -> HashMap<String, ArtistId> {
+    let mut lookup = HashMap::new();
+    for artist in artists {
+        let name = artist.name.to_lowercase();
+        if artist.roles.contains(&ArtistRole::Composer) {
+            lookup.insert(name, artist.id);
+        } else {
+            lookup.entry(name).or_insert(artist.id);
+        }
+    }
+    lookup
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::path::PathBuf;
+    use tessitura_core::model::{AudioFormat, ReleaseTrack, WorkCredit};
+
+    struct Fixture {
+        db: Database,
+        work: Work,
+        expr: Expression,
+        man: Manifestation,
+        item: Item,
+        composer: Artist,
+        quartet: Artist,
+    }
+
+    fn fixture() -> Fixture {
+        let db = Database::open_in_memory().unwrap();
+
+        let composer = Artist::new("Béla Bartók").with_role(ArtistRole::Composer);
+        let quartet = Artist::new("Takács Quartet").with_role(ArtistRole::Ensemble);
+        db.insert_artist(&composer).unwrap();
+        db.insert_artist(&quartet).unwrap();
+
+        let work = Work::new("String Quartet No. 4").with_composer("Béla Bartók");
+        db.insert_work(&work).unwrap();
+
+        let expr = Expression::new(work.id)
+            .with_title("String Quartet No. 4")
+            .with_performer(quartet.id);
+        db.insert_expression(&expr).unwrap();
+
+        let man = Manifestation::new("Bartók: The String Quartets");
+        db.insert_manifestation(&man).unwrap();
+        db.link_release_track(&ReleaseTrack::new(man.id, expr.id).with_track_number(4))
+            .unwrap();
+
+        let mut item = Item::new(
+            PathBuf::from("/music/bartok/04.flac"),
+            AudioFormat::Flac,
+            1024,
+            Utc::now(),
+        );
+        item.expression_id = Some(expr.id);
+        item.manifestation_id = Some(man.id);
+        db.insert_item(&item).unwrap();
+
+        Fixture {
+            db,
+            work,
+            expr,
+            man,
+            item,
+            composer,
+            quartet,
+        }
+    }
+
+    #[test]
+    fn test_load_builds_typed_nodes_and_edges() {
+        let f = fixture();
+        let graph = MusicGraph::load(&f.db).unwrap();
+
+        assert_eq!(graph.node_count(), 6);
+        // composed-by, performance-of, performed-by, released-on, 2x instance-of
+        assert_eq!(graph.edge_count(), 6);
+
+        assert_eq!(graph.composer_of(f.work.id).unwrap().id, f.composer.id);
+        assert_eq!(graph.works_by_composer(f.composer.id).len(), 1);
+        assert_eq!(graph.expressions_of(f.work.id)[0].id, f.expr.id);
+        assert_eq!(graph.performers_of(f.expr.id)[0].0.id, f.quartet.id);
+        assert_eq!(graph.releases_of(f.expr.id)[0].id, f.man.id);
+        assert_eq!(graph.items_of_work(f.work.id)[0].id, f.item.id);
+        assert_eq!(graph.works_performed_by(f.quartet.id)[0].id, f.work.id);
+        assert!(graph.conductor_of(f.expr.id).is_none());
+    }
+
+    #[test]
+    fn test_movements_roll_up_to_their_work() {
+        let f = fixture();
+        let first = Work::new("String Quartet No. 4: I. Allegro").with_parent(f.work.id, Some(1));
+        let fifth =
+            Work::new("String Quartet No. 4: V. Allegro molto").with_parent(f.work.id, Some(5));
+        for movement in [&fifth, &first] {
+            f.db.insert_work(movement).unwrap();
+        }
+        let expr = Expression::new(fifth.id).with_performer(f.quartet.id);
+        f.db.insert_expression(&expr).unwrap();
+        let mut item = Item::new(
+            PathBuf::from("/music/bartok/05.flac"),
+            AudioFormat::Flac,
+            1024,
+            Utc::now(),
+        );
+        item.expression_id = Some(expr.id);
+        f.db.insert_item(&item).unwrap();
+
+        let graph = MusicGraph::load(&f.db).unwrap();
+        assert_eq!(graph.parent_of(fifth.id).unwrap().id, f.work.id);
+        assert!(graph.parent_of(f.work.id).is_none());
+        assert_eq!(
+            graph
+                .movements_of(f.work.id)
+                .iter()
+                .map(|w| w.id)
+                .collect::<Vec<_>>(),
+            [first.id, fifth.id]
+        );
+        assert_eq!(graph.root_work_of(fifth.id).unwrap().id, f.work.id);
+        assert_eq!(graph.work_and_movements(f.work.id).len(), 3);
+        assert_eq!(graph.items_of_work(f.work.id).len(), 2);
+        assert_eq!(graph.items_of_work(fifth.id)[0].id, item.id);
+    }
+
+    #[test]
+    fn test_work_credits_link_artists() {
+        let f = fixture();
+        let arranger = Artist::new("Arranger");
+        f.db.insert_artist(&arranger).unwrap();
+        for credit in [
+            WorkCredit::new(f.work.id, f.composer.id, WorkRole::Composer),
+            WorkCredit::new(f.work.id, arranger.id, WorkRole::Arranger),
+        ] {
+            f.db.link_work_credit(&credit).unwrap();
+        }
+
+        let mut graph = MusicGraph::load(&f.db).unwrap();
+        // The composer credit and the composer's name give one edge
+        assert_eq!(graph.edge_count(), 7);
+        assert_eq!(graph.composers_of(f.work.id).len(), 1);
+        let mut credits: Vec<_> = graph
+            .credits_of(f.work.id)
+            .into_iter()
+            .map(|(artist, role)| (artist.id, role))
+            .collect();
+        credits.sort_by_key(|(_, role)| *role);
+        assert_eq!(
+            credits,
+            [
+                (f.composer.id, WorkRole::Composer),
+                (arranger.id, WorkRole::Arranger)
+            ]
+        );
+        assert!(graph.refresh(&f.db).unwrap().is_unchanged());
+    }
+
+    #[test]
+    fn test_released_on_carries_track_position() {
+        let f = fixture();
+        let graph = MusicGraph::load(&f.db).unwrap();
+        let released = graph
+            .outgoing(NodeKey::Expression(f.expr.id))
+            .into_iter()
+            .find_map(|(edge, _)| match edge {
+                Edge::ReleasedOn { track_number, .. } => Some(*track_number),
+                _ => None,
+            });
+        assert_eq!(released, Some(Some(4)));
+    }
+
+    #[test]
+    fn test_refresh_without_changes_is_a_no_op() {
+        let f = fixture();
+        let mut graph = MusicGraph::load(&f.db).unwrap();
+        let stats = graph.refresh(&f.db).unwrap();
+        assert!(stats.is_unchanged(), "unexpected changes: {stats:?}");
+    }
+
+    #[test]
+    fn test_refresh_picks_up_new_and_changed_entities() {
+        let f = fixture();
+        let mut graph = MusicGraph::load(&f.db).unwrap();
+
+        let conductor = Artist::new("Pierre Boulez").with_role(ArtistRole::Conductor);
+        f.db.insert_artist(&conductor).unwrap();
+        let mut expr = f.expr.clone();
+        expr.conductor_id = Some(conductor.id);
+        f.db.upsert_expression(&expr).unwrap();
+
+        let stats = graph.refresh(&f.db).unwrap();
+        assert_eq!(stats.nodes_added, 1);
+        assert_eq!(stats.nodes_updated, 1);
+        assert_eq!(stats.edges_added, 1);
+        assert_eq!(stats.edges_removed, 0);
+        assert_eq!(graph.conductor_of(f.expr.id).unwrap().id, conductor.id);
+    }
+
+    #[test]
+    fn test_refresh_removes_deleted_entities() {
+        let f = fixture();
+        let mut graph = MusicGraph::load(&f.db).unwrap();
+
+        f.db.conn()
+            .execute("DELETE FROM items WHERE id = ?1", [f.item.id.to_string()])
+            .unwrap();
+
+        let stats = graph.refresh(&f.db).unwrap();
+        assert_eq!(stats.nodes_removed, 1);
+        assert_eq!(stats.edges_removed, 2);
+        assert!(graph.node(NodeKey::Item(f.item.id)).is_none());
+        assert!(graph.items_of_expression(f.expr.id).is_empty());
+    }
+}
@@ -5,3 +5,15 @@
 
 #![deny(unsafe_code)]
 #![warn(missing_debug_implementations)]
+
+pub mod edge;
+pub mod error;
+pub mod graph;
+pub mod node;
+pub mod query;
+
+pub use edge::Edge;
+pub use error::{Error, Result};
+pub use graph::{MusicGraph, RefreshStats};
+pub use node::{Node, NodeKey};
+pub use query::{FacetIndex, FacetMode, WorkFacets, WorkMatch, WorkQuery};
@@ -0,0 +1,115 @@
+use tessitura_core::model::{
+    Artist, ArtistId, Expression, ExpressionId, Item, ItemId, Manifestation, ManifestationId, Work,
+    WorkId,
+};
+
+/// Stable identity of a graph node, independent of its petgraph index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeKey {
+    Work(WorkId),
+    Expression(ExpressionId),
+    Manifestation(ManifestationId),
+    Item(ItemId),
+    Artist(ArtistId),
+}
+
+/// A node in the music graph, carrying the full FRBR entity it represents.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Work(Work),
+    Expression(Expression),
+    Manifestation(Manifestation),
+    Item(Item),
+    Artist(Artist),
+}
+
+impl Node {
+    /// The stable key for this node.
+    #[must_use]
+    pub const fn key(&self) -> NodeKey {
+        match self {
+            Self::Work(w) => NodeKey::Work(w.id),
+            Self::Expression(e) => NodeKey::Expression(e.id),
+            Self::Manifestation(m) => NodeKey::Manifestation(m.id),
+            Self::Item(i) => NodeKey::Item(i.id),
+            Self::Artist(a) => NodeKey::Artist(a.id),
+        }
+    }
+
+    /// A short human-readable label (title, name, or file path).
+    #[must_use]
+    pub fn label(&self) -> String {
+        match self {
+            Self::Work(w) => w.title.clone(),
+            Self::Expression(e) => e
+                .title
+                .clone()
+                .unwrap_or_else(|| format!("Expression {}", e.id)),
+            Self::Manifestation(m) => m.title.clone(),
+            Self::Item(i) => i.file_path.display().to_string(),
+            Self::Artist(a) => a.name.clone(),
+        }
+    }
+
+    #[must_use]
+    pub const fn as_work(&self) -> Option<&Work> {
+        match self {
+            Self::Work(w) => Some(w),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_expression(&self) -> Option<&Expression> {
+        match self {
+            Self::Expression(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_manifestation(&self) -> Option<&Manifestation> {
+        match self {
+            Self::Manifestation(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_item(&self) -> Option<&Item> {
+        match self {
+            Self::Item(i) => Some(i),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_artist(&self) -> Option<&Artist> {
+        match self {
+            Self::Artist(a) => Some(a),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_key_matches_entity_id() {
+        let work = Work::new("String Quartet No. 4");
+        let node = Node::Work(work.clone());
+        assert_eq!(node.key(), NodeKey::Work(work.id));
+        assert_eq!(node.label(), "String Quartet No. 4");
+        assert!(node.as_work().is_some());
+        assert!(node.as_artist().is_none());
+    }
+
+    #[test]
+    fn test_expression_label_falls_back_to_id() {
+        let expr = Expression::new(WorkId::new());
+        let node = Node::Expression(expr.clone());
+        assert_eq!(node.label(), format!("Expression {}", expr.id));
+    }
+}
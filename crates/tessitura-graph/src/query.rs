@@ -0,0 +1,936 @@
+//! Faceted structured queries over the music graph.
+//!
+//! A [`WorkQuery`] filters works by canonical harmonized values (form,
+//! period, genre, instrumentation) held in a [`FacetIndex`], together with
+//! fields stored on the Work itself (key, catalog number, composer,
+//! composition year) and the performers of its recordings.
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use tessitura_core::model::{
+    Artist, CatalogNumber, Expression, Item, MusicalKey, Work, WorkId, WorkRole,
+};
+use tessitura_core::provenance::{ProposalStatus, Source};
+use tessitura_core::schema::Database;
+use tessitura_core::taxonomy::MappingRules;
+
+use crate::error::Result;
+use crate::graph::MusicGraph;
+use crate::node::Node;
+
+/// How the facets of a query combine.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FacetMode {
+    /// Every given facet must match (AND).
+    #[default]
+    All,
+    /// At least one given facet must match (OR).
+    Any,
+}
+
+/// A faceted query over works.
+///
+/// Multiple values for the same facet are alternatives (OR); distinct
+/// facets are combined according to [`FacetMode`].
+#[derive(Debug, Clone, Default)]
+pub struct WorkQuery {
+    pub forms: Vec<String>,
+    pub periods: Vec<String>,
+    pub genres: Vec<String>,
+    pub instruments: Vec<String>,
+    /// Keys in any spelling [`MusicalKey`] parses ("fis-moll" finds
+    /// "F-sharp minor").
+    pub keys: Vec<String>,
+    /// Also match keys spelled differently that sound the same
+    /// ("F-sharp major" finds "G-flat major").
+    pub enharmonic_keys: bool,
+    /// Catalog numbers in any spelling ("KV 550" finds "K. 550"); a whole
+    /// opus finds the works in it.
+    pub catalog_numbers: Vec<String>,
+    pub composers: Vec<String>,
+    /// Arrangers and orchestrators.
+    pub arrangers: Vec<String>,
+    /// Lyricists, librettists and other writers of the words.
+    pub lyricists: Vec<String>,
+    pub performers: Vec<String>,
+    /// Instruments or voices performers are credited with. Given with
+    /// `performers`, one credit must match both.
+    pub performer_roles: Vec<String>,
+
+    /// Inclusive composition year range.
+    pub composed_between: Option<(i32, i32)>,
+
+    /// Inclusive range the recording sessions must overlap.
+    pub recorded_between: Option<(i32, i32)>,
+    /// Live (`true`) or studio (`false`) recordings only.
+    pub live: Option<bool>,
+
+    pub mode: FacetMode,
+}
+
+impl WorkQuery {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn with_form(mut self, form: impl Into<String>) -> Self {
+        self.forms.push(form.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_period(mut self, period: impl Into<String>) -> Self {
+        self.periods.push(period.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_genre(mut self, genre: impl Into<String>) -> Self {
+        self.genres.push(genre.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_instrument(mut self, instrument: impl Into<String>) -> Self {
+        self.instruments.push(instrument.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.keys.push(key.into());
+        self
+    }
+
+    #[must_use]
+    pub const fn with_enharmonic_keys(mut self, enharmonic: bool) -> Self {
+        self.enharmonic_keys = enharmonic;
+        self
+    }
+
+    #[must_use]
+    pub fn with_catalog_number(mut self, catalog_number: impl Into<String>) -> Self {
+        self.catalog_numbers.push(catalog_number.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_composer(mut self, composer: impl Into<String>) -> Self {
+        self.composers.push(composer.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_arranger(mut self, arranger: impl Into<String>) -> Self {
+        self.arrangers.push(arranger.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_lyricist(mut self, lyricist: impl Into<String>) -> Self {
+        self.lyricists.push(lyricist.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_performer(mut self, performer: impl Into<String>) -> Self {
+        self.performers.push(performer.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_performer_role(mut self, role: impl Into<String>) -> Self {
+        self.performer_roles.push(role.into());
+        self
+    }
+
+    #[must_use]
+    pub const fn composed_between(mut self, start: i32, end: i32) -> Self {
+        self.composed_between = Some((start, end));
+        self
+    }
+
+    #[must_use]
+    pub const fn recorded_between(mut self, start: i32, end: i32) -> Self {
+        self.recorded_between = Some((start, end));
+        self
+    }
+
+    #[must_use]
+    pub const fn with_live(mut self, live: bool) -> Self {
+        self.live = Some(live);
+        self
+    }
+
+    #[must_use]
+    pub const fn with_mode(mut self, mode: FacetMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Whether no facet has been given (matches every work).
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.forms.is_empty()
+            && self.periods.is_empty()
+            && self.genres.is_empty()
+            && self.instruments.is_empty()
+            && self.keys.is_empty()
+            && self.catalog_numbers.is_empty()
+            && self.composers.is_empty()
+            && self.arrangers.is_empty()
+            && self.lyricists.is_empty()
+            && self.performers.is_empty()
+            && self.performer_roles.is_empty()
+            && self.composed_between.is_none()
+            && self.recorded_between.is_none()
+            && self.live.is_none()
+    }
+}
+
+/// Canonical harmonized values for one work.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkFacets {
+    pub forms: BTreeSet<String>,
+    pub periods: BTreeSet<String>,
+    pub genres: BTreeSet<String>,
+    pub instruments: BTreeSet<String>,
+}
+
+impl WorkFacets {
+    /// Record a canonical value for a harmonized field.
+    ///
+    /// Unknown fields are ignored.
+    pub fn insert(&mut self, field: &str, value: impl Into<String>) {
+        let set = match field {
+            "form" => &mut self.forms,
+            "period" => &mut self.periods,
+            "genre" => &mut self.genres,
+            "instrumentation" => &mut self.instruments,
+            _ => return,
+        };
+        set.insert(value.into());
+    }
+}
+
+/// Canonical harmonized values for every work, keyed by Work ID.
+///
+/// Building it reads the proposals and overrides of every item, so callers
+/// that search repeatedly should build it once, or keep it between runs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FacetIndex {
+    works: HashMap<WorkId, WorkFacets>,
+}
+
+impl FacetIndex {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collect the reviewed values of every identified item and roll them up
+    /// to the item's work and, for a movement, to the work it is part of.
+    ///
+    /// As for export, an item's values are its accepted and pending
+    /// proposals and its [`Source::User`] overrides; rejected and overridden
+    /// proposals are left out. When none of a work's values is a period, the
+    /// work's own composer and composition year are tried.
+    pub fn build(graph: &MusicGraph, db: &Database, rules: &MappingRules) -> Result<Self> {
+        let mut index = Self::new();
+
+        for node in graph.nodes() {
+            let Node::Item(item) = node else { continue };
+            let Some(work) = item.expression_id.and_then(|id| graph.work_of(id)) else {
+                continue;
+            };
+
+            let root = graph.root_work_of(work.id).unwrap_or(work);
+
+            let entity_id = item.id.to_string();
+            let proposals = db.get_proposals_for_entity(&entity_id)?;
+            let overrides = db.get_assertions_for_entity(&entity_id)?;
+            let proposed = proposals
+                .iter()
+                .filter(|p| matches!(p.status, ProposalStatus::Accepted | ProposalStatus::Pending))
+                .map(|p| (p.field.as_str(), p.value.as_str()));
+            let overridden = overrides
+                .iter()
+                .filter(|a| a.source == Source::User)
+                .filter_map(|a| Some((a.field.as_str(), a.value.as_str()?)));
+            for (field, value) in proposed.chain(overridden) {
+                if root.id != work.id {
+                    index.insert(root.id, field, value);
+                }
+                index.insert(work.id, field, value);
+            }
+        }
+
+        for node in graph.nodes() {
+            let Node::Work(work) = node else { continue };
+            let has_period = index
+                .works
+                .get(&work.id)
+                .is_some_and(|facets| !facets.periods.is_empty());
+            if !has_period {
+                if let Some(period) =
+                    rules.apply_period_rules(work.composer.as_deref(), work.composed_year)
+                {
+                    index.insert(work.id, &period.field, period.value);
+                }
+            }
+        }
+
+        Ok(index)
+    }
+
+    /// Record a canonical value for a work.
+    pub fn insert(&mut self, work: WorkId, field: &str, value: impl Into<String>) {
+        self.works.entry(work).or_default().insert(field, value);
+    }
+
+    /// Canonical values for a work, if any were recorded.
+    #[must_use]
+    pub fn get(&self, work: WorkId) -> Option<&WorkFacets> {
+        self.works.get(&work)
+    }
+}
+
+/// A work matched by a [`WorkQuery`], with the files that hold it.
+#[derive(Debug)]
+pub struct WorkMatch<'a> {
+    pub work: &'a Work,
+    pub composer: Option<&'a str>,
+    pub items: Vec<&'a Item>,
+}
+
+impl MusicGraph {
+    /// Find works matching a faceted query, ordered by composer, then
+    /// catalog number, then title.
+    ///
+    /// Movements are not listed on their own: their recordings and files
+    /// count towards the work they are part of.
+    pub fn search<'a>(&'a self, query: &WorkQuery, facets: &FacetIndex) -> Vec<WorkMatch<'a>> {
+        let mut matches: Vec<WorkMatch<'a>> = self
+            .nodes()
+            .filter_map(Node::as_work)
+            .filter(|work| self.parent_of(work.id).is_none())
+            .filter(|work| self.work_matches(work, query, facets))
+            .map(|work| WorkMatch {
+                work,
+                composer: work
+                    .composer
+                    .as_deref()
+                    .or_else(|| self.composer_of(work.id).map(|a| a.name.as_str())),
+                items: self.items_of_work(work.id),
+            })
+            .collect();
+
+        matches.sort_by_cached_key(|m| {
+            let catalog = m.work.catalog();
+            (
+                m.composer.unwrap_or_default().to_lowercase(),
+                catalog.is_none(),
+                catalog,
+                m.work.title.to_lowercase(),
+            )
+        });
+        matches
+    }
+
+    fn work_matches(&self, work: &Work, query: &WorkQuery, facets: &FacetIndex) -> bool {
+        if query.is_empty() {
+            return true;
+        }
+
+        let canonical = facets.get(work.id);
+        let canonical_values = |pick: fn(&WorkFacets) -> Vec<&String>| {
+            canonical
+                .map(pick)
+                .unwrap_or_default()
+                .into_iter()
+                .map(String::as_str)
+        };
+
+        let mut results: Vec<bool> = Vec::new();
+
+        if !query.forms.is_empty() {
+            // LCGFT genre/form labels ("String quartets") also describe form
+            let forms = canonical_values(|f| f.forms.iter().chain(&f.genres).collect());
+            results.push(any_matches(&query.forms, forms));
+        }
+        if !query.periods.is_empty() {
+            let periods = canonical_values(|f| f.periods.iter().collect());
+            results.push(any_matches(&query.periods, periods));
+        }
+        if !query.genres.is_empty() {
+            let genres = canonical_values(|f| f.genres.iter().collect());
+            results.push(any_matches(&query.genres, genres));
+        }
+        if !query.instruments.is_empty() {
+            let instruments = canonical_values(|f| f.instruments.iter().collect());
+            results.push(any_matches(&query.instruments, instruments));
+        }
+        if !query.composers.is_empty() {
+            let composers = work.composer.as_deref().into_iter().chain(
+                self.credited(work, &[WorkRole::Composer, WorkRole::Writer])
+                    .map(|a| a.name.as_str()),
+            );
+            results.push(any_matches(&query.composers, composers));
+        }
+        if !query.arrangers.is_empty() {
+            let arrangers = self
+                .credited(work, &[WorkRole::Arranger, WorkRole::Orchestrator])
+                .map(|a| a.name.as_str());
+            results.push(any_matches(&query.arrangers, arrangers));
+        }
+        if !query.lyricists.is_empty() {
+            let lyricists = self
+                .credited(
+                    work,
+                    &[WorkRole::Lyricist, WorkRole::Librettist, WorkRole::Writer],
+                )
+                .map(|a| a.name.as_str());
+            results.push(any_matches(&query.lyricists, lyricists));
+        }
+        if !query.performers.is_empty()
+            || !query.performer_roles.is_empty()
+            || query.recorded_between.is_some()
+            || query.live.is_some()
+        {
+            results.push(self.performed(work, query));
+        }
+        if !query.keys.is_empty() {
+            results.push(work.key.as_deref().is_some_and(|key| {
+                query
+                    .keys
+                    .iter()
+                    .any(|k| keys_match(k, key, query.enharmonic_keys))
+            }));
+        }
+        if !query.catalog_numbers.is_empty() {
+            let catalog = work.catalog();
+            results.push(query.catalog_numbers.iter().any(|wanted| {
+                match wanted.parse::<CatalogNumber>() {
+                    Ok(wanted) => catalog.as_ref().is_some_and(|c| wanted.includes(c)),
+                    Err(_) => work
+                        .catalog_number
+                        .as_deref()
+                        .is_some_and(|c| text_matches(wanted, c)),
+                }
+            }));
+        }
+        if let Some((start, end)) = query.composed_between {
+            results.push(
+                work.composed_year
+                    .is_some_and(|year| year >= start && year <= end),
+            );
+        }
+
+        match query.mode {
+            FacetMode::All => results.iter().all(|&r| r),
+            FacetMode::Any => results.iter().any(|&r| r),
+        }
+    }
+
+    /// Whether a recording of a work or its movements matches all the
+    /// query's recording facets: it was made in the wanted years and live or
+    /// in a studio as asked, and credits a performer matching the query's
+    /// performers and performer roles. Conductors match by name when no role
+    /// is given.
+    fn performed(&self, work: &Work, query: &WorkQuery) -> bool {
+        let session_matches = |expr: &Expression| {
+            query.recorded_between.is_none_or(|(start, end)| {
+                expr.recorded_years()
+                    .is_some_and(|(from, until)| from <= end && until >= start)
+            }) && query.live.is_none_or(|live| expr.live == Some(live))
+        };
+        let name_matches = |artist: &Artist| {
+            query.performers.is_empty()
+                || query
+                    .performers
+                    .iter()
+                    .any(|p| text_matches(p, &artist.name))
+        };
+        let role_matches = |role: Option<&str>| {
+            query.performer_roles.is_empty()
+                || role
+                    .is_some_and(|role| query.performer_roles.iter().any(|r| text_matches(r, role)))
+        };
+        self.work_and_movements(work.id)
+            .into_iter()
+            .flat_map(|w| self.expressions_of(w.id))
+            .filter(|expr| session_matches(expr))
+            .any(|expr| {
+                (query.performers.is_empty() && query.performer_roles.is_empty())
+                    || self
+                        .performers_of(expr.id)
+                        .into_iter()
+                        .any(|(artist, role)| name_matches(artist) && role_matches(role))
+                    || (query.performer_roles.is_empty()
+                        && self.conductor_of(expr.id).is_some_and(name_matches))
+            })
+    }
+
+    /// Artists credited in any of `roles` on a work or its movements.
+    fn credited<'a>(
+        &'a self,
+        work: &Work,
+        roles: &'a [WorkRole],
+    ) -> impl Iterator<Item = &'a Artist> + 'a {
+        self.work_and_movements(work.id)
+            .into_iter()
+            .flat_map(|w| self.credits_of(w.id))
+            .filter(|(_, role)| roles.contains(role))
+            .map(|(artist, _)| artist)
+    }
+}
+
+/// Whether any candidate value matches any of the wanted terms.
+fn any_matches<'a>(wanted: &[String], candidates: impl Iterator<Item = &'a str>) -> bool {
+    let candidates: Vec<&str> = candidates.collect();
+    wanted
+        .iter()
+        .any(|w| candidates.iter().any(|c| text_matches(w, c)))
+}
+
+/// Case-insensitive substring match of a query term against a value.
+fn text_matches(wanted: &str, candidate: &str) -> bool {
+    candidate
+        .to_lowercase()
+        .contains(&wanted.trim().to_lowercase())
+}
+
+/// Compare keys as [`MusicalKey`]s, however they are spelled ("As-Dur"
+/// matches "A-flat major"), and if either does not parse, by name ignoring
+/// case, hyphens and repeated whitespace. With `enharmonic`, keys that sound
+/// the same match too.
+fn keys_match(wanted: &str, candidate: &str, enharmonic: bool) -> bool {
+    if let (Ok(wanted), Ok(candidate)) = (
+        wanted.parse::<MusicalKey>(),
+        candidate.parse::<MusicalKey>(),
+    ) {
+        return if enharmonic {
+            wanted.is_enharmonic(&candidate)
+        } else {
+            wanted == candidate
+        };
+    }
+    let normalize = |s: &str| {
+        s.replace('-', " ")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+    };
+    normalize(wanted) == normalize(candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::path::PathBuf;
+    use tessitura_core::model::{ArtistRole, AudioFormat, Performer, WorkCredit};
+    use tessitura_core::provenance::{Assertion, Proposal};
+    use tessitura_core::taxonomy::rules::ProposedTag;
+
+    struct Library {
+        db: Database,
+        bartok: Work,
+        mozart: Work,
+    }
+
+    fn library() -> Library {
+        let db = Database::open_in_memory().unwrap();
+
+        let quartet = Artist::new("Takács Quartet").with_role(ArtistRole::Ensemble);
+        let pianist = Artist::new("Mitsuko Uchida").with_role(ArtistRole::Performer);
+        db.insert_artist(&quartet).unwrap();
+        db.insert_artist(&pianist).unwrap();
+
+        let bartok = Work::new("String Quartet No. 4")
+            .with_composer("Béla Bartók")
+            .with_key("C major")
+            .with_composed_year(1928);
+        let mozart = Work::new("Piano Sonata No. 8")
+            .with_composer("Wolfgang Amadeus Mozart")
+            .with_key("A minor")
+            .with_composed_year(1778);
+        db.insert_work(&bartok).unwrap();
+        db.insert_work(&mozart).unwrap();
+
+        for (work, artist, path) in [
+            (&bartok, &quartet, "/music/bartok.flac"),
+            (&mozart, &pianist, "/music/mozart.flac"),
+        ] {
+            let expr = Expression::new(work.id).with_performer(artist.id);
+            db.insert_expression(&expr).unwrap();
+            let mut item = Item::new(PathBuf::from(path), AudioFormat::Flac, 1, Utc::now());
+            item.expression_id = Some(expr.id);
+            db.insert_item(&item).unwrap();
+        }
+
+        Library { db, bartok, mozart }
+    }
+
+    fn facets(lib: &Library) -> FacetIndex {
+        let mut index = FacetIndex::new();
+        index.insert(lib.bartok.id, "form", "String quartet");
+        index.insert(lib.bartok.id, "period", "20th Century");
+        index.insert(lib.bartok.id, "instrumentation", "Violin");
+        index.insert(lib.mozart.id, "form", "Sonata");
+        index.insert(lib.mozart.id, "period", "Classical");
+        index.insert(lib.mozart.id, "instrumentation", "Piano");
+        index
+    }
+
+    #[test]
+    fn test_search_single_facet() {
+        let lib = library();
+        let graph = MusicGraph::load(&lib.db).unwrap();
+        let results = graph.search(&WorkQuery::new().with_form("string quartet"), &facets(&lib));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].work.id, lib.bartok.id);
+        assert_eq!(results[0].items.len(), 1);
+    }
+
+    #[test]
+    fn test_search_facets_combine_with_and() {
+        let lib = library();
+        let graph = MusicGraph::load(&lib.db).unwrap();
+        let query = WorkQuery::new()
+            .with_period("20th century")
+            .with_key("a minor");
+        assert!(graph.search(&query, &facets(&lib)).is_empty());
+    }
+
+    #[test]
+    fn test_search_facets_combine_with_or() {
+        let lib = library();
+        let graph = MusicGraph::load(&lib.db).unwrap();
+        let query = WorkQuery::new()
+            .with_period("20th century")
+            .with_key("A minor")
+            .with_mode(FacetMode::Any);
+        let results = graph.search(&query, &facets(&lib));
+        assert_eq!(results.len(), 2);
+        // Ordered by composer
+        assert_eq!(results[0].work.id, lib.bartok.id);
+    }
+
+    #[test]
+    fn test_facet_index_uses_reviewed_values() {
+        let lib = library();
+        let item = lib
+            .db
+            .list_identified_items()
+            .unwrap()
+            .into_iter()
+            .find(|i| i.file_path.ends_with("bartok.flac"))
+            .unwrap();
+        let entity_id = item.id.to_string();
+        for (field, value, status) in [
+            ("form", "String quartet", ProposalStatus::Accepted),
+            ("period", "20th Century", ProposalStatus::Pending),
+            ("genre", "Folk", ProposalStatus::Rejected),
+            ("genre", "Jazz", ProposalStatus::Overridden),
+        ] {
+            let tag = ProposedTag {
+                field: field.to_string(),
+                value: value.to_string(),
+                source: Source::MusicBrainz,
+                rule_name: "test".to_string(),
+                confidence: 0.9,
+                alternatives: Vec::new(),
+            };
+            let mut proposal = Proposal::new(&entity_id, tag);
+            proposal.status = status;
+            lib.db.insert_proposal(&proposal).unwrap();
+        }
+        lib.db
+            .insert_assertion(&Assertion::new(
+                &entity_id,
+                "genre",
+                "Chamber music".into(),
+                Source::User,
+            ))
+            .unwrap();
+
+        let graph = MusicGraph::load(&lib.db).unwrap();
+        let index = FacetIndex::build(&graph, &lib.db, &MappingRules::default()).unwrap();
+        let facets = index.get(lib.bartok.id).unwrap();
+        assert!(facets.forms.contains("String quartet"));
+        assert!(facets.periods.contains("20th Century"));
+        assert_eq!(facets.genres.iter().collect::<Vec<_>>(), ["Chamber music"]);
+        assert!(index.get(lib.mozart.id).is_none());
+    }
+
+    #[test]
+    fn test_search_by_performer_and_year() {
+        let lib = library();
+        let graph = MusicGraph::load(&lib.db).unwrap();
+        let query = WorkQuery::new()
+            .with_performer("uchida")
+            .composed_between(1750, 1800);
+        let results = graph.search(&query, &facets(&lib));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].work.id, lib.mozart.id);
+    }
+
+    #[test]
+    fn test_search_by_performer_role() {
+        let lib = library();
+        let pianist = Artist::new("Martha Argerich");
+        let violinist = Artist::new("Gidon Kremer");
+        lib.db.insert_artist(&pianist).unwrap();
+        lib.db.insert_artist(&violinist).unwrap();
+        let sonata = Work::new("Violin Sonata No. 1").with_composer("Béla Bartók");
+        lib.db.insert_work(&sonata).unwrap();
+        let expr = Expression::new(sonata.id)
+            .with_credited_performer(Performer::new(pianist.id).with_role("piano"))
+            .with_credited_performer(Performer::new(violinist.id).with_role("violin"));
+        lib.db.insert_expression(&expr).unwrap();
+
+        let graph = MusicGraph::load(&lib.db).unwrap();
+        let search = |query: WorkQuery| -> Vec<WorkId> {
+            graph
+                .search(&query, &facets(&lib))
+                .iter()
+                .map(|m| m.work.id)
+                .collect()
+        };
+        assert_eq!(
+            search(
+                WorkQuery::new()
+                    .with_performer("argerich")
+                    .with_performer_role("piano")
+            ),
+            [sonata.id]
+        );
+        assert_eq!(
+            search(WorkQuery::new().with_performer_role("violin")),
+            [sonata.id]
+        );
+        // The same performer must match both
+        assert!(search(
+            WorkQuery::new()
+                .with_performer("argerich")
+                .with_performer_role("violin")
+        )
+        .is_empty());
+        // Uchida's recording has no credited instrument
+        assert!(search(
+            WorkQuery::new()
+                .with_performer("uchida")
+                .with_performer_role("piano")
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_search_by_recording_session() {
+        let lib = library();
+        let live = Expression::new(lib.bartok.id)
+            .with_recording_dates("1965-05", "1965-05")
+            .with_live(true);
+        let studio = Expression::new(lib.mozart.id)
+            .with_recording_dates("1959-12-30", "1960-01-04")
+            .with_live(false);
+        lib.db.insert_expression(&live).unwrap();
+        lib.db.insert_expression(&studio).unwrap();
+
+        let graph = MusicGraph::load(&lib.db).unwrap();
+        let search = |query: WorkQuery| -> Vec<WorkId> {
+            graph
+                .search(&query, &facets(&lib))
+                .iter()
+                .map(|m| m.work.id)
+                .collect()
+        };
+        assert_eq!(
+            search(
+                WorkQuery::new()
+                    .with_live(true)
+                    .recorded_between(1960, 1969)
+            ),
+            [lib.bartok.id]
+        );
+        // Sessions spanning the new year overlap the 1960s
+        assert_eq!(
+            search(WorkQuery::new().recorded_between(1960, 1969)),
+            [lib.bartok.id, lib.mozart.id]
+        );
+        assert_eq!(search(WorkQuery::new().with_live(false)), [lib.mozart.id]);
+        // One recording must match every recording facet
+        assert!(search(
+            WorkQuery::new()
+                .with_performer("uchida")
+                .recorded_between(1960, 1969)
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_search_by_credits() {
+        let lib = library();
+        let arranger = Artist::new("Zoltán Kodály");
+        let co_composer = Artist::new("Co-composer");
+        for artist in [&arranger, &co_composer] {
+            lib.db.insert_artist(artist).unwrap();
+        }
+        lib.db
+            .link_work_credit(&WorkCredit::new(
+                lib.bartok.id,
+                arranger.id,
+                WorkRole::Arranger,
+            ))
+            .unwrap();
+        lib.db
+            .link_work_credit(&WorkCredit::new(
+                lib.mozart.id,
+                co_composer.id,
+                WorkRole::Composer,
+            ))
+            .unwrap();
+
+        let graph = MusicGraph::load(&lib.db).unwrap();
+        let search = |query: WorkQuery| -> Vec<WorkId> {
+            graph
+                .search(&query, &facets(&lib))
+                .iter()
+                .map(|m| m.work.id)
+                .collect()
+        };
+        assert_eq!(
+            search(WorkQuery::new().with_arranger("kodály")),
+            [lib.bartok.id]
+        );
+        assert!(search(WorkQuery::new().with_lyricist("kodály")).is_empty());
+        assert_eq!(
+            search(WorkQuery::new().with_composer("co-composer")),
+            [lib.mozart.id]
+        );
+    }
+
+    #[test]
+    fn test_empty_query_matches_everything() {
+        let lib = library();
+        let graph = MusicGraph::load(&lib.db).unwrap();
+        assert_eq!(graph.search(&WorkQuery::new(), &FacetIndex::new()).len(), 2);
+    }
+
+    #[test]
+    fn test_search_rolls_movements_up_to_their_work() {
+        let lib = library();
+        let rondo = Work::new("Piano Sonata No. 8: III. Presto")
+            .with_composer("Wolfgang Amadeus Mozart")
+            .with_parent(lib.mozart.id, Some(3));
+        lib.db.insert_work(&rondo).unwrap();
+        let pianist = Artist::new("Alicia de Larrocha").with_role(ArtistRole::Performer);
+        lib.db.insert_artist(&pianist).unwrap();
+        let expr = Expression::new(rondo.id).with_performer(pianist.id);
+        lib.db.insert_expression(&expr).unwrap();
+        let mut item = Item::new(
+            PathBuf::from("/music/mozart-iii.flac"),
+            AudioFormat::Flac,
+            1,
+            Utc::now(),
+        );
+        item.expression_id = Some(expr.id);
+        lib.db.insert_item(&item).unwrap();
+
+        let graph = MusicGraph::load(&lib.db).unwrap();
+        let results = graph.search(&WorkQuery::new().with_performer("larrocha"), &facets(&lib));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].work.id, lib.mozart.id);
+        assert_eq!(results[0].items.len(), 2);
+        assert_eq!(graph.search(&WorkQuery::new(), &FacetIndex::new()).len(), 2);
+    }
+
+    #[test]
+    fn test_keys_match_ignores_case_and_hyphens() {
+        assert!(keys_match("a-flat major", "A flat  Major", false));
+        assert!(!keys_match("a minor", "A major", false));
+        assert!(keys_match("twelve-tone", "Twelve tone", false));
+    }
+
+    #[test]
+    fn test_search_by_catalog_number() {
+        let lib = library();
+        let quartets: Vec<Work> = [
+            "String Quartet No. 14 in C-sharp minor, Op. 131",
+            "String Quartet No. 2 in G major, Op. 18 No. 2",
+            "String Quartet No. 10 in E-flat major, Op. 74",
+            "String Quartet No. 1 in F major, Op. 18 No. 1",
+        ]
+        .into_iter()
+        .map(|title| Work::new(title).with_composer("Ludwig van Beethoven"))
+        .collect();
+        for quartet in &quartets {
+            lib.db.insert_work(quartet).unwrap();
+        }
+        let sonata = Work::new("Piano Sonata No. 11")
+            .with_composer("Wolfgang Amadeus Mozart")
+            .with_catalog_number("K. 331/300i");
+        lib.db.insert_work(&sonata).unwrap();
+
+        let graph = MusicGraph::load(&lib.db).unwrap();
+        let search = |query: WorkQuery| -> Vec<WorkId> {
+            graph
+                .search(&query, &facets(&lib))
+                .iter()
+                .map(|m| m.work.id)
+                .collect()
+        };
+        assert_eq!(
+            search(WorkQuery::new().with_catalog_number("op.131")),
+            [quartets[0].id]
+        );
+        assert_eq!(
+            search(WorkQuery::new().with_catalog_number("KV 300i")),
+            [sonata.id]
+        );
+        // A whole opus finds its works
+        assert_eq!(
+            search(WorkQuery::new().with_catalog_number("Op. 18")),
+            [quartets[3].id, quartets[1].id]
+        );
+        // Ordered by catalog number within a composer
+        assert_eq!(
+            search(WorkQuery::new().with_composer("beethoven")),
+            [
+                quartets[3].id,
+                quartets[1].id,
+                quartets[2].id,
+                quartets[0].id
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keys_match_across_spellings() {
+        assert!(keys_match("As-Dur", "A-flat major", false));
+        assert!(keys_match("la mineur", "A minor", false));
+        assert!(!keys_match("G-sharp major", "A-flat major", false));
+        assert!(keys_match("G-sharp major", "A-flat major", true));
+        assert!(!keys_match("G-sharp minor", "A-flat major", true));
+    }
+
+    #[test]
+    fn test_search_by_enharmonic_key() {
+        let lib = library();
+        let prelude = Work::new("Prelude No. 15").with_key("D-flat major");
+        lib.db.insert_work(&prelude).unwrap();
+        let graph = MusicGraph::load(&lib.db).unwrap();
+
+        let query = WorkQuery::new().with_key("cis-Dur");
+        assert!(graph.search(&query, &facets(&lib)).is_empty());
+        let results = graph.search(&query.with_enharmonic_keys(true), &facets(&lib));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].work.id, prelude.id);
+    }
+}
@@ -0,0 +1,223 @@
+//! Deterministic feature-hashing embeddings.
+//!
+//! Each [`Feature`] is hashed (FNV-1a, stable across platforms and Rust
+//! releases) into one of `dimensions` buckets with a hash-derived sign, and
+//! the resulting vector is L2-normalized so that cosine similarity reduces
+//! to a dot product.
+
+/// Default embedding width.
+pub const DEFAULT_DIMENSIONS: usize = 256;
+
+/// Weight given to the individual words of a multi-word feature value,
+/// relative to the whole value.
+const TOKEN_WEIGHT: f32 = 0.3;
+
+/// A named, weighted feature (e.g., `form:string quartet` with weight 3.0).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feature {
+    pub name: String,
+    pub weight: f32,
+}
+
+impl Feature {
+    /// Create a feature in a namespace from a raw value.
+    ///
+    /// The value is case-folded and diacritics are stripped, so
+    /// "Bartók" and "bartok" produce the same feature.
+    #[must_use]
+    pub fn new(namespace: &str, value: &str, weight: f32) -> Self {
+        Self {
+            name: format!("{namespace}:{}", normalize(value)),
+            weight,
+        }
+    }
+}
+
+/// Hashes feature sets into fixed-width unit vectors.
+#[derive(Debug, Clone, Copy)]
+pub struct Embedder {
+    dimensions: usize,
+}
+
+impl Default for Embedder {
+    fn default() -> Self {
+        Self::new(DEFAULT_DIMENSIONS)
+    }
+}
+
+impl Embedder {
+    /// Create an embedder producing vectors of the given width (at least 1).
+    #[must_use]
+    pub fn new(dimensions: usize) -> Self {
+        Self {
+            dimensions: dimensions.max(1),
+        }
+    }
+
+    #[must_use]
+    pub const fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// Embed a set of features.
+    ///
+    /// Multi-word values also contribute each word at a reduced weight, so
+    /// "piano quartet" and "string quartet" are closer than unrelated forms.
+    /// Returns the zero vector when there are no features.
+    #[must_use]
+    pub fn embed(&self, features: &[Feature]) -> Vec<f32> {
+        let mut vector = vec![0.0_f32; self.dimensions];
+
+        for feature in features {
+            self.add(&mut vector, &feature.name, feature.weight);
+
+            if let Some((namespace, value)) = feature.name.split_once(':') {
+                let words: Vec<&str> = value.split_whitespace().collect();
+                if words.len() > 1 {
+                    for word in words {
+                        self.add(
+                            &mut vector,
+                            &format!("{namespace}~{word}"),
+                            feature.weight * TOKEN_WEIGHT,
+                        );
+                    }
+                }
+            }
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for v in &mut vector {
+                *v /= norm;
+            }
+        }
+        vector
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn add(self, vector: &mut [f32], name: &str, weight: f32) {
+        let hash = fnv1a(name.as_bytes());
+        // Truncation is fine: only the bucket index matters
+        let bucket = (hash % self.dimensions as u64) as usize;
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[bucket] += sign * weight;
+    }
+}
+
+/// Cosine similarity of two vectors (0.0 if either is zero or lengths differ).
+#[must_use]
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|v| v * v).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// Lower-case, strip common Latin diacritics and collapse whitespace.
+#[must_use]
+pub fn normalize(value: &str) -> String {
+    value
+        .to_lowercase()
+        .chars()
+        .map(fold_diacritic)
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+const fn fold_diacritic(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => 'a',
+        'ç' | 'ć' | 'č' => 'c',
+        'ď' => 'd',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => 'e',
+        'ì' | 'í' | 'î' | 'ï' | 'ī' => 'i',
+        'ł' => 'l',
+        'ñ' | 'ń' | 'ň' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => 'o',
+        'ř' => 'r',
+        'ś' | 'š' | 'ş' => 's',
+        'ť' => 't',
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' => 'u',
+        'ý' | 'ÿ' => 'y',
+        'ź' | 'ż' | 'ž' => 'z',
+        other => other,
+    }
+}
+
+/// 64-bit FNV-1a hash.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedding_is_deterministic_and_normalized() {
+        let embedder = Embedder::default();
+        let features = vec![
+            Feature::new("form", "String quartet", 3.0),
+            Feature::new("period", "20th Century", 2.0),
+        ];
+        let a = embedder.embed(&features);
+        let b = embedder.embed(&features);
+        assert_eq!(a, b);
+        assert_eq!(a.len(), DEFAULT_DIMENSIONS);
+        let norm: f32 = a.iter().map(|v| v * v).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_empty_features_embed_to_zero() {
+        let v = Embedder::new(8).embed(&[]);
+        assert_eq!(v, vec![0.0; 8]);
+    }
+
+    #[test]
+    fn test_shared_features_are_more_similar() {
+        let embedder = Embedder::default();
+        let quartet = embedder.embed(&[
+            Feature::new("form", "String quartet", 3.0),
+            Feature::new("period", "20th Century", 2.0),
+        ]);
+        let other_quartet = embedder.embed(&[
+            Feature::new("form", "String quartet", 3.0),
+            Feature::new("period", "Classical", 2.0),
+        ]);
+        let opera = embedder.embed(&[
+            Feature::new("form", "Opera", 3.0),
+            Feature::new("period", "Romantic", 2.0),
+        ]);
+        assert!(cosine_similarity(&quartet, &other_quartet) > cosine_similarity(&quartet, &opera));
+    }
+
+    #[test]
+    fn test_feature_normalizes_diacritics() {
+        assert_eq!(
+            Feature::new("composer", "Béla  Bartók", 1.0).name,
+            "composer:bela bartok"
+        );
+    }
+
+    #[test]
+    fn test_fnv1a_known_value() {
+        // Reference value for the empty input is the offset basis
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
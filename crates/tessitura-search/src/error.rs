@@ -0,0 +1,21 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("core error: {0}")]
+    Core(#[from] tessitura_core::Error),
+
+    #[error("graph error: {0}")]
+    Graph(#[from] tessitura_graph::Error),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("vector has {found} dimensions, index expects {expected}")]
+    DimensionMismatch { expected: usize, found: usize },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,165 @@
+//! Feature extraction for works and recordings.
+//!
+//! Features are drawn from the harmonized facets in a [`FacetIndex`], the
+//! FRBR relationships in a [`MusicGraph`], and folksonomy tags asserted on
+//! the files. Weights reflect how strongly each facet should pull two
+//! entities together: sharing a form matters more than sharing a key.
+
+use tessitura_core::model::{Expression, Work};
+use tessitura_core::provenance::Assertion;
+use tessitura_graph::{FacetIndex, MusicGraph};
+
+use crate::embedding::Feature;
+
+pub const FORM_WEIGHT: f32 = 3.0;
+pub const GENRE_WEIGHT: f32 = 2.0;
+pub const PERIOD_WEIGHT: f32 = 2.0;
+pub const INSTRUMENT_WEIGHT: f32 = 1.5;
+pub const COMPOSER_WEIGHT: f32 = 1.0;
+pub const PERFORMER_WEIGHT: f32 = 1.0;
+pub const TAG_WEIGHT: f32 = 1.0;
+pub const KEY_WEIGHT: f32 = 0.5;
+pub const DECADE_WEIGHT: f32 = 0.5;
+
+/// Features describing a work.
+#[must_use]
+pub fn work_features(graph: &MusicGraph, facets: &FacetIndex, work: &Work) -> Vec<Feature> {
+    let mut features = Vec::new();
+
+    if let Some(work_facets) = facets.get(work.id) {
+        let families = [
+            ("form", &work_facets.forms, FORM_WEIGHT),
+            ("genre", &work_facets.genres, GENRE_WEIGHT),
+            ("period", &work_facets.periods, PERIOD_WEIGHT),
+            ("instrument", &work_facets.instruments, INSTRUMENT_WEIGHT),
+        ];
+        for (namespace, values, weight) in families {
+            features.extend(values.iter().map(|v| Feature::new(namespace, v, weight)));
+        }
+    }
+
+    let composer = graph
+        .composer_of(work.id)
+        .map(|artist| artist.name.as_str())
+        .or(work.composer.as_deref());
+    if let Some(composer) = composer {
+        features.push(Feature::new("composer", composer, COMPOSER_WEIGHT));
+    }
+
+    // Spellings of the same key share a feature
+    let key = work
+        .musical_key()
+        .map(|k| k.to_string())
+        .or_else(|| work.key.clone());
+    if let Some(key) = key {
+        features.push(Feature::new("key", &key, KEY_WEIGHT));
+    }
+
+    features
+}
+
+/// Features describing a recording: those of its work plus performers,
+/// conductor and recording decade.
+#[must_use]
+pub fn expression_features(
+    graph: &MusicGraph,
+    facets: &FacetIndex,
+    expression: &Expression,
+) -> Vec<Feature> {
+    let mut features = graph
+        .work_of(expression.id)
+        .map(|work| work_features(graph, facets, work))
+        .unwrap_or_default();
+
+    for (artist, _) in graph.performers_of(expression.id) {
+        features.push(Feature::new("performer", &artist.name, PERFORMER_WEIGHT));
+    }
+    if let Some(conductor) = graph.conductor_of(expression.id) {
+        features.push(Feature::new("conductor", &conductor.name, PERFORMER_WEIGHT));
+    }
+    if let Some(year) = expression.recorded_year {
+        let decade = year - year.rem_euclid(10);
+        features.push(Feature::new("decade", &format!("{decade}s"), DECADE_WEIGHT));
+    }
+
+    features
+}
+
+/// Features from folksonomy tag assertions (e.g., Last.fm top tags).
+///
+/// Tags with a `count` (0–100) are scaled by it, so a tag applied by few
+/// listeners contributes little.
+#[must_use]
+pub fn tag_features(assertions: &[Assertion]) -> Vec<Feature> {
+    assertions
+        .iter()
+        .filter(|a| a.field == "tag")
+        .filter_map(|a| {
+            let name = a
+                .value
+                .get("name")
+                .and_then(|v| v.as_str())
+                .or_else(|| a.value.as_str())?;
+            #[allow(clippy::cast_possible_truncation)]
+            let scale = a
+                .value
+                .get("count")
+                .and_then(serde_json::Value::as_f64)
+                .map_or(1.0, |count| (count / 100.0).clamp(0.1, 1.0) as f32);
+            Some(Feature::new("tag", name, TAG_WEIGHT * scale))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tessitura_core::provenance::Source;
+
+    #[test]
+    fn test_work_features_from_facets() {
+        let work = Work::new("String Quartet No. 4")
+            .with_composer("Béla Bartók")
+            .with_key("C-Dur");
+        let mut facets = FacetIndex::new();
+        facets.insert(work.id, "form", "String quartets");
+        facets.insert(work.id, "period", "20th Century");
+
+        let features = work_features(&MusicGraph::new(), &facets, &work);
+        let names: Vec<&str> = features.iter().map(|f| f.name.as_str()).collect();
+        assert!(names.contains(&"form:string quartets"));
+        assert!(names.contains(&"period:20th century"));
+        assert!(names.contains(&"composer:bela bartok"));
+        assert!(names.contains(&"key:c major"));
+    }
+
+    #[test]
+    fn test_tag_features_scale_by_count() {
+        let assertions = vec![
+            Assertion::new(
+                "item",
+                "tag",
+                serde_json::json!({"name": "chamber music", "count": 100}),
+                Source::LastFm,
+            ),
+            Assertion::new(
+                "item",
+                "tag",
+                serde_json::json!({"name": "seen live", "count": 5}),
+                Source::LastFm,
+            ),
+            Assertion::new(
+                "item",
+                "genre",
+                serde_json::json!("Classical"),
+                Source::LastFm,
+            ),
+        ];
+
+        let features = tag_features(&assertions);
+        assert_eq!(features.len(), 2);
+        assert_eq!(features[0].name, "tag:chamber music");
+        assert!((features[0].weight - TAG_WEIGHT).abs() < f32::EPSILON);
+        assert!(features[1].weight < features[0].weight);
+    }
+}
@@ -0,0 +1,548 @@
+//! On-disk vector index with brute-force nearest-neighbour search.
+//!
+//! A personal library holds thousands, not millions, of works, so an exact
+//! scan over unit vectors is fast enough and keeps the index a single JSON
+//! file that can be deleted and rebuilt at any time.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tessitura_core::model::ItemId;
+use tessitura_core::schema::{CatalogStamp, Database};
+use tessitura_graph::{FacetIndex, MusicGraph, Node};
+
+use crate::embedding::{cosine_similarity, normalize, Embedder, Feature};
+use crate::error::{Error, Result};
+use crate::features::{expression_features, tag_features, work_features};
+
+/// Bumped whenever feature extraction or hashing changes, so indices built
+/// by older versions are treated as stale.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// The kind of entity an index entry describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntityKind {
+    Work,
+    Expression,
+}
+
+/// An embedded entity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub kind: EntityKind,
+    /// The entity's ID (`WorkId` or `ExpressionId` as a string).
+    pub id: String,
+    /// Human-readable label, also used for fuzzy lookup.
+    pub label: String,
+    pub vector: Vec<f32>,
+}
+
+/// A search result with its cosine similarity score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Neighbor {
+    pub kind: EntityKind,
+    pub id: String,
+    pub label: String,
+    pub score: f32,
+}
+
+/// A persisted collection of embedded works and recordings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorIndex {
+    version: u32,
+    dimensions: usize,
+    built_at: DateTime<Utc>,
+    sources: Sources,
+    entries: Vec<IndexEntry>,
+}
+
+/// What an index was built from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct Sources {
+    catalog: CatalogStamp,
+    /// Modification time of the mapping rules file, if there is one.
+    rules_modified: Option<DateTime<Utc>>,
+}
+
+impl Sources {
+    fn read(db: &Database, rules_path: &Path) -> Result<Self> {
+        Ok(Self {
+            catalog: db.catalog_stamp()?,
+            rules_modified: fs::metadata(rules_path)
+                .and_then(|m| m.modified())
+                .ok()
+                .map(DateTime::from),
+        })
+    }
+}
+
+impl VectorIndex {
+    /// Create an empty index for vectors of the given width.
+    #[must_use]
+    pub fn new(dimensions: usize) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            dimensions,
+            built_at: Utc::now(),
+            sources: Sources::default(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Embed every work and recording in the graph.
+    ///
+    /// Movements are not embedded on their own: the files of their
+    /// recordings count towards the work they are part of. `rules_path` is
+    /// the mapping rules file the facets were built with, recorded so that
+    /// editing it makes the index stale.
+    pub fn build(
+        graph: &MusicGraph,
+        facets: &FacetIndex,
+        db: &Database,
+        rules_path: &Path,
+        embedder: &Embedder,
+    ) -> Result<Self> {
+        let mut index = Self::new(embedder.dimensions());
+        index.sources = Sources::read(db, rules_path)?;
+        let mut tags = TagCache::default();
+
+        for node in graph.nodes() {
+            let (kind, id, label, mut features) = match node {
+                Node::Work(work) if graph.parent_of(work.id).is_some() => continue,
+                Node::Work(work) => {
+                    let features = work_features(graph, facets, work);
+                    let label = match graph
+                        .composer_of(work.id)
+                        .map(|a| a.name.as_str())
+                        .or(work.composer.as_deref())
+                    {
+                        Some(composer) => format!("{composer}: {}", work.title),
+                        None => work.title.clone(),
+                    };
+                    (EntityKind::Work, work.id.to_string(), label, features)
+                }
+                Node::Expression(expr) => {
+                    let features = expression_features(graph, facets, expr);
+                    let title = graph
+                        .work_of(expr.id)
+                        .map(|w| w.title.clone())
+                        .or_else(|| expr.title.clone())
+                        .unwrap_or_else(|| node.label());
+                    let performers: Vec<&str> = graph
+                        .performers_of(expr.id)
+                        .into_iter()
+                        .map(|(a, _)| a.name.as_str())
+                        .chain(graph.conductor_of(expr.id).map(|a| a.name.as_str()))
+                        .collect();
+                    let label = if performers.is_empty() {
+                        title
+                    } else {
+                        format!("{title} ({})", performers.join(", "))
+                    };
+                    (EntityKind::Expression, expr.id.to_string(), label, features)
+                }
+                _ => continue,
+            };
+
+            let items = match node {
+                Node::Work(work) => graph.items_of_work(work.id),
+                Node::Expression(expr) => graph.items_of_expression(expr.id),
+                _ => Vec::new(),
+            };
+            for item in items {
+                features.extend(tags.get(db, item.id)?.iter().cloned());
+            }
+
+            // Graph nodes are unique, so entries can be appended directly
+            index.entries.push(IndexEntry {
+                kind,
+                id,
+                label,
+                vector: embedder.embed(&features),
+            });
+        }
+
+        log::info!("Built similarity index with {} entries", index.len());
+        Ok(index)
+    }
+
+    /// Load an index from disk.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let json = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Write the index to disk, replacing any existing file atomically.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string(self)?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Whether the database or the mapping rules file at `rules_path` have
+    /// changed since this index was built, or the index was written by an
+    /// incompatible version. Rows deleted from the database count as a
+    /// change.
+    pub fn is_stale(&self, db: &Database, rules_path: &Path) -> Result<bool> {
+        Ok(self.version != FORMAT_VERSION || Sources::read(db, rules_path)? != self.sources)
+    }
+
+    #[must_use]
+    pub const fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    #[must_use]
+    pub const fn built_at(&self) -> DateTime<Utc> {
+        self.built_at
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &IndexEntry> {
+        self.entries.iter()
+    }
+
+    /// Insert an entry, replacing any existing entry for the same entity.
+    pub fn upsert(&mut self, entry: IndexEntry) -> Result<()> {
+        if entry.vector.len() != self.dimensions {
+            return Err(Error::DimensionMismatch {
+                expected: self.dimensions,
+                found: entry.vector.len(),
+            });
+        }
+        match self
+            .entries
+            .iter_mut()
+            .find(|e| e.kind == entry.kind && e.id == entry.id)
+        {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+        Ok(())
+    }
+
+    /// Remove an entity's entry. Returns whether it was present.
+    pub fn remove(&mut self, kind: EntityKind, id: &str) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| !(e.kind == kind && e.id == id));
+        self.entries.len() != before
+    }
+
+    #[must_use]
+    pub fn get(&self, kind: EntityKind, id: &str) -> Option<&IndexEntry> {
+        self.entries.iter().find(|e| e.kind == kind && e.id == id)
+    }
+
+    /// Find the entry whose label best matches free text.
+    ///
+    /// Matching is by word overlap after case and diacritic folding, so
+    /// "bartok quartet 4" finds "Béla Bartók: String Quartet No. 4". At least
+    /// half of the query words must appear in the label; ties go to the
+    /// shorter label.
+    #[must_use]
+    pub fn find(&self, text: &str, kind: Option<EntityKind>) -> Option<&IndexEntry> {
+        let query = words(text);
+        if query.is_empty() {
+            return None;
+        }
+
+        self.entries
+            .iter()
+            .filter(|e| kind.is_none_or(|k| e.kind == k))
+            .filter_map(|e| {
+                let label = words(&e.label);
+                let hits = query.iter().filter(|w| label.contains(w)).count();
+                (hits * 2 >= query.len()).then_some((hits, e))
+            })
+            .max_by(|(a_hits, a), (b_hits, b)| {
+                a_hits
+                    .cmp(b_hits)
+                    .then_with(|| b.label.len().cmp(&a.label.len()))
+            })
+            .map(|(_, e)| e)
+    }
+
+    /// The `k` entries closest to a vector, best first. Entries with no
+    /// similarity at all are omitted.
+    #[must_use]
+    pub fn nearest(&self, vector: &[f32], kind: Option<EntityKind>, k: usize) -> Vec<Neighbor> {
+        let mut scored: Vec<Neighbor> = self
+            .entries
+            .iter()
+            .filter(|e| kind.is_none_or(|kind| e.kind == kind))
+            .map(|e| Neighbor {
+                kind: e.kind,
+                id: e.id.clone(),
+                label: e.label.clone(),
+                score: cosine_similarity(vector, &e.vector),
+            })
+            .filter(|n| n.score > 0.0)
+            .collect();
+
+        scored.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.label.cmp(&b.label))
+        });
+        scored.truncate(k);
+        scored
+    }
+
+    /// The `k` entries of the same kind closest to an existing entry,
+    /// excluding the entry itself.
+    #[must_use]
+    pub fn similar_to(&self, entry: &IndexEntry, k: usize) -> Vec<Neighbor> {
+        let mut neighbors = self.nearest(&entry.vector, Some(entry.kind), k + 1);
+        neighbors.retain(|n| n.id != entry.id);
+        neighbors.truncate(k);
+        neighbors
+    }
+}
+
+/// Per-item tag features, so items shared by a work and its recordings are
+/// only read once.
+#[derive(Debug, Default)]
+struct TagCache {
+    items: HashMap<ItemId, Vec<Feature>>,
+}
+
+impl TagCache {
+    fn get(&mut self, db: &Database, item: ItemId) -> Result<&[Feature]> {
+        match self.items.entry(item) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let assertions = db.get_assertions_for_entity(&item.to_string())?;
+                Ok(entry.insert(tag_features(&assertions)))
+            }
+        }
+    }
+}
+
+/// Folded words of a label or query, with punctuation removed.
+fn words(text: &str) -> Vec<String> {
+    normalize(text)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tessitura_core::model::{Artist, AudioFormat, Expression, Item, Work};
+    use tessitura_core::provenance::{Assertion, Source};
+
+    fn entry(kind: EntityKind, id: &str, label: &str, vector: Vec<f32>) -> IndexEntry {
+        IndexEntry {
+            kind,
+            id: id.to_string(),
+            label: label.to_string(),
+            vector,
+        }
+    }
+
+    #[test]
+    fn test_nearest_orders_by_score() {
+        let mut index = VectorIndex::new(2);
+        index
+            .upsert(entry(EntityKind::Work, "a", "A", vec![1.0, 0.0]))
+            .unwrap();
+        index
+            .upsert(entry(EntityKind::Work, "b", "B", vec![0.6, 0.8]))
+            .unwrap();
+        index
+            .upsert(entry(EntityKind::Work, "c", "C", vec![0.0, 1.0]))
+            .unwrap();
+
+        let neighbors = index.nearest(&[1.0, 0.0], None, 5);
+        let ids: Vec<&str> = neighbors.iter().map(|n| n.id.as_str()).collect();
+        // "c" is orthogonal and therefore omitted
+        assert_eq!(ids, vec!["a", "b"]);
+        assert!((neighbors[0].score - 1.0).abs() < 1e-6);
+
+        let a = index.get(EntityKind::Work, "a").unwrap().clone();
+        let similar = index.similar_to(&a, 1);
+        assert_eq!(similar.len(), 1);
+        assert_eq!(similar[0].id, "b");
+    }
+
+    #[test]
+    fn test_upsert_replaces_and_checks_dimensions() {
+        let mut index = VectorIndex::new(2);
+        index
+            .upsert(entry(EntityKind::Work, "a", "Old", vec![1.0, 0.0]))
+            .unwrap();
+        index
+            .upsert(entry(EntityKind::Work, "a", "New", vec![0.0, 1.0]))
+            .unwrap();
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.get(EntityKind::Work, "a").unwrap().label, "New");
+
+        let err = index
+            .upsert(entry(EntityKind::Work, "b", "B", vec![1.0]))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::DimensionMismatch {
+                expected: 2,
+                found: 1
+            }
+        ));
+
+        assert!(index.remove(EntityKind::Work, "a"));
+        assert!(!index.remove(EntityKind::Work, "a"));
+        assert!(index.is_empty());
+    }
+
+    #[test]
+    fn test_find_by_label_words() {
+        let mut index = VectorIndex::new(1);
+        index
+            .upsert(entry(
+                EntityKind::Work,
+                "1",
+                "Béla Bartók: String Quartet No. 4",
+                vec![1.0],
+            ))
+            .unwrap();
+        index
+            .upsert(entry(
+                EntityKind::Work,
+                "2",
+                "Ludwig van Beethoven: Symphony No. 5",
+                vec![1.0],
+            ))
+            .unwrap();
+
+        assert_eq!(index.find("bartok quartet 4", None).unwrap().id, "1");
+        assert_eq!(index.find("Beethoven 5", None).unwrap().id, "2");
+        assert!(index.find("mahler", None).is_none());
+        assert!(index.find("bartok", Some(EntityKind::Expression)).is_none());
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.json");
+
+        let mut index = VectorIndex::new(2);
+        index
+            .upsert(entry(EntityKind::Expression, "x", "X", vec![0.6, 0.8]))
+            .unwrap();
+        index.save(&path).unwrap();
+
+        let loaded = VectorIndex::load(&path).unwrap();
+        assert_eq!(loaded.dimensions(), 2);
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded.get(EntityKind::Expression, "x").unwrap().label, "X");
+    }
+
+    #[test]
+    fn test_build_ranks_related_works_first() {
+        let db = Database::open_in_memory().unwrap();
+        let bartok = Artist::new("Béla Bartók");
+        db.insert_artist(&bartok).unwrap();
+
+        let quartet4 = Work::new("String Quartet No. 4").with_composer("Béla Bartók");
+        let quartet5 = Work::new("String Quartet No. 5").with_composer("Béla Bartók");
+        let opera = Work::new("Bluebeard's Castle").with_composer("Béla Bartók");
+        for work in [&quartet4, &quartet5, &opera] {
+            db.insert_work(work).unwrap();
+        }
+
+        let expr = Expression::new(quartet4.id);
+        db.insert_expression(&expr).unwrap();
+        let mut item = Item::new(
+            PathBuf::from("/music/bartok/04.flac"),
+            AudioFormat::Flac,
+            1024,
+            Utc::now(),
+        );
+        item.expression_id = Some(expr.id);
+        db.insert_item(&item).unwrap();
+        db.insert_assertion(&Assertion::new(
+            item.id.to_string(),
+            "tag",
+            serde_json::json!({"name": "string quartet", "count": 100}),
+            Source::LastFm,
+        ))
+        .unwrap();
+
+        let mut facets = FacetIndex::new();
+        for work in [&quartet4, &quartet5] {
+            facets.insert(work.id, "form", "String quartets");
+            facets.insert(work.id, "period", "20th Century");
+        }
+        facets.insert(opera.id, "form", "Operas");
+        facets.insert(opera.id, "period", "20th Century");
+
+        let graph = MusicGraph::load(&db).unwrap();
+        let rules = Path::new("/nonexistent/mapping_rules.toml");
+        let index = VectorIndex::build(&graph, &facets, &db, rules, &Embedder::default()).unwrap();
+        assert_eq!(index.len(), 4);
+        assert!(!index.is_stale(&db, rules).unwrap());
+
+        let source = index.find("Bartok String Quartet 4", Some(EntityKind::Work));
+        let source = source.unwrap();
+        assert_eq!(source.id, quartet4.id.to_string());
+
+        let similar = index.similar_to(source, 2);
+        assert_eq!(similar[0].id, quartet5.id.to_string());
+        assert_eq!(similar[1].id, opera.id.to_string());
+        assert!(similar[0].score > similar[1].score);
+    }
+
+    #[test]
+    fn test_stale_after_deletion_or_rules_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let rules = dir.path().join("mapping_rules.toml");
+        fs::write(&rules, "").unwrap();
+        let db = Database::open_in_memory().unwrap();
+        let work = Work::new("Vocalise");
+        db.insert_work(&work).unwrap();
+
+        let build = |db: &Database| {
+            let graph = MusicGraph::load(db).unwrap();
+            VectorIndex::build(&graph, &FacetIndex::new(), db, &rules, &Embedder::default())
+                .unwrap()
+        };
+        let index = build(&db);
+        assert!(!index.is_stale(&db, &rules).unwrap());
+
+        // Deleting a work dates no change
+        db.delete_work(&work.id).unwrap();
+        assert!(index.is_stale(&db, &rules).unwrap());
+        let index = build(&db);
+        assert!(!index.is_stale(&db, &rules).unwrap());
+
+        let later = std::time::SystemTime::now() + std::time::Duration::from_mins(1);
+        fs::File::options()
+            .write(true)
+            .open(&rules)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert!(index.is_stale(&db, &rules).unwrap());
+    }
+}
@@ -1,7 +1,18 @@
 //! Vector search for tessitura.
 //!
-//! Manages `LanceDB` vector indices for similarity and fuzzy search
-//! across the music catalog.
+//! Maintains an embedded, on-disk vector index for similarity and fuzzy
+//! search across the music catalog. Embeddings are computed offline by
+//! feature hashing the harmonized metadata of each work and recording, so
+//! no model service or network access is needed.
 
 #![deny(unsafe_code)]
 #![warn(missing_debug_implementations)]
+
+pub mod embedding;
+pub mod error;
+pub mod features;
+pub mod index;
+
+pub use embedding::{Embedder, Feature, DEFAULT_DIMENSIONS};
+pub use error::{Error, Result};
+pub use index::{EntityKind, IndexEntry, Neighbor, VectorIndex};